@@ -0,0 +1,11 @@
+use crate::{cli, config, error::Error, fs::OutputFile};
+use std::path::PathBuf;
+
+pub fn command(root: String, package: String, version: String) -> Result<(), Error> {
+    let path = PathBuf::from(root).join("gleam.toml");
+    let toml = crate::fs::read(&path)?;
+    let text = config::add_dependency(&path, &toml, &package, &version)?;
+    crate::fs::write_output(&OutputFile { path, text })?;
+    cli::print_green_prefix("    Added", &format!("{} {}", package, version));
+    Ok(())
+}
@@ -1,14 +1,18 @@
 mod constant;
 mod typed;
 mod untyped;
+pub mod visit;
 
 pub use self::typed::TypedExpr;
 pub use self::untyped::UntypedExpr;
 
 pub use self::constant::{Constant, TypedConstant, UntypedConstant};
 
+use crate::build::Target;
 use crate::typ::{self, ModuleValueConstructor, PatternConstructor, Type, ValueConstructor};
+use heck::SnakeCase;
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 pub const CAPTURE_VARIABLE: &str = "gleam@capture_variable";
@@ -27,6 +31,50 @@ pub struct Module<T, Expr, Info, ConstantRecordTag> {
     pub documentation: Vec<String>,
     pub type_info: Info,
     pub statements: Vec<Statement<T, Expr, ConstantRecordTag>>,
+    pub target_attributes: Vec<TargetAttribute>,
+    pub deprecated_attributes: Vec<DeprecatedAttribute>,
+    pub internal_attributes: Vec<InternalAttribute>,
+    pub allow_attributes: Vec<AllowAttribute>,
+}
+
+/// A `@target(...)` attribute, limiting the statement after it to being
+/// compiled for one target.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TargetAttribute {
+    pub location: SrcSpan,
+    pub target: Target,
+    /// The start of the location of the statement the attribute applies to.
+    pub statement_start: usize,
+}
+
+/// A `@deprecated("...")` attribute, causing a warning with the message
+/// wherever the function or type defined by the statement after it is used.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeprecatedAttribute {
+    pub location: SrcSpan,
+    pub message: String,
+    /// The start of the location of the statement the attribute applies to.
+    pub statement_start: usize,
+}
+
+/// An `@internal` attribute, hiding the public function or type defined by the
+/// statement after it from the documentation of the package, and causing a
+/// warning wherever it is used by another package.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InternalAttribute {
+    pub location: SrcSpan,
+    /// The start of the location of the statement the attribute applies to.
+    pub statement_start: usize,
+}
+
+/// An `@allow(...)` attribute, suppressing the warnings with the given codes
+/// within the statement after it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AllowAttribute {
+    pub location: SrcSpan,
+    pub codes: Vec<String>,
+    /// The start of the location of the statement the attribute applies to.
+    pub statement_start: usize,
 }
 
 impl<A, B, C, D> Module<A, B, C, D> {
@@ -34,6 +82,73 @@ impl<A, B, C, D> Module<A, B, C, D> {
         self.name.join("/")
     }
 
+    /// The target that the statement starting at the given offset is limited
+    /// to, if it has a `@target(...)` attribute.
+    pub fn target_of(&self, statement_start: usize) -> Option<Target> {
+        self.target_attributes
+            .iter()
+            .find(|attribute| attribute.statement_start == statement_start)
+            .map(|attribute| attribute.target)
+    }
+
+    /// The deprecation message of the statement starting at the given offset,
+    /// if it has a `@deprecated(...)` attribute.
+    pub fn deprecation_of(&self, statement_start: usize) -> Option<&str> {
+        self.deprecated_attributes
+            .iter()
+            .find(|attribute| attribute.statement_start == statement_start)
+            .map(|attribute| attribute.message.as_str())
+    }
+
+    /// Whether the statement starting at the given offset has an `@internal`
+    /// attribute.
+    pub fn is_internal(&self, statement_start: usize) -> bool {
+        self.internal_attributes
+            .iter()
+            .any(|attribute| attribute.statement_start == statement_start)
+    }
+
+    /// The warning codes allowed by the `@allow(...)` attribute of the
+    /// statement starting at the given offset.
+    pub fn allowed_codes_of(&self, statement_start: usize) -> &[String] {
+        self.allow_attributes
+            .iter()
+            .find(|attribute| attribute.statement_start == statement_start)
+            .map(|attribute| attribute.codes.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Whether warnings with the code are allowed at the given offset, by an
+    /// `@allow(...)` attribute of the statement that the offset is within.
+    /// A statement is taken to run up to the start of the next one.
+    pub fn allows(&self, code: &str, offset: usize) -> bool {
+        let statement_start = self
+            .statements
+            .iter()
+            .map(|statement| statement.location().start)
+            .filter(|start| *start <= offset)
+            .max();
+        match statement_start {
+            Some(start) => self.allowed_codes_of(start).iter().any(|c| c == code),
+            None => false,
+        }
+    }
+
+    /// Remove the statements that are limited to a target other than the
+    /// given one.
+    pub fn retain_target(&mut self, target: Target) {
+        let excluded: Vec<usize> = self
+            .target_attributes
+            .iter()
+            .filter(|attribute| attribute.target != target)
+            .map(|attribute| attribute.statement_start)
+            .collect();
+        self.statements
+            .retain(|statement| !excluded.contains(&statement.location().start));
+        self.target_attributes
+            .retain(|attribute| attribute.target == target);
+    }
+
     // TODO: return &str not String once module is a String not a Vector
     pub fn dependencies(&self) -> Vec<(String, SrcSpan)> {
         self.statements
@@ -93,15 +208,19 @@ pub enum ArgNames {
     NamedLabelled { name: String, label: String },
 }
 
+pub type TypedRecordConstructor = RecordConstructor<Arc<Type>>;
+
 #[derive(Debug, Clone, PartialEq)]
-pub struct RecordConstructor {
+pub struct RecordConstructor<T> {
     pub location: SrcSpan,
     pub name: String,
     pub args: Vec<(Option<String>, TypeAst, SrcSpan)>,
     pub documentation: Option<String>,
+    /// The type of the constructor, a function for constructors with fields.
+    pub typ: T,
 }
 
-impl RecordConstructor {
+impl<T> RecordConstructor<T> {
     pub fn put_doc<'a>(&mut self, new_doc: impl Iterator<Item = &'a str>) {
         let mut new_doc = new_doc.peekable();
         if new_doc.peek().is_none() {
@@ -112,6 +231,55 @@ impl RecordConstructor {
     }
 }
 
+/// A helper function that can be generated for a custom type by annotating
+/// it with `@derive(...)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Derive {
+    /// `fn(a, a) -> Bool`, true if the two values are structurally equal.
+    Equal,
+    /// `fn(a, a) -> Int`, negative, zero, or positive if the first value is
+    /// less than, equal to, or greater than the second. Values are ordered by
+    /// the order in which their constructors are defined, and then by their
+    /// fields from first to last. The fields are compared in Erlang term
+    /// order, so fields of other custom types are not ordered by their own
+    /// derived `compare` functions.
+    Compare,
+}
+
+impl Derive {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "equal" => Some(Self::Equal),
+            "compare" => Some(Self::Compare),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Equal => "equal",
+            Self::Compare => "compare",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DerivedFunction {
+    pub location: SrcSpan,
+    pub derive: Derive,
+    pub name: String,
+}
+
+impl DerivedFunction {
+    pub fn new(location: SrcSpan, derive: Derive, type_name: &str) -> Self {
+        Self {
+            location,
+            derive,
+            name: format!("{}_{}", type_name.to_snake_case(), derive.name()),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum TypeAst {
     Constructor {
@@ -153,6 +321,20 @@ impl TypeAst {
             | TypeAst::Constructor { location, .. } => location,
         }
     }
+
+    /// Whether the type variable with the given name is used in the type.
+    ///
+    pub fn uses_var(&self, var: &str) -> bool {
+        match self {
+            TypeAst::Var { name, .. } => name == var,
+            TypeAst::Hole { .. } => false,
+            TypeAst::Constructor { args, .. } => args.iter().any(|arg| arg.uses_var(var)),
+            TypeAst::Tuple { elems, .. } => elems.iter().any(|elem| elem.uses_var(var)),
+            TypeAst::Fn { args, retrn, .. } => {
+                args.iter().any(|arg| arg.uses_var(var)) || retrn.uses_var(var)
+            }
+        }
+    }
 }
 
 pub type TypedStatement = Statement<Arc<Type>, TypedExpr, String>;
@@ -186,10 +368,12 @@ pub enum Statement<T, Expr, ConstantRecordTag> {
         location: SrcSpan,
         name: String,
         parameters: Vec<String>,
+        parameter_locations: Vec<SrcSpan>,
         public: bool,
-        constructors: Vec<RecordConstructor>,
+        constructors: Vec<RecordConstructor<T>>,
         doc: Option<String>,
         opaque: bool,
+        derives: Vec<DerivedFunction>,
     },
 
     ExternalFn {
@@ -336,9 +520,51 @@ impl BinOp {
             Self::MultInt | Self::MultFloat | Self::DivInt | Self::DivFloat | Self::ModuloInt => 7,
         }
     }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::And => "&&",
+            Self::Or => "||",
+            Self::LtInt => "<",
+            Self::LtEqInt => "<=",
+            Self::LtFloat => "<.",
+            Self::LtEqFloat => "<=.",
+            Self::Eq => "==",
+            Self::NotEq => "!=",
+            Self::GtEqInt => ">=",
+            Self::GtInt => ">",
+            Self::GtEqFloat => ">=.",
+            Self::GtFloat => ">.",
+            Self::AddInt => "+",
+            Self::AddFloat => "+.",
+            Self::SubInt => "-",
+            Self::SubFloat => "-.",
+            Self::MultInt => "*",
+            Self::MultFloat => "*.",
+            Self::DivInt => "/",
+            Self::DivFloat => "/.",
+            Self::ModuloInt => "%",
+        }
+    }
+
+    pub fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            Self::Eq
+                | Self::NotEq
+                | Self::LtInt
+                | Self::LtEqInt
+                | Self::LtFloat
+                | Self::LtEqFloat
+                | Self::GtEqInt
+                | Self::GtInt
+                | Self::GtEqFloat
+                | Self::GtFloat
+        )
+    }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct CallArg<A> {
     pub label: Option<String>,
     pub location: SrcSpan,
@@ -522,12 +748,38 @@ impl TypedClauseGuard {
     }
 }
 
-#[derive(Debug, PartialEq, Default, Clone)]
+#[derive(Debug, PartialEq, Default, Clone, Serialize, Deserialize)]
 pub struct SrcSpan {
     pub start: usize,
     pub end: usize,
 }
 
+/// The value of an integer literal as written in the source, if it fits in a
+/// 64-bit integer.
+///
+pub fn parse_int(value: &str) -> Option<i64> {
+    let value = value.replace("_", "");
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(value) => (true, value),
+        None => (false, value.as_str()),
+    };
+    let (digits, radix) = if let Some(digits) = value.strip_prefix("0x") {
+        (digits, 16)
+    } else if let Some(digits) = value.strip_prefix("0o") {
+        (digits, 8)
+    } else if let Some(digits) = value.strip_prefix("0b") {
+        (digits, 2)
+    } else {
+        (value, 10)
+    };
+    let magnitude = i64::from_str_radix(digits, radix).ok()?;
+    if negative {
+        magnitude.checked_neg()
+    } else {
+        Some(magnitude)
+    }
+}
+
 pub type UntypedPattern = Pattern<(), ()>;
 pub type TypedPattern = Pattern<PatternConstructor, Arc<Type>>;
 
@@ -634,6 +886,36 @@ impl<A, B> Pattern<A, B> {
     }
 }
 
+impl TypedPattern {
+    // A pattern is irrefutable if it matches every value of its type.
+    pub fn is_irrefutable(&self) -> bool {
+        match self {
+            Pattern::Var { .. } | Pattern::Discard { .. } => true,
+
+            Pattern::Let { pattern, .. } => pattern.is_irrefutable(),
+
+            Pattern::Tuple { elems, .. } => elems.iter().all(Self::is_irrefutable),
+
+            Pattern::Constructor {
+                constructor:
+                    PatternConstructor::Record {
+                        constructors_count, ..
+                    },
+                args,
+                ..
+            } => *constructors_count == 1 && args.iter().all(|arg| arg.value.is_irrefutable()),
+
+            Pattern::Int { .. }
+            | Pattern::Float { .. }
+            | Pattern::String { .. }
+            | Pattern::VarCall { .. }
+            | Pattern::Nil { .. }
+            | Pattern::Cons { .. }
+            | Pattern::BitString { .. } => false,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum BindingKind {
     Let,
@@ -652,7 +934,7 @@ pub type UntypedConstantBitStringSegment = BitStringSegment<UntypedConstant, ()>
 pub type UntypedPatternBitStringSegment = BitStringSegment<UntypedPattern, ()>;
 pub type TypedPatternBitStringSegment = BitStringSegment<TypedPattern, Arc<Type>>;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BitStringSegment<Value, Type> {
     pub location: SrcSpan,
     pub value: Box<Value>,
@@ -660,7 +942,7 @@ pub struct BitStringSegment<Value, Type> {
     pub typ: Type,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub enum BitStringSegmentOption<Value> {
     Binary {
         location: SrcSpan,
@@ -4,7 +4,7 @@ use crate::typ::HasType;
 pub type TypedConstant = Constant<Arc<Type>, String>;
 pub type UntypedConstant = Constant<(), ()>;
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub enum Constant<T, RecordTag> {
     Int {
         location: SrcSpan,
@@ -23,12 +23,12 @@ pub enum Constant<T, RecordTag> {
 
     Tuple {
         location: SrcSpan,
-        elements: Vec<Self>,
+        elements: Vec<Constant<T, RecordTag>>,
     },
 
     List {
         location: SrcSpan,
-        elements: Vec<Self>,
+        elements: Vec<Constant<T, RecordTag>>,
         typ: T,
     },
 
@@ -36,14 +36,14 @@ pub enum Constant<T, RecordTag> {
         location: SrcSpan,
         module: Option<String>,
         name: String,
-        args: Vec<CallArg<Self>>,
+        args: Vec<CallArg<Constant<T, RecordTag>>>,
         tag: RecordTag,
         typ: T,
     },
 
     BitString {
         location: SrcSpan,
-        segments: Vec<BitStringSegment<Self, T>>,
+        segments: Vec<BitStringSegment<Constant<T, RecordTag>, T>>,
     },
 }
 
@@ -83,6 +83,11 @@ pub enum TypedExpr {
         pattern: Pattern<PatternConstructor, Arc<Type>>,
         then: Box<Self>,
         kind: BindingKind,
+        /// The location of the type annotation if the value has the same
+        /// type without it.
+        redundant_annotation: Option<SrcSpan>,
+        /// The message of an `assert`, for when the pattern does not match.
+        message: Option<Box<Self>>,
     },
 
     Case {
@@ -124,7 +129,19 @@ pub enum TypedExpr {
 
     Todo {
         location: SrcSpan,
-        label: Option<String>,
+        message: Option<Box<Self>>,
+        typ: Arc<Type>,
+    },
+
+    Panic {
+        location: SrcSpan,
+        message: Option<Box<Self>>,
+        typ: Arc<Type>,
+    },
+
+    RawErlang {
+        location: SrcSpan,
+        code: String,
         typ: Arc<Type>,
     },
 
@@ -151,6 +168,8 @@ impl TypedExpr {
             Self::Int { location, .. } => location,
             Self::Var { location, .. } => location,
             Self::Todo { location, .. } => location,
+            Self::Panic { location, .. } => location,
+            Self::RawErlang { location, .. } => location,
             Self::Case { location, .. } => location,
             Self::Call { location, .. } => location,
             Self::Pipe { location, .. } => location,
@@ -182,6 +201,8 @@ impl TypedExpr {
             Self::Int { location, .. } => location,
             Self::Var { location, .. } => location,
             Self::Todo { location, .. } => location,
+            Self::Panic { location, .. } => location,
+            Self::RawErlang { location, .. } => location,
             Self::Case { location, .. } => location,
             Self::Call { location, .. } => location,
             Self::Pipe { location, .. } => location,
@@ -215,6 +236,8 @@ impl TypedExpr {
             Self::Int { typ, .. } => typ.clone(),
             Self::Seq { then, .. } => then.typ(),
             Self::Todo { typ, .. } => typ.clone(),
+            Self::Panic { typ, .. } => typ.clone(),
+            Self::RawErlang { typ, .. } => typ.clone(),
             Self::Case { typ, .. } => typ.clone(),
             Self::ListCons { typ, .. } => typ.clone(),
             Self::Call { typ, .. } => typ.clone(),
@@ -54,6 +54,7 @@ pub enum UntypedExpr {
     BinOp {
         location: SrcSpan,
         name: BinOp,
+        name_location: SrcSpan,
         left: Box<Self>,
         right: Box<Self>,
     },
@@ -71,6 +72,8 @@ pub enum UntypedExpr {
         then: Box<Self>,
         kind: BindingKind,
         annotation: Option<TypeAst>,
+        /// The message of an `assert`, for when the pattern does not match.
+        message: Option<Box<Self>>,
     },
 
     Case {
@@ -98,7 +101,18 @@ pub enum UntypedExpr {
 
     Todo {
         location: SrcSpan,
-        label: Option<String>,
+        message: Option<Box<Self>>,
+    },
+
+    Panic {
+        location: SrcSpan,
+        message: Option<Box<Self>>,
+    },
+
+    RawErlang {
+        location: SrcSpan,
+        code: String,
+        annotation: TypeAst,
     },
 
     BitString {
@@ -124,6 +138,8 @@ impl UntypedExpr {
             Self::Var { location, .. } => location,
             Self::Int { location, .. } => location,
             Self::Todo { location, .. } => location,
+            Self::Panic { location, .. } => location,
+            Self::RawErlang { location, .. } => location,
             Self::Case { location, .. } => location,
             Self::Call { location, .. } => location,
             Self::Float { location, .. } => location,
@@ -143,6 +159,8 @@ impl UntypedExpr {
         match self {
             Self::Seq { first, .. } => first.start_byte_index(),
             Self::Pipe { left, .. } => left.start_byte_index(),
+            Self::FieldAccess { container, .. } => container.start_byte_index(),
+            Self::TupleIndex { tuple, .. } => tuple.start_byte_index(),
             Self::Let { location, .. } => location.start,
             _ => self.location().start,
         }
@@ -152,7 +170,16 @@ impl UntypedExpr {
         match self {
             Self::BinOp { name, .. } => name.precedence(),
             Self::Pipe { .. } => 5,
-            _ => std::u8::MAX,
+            // A message is parsed up to the end of the expression, so these
+            // are given braces when used as an operand
+            Self::Todo {
+                message: Some(_), ..
+            }
+            | Self::Panic {
+                message: Some(_), ..
+            }
+            | Self::RawErlang { .. } => 0,
+            _ => u8::MAX,
         }
     }
 
@@ -0,0 +1,186 @@
+use super::*;
+
+/// A traversal of a typed module. Each method visits a node and by default
+/// continues into its children, so an implementation overrides the methods
+/// for the nodes it is interested in, calling the matching `walk_` function
+/// to carry on into the children.
+///
+pub trait Visit<'ast> {
+    fn visit_module(&mut self, module: &'ast TypedModule) {
+        walk_module(self, module)
+    }
+
+    fn visit_statement(&mut self, statement: &'ast TypedStatement) {
+        walk_statement(self, statement)
+    }
+
+    fn visit_expr(&mut self, expr: &'ast TypedExpr) {
+        walk_expr(self, expr)
+    }
+
+    fn visit_clause(&mut self, clause: &'ast TypedClause) {
+        walk_clause(self, clause)
+    }
+}
+
+pub fn walk_module<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, module: &'ast TypedModule) {
+    for statement in module.statements.iter() {
+        visitor.visit_statement(statement);
+    }
+}
+
+pub fn walk_statement<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    statement: &'ast TypedStatement,
+) {
+    if let Statement::Fn { body, .. } = statement {
+        visitor.visit_expr(body);
+    }
+}
+
+pub fn walk_clause<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, clause: &'ast TypedClause) {
+    visitor.visit_expr(&clause.then);
+}
+
+pub fn walk_expr<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, expr: &'ast TypedExpr) {
+    match expr {
+        TypedExpr::Int { .. }
+        | TypedExpr::Float { .. }
+        | TypedExpr::String { .. }
+        | TypedExpr::Var { .. }
+        | TypedExpr::ListNil { .. }
+        | TypedExpr::ModuleSelect { .. }
+        | TypedExpr::RawErlang { .. }
+        | TypedExpr::Todo { message: None, .. }
+        | TypedExpr::Panic { message: None, .. } => (),
+
+        TypedExpr::Todo {
+            message: Some(message),
+            ..
+        }
+        | TypedExpr::Panic {
+            message: Some(message),
+            ..
+        } => visitor.visit_expr(message),
+
+        TypedExpr::Seq { first, then, .. } => {
+            visitor.visit_expr(first);
+            visitor.visit_expr(then);
+        }
+
+        TypedExpr::Let {
+            value,
+            message,
+            then,
+            ..
+        } => {
+            visitor.visit_expr(value);
+            if let Some(message) = message {
+                visitor.visit_expr(message);
+            }
+            visitor.visit_expr(then);
+        }
+
+        TypedExpr::Fn { body, .. } => visitor.visit_expr(body),
+
+        TypedExpr::ListCons { head, tail, .. } => {
+            visitor.visit_expr(head);
+            visitor.visit_expr(tail);
+        }
+
+        TypedExpr::Call { fun, args, .. } => {
+            visitor.visit_expr(fun);
+            for arg in args {
+                visitor.visit_expr(&arg.value);
+            }
+        }
+
+        TypedExpr::BinOp { left, right, .. } | TypedExpr::Pipe { left, right, .. } => {
+            visitor.visit_expr(left);
+            visitor.visit_expr(right);
+        }
+
+        TypedExpr::Case {
+            subjects, clauses, ..
+        } => {
+            for subject in subjects {
+                visitor.visit_expr(subject);
+            }
+            for clause in clauses {
+                visitor.visit_clause(clause);
+            }
+        }
+
+        TypedExpr::RecordAccess { record, .. } => visitor.visit_expr(record),
+
+        TypedExpr::TupleIndex { tuple, .. } => visitor.visit_expr(tuple),
+
+        TypedExpr::Tuple { elems, .. } => {
+            for elem in elems {
+                visitor.visit_expr(elem);
+            }
+        }
+
+        TypedExpr::BitString { segments, .. } => {
+            for segment in segments {
+                visitor.visit_expr(&segment.value);
+                for option in segment.options.iter() {
+                    match option {
+                        BitStringSegmentOption::Size { value, .. }
+                        | BitStringSegmentOption::Unit { value, .. } => visitor.visit_expr(value),
+                        _ => (),
+                    }
+                }
+            }
+        }
+
+        TypedExpr::RecordUpdate { spread, args, .. } => {
+            visitor.visit_expr(spread);
+            for arg in args {
+                visitor.visit_expr(&arg.value);
+            }
+        }
+    }
+}
+
+#[test]
+fn count_calls_test() {
+    struct CountCalls(usize);
+
+    impl<'ast> Visit<'ast> for CountCalls {
+        fn visit_expr(&mut self, expr: &'ast TypedExpr) {
+            if let TypedExpr::Call { .. } = expr {
+                self.0 += 1;
+            }
+            walk_expr(self, expr)
+        }
+    }
+
+    let src = "
+fn id(x) { x }
+fn one() { id(1) }
+pub fn main() {
+  let f = fn(x) { id(id(x)) }
+  let bits = <<1:size(one())>>
+  case one() {
+    1 -> f(2)
+    _ -> tuple(id(1), bits).0
+  }
+}";
+    let mut ast = crate::grammar::ModuleParser::new()
+        .parse(src)
+        .expect("syntax error");
+    ast.name = vec!["my_module".to_string()];
+    let module = typ::infer_module(
+        &mut 0,
+        ast,
+        "",
+        &std::collections::HashMap::new(),
+        &mut vec![],
+    )
+    .expect("should successfully infer");
+
+    let mut visitor = CountCalls(0);
+    visitor.visit_module(&module);
+    assert_eq!(7, visitor.0);
+}
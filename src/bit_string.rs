@@ -1,4 +1,7 @@
-use crate::ast::{BitStringSegmentOption, SegmentOptionCategory as Category, SrcSpan};
+use crate::ast::{
+    parse_int, BitStringSegmentOption, Constant, SegmentOptionCategory as Category, SrcSpan,
+    TypedConstant, TypedExpr,
+};
 use crate::typ::Type;
 use std::sync::Arc;
 
@@ -119,6 +122,33 @@ impl<T> BinaryTypeSpecifier<T> {
                 typ: t.label(),
             }),
 
+            Self {
+                typ: Some(t),
+                size: Some(s),
+                ..
+            } if !t.size_is_allowed() => Err(Error::TypeDoesNotAllowSize {
+                location: s.location().clone(),
+                typ: t.label(),
+            }),
+
+            Self {
+                typ: Some(t),
+                signedness: Some(s),
+                ..
+            } if !t.signedness_is_allowed() => Err(Error::TypeDoesNotAllowSignedness {
+                location: s.location().clone(),
+                typ: t.label(),
+            }),
+
+            Self {
+                typ: Some(t),
+                endianness: Some(e),
+                ..
+            } if !t.endianness_is_allowed() => Err(Error::TypeDoesNotAllowEndianness {
+                location: e.location().clone(),
+                typ: t.label(),
+            }),
+
             Self {
                 size: None,
                 typ: Some(BitStringSegmentOption::Binary { .. }),
@@ -155,6 +185,93 @@ impl<T> BinaryTypeSpecifier<T> {
     }
 }
 
+impl<T: IntLiteral> BinaryTypeSpecifier<T> {
+    /// An integer literal that does not fit in the bits of its segment would
+    /// be truncated, so when the size of the segment is also a literal this
+    /// is an error. Negative values are accepted down to the smallest signed
+    /// value of the size whether or not the segment is signed, as Erlang does.
+    ///
+    pub fn check_literal_fits(&self, value: &T, location: &SrcSpan) -> Result<(), Error> {
+        if !matches!(
+            self.typ,
+            None | Some(BitStringSegmentOption::Integer { .. })
+        ) {
+            return Ok(());
+        }
+        let integer = match value.int_literal() {
+            Some(integer) => integer,
+            None => return Ok(()),
+        };
+
+        // Integer segments are 8 bits with a unit of 1 by default
+        let size = match &self.size {
+            None => 8,
+            Some(BitStringSegmentOption::Size { value, .. }) => match value.int_literal() {
+                Some(size) => size,
+                None => return Ok(()),
+            },
+            Some(_) => return Ok(()),
+        };
+        let unit = match &self.unit {
+            None => 1,
+            Some(BitStringSegmentOption::Unit { value, .. }) => match value.int_literal() {
+                Some(unit) => unit,
+                None => return Ok(()),
+            },
+            Some(_) => return Ok(()),
+        };
+        let bits = match size.checked_mul(unit) {
+            Some(bits) if (0..64).contains(&bits) => bits,
+            _ => return Ok(()),
+        };
+
+        let (min, max) = int_range(bits);
+        if integer < min || integer > max {
+            return Err(Error::IntDoesNotFit {
+                location: location.clone(),
+                value: integer,
+                bits,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// The smallest and largest integers that can be written to a segment of the
+/// given number of bits without being truncated.
+///
+pub fn int_range(bits: i64) -> (i64, i64) {
+    if bits == 0 {
+        (0, 0)
+    } else {
+        (-(1 << (bits - 1)), (1 << bits) - 1)
+    }
+}
+
+/// A value that may be an integer literal, such as the value of a segment.
+///
+pub trait IntLiteral {
+    fn int_literal(&self) -> Option<i64>;
+}
+
+impl IntLiteral for TypedExpr {
+    fn int_literal(&self) -> Option<i64> {
+        match self {
+            TypedExpr::Int { value, .. } => parse_int(value),
+            _ => None,
+        }
+    }
+}
+
+impl IntLiteral for TypedConstant {
+    fn int_literal(&self) -> Option<i64> {
+        match self {
+            Constant::Int { value, .. } => parse_int(value),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum Error {
     ConflictingTypeOptions {
@@ -190,7 +307,28 @@ pub enum Error {
         typ: String,
     },
 
+    TypeDoesNotAllowSize {
+        location: SrcSpan,
+        typ: String,
+    },
+
+    TypeDoesNotAllowSignedness {
+        location: SrcSpan,
+        typ: String,
+    },
+
+    TypeDoesNotAllowEndianness {
+        location: SrcSpan,
+        typ: String,
+    },
+
     SegmentMustHaveSize,
+
+    IntDoesNotFit {
+        location: SrcSpan,
+        value: i64,
+        bits: i64,
+    },
 }
 
 impl<A> BitStringSegmentOption<A> {
@@ -201,4 +339,32 @@ impl<A> BitStringSegmentOption<A> {
             | BitStringSegmentOption::UTF32 { .. }
         )
     }
+
+    /// A string is encoded with as many bits as its characters need, so its
+    /// segment cannot be given a size any more than a unit.
+    ///
+    pub fn size_is_allowed(&self) -> bool {
+        self.unit_is_allowed()
+    }
+
+    /// Only integers can be read as signed or unsigned.
+    ///
+    pub fn signedness_is_allowed(&self) -> bool {
+        matches!(self, BitStringSegmentOption::Integer { .. })
+    }
+
+    /// Integers, floats, and the UTF-16 and UTF-32 encodings are made of more
+    /// than one byte, so only they can be given a byte order.
+    ///
+    pub fn endianness_is_allowed(&self) -> bool {
+        matches!(
+            self,
+            BitStringSegmentOption::Integer { .. }
+                | BitStringSegmentOption::Float { .. }
+                | BitStringSegmentOption::UTF16 { .. }
+                | BitStringSegmentOption::UTF32 { .. }
+                | BitStringSegmentOption::UTF16Codepoint { .. }
+                | BitStringSegmentOption::UTF32Codepoint { .. }
+        )
+    }
 }
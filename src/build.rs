@@ -6,7 +6,6 @@
 // TODO: Track removed files in src and test so they can be removed from _build
 // TODO: Test profile and default profile
 // TODO: Only compile test code in test profile
-// TODO: Full .app generation
 // TODO: Validate config.otp_start_module does not contain '
 // TODO: Validate config.otp_start_module has a start function
 // TODO: Support flexible compiler interface for use by rebar3 + mix
@@ -16,9 +15,12 @@
 
 mod dep_tree;
 mod erlang_code_generator;
+mod interface_cache;
+pub mod manifest;
 mod package_compiler;
 mod project_compiler;
 pub mod project_root;
+pub mod timings;
 
 #[cfg(test)]
 mod package_compilation_tests;
@@ -27,7 +29,7 @@ use crate::{
     ast::TypedModule,
     build::{
         erlang_code_generator::ErlangCodeGenerator, project_compiler::ProjectCompiler,
-        project_root::ProjectRoot,
+        project_root::ProjectRoot, timings::Timings,
     },
     config::{self, PackageConfig},
     erl,
@@ -36,6 +38,7 @@ use crate::{
     grammar, parser, typ,
 };
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::ffi::OsString;
 use std::fs::DirEntry;
@@ -43,6 +46,16 @@ use std::path::PathBuf;
 use std::process;
 
 pub fn main(root_config: PackageConfig, path: PathBuf) -> Result<HashMap<String, Package>, Error> {
+    main_with_timings(root_config, path, &mut Timings::default())
+}
+
+/// Build the project, recording how long each module took to compile.
+///
+pub fn main_with_timings(
+    root_config: PackageConfig,
+    path: PathBuf,
+    timings: &mut Timings,
+) -> Result<HashMap<String, Package>, Error> {
     let root = ProjectRoot::new(path);
 
     tracing::info!("Copying root package to _build");
@@ -52,7 +65,7 @@ pub fn main(root_config: PackageConfig, path: PathBuf) -> Result<HashMap<String,
     let configs = root.package_configs(&root_config.name)?;
 
     tracing::info!("Compiling packages");
-    let packages = ProjectCompiler::new(&root, root_config, configs).compile()?;
+    let packages = ProjectCompiler::new(&root, root_config, configs).compile(timings)?;
 
     tracing::info!("Writing generated Erlang source code to disc");
     for package in packages.values() {
@@ -70,6 +83,9 @@ pub struct Package {
     pub config: PackageConfig,
     pub modules: Vec<Module>,
     pub outputs: Vec<OutputFile>,
+    /// The source path and instrumented lines of each module compiled with
+    /// coverage instrumentation.
+    pub coverage_lines: Vec<(String, Vec<usize>)>,
 }
 
 #[derive(Debug)]
@@ -81,12 +97,42 @@ pub struct Module {
     pub ast: TypedModule,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum Origin {
     Src,
     Test,
 }
 
+/// A platform that Gleam code can be compiled for. Definitions can be limited
+/// to a single target with a `@target(...)` attribute.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Target {
+    Erlang,
+    /// Not yet supported by the code generator, so definitions for this target
+    /// are left out of every build.
+    JavaScript,
+}
+
+impl Target {
+    /// The target that modules are compiled for.
+    pub const ACTIVE: Self = Self::Erlang;
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "erlang" => Some(Self::Erlang),
+            "javascript" => Some(Self::JavaScript),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Erlang => "erlang",
+            Self::JavaScript => "javascript",
+        }
+    }
+}
+
 fn compile_erlang_to_beam(
     root: &ProjectRoot,
     packages: &HashMap<String, Package>,
@@ -1,7 +1,11 @@
 use crate::{
-    build::{project_root::ProjectRoot, Module},
+    build::{
+        project_root::ProjectRoot,
+        timings::{Stage, Timings},
+        Module, Origin,
+    },
     config::PackageConfig,
-    erl,
+    erl, eunit,
     fs::OutputFile,
 };
 use itertools::Itertools;
@@ -25,22 +29,33 @@ impl<'a> ErlangCodeGenerator<'a> {
         }
     }
 
-    pub fn render(&self) -> Vec<OutputFile> {
+    /// Render the package's files, along with the source path and
+    /// instrumented lines of each module compiled for coverage.
+    ///
+    pub fn render(&self, timings: &mut Timings) -> (Vec<OutputFile>, Vec<(String, Vec<usize>)>) {
         let num_modules = self.modules.len();
         let mut outputs = Vec::with_capacity(num_modules);
+        let mut coverage_lines = vec![];
 
         // Render Erlang modules and header files
         for module in self.modules {
-            self.render_module(module, &mut outputs);
+            let lines = timings.time(&module.name, Stage::Generate, || {
+                self.render_module(module, &mut outputs)
+            });
+            coverage_lines.extend(lines);
         }
 
         // Render ebin/package.app
         outputs.push(self.package_app_file());
 
-        outputs
+        (outputs, coverage_lines)
     }
 
-    pub fn render_module(&self, module: &Module, outputs: &mut Vec<OutputFile>) {
+    pub fn render_module(
+        &self,
+        module: &Module,
+        outputs: &mut Vec<OutputFile>,
+    ) -> Option<(String, Vec<usize>)> {
         let erl_name = module.name.replace("/", "@");
         let dir = self
             .root
@@ -56,12 +71,32 @@ impl<'a> ErlangCodeGenerator<'a> {
             });
         }
 
+        if self.config.erlang.module_headers {
+            if let Some(text) = erl::module_header(&module.ast) {
+                let name = format!("{}.hrl", erl_name);
+                tracing::trace!(name = ?name, "Generated Erlang header");
+                outputs.push(OutputFile {
+                    path: dir.join(name),
+                    text,
+                });
+            }
+        }
+
         // Render Erlang module file
-        let text = erl::module(&module.ast);
+        let (text, coverage_lines) = if self.config.erlang.coverage && module.origin == Origin::Src
+        {
+            let path = eunit::coverage::source_path(&module.name);
+            let (text, lines) =
+                erl::instrumented_module(&module.ast, &self.config.erlang, &path, &module.code);
+            (text, Some((path, lines)))
+        } else {
+            (erl::module(&module.ast, &self.config.erlang), None)
+        };
         let name = format!("{}.erl", erl_name);
         tracing::trace!(name = ?name, "Generated Erlang module");
         let path = dir.join(name);
         outputs.push(OutputFile { path, text });
+        coverage_lines
     }
 
     pub fn package_app_file(&self) -> OutputFile {
@@ -88,16 +123,22 @@ impl<'a> ErlangCodeGenerator<'a> {
         modules.sort();
         let modules = modules.join(",\n               ");
 
-        let mut applications: Vec<_> = self.config.dependencies.iter().map(|m| m.0).collect();
-        applications.sort();
-        let applications = applications.into_iter().join(",\n                    ");
+        // kernel and stdlib are always required, the package's dependencies
+        // follow so that release tools such as relx can find them.
+        let mut dependencies: Vec<_> = self.config.dependencies.keys().collect();
+        dependencies.sort();
+        let applications = ["kernel", "stdlib"]
+            .iter()
+            .copied()
+            .chain(dependencies.into_iter().map(|s| s.as_str()))
+            .join(",\n                    ");
 
         let text = format!(
             r#"{{application, {package}, [
 {start_module}{version}    {{applications, [{applications}]}},
     {{description, "{description}"}},
     {{modules, [{modules}]}},
-    {{registered, []}},
+    {{registered, []}}
 ]}}.
 "#,
             applications = applications,
@@ -113,5 +154,5 @@ impl<'a> ErlangCodeGenerator<'a> {
 }
 
 fn tuple(key: &str, value: &str) -> String {
-    format!("    {{{}, {}}}\n", key, value)
+    format!("    {{{}, {}}},\n", key, value)
 }
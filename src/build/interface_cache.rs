@@ -0,0 +1,112 @@
+use crate::{
+    build::{manifest::SourceManifest, Origin, Package},
+    error::{Error, GleamExpect},
+    fs::OutputFile,
+    typ,
+};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// The file in the package's build directory that the cache is written to.
+const CACHE_FILE: &str = "gleam-interfaces.json";
+
+/// The type information of the modules of a dependency package, written to
+/// the package's build directory once it has been compiled so that later
+/// builds can use it rather than compiling the package again.
+///
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PackageCache {
+    /// The version of the compiler that wrote the cache, as the type
+    /// information may differ between versions.
+    compiler_version: String,
+    /// The hash of the package's `gleam.toml`, which the generated code
+    /// depends on.
+    config: String,
+    sources: SourceManifest,
+    /// The files generated by compiling the package, which are left in place
+    /// when the cache is used.
+    outputs: Vec<PathBuf>,
+    pub modules: Vec<CachedModule>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CachedModule {
+    pub name: String,
+    pub path: PathBuf,
+    pub origin: Origin,
+    pub interface: typ::Module,
+}
+
+/// The state of the package's files that the cache is valid for.
+///
+#[derive(Debug, PartialEq)]
+pub struct PackageSources {
+    config: String,
+    sources: SourceManifest,
+}
+
+impl PackageSources {
+    pub fn read(package_path: &Path) -> Result<Self, Error> {
+        let config = crate::fs::read(package_path.join("gleam.toml"))?;
+        Ok(Self {
+            config: crate::build::manifest::hash(&config),
+            sources: SourceManifest::of_sources(package_path)?,
+        })
+    }
+}
+
+impl PackageCache {
+    pub fn new(sources: PackageSources, package: &Package) -> Self {
+        Self {
+            compiler_version: env!("CARGO_PKG_VERSION").to_string(),
+            config: sources.config,
+            sources: sources.sources,
+            outputs: package
+                .outputs
+                .iter()
+                .map(|file| file.path.clone())
+                .collect(),
+            modules: package
+                .modules
+                .iter()
+                .map(|module| CachedModule {
+                    name: module.name.clone(),
+                    path: module.path.clone(),
+                    origin: module.origin,
+                    interface: module.ast.type_info.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// The cache of the package, if there is one that can be used for its
+    /// current sources.
+    ///
+    pub fn read_fresh(
+        package_path: &Path,
+        sources: &PackageSources,
+    ) -> Result<Option<Self>, Error> {
+        let path = package_path.join(CACHE_FILE);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        // A cache that cannot be read, such as one written by a different
+        // version of the compiler, is replaced when the package is compiled
+        let cache: Self = match serde_json::from_str(&crate::fs::read(&path)?) {
+            Ok(cache) => cache,
+            Err(_) => return Ok(None),
+        };
+        let fresh = cache.compiler_version == env!("CARGO_PKG_VERSION")
+            && cache.config == sources.config
+            && cache.sources == sources.sources
+            && cache.outputs.iter().all(|path| path.is_file());
+        Ok(if fresh { Some(cache) } else { None })
+    }
+
+    pub fn write(&self, package_path: &Path) -> Result<(), Error> {
+        crate::fs::write_output(&OutputFile {
+            path: package_path.join(CACHE_FILE),
+            text: serde_json::to_string(self).gleam_expect("Package cache serialisation"),
+        })
+    }
+}
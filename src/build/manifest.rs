@@ -0,0 +1,167 @@
+use crate::{
+    error::{Error, GleamExpect, SourceChange},
+    fs::OutputFile,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// The file in the project root that the manifest is written to.
+pub const MANIFEST_FILE: &str = "gleam-sources.toml";
+
+/// The directories of the project whose Gleam files are recorded.
+const SOURCE_DIRS: [&str; 2] = ["src", "test"];
+
+/// The source files of a project and the hashes of their contents, written
+/// by `gleam build --write-manifest` so that a later build can be checked to
+/// be of the same sources with `gleam build --verify-manifest`.
+///
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SourceManifest {
+    /// The SHA-256 hash of each file, keyed by its path relative to the
+    /// project root. Line endings are normalised before hashing, as they are
+    /// when the file is compiled.
+    pub sources: BTreeMap<String, String>,
+}
+
+impl SourceManifest {
+    /// Hash the current source files of the project.
+    ///
+    pub fn of_sources(root: &Path) -> Result<Self, Error> {
+        let mut sources = BTreeMap::new();
+        for dir in SOURCE_DIRS.iter() {
+            for path in crate::fs::all_gleam_files(&root.join(dir)) {
+                let src = crate::fs::read(&path)?;
+                let relative = path
+                    .strip_prefix(root)
+                    .gleam_expect("manifest::of_sources(): path strip prefix");
+                let _ = sources.insert(manifest_path(relative), hash(&src));
+            }
+        }
+        Ok(Self { sources })
+    }
+
+    /// The files of the current sources that are not as recorded in this
+    /// manifest, in order of their paths.
+    ///
+    pub fn changes(&self, current: &Self) -> Vec<(PathBuf, SourceChange)> {
+        let mut changes: Vec<_> = self
+            .sources
+            .iter()
+            .filter_map(|(path, hash)| match current.sources.get(path) {
+                None => Some((PathBuf::from(path), SourceChange::Removed)),
+                Some(current) if current != hash => {
+                    Some((PathBuf::from(path), SourceChange::Changed))
+                }
+                Some(_) => None,
+            })
+            .chain(
+                current
+                    .sources
+                    .keys()
+                    .filter(|path| !self.sources.contains_key(*path))
+                    .map(|path| (PathBuf::from(path), SourceChange::Added)),
+            )
+            .collect();
+        changes.sort_by(|(a, _), (b, _)| a.cmp(b));
+        changes
+    }
+}
+
+/// Record the current source files of the project in its manifest.
+///
+pub fn write(root: &Path) -> Result<(), Error> {
+    let manifest = SourceManifest::of_sources(root)?;
+    let toml = toml::to_string(&manifest).gleam_expect("manifest::write(): serialise manifest");
+    crate::fs::write_output(&OutputFile {
+        path: root.join(MANIFEST_FILE),
+        text: format!(
+            "# The source files of this project and the hashes of their contents,
+# written by `gleam build --write-manifest`.
+
+{}",
+            toml
+        ),
+    })
+}
+
+/// Check that the current source files of the project are those recorded in
+/// its manifest.
+///
+pub fn verify(root: &Path) -> Result<(), Error> {
+    let path = root.join(MANIFEST_FILE);
+    let toml = crate::fs::read(&path)?;
+    let manifest: SourceManifest = toml::from_str(&toml).map_err(|e| Error::InvalidConfig {
+        path,
+        err: e.to_string(),
+    })?;
+    let changes = manifest.changes(&SourceManifest::of_sources(root)?);
+    if changes.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::SourceManifestMismatch { changes })
+    }
+}
+
+/// The path as it is written in the manifest, which uses `/` on every
+/// platform so that a manifest can be checked wherever it was written.
+///
+fn manifest_path(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// The SHA-256 hash of the text, in hexadecimal.
+///
+pub fn hash(src: &str) -> String {
+    Sha256::digest(src.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+#[test]
+fn hash_test() {
+    assert_eq!(
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        hash("")
+    );
+}
+
+#[test]
+fn verify_test() {
+    let dir = crate::test_support::TempDir::new("manifest-test");
+    let root = dir.path();
+    let src = root.join("src");
+    std::fs::create_dir_all(src.join("one")).unwrap();
+    std::fs::write(src.join("one.gleam"), "pub fn one() { 1 }\n").unwrap();
+    std::fs::write(src.join("one/two.gleam"), "pub fn two() { 2 }\n").unwrap();
+
+    // There is nothing to check against until a manifest has been written
+    assert!(matches!(verify(&root), Err(Error::FileIO { .. })));
+
+    write(&root).unwrap();
+    assert_eq!(Ok(()), verify(&root));
+
+    // Line endings are normalised as they are when compiling
+    std::fs::write(src.join("one.gleam"), "pub fn one() { 1 }\r\n").unwrap();
+    assert_eq!(Ok(()), verify(&root));
+
+    std::fs::write(src.join("one.gleam"), "pub fn one() { 100 }\n").unwrap();
+    std::fs::remove_file(src.join("one/two.gleam")).unwrap();
+    std::fs::create_dir_all(root.join("test")).unwrap();
+    std::fs::write(root.join("test/one_test.gleam"), "").unwrap();
+    assert_eq!(
+        Err(Error::SourceManifestMismatch {
+            changes: vec![
+                (PathBuf::from("src/one/two.gleam"), SourceChange::Removed),
+                (PathBuf::from("src/one.gleam"), SourceChange::Changed),
+                (PathBuf::from("test/one_test.gleam"), SourceChange::Added),
+            ]
+        }),
+        verify(&root)
+    );
+}
@@ -3,6 +3,7 @@ use crate::{
     build::{
         package_compiler::{PackageCompiler, Source},
         project_root::ProjectRoot,
+        timings::Timings,
         Origin,
     },
     config::{BuildTool, Docs, PackageConfig},
@@ -22,6 +23,12 @@ fn package_compiler_test() {
                 name: "the_package".to_string(),
                 docs: Default::default(),
                 otp_start_module: None,
+                erlang: Default::default(),
+                format: Default::default(),
+                warnings: Default::default(),
+                profile: Default::default(),
+                run: Default::default(),
+                modules: HashMap::new(),
                 tool: BuildTool::Gleam,
             };
             let root = ProjectRoot::new(PathBuf::new());
@@ -29,7 +36,11 @@ fn package_compiler_test() {
             compiler.sources = $sources;
             compiler.print_progress = false;
             let outputs = compiler
-                .compile(&mut modules, &mut HashMap::with_capacity(4))
+                .compile(
+                    &mut modules,
+                    &mut HashMap::with_capacity(4),
+                    &mut Timings::default(),
+                )
                 .map(get_sorted_outputs)
                 .map_err(|e| normalise_error(e));
             assert_eq!($expected_output, outputs);
@@ -40,11 +51,12 @@ fn package_compiler_test() {
         OutputFile {
             text: format!(
                 r#"{{application, the_package, [
-    {{vsn, "1.1.0"}}
-    {{applications, []}},
+    {{vsn, "1.1.0"}},
+    {{applications, [kernel,
+                    stdlib]}},
     {{description, "the description"}},
     {{modules, [{}]}},
-    {{registered, []}},
+    {{registered, []}}
 ]}}.
 "#,
                 modules.join(",\n               ")
@@ -1080,7 +1092,11 @@ fn config_compilation_test() {
             compiler.print_progress = false;
             compiler.sources = $sources;
             let outputs = compiler
-                .compile(&mut modules, &mut HashMap::with_capacity(4))
+                .compile(
+                    &mut modules,
+                    &mut HashMap::with_capacity(4),
+                    &mut Timings::default(),
+                )
                 .map(get_sorted_outputs)
                 .expect("Should compile OK");
             assert_eq!($expected_output, outputs);
@@ -1095,6 +1111,12 @@ fn config_compilation_test() {
             name: "the_package".to_string(),
             docs: Default::default(),
             otp_start_module: None,
+            erlang: Default::default(),
+            format: Default::default(),
+            warnings: Default::default(),
+            profile: Default::default(),
+            run: Default::default(),
+            modules: HashMap::new(),
             tool: BuildTool::Gleam,
         }
     }
@@ -1104,10 +1126,11 @@ fn config_compilation_test() {
         vec![],
         vec![OutputFile {
             text: r#"{application, the_package, [
-    {applications, []},
+    {applications, [kernel,
+                    stdlib]},
     {description, ""},
     {modules, []},
-    {registered, []},
+    {registered, []}
 ]}.
 "#
             .to_string(),
@@ -1123,11 +1146,12 @@ fn config_compilation_test() {
         vec![],
         vec![OutputFile {
             text: r#"{application, the_package, [
-    {vsn, "1.3.5"}
-    {applications, []},
+    {vsn, "1.3.5"},
+    {applications, [kernel,
+                    stdlib]},
     {description, ""},
     {modules, []},
-    {registered, []},
+    {registered, []}
 ]}.
 "#
             .to_string(),
@@ -1143,10 +1167,11 @@ fn config_compilation_test() {
         vec![],
         vec![OutputFile {
             text: r#"{application, the_package, [
-    {applications, []},
+    {applications, [kernel,
+                    stdlib]},
     {description, "Very exciting"},
     {modules, []},
-    {registered, []},
+    {registered, []}
 ]}.
 "#
             .to_string(),
@@ -1170,19 +1195,115 @@ fn config_compilation_test() {
         vec![],
         vec![OutputFile {
             text: r#"{application, the_package, [
-    {applications, [gleam_otp,
+    {applications, [kernel,
+                    stdlib,
+                    gleam_otp,
                     gleam_stdlib,
                     midas,
                     simple_json]},
     {description, ""},
     {modules, []},
-    {registered, []},
+    {registered, []}
 ]}.
 "#
             .to_string(),
             path: PathBuf::from("_build/default/lib/the_package/ebin/the_package.app"),
         }]
     );
+
+    // All compiled modules are listed along with the configured version
+    let mut config = make_config();
+    config.version = Some("2.0.1".to_string());
+    assert_config_compile!(
+        config,
+        vec![
+            Source {
+                path: PathBuf::from("src/one.gleam"),
+                name: "one".to_string(),
+                code: "".to_string(),
+                origin: Origin::Src,
+            },
+            Source {
+                path: PathBuf::from("src/two/three.gleam"),
+                name: "two/three".to_string(),
+                code: "".to_string(),
+                origin: Origin::Src,
+            },
+        ],
+        vec![
+            OutputFile {
+                text: r#"{application, the_package, [
+    {vsn, "2.0.1"},
+    {applications, [kernel,
+                    stdlib]},
+    {description, ""},
+    {modules, [one,
+               two@three]},
+    {registered, []}
+]}.
+"#
+                .to_string(),
+                path: PathBuf::from("_build/default/lib/the_package/ebin/the_package.app"),
+            },
+            OutputFile {
+                text: "-module(one).\n-compile(no_auto_import).\n\n\n".to_string(),
+                path: PathBuf::from("_build/default/lib/the_package/src/one.erl"),
+            },
+            OutputFile {
+                text: "-module(two@three).\n-compile(no_auto_import).\n\n\n".to_string(),
+                path: PathBuf::from("_build/default/lib/the_package/src/two@three.erl"),
+            },
+        ]
+    );
+}
+
+#[test]
+fn timings_test() {
+    let config = PackageConfig {
+        dependencies: HashMap::new(),
+        description: "".to_string(),
+        version: None,
+        name: "the_package".to_string(),
+        docs: Default::default(),
+        otp_start_module: None,
+        erlang: Default::default(),
+        format: Default::default(),
+        warnings: Default::default(),
+        profile: Default::default(),
+        run: Default::default(),
+        modules: HashMap::new(),
+        tool: BuildTool::Gleam,
+    };
+    let root = ProjectRoot::new(PathBuf::new());
+    let mut compiler = PackageCompiler::new(&root, config);
+    compiler.print_progress = false;
+    compiler.sources = vec![
+        Source {
+            path: PathBuf::from("src/one.gleam"),
+            name: "one".to_string(),
+            code: "pub fn go() { 1 }".to_string(),
+            origin: Origin::Src,
+        },
+        Source {
+            path: PathBuf::from("src/two/three.gleam"),
+            name: "two/three".to_string(),
+            code: "import one pub fn go() { one.go() }".to_string(),
+            origin: Origin::Src,
+        },
+    ];
+    let mut timings = Timings::default();
+    let _ = compiler
+        .compile(&mut HashMap::new(), &mut HashMap::new(), &mut timings)
+        .expect("should compile");
+
+    let report = timings.report();
+    assert!(
+        report.starts_with("Slowest modules (2 of 2):\n"),
+        "{}",
+        report
+    );
+    assert!(report.contains("  one  (parse "), "{}", report);
+    assert!(report.contains("  two/three  (parse "), "{}", report);
 }
 
 fn normalise_error(e: Error) -> Error {
@@ -1,8 +1,11 @@
 use crate::{
     ast::{SrcSpan, TypedModule, UntypedModule},
     build::{
-        dep_tree, erlang_code_generator::ErlangCodeGenerator, project_root::ProjectRoot, Module,
-        Origin, Package,
+        dep_tree,
+        erlang_code_generator::ErlangCodeGenerator,
+        project_root::ProjectRoot,
+        timings::{Stage, Timings},
+        Module, Origin, Package, Target,
     },
     config::PackageConfig,
     error::{self, Error, GleamExpect},
@@ -38,6 +41,7 @@ impl<'a> PackageCompiler<'a> {
         self,
         existing_modules: &mut HashMap<String, (Origin, typ::Module)>,
         already_defined_modules: &mut HashMap<String, PathBuf>,
+        timings: &mut Timings,
     ) -> Result<Package, Error> {
         if self.print_progress {
             crate::cli::print_compiling(self.config.name.as_str());
@@ -47,7 +51,7 @@ impl<'a> PackageCompiler<'a> {
         let _enter = span.enter();
 
         tracing::info!("Parsing source code");
-        let parsed_modules = parse_sources(self.sources, already_defined_modules)?;
+        let parsed_modules = parse_sources(self.sources, already_defined_modules, timings)?;
 
         // Determine order in which modules are to be processed
         let sequence =
@@ -55,16 +59,23 @@ impl<'a> PackageCompiler<'a> {
                 .map_err(convert_deps_tree_error)?;
 
         tracing::info!("Type checking modules");
-        let modules = type_check(sequence, parsed_modules, existing_modules)?;
+        let modules = type_check(
+            &self.config.name,
+            sequence,
+            parsed_modules,
+            existing_modules,
+            timings,
+        )?;
 
         tracing::info!("Generating Erlang source code");
-        let outputs =
-            ErlangCodeGenerator::new(&self.root, &self.config, modules.as_slice()).render();
+        let (outputs, coverage_lines) =
+            ErlangCodeGenerator::new(&self.root, &self.config, modules.as_slice()).render(timings);
 
         Ok(Package {
             config: self.config,
             modules,
             outputs,
+            coverage_lines,
         })
     }
 
@@ -92,9 +103,11 @@ impl<'a> PackageCompiler<'a> {
 }
 
 fn type_check(
+    package: &str,
     sequence: Vec<String>,
     mut parsed_modules: HashMap<String, Parsed>,
     module_types: &mut HashMap<String, (Origin, typ::Module)>,
+    timings: &mut Timings,
 ) -> Result<Vec<Module>, Error> {
     let mut warnings = vec![];
     let mut modules = Vec::with_capacity(parsed_modules.len());
@@ -112,13 +125,14 @@ fn type_check(
             .gleam_expect("Getting parsed module for name");
 
         tracing::trace!(module = ?name, "Type checking");
-        let ast =
-            typ::infer_module(&mut uid, ast, module_types, &mut warnings).map_err(|error| {
-                Error::Type {
-                    path: path.clone(),
-                    src: code.clone(),
-                    error,
-                }
+        let ast = timings
+            .time(&name, Stage::TypeCheck, || {
+                typ::infer_module(&mut uid, ast, package, module_types, &mut warnings)
+            })
+            .map_err(|error| Error::Type {
+                path: path.clone(),
+                src: code.clone(),
+                error,
             })?;
 
         module_types.insert(name.clone(), (origin, ast.type_info.clone()));
@@ -157,6 +171,7 @@ fn module_deps_for_graph(module: &Parsed) -> (String, Vec<String>) {
 fn parse_sources(
     sources: Vec<Source>,
     already_defined_modules: &mut HashMap<String, PathBuf>,
+    timings: &mut Timings,
 ) -> Result<HashMap<String, Parsed>, Error> {
     let mut parsed_modules = HashMap::with_capacity(sources.len());
     for source in sources.into_iter() {
@@ -166,7 +181,9 @@ fn parse_sources(
             path,
             origin,
         } = source;
-        let ast = parse_source(code.as_str(), name.as_str(), &path)?;
+        let ast = timings.time(&name, Stage::Parse, || {
+            parse_source(code.as_str(), name.as_str(), &path)
+        })?;
         let module = Parsed {
             origin,
             path,
@@ -213,6 +230,9 @@ fn parse_source(src: &str, name: &str, path: &PathBuf) -> Result<UntypedModule,
         .map(|s| s.to_string())
         .collect();
 
+    // Leave out definitions for other targets
+    module.retain_target(Target::ACTIVE);
+
     // Store the name
     module.name = name.split("/").map(String::from).collect(); // TODO: store the module name as a string
 
@@ -1,12 +1,20 @@
 use crate::{
     build::{
-        dep_tree, package_compiler::PackageCompiler, project_root::ProjectRoot, Origin, Package,
+        dep_tree,
+        interface_cache::{PackageCache, PackageSources},
+        package_compiler::PackageCompiler,
+        project_root::ProjectRoot,
+        timings::Timings,
+        Origin, Package,
     },
     config::PackageConfig,
     error::{Error, GleamExpect},
     typ,
 };
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
 
 #[derive(Debug)]
 pub struct ProjectCompiler<'a> {
@@ -16,6 +24,9 @@ pub struct ProjectCompiler<'a> {
     packages: HashMap<String, Package>,
     type_manifests: HashMap<String, (Origin, typ::Module)>,
     defined_modules: HashMap<String, PathBuf>,
+    /// The dependency packages that have been compiled rather than loaded
+    /// from their cache in this build.
+    recompiled: HashSet<String>,
 }
 
 // TODO: test top level package has test modules compiled
@@ -33,13 +44,14 @@ impl<'a> ProjectCompiler<'a> {
             packages: HashMap::with_capacity(configs.len()),
             type_manifests: HashMap::with_capacity(estimated_number_of_modules),
             defined_modules: HashMap::with_capacity(estimated_number_of_modules),
+            recompiled: HashSet::new(),
             root_config,
             configs,
             root,
         }
     }
 
-    pub fn compile(mut self) -> Result<HashMap<String, Package>, Error> {
+    pub fn compile(mut self, timings: &mut Timings) -> Result<HashMap<String, Package>, Error> {
         // Determine package processing order
         let sequence = order_packges(&self.configs)?;
 
@@ -49,22 +61,88 @@ impl<'a> ProjectCompiler<'a> {
                 .configs
                 .remove(name.as_str())
                 .gleam_expect("Missing package config");
-            self.compile_package(name, config, SourceLocations::Src)?;
+            self.compile_dependency(name, config, timings)?;
         }
 
         // Read and type check top level package
         let root_config = std::mem::replace(&mut self.root_config, Default::default());
         let name = root_config.name.clone();
-        self.compile_package(name, root_config, SourceLocations::SrcAndTest)?;
+        self.compile_package(name, root_config, SourceLocations::SrcAndTest, timings)?;
 
         Ok(self.packages)
     }
 
+    /// Dependency packages are only compiled again when their files, or any
+    /// of the packages they depend on, have changed. Otherwise the type
+    /// information of their modules is loaded from the cache written when
+    /// they were last compiled, and the Erlang generated then is used.
+    ///
+    fn compile_dependency(
+        &mut self,
+        name: String,
+        config: PackageConfig,
+        timings: &mut Timings,
+    ) -> Result<(), Error> {
+        let package_path = self.root.default_build_lib_package_path(&name);
+        let sources = PackageSources::read(&package_path)?;
+        let dependencies_unchanged = config
+            .dependencies
+            .keys()
+            .all(|dependency| !self.recompiled.contains(dependency));
+
+        if dependencies_unchanged {
+            if let Some(cache) = PackageCache::read_fresh(&package_path, &sources)? {
+                tracing::info!(package = name.as_str(), "Using cached module interfaces");
+                for module in cache.modules {
+                    self.load_cached_module(
+                        module.name,
+                        module.path,
+                        module.origin,
+                        module.interface,
+                    )?;
+                }
+                let package = Package {
+                    config,
+                    modules: vec![],
+                    outputs: vec![],
+                    coverage_lines: vec![],
+                };
+                self.packages.insert(name, package);
+                return Ok(());
+            }
+        }
+
+        self.compile_package(name.clone(), config, SourceLocations::Src, timings)?;
+        let package = self.packages.get(&name).gleam_expect("Compiled package");
+        PackageCache::new(sources, package).write(&package_path)?;
+        self.recompiled.insert(name);
+        Ok(())
+    }
+
+    fn load_cached_module(
+        &mut self,
+        name: String,
+        path: PathBuf,
+        origin: Origin,
+        interface: typ::Module,
+    ) -> Result<(), Error> {
+        if let Some(first) = self.defined_modules.insert(name.clone(), path.clone()) {
+            return Err(Error::DuplicateModule {
+                module: name,
+                first,
+                second: path,
+            });
+        }
+        self.type_manifests.insert(name, (origin, interface));
+        Ok(())
+    }
+
     fn compile_package(
         &mut self,
         name: String,
         config: PackageConfig,
         locations: SourceLocations,
+        timings: &mut Timings,
     ) -> Result<(), Error> {
         let mut compiler = PackageCompiler::new(self.root, config);
 
@@ -75,7 +153,8 @@ impl<'a> ProjectCompiler<'a> {
         }
 
         // Parse and type check
-        let compiled = compiler.compile(&mut self.type_manifests, &mut self.defined_modules)?;
+        let compiled =
+            compiler.compile(&mut self.type_manifests, &mut self.defined_modules, timings)?;
         self.packages.insert(name, compiled);
         Ok(())
     }
@@ -107,3 +186,67 @@ fn package_deps_for_graph(config: &PackageConfig) -> (String, Vec<String>) {
         .collect();
     (name, deps)
 }
+
+#[test]
+fn dependency_cache_test() {
+    let temp = crate::test_support::TempDir::new("dependency-cache");
+    let dir = temp.path();
+    let root = ProjectRoot::new(dir.to_path_buf());
+    let write = |package: &str, file: &str, text: &str| {
+        let path = root.default_build_lib_package_path(package).join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, text).unwrap();
+    };
+    let build = || {
+        let config = PackageConfig {
+            name: "app".to_string(),
+            ..Default::default()
+        };
+        let configs = root.package_configs("app").unwrap();
+        let packages = ProjectCompiler::new(&root, config, configs)
+            .compile(&mut Timings::default())
+            .expect("should compile");
+        for package in packages.values() {
+            crate::fs::write_outputs(&package.outputs).unwrap();
+        }
+        packages
+    };
+
+    write("dep", "gleam.toml", "name = \"dep\"");
+    write(
+        "dep",
+        "src/dep.gleam",
+        "pub type Box { Box(Int) } pub const one = 1",
+    );
+    write("app", "gleam.toml", "name = \"app\"");
+    write(
+        "app",
+        "src/app.gleam",
+        "import dep pub fn main() { dep.Box(dep.one) }",
+    );
+    assert_eq!(1, build()["dep"].modules.len());
+
+    // A change to the downstream package uses the cached dependency
+    write(
+        "app",
+        "src/app.gleam",
+        "import dep pub fn main() { dep.one }",
+    );
+    let packages = build();
+    assert!(packages["dep"].modules.is_empty());
+    assert!(packages["app"].outputs[0]
+        .text
+        .contains("main() ->\n    1."));
+
+    // A change to the dependency compiles it again
+    write(
+        "dep",
+        "src/dep.gleam",
+        "pub type Box { Box(Int) } pub const one = 2",
+    );
+    let packages = build();
+    assert_eq!(1, packages["dep"].modules.len());
+    assert!(packages["app"].outputs[0]
+        .text
+        .contains("main() ->\n    2."));
+}
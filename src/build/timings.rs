@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// The number of modules listed in the report.
+const SLOWEST_MODULES: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Stage {
+    Parse,
+    TypeCheck,
+    Generate,
+}
+
+/// How long each stage of compilation took for each module, reported by
+/// `gleam build --timings` to help find the modules that make a build slow.
+#[derive(Debug, Default)]
+pub struct Timings {
+    modules: HashMap<String, ModuleTimings>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ModuleTimings {
+    pub parse: Duration,
+    pub type_check: Duration,
+    pub generate: Duration,
+}
+
+impl ModuleTimings {
+    pub fn total(&self) -> Duration {
+        self.parse + self.type_check + self.generate
+    }
+}
+
+impl Timings {
+    /// Run the stage of compilation for the module, recording how long it
+    /// took.
+    ///
+    pub fn time<T>(&mut self, module: &str, stage: Stage, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        self.record(module, stage, start.elapsed());
+        result
+    }
+
+    pub fn record(&mut self, module: &str, stage: Stage, duration: Duration) {
+        let timings = self.modules.entry(module.to_string()).or_default();
+        match stage {
+            Stage::Parse => timings.parse += duration,
+            Stage::TypeCheck => timings.type_check += duration,
+            Stage::Generate => timings.generate += duration,
+        }
+    }
+
+    /// The modules that took the longest to compile, slowest first, with the
+    /// time taken by each stage.
+    ///
+    pub fn report(&self) -> String {
+        let mut modules: Vec<_> = self.modules.iter().collect();
+        modules.sort_by(|(a_name, a), (b_name, b)| {
+            b.total().cmp(&a.total()).then_with(|| a_name.cmp(b_name))
+        });
+
+        let mut report = format!(
+            "Slowest modules ({} of {}):\n",
+            modules.len().min(SLOWEST_MODULES),
+            modules.len()
+        );
+        for (name, timings) in modules.into_iter().take(SLOWEST_MODULES) {
+            report.push_str(&format!(
+                "{:>10.2?}  {}  (parse {:.2?}, type check {:.2?}, generate {:.2?})\n",
+                timings.total(),
+                name,
+                timings.parse,
+                timings.type_check,
+                timings.generate,
+            ));
+        }
+        report
+    }
+}
+
+#[test]
+fn report_test() {
+    let mut timings = Timings::default();
+    let ms = Duration::from_millis;
+    timings.record("one", Stage::Parse, ms(1));
+    timings.record("one", Stage::TypeCheck, ms(2));
+    timings.record("two", Stage::TypeCheck, ms(5));
+    timings.record("two", Stage::Generate, ms(1));
+    timings.record("three", Stage::Parse, ms(1));
+    timings.record("three", Stage::Parse, ms(2));
+
+    assert_eq!(
+        "Slowest modules (3 of 3):
+    6.00ms  two  (parse 0.00ns, type check 5.00ms, generate 1.00ms)
+    3.00ms  one  (parse 1.00ms, type check 2.00ms, generate 0.00ns)
+    3.00ms  three  (parse 3.00ms, type check 0.00ns, generate 0.00ns)
+",
+        timings.report()
+    );
+}
@@ -0,0 +1,57 @@
+use crate::{
+    config::{self, BuildTool},
+    error::Error,
+    project, warning,
+};
+use std::path::Path;
+
+/// Parse and type check a project, reporting any errors and warnings, without
+/// generating Erlang or writing any files.
+///
+pub fn command(root: impl AsRef<Path>, warning_format: warning::Format) -> Result<(), Error> {
+    // The new build tool can only compile a project by writing it to `_build`,
+    // and does not yet report warnings
+    if config::read_project_config(&root)?.tool == BuildTool::Gleam {
+        return Err(Error::UnsupportedBuildToolFlag {
+            flag: "gleam check".to_string(),
+        });
+    }
+    let (_config, analysed) = project::read_and_analyse(root)?;
+    warning::print_all(analysed.as_slice(), warning_format);
+    println!("Done!");
+    Ok(())
+}
+
+#[test]
+fn check_writes_no_files_test() {
+    let dir = crate::test_support::TempDir::new("check-test");
+    let root = dir.path();
+    let src = root.join("src");
+    std::fs::create_dir_all(&src).unwrap();
+    std::fs::write(root.join("gleam.toml"), "name = \"check_test\"\n").unwrap();
+
+    // Type errors are reported
+    std::fs::write(src.join("check_test.gleam"), "pub fn go() { 1 + 1.0 }").unwrap();
+    let result = command(root, warning::Format::Human);
+    assert!(matches!(result, Err(Error::Type { .. })), "{:?}", result);
+    assert!(!root.join(project::OUTPUT_DIR_NAME).exists());
+
+    // A valid project is checked without generating any Erlang
+    std::fs::write(src.join("check_test.gleam"), "pub fn go() { 1 + 1 }").unwrap();
+    assert_eq!(Ok(()), command(root, warning::Format::Human));
+    assert!(!root.join(project::OUTPUT_DIR_NAME).exists());
+
+    // Projects using the new build tool cannot yet be checked
+    std::fs::write(
+        root.join("gleam.toml"),
+        "name = \"check_test\"\ntool = \"gleam\"\n",
+    )
+    .unwrap();
+    assert_eq!(
+        Err(Error::UnsupportedBuildToolFlag {
+            flag: "gleam check".to_string()
+        }),
+        command(root, warning::Format::Human)
+    );
+    assert!(!root.join("_build").exists());
+}
@@ -45,6 +45,18 @@ pub fn print_green_prefix(prefix: &str, text: &str) {
     buffer_writer.print(&buffer).unwrap();
 }
 
+pub fn print_warning(text: &str) {
+    let buffer_writer = stderr_buffer_writer();
+    let mut buffer = buffer_writer.buffer();
+    buffer
+        .set_color(ColorSpec::new().set_bold(true).set_fg(Some(Color::Yellow)))
+        .unwrap();
+    write!(buffer, "warning:").unwrap();
+    buffer.set_color(&ColorSpec::new()).unwrap();
+    writeln!(buffer, " {}", text).unwrap();
+    buffer_writer.print(&buffer).unwrap();
+}
+
 pub fn stderr_buffer_writer() -> BufferWriter {
     // Don't add color codes to the output if standard error isn't connected to a terminal
     termcolor::BufferWriter::stderr(color_choice())
@@ -1,4 +1,4 @@
-use crate::error::{Error, FileIOAction, FileKind};
+use crate::error::{Error, GleamExpect};
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -18,6 +18,20 @@ pub struct PackageConfig {
     pub dependencies: HashMap<String, String>,
     #[serde(default)]
     pub otp_start_module: Option<String>,
+    #[serde(default)]
+    pub erlang: ErlangConfig,
+    #[serde(default)]
+    pub format: FormatConfig,
+    #[serde(default)]
+    pub warnings: WarningsConfig,
+    #[serde(default)]
+    pub profile: ProfileConfig,
+    #[serde(default)]
+    pub run: RunConfig,
+    /// Module names to use in place of those derived from the paths of the
+    /// given source files, relative to the project root.
+    #[serde(default)]
+    pub modules: HashMap<PathBuf, String>,
 }
 
 #[derive(Deserialize, Debug, PartialEq)]
@@ -33,9 +47,139 @@ impl Default for BuildTool {
     }
 }
 
+#[derive(Deserialize, Default, Debug, PartialEq)]
+pub struct ErlangConfig {
+    #[serde(default)]
+    pub int_arithmetic: IntArithmetic,
+    /// Whether `-type` declarations are generated for custom types so that
+    /// tools such as dialyzer can check the generated code.
+    #[serde(default)]
+    pub type_declarations: bool,
+    /// Whether a header file is generated for each module with the record
+    /// definitions of all of its public custom types, so that hand-written
+    /// Erlang can include them with a single `-include`.
+    #[serde(default)]
+    pub module_headers: bool,
+    /// Whether the project's source modules record which of their lines are
+    /// run, for the coverage report of `gleam eunit --coverage`.
+    #[serde(skip)]
+    pub coverage: bool,
+    /// The optimisations to apply to the generated code when building for
+    /// release with `gleam build --release`.
+    #[serde(skip)]
+    pub release: Option<ReleaseProfile>,
+}
+
+/// How integer arithmetic is compiled to Erlang. By default Gleam's Int is an
+/// Erlang bignum, but performance sensitive numeric code may opt into
+/// wrapping the results of arithmetic to signed 64-bit integers.
+#[derive(Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+pub enum IntArithmetic {
+    Bignum,
+    Wrap64,
+}
+
+impl Default for IntArithmetic {
+    fn default() -> Self {
+        Self::Bignum
+    }
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(default)]
+pub struct FormatConfig {
+    /// The number of spaces used for each level of indentation.
+    pub indent: usize,
+    /// Globs matching paths that are skipped when formatting a directory,
+    /// such as generated or vendored code.
+    pub ignore: Vec<String>,
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        Self {
+            indent: 2,
+            ignore: vec![],
+        }
+    }
+}
+
+/// Warnings that are off by default as they are a matter of style, enabled
+/// in the `[warnings]` section of `gleam.toml`.
+#[derive(Deserialize, Default, Debug, PartialEq)]
+#[serde(default)]
+pub struct WarningsConfig {
+    /// Warn about functions that return a `Result` but can never return an
+    /// `Error`.
+    pub unnecessary_result: bool,
+
+    /// Warn about `let` type annotations that match the inferred type of the
+    /// value.
+    pub redundant_annotation: bool,
+
+    /// Warn about functions that call themselves other than in tail
+    /// position, and so grow the stack.
+    pub non_tail_recursion: bool,
+}
+
+#[derive(Deserialize, Default, Debug, PartialEq)]
+#[serde(default)]
+pub struct ProfileConfig {
+    pub release: ReleaseProfile,
+}
+
+/// The optimisations of a release build, which may be turned off in the
+/// `[profile.release]` section of `gleam.toml`.
+#[derive(Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(default)]
+pub struct ReleaseProfile {
+    /// Evaluate arithmetic on integer literals at compile time.
+    pub fold_constants: bool,
+    /// Leave out private functions that are never used by the public
+    /// functions of their module.
+    pub remove_unused_functions: bool,
+}
+
+impl Default for ReleaseProfile {
+    fn default() -> Self {
+        Self {
+            fold_constants: true,
+            remove_unused_functions: true,
+        }
+    }
+}
+
+/// The function called by `gleam run`, configured in the `[run]` section of
+/// `gleam.toml`.
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(default)]
+pub struct RunConfig {
+    /// The module of the function. Defaults to the module with the same name
+    /// as the project.
+    pub module: Option<String>,
+    /// The function, which takes no arguments.
+    pub function: String,
+}
+
+impl Default for RunConfig {
+    fn default() -> Self {
+        Self {
+            module: None,
+            function: "main".to_string(),
+        }
+    }
+}
+
 #[derive(Deserialize, Default, Debug, PartialEq)]
 pub struct Docs {
+    #[serde(default)]
     pub pages: Vec<DocsPage>,
+    /// The modules that make up the public API of the package. When given
+    /// only these modules are documented, so internal modules can be kept
+    /// out of the generated docs.
+    #[serde(default)]
+    pub modules: Option<Vec<String>>,
 }
 
 #[derive(Deserialize, Debug, PartialEq, Clone)]
@@ -47,11 +191,240 @@ pub struct DocsPage {
 
 pub fn read_project_config(root: impl AsRef<Path>) -> Result<PackageConfig, Error> {
     let config_path = root.as_ref().join("gleam.toml");
+    if !config_path.is_file() {
+        return Err(Error::MissingConfig {
+            path: root.as_ref().to_path_buf(),
+            searched_up_to: None,
+        });
+    }
     let toml = crate::fs::read(&config_path)?;
-    toml::from_str(&toml).map_err(|e| Error::FileIO {
-        action: FileIOAction::Parse,
-        kind: FileKind::File,
-        path: config_path.clone(),
-        err: Some(e.to_string()),
+    parse(&config_path, &toml)
+}
+
+/// The numbers of spaces that may be used for each level of indentation, as
+/// with none the nesting of the code would be lost.
+const FORMAT_INDENTS: std::ops::RangeInclusive<usize> = 1..=8;
+
+fn parse(path: &Path, toml: &str) -> Result<PackageConfig, Error> {
+    let config: PackageConfig = toml::from_str(toml).map_err(|e| Error::InvalidConfig {
+        path: path.to_path_buf(),
+        err: e.to_string(),
+    })?;
+    if !FORMAT_INDENTS.contains(&config.format.indent) {
+        return Err(Error::InvalidConfig {
+            path: path.to_path_buf(),
+            err: format!(
+                "format.indent must be from {} to {}, but it is {}",
+                FORMAT_INDENTS.start(),
+                FORMAT_INDENTS.end(),
+                config.format.indent
+            ),
+        });
+    }
+    Ok(config)
+}
+
+/// The directory of the project that `path` is in, which is the closest of it
+/// and its parent directories to contain a `gleam.toml` file.
+///
+pub fn find_project_root(path: impl AsRef<Path>) -> Result<PathBuf, Error> {
+    let path = path.as_ref();
+    if path.join("gleam.toml").is_file() {
+        return Ok(path.to_path_buf());
+    }
+
+    // A relative path such as `.` has no parent, so the search continues from
+    // its absolute form
+    let absolute = std::fs::canonicalize(path).map_err(|_| Error::MissingConfig {
+        path: path.to_path_buf(),
+        searched_up_to: None,
+    })?;
+    let mut searched_up_to = absolute.clone();
+    for dir in absolute.ancestors().skip(1) {
+        if dir.join("gleam.toml").is_file() {
+            return Ok(dir.to_path_buf());
+        }
+        searched_up_to = dir.to_path_buf();
+    }
+    Err(Error::MissingConfig {
+        path: path.to_path_buf(),
+        searched_up_to: Some(searched_up_to),
     })
 }
+
+/// Add a dependency to the source of a `gleam.toml` config. The entry goes at
+/// the end of the `[dependencies]` table, which is added to the end of the
+/// file if there isn't one, so the rest of the file is left as it was.
+///
+pub fn add_dependency(path: &Path, toml: &str, name: &str, version: &str) -> Result<String, Error> {
+    if parse(path, toml)?.dependencies.contains_key(name) {
+        return Err(Error::DuplicateDependency {
+            name: name.to_string(),
+        });
+    }
+
+    let mut entry = std::collections::BTreeMap::new();
+    let _ = entry.insert(name, version);
+    let entry = toml::to_string(&entry).gleam_expect("Serializing a dependency");
+
+    let mut lines: Vec<_> = toml.lines().map(str::to_string).collect();
+    let code = |line: &str| line.split('#').next().unwrap_or("").trim().to_string();
+    let table = lines.iter().position(|line| code(line) == "[dependencies]");
+    match table {
+        Some(start) => {
+            let end = lines[start + 1..]
+                .iter()
+                .position(|line| code(line).starts_with('['))
+                .map_or(lines.len(), |i| start + 1 + i);
+            // After the last entry rather than the blank lines and comments
+            // before the next table
+            let last = lines[start..end]
+                .iter()
+                .rposition(|line| !code(line).is_empty())
+                .map_or(start, |i| start + i);
+            lines.insert(last + 1, entry.trim_end().to_string());
+        }
+        None => {
+            if matches!(lines.last(), Some(line) if !line.trim().is_empty()) {
+                lines.push("".to_string());
+            }
+            lines.push("[dependencies]".to_string());
+            lines.push(entry.trim_end().to_string());
+        }
+    }
+    let mut toml = lines.join("\n");
+    toml.push('\n');
+
+    // Any other way of defining dependencies would be broken by the new table
+    let _ = parse(path, &toml)?;
+    Ok(toml)
+}
+
+#[test]
+fn missing_config_test() {
+    let dir = crate::test_support::TempDir::new("config-test");
+    let root = dir.path();
+    let nested = root.join("project/src/nested");
+    std::fs::create_dir_all(&nested).unwrap();
+
+    assert!(matches!(
+        read_project_config(&nested),
+        Err(Error::MissingConfig {
+            searched_up_to: None,
+            ..
+        })
+    ));
+
+    // The project root is found from any of its directories
+    std::fs::write(root.join("project/gleam.toml"), "name = \"project\"\n").unwrap();
+    let found = find_project_root(&nested).unwrap();
+    assert_eq!(std::fs::canonicalize(root.join("project")).unwrap(), found);
+    assert_eq!("project", read_project_config(&found).unwrap().name);
+    assert_eq!(
+        root.join("project"),
+        find_project_root(root.join("project")).unwrap()
+    );
+
+    // The search continues up to the root of the file system
+    std::fs::remove_file(root.join("project/gleam.toml")).unwrap();
+    match find_project_root(&nested) {
+        Err(Error::MissingConfig {
+            path,
+            searched_up_to: Some(searched_up_to),
+        }) => {
+            assert_eq!(nested, path);
+            assert_eq!(None, searched_up_to.parent());
+        }
+        result => panic!("unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn invalid_config_test() {
+    let dir = crate::test_support::TempDir::new("invalid-config-test");
+    let root = dir.path();
+    std::fs::write(root.join("gleam.toml"), "name = \"project\n").unwrap();
+
+    // A config that cannot be parsed is reported rather than searching further
+    assert_eq!(root, find_project_root(root).unwrap());
+    match read_project_config(root) {
+        Err(Error::InvalidConfig { path, err }) => {
+            assert_eq!(root.join("gleam.toml"), path);
+            assert!(err.contains("line 1"), "{}", err);
+        }
+        result => panic!("unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn format_indent_test() {
+    fn indent(indent: usize) -> Result<usize, Error> {
+        let toml = format!("name = \"project\"\n[format]\nindent = {}\n", indent);
+        parse(Path::new("gleam.toml"), &toml).map(|config| config.format.indent)
+    }
+    assert_eq!(Ok(1), indent(1));
+    assert_eq!(Ok(8), indent(8));
+    assert_eq!(
+        Err(Error::InvalidConfig {
+            path: PathBuf::from("gleam.toml"),
+            err: "format.indent must be from 1 to 8, but it is 0".to_string(),
+        }),
+        indent(0)
+    );
+    assert!(indent(100).is_err());
+}
+
+#[test]
+fn add_dependency_test() {
+    fn add(toml: &str) -> Result<String, Error> {
+        add_dependency(Path::new("gleam.toml"), toml, "gleam_http", "~> 2.0")
+    }
+
+    // A dependency is added to the end of the existing table
+    assert_eq!(
+        "name = \"app\"
+
+[dependencies]
+gleam_stdlib = \"~> 0.12\"
+gleam_http = \"~> 2.0\"
+
+# Docs
+[docs]
+pages = []
+",
+        add("name = \"app\"
+
+[dependencies]
+gleam_stdlib = \"~> 0.12\"
+
+# Docs
+[docs]
+pages = []
+")
+        .unwrap()
+    );
+
+    // Or to a new table if there isn't one
+    let toml = add("name = \"app\"\n").unwrap();
+    assert_eq!(
+        "name = \"app\"
+
+[dependencies]
+gleam_http = \"~> 2.0\"
+",
+        toml
+    );
+    let config: PackageConfig = toml::from_str(&toml).unwrap();
+    assert_eq!(
+        Some(&"~> 2.0".to_string()),
+        config.dependencies.get("gleam_http")
+    );
+
+    // Packages can only be added once
+    assert_eq!(
+        Err(Error::DuplicateDependency {
+            name: "gleam_http".to_string()
+        }),
+        add(&toml)
+    );
+}
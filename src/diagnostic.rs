@@ -1,6 +1,7 @@
-use codespan::{FileId, Files};
+use crate::line_numbers::LineNumbers;
 pub use codespan_reporting::diagnostic::{LabelStyle, Severity};
-use codespan_reporting::{diagnostic::Label, term::emit};
+use codespan_reporting::{diagnostic::Label, files::Files, term::emit};
+use std::ops::Range;
 use termcolor::Buffer;
 
 pub struct DiagnosticLabel {
@@ -39,14 +40,13 @@ pub fn write(buffer: &mut Buffer, d: Diagnostic, severity: Severity) {
 }
 
 pub fn write_diagnostic(mut buffer: &mut Buffer, d: MultiLineDiagnostic, severity: Severity) {
-    let mut files = Files::new();
-    let file_id: FileId = files.add(d.file, d.src);
+    let file = File::new(d.file, d.src);
 
-    let labels: Vec<Label<FileId>> = d
+    let labels: Vec<Label<()>> = d
         .labels
         .iter()
         .map(|l| {
-            Label::new(l.style, file_id, (l.location.start)..(l.location.end))
+            Label::new(l.style, (), (l.location.start)..(l.location.end))
                 .with_message(l.label.clone())
         })
         .collect();
@@ -56,7 +56,52 @@ pub fn write_diagnostic(mut buffer: &mut Buffer, d: MultiLineDiagnostic, severit
         .with_labels(labels);
 
     let config = codespan_reporting::term::Config::default();
-    emit(&mut buffer, &config, &files, &diagnostic).unwrap();
+    emit(&mut buffer, &config, &file, &diagnostic).unwrap();
+}
+
+/// The single source file a diagnostic is reported for.
+///
+struct File {
+    name: String,
+    src: String,
+    line_numbers: LineNumbers,
+}
+
+impl File {
+    fn new(name: String, src: String) -> Self {
+        let line_numbers = LineNumbers::new(&src);
+        Self {
+            name,
+            src,
+            line_numbers,
+        }
+    }
+}
+
+impl<'a> Files<'a> for File {
+    type FileId = ();
+    type Name = &'a str;
+    type Source = &'a str;
+
+    fn name(&'a self, _id: ()) -> Option<&'a str> {
+        Some(&self.name)
+    }
+
+    fn source(&'a self, _id: ()) -> Option<&'a str> {
+        Some(&self.src)
+    }
+
+    fn line_index(&'a self, _id: (), byte_index: usize) -> Option<usize> {
+        Some(self.line_numbers.line_index(byte_index))
+    }
+
+    fn column_number(&'a self, _id: (), _line_index: usize, byte_index: usize) -> Option<usize> {
+        Some(self.line_numbers.column_index(&self.src, byte_index) + 1)
+    }
+
+    fn line_range(&'a self, _id: (), line_index: usize) -> Option<Range<usize>> {
+        self.line_numbers.line_range(line_index)
+    }
 }
 
 /// Describes an error encountered while compiling the project (eg. a name collision
@@ -86,3 +131,319 @@ pub fn write_project(buffer: &mut Buffer, d: ProjectErrorDiagnostic) {
     buffer.set_color(&ColorSpec::new()).unwrap();
     writeln!(buffer, "{}", d.label).unwrap();
 }
+
+/// The extended explanation of each warning code, with an example of code
+/// that causes the warning and how it can be fixed. Printed by `gleam explain`.
+///
+const EXPLANATIONS: &[(&str, &str)] = &[
+    (
+        "todo",
+        "A `todo` expression marks code that has not been written yet. The program
+compiles so that the rest of it can be type checked, but it crashes if the
+`todo` is evaluated.
+
+    pub fn area(shape) {
+      todo
+    }
+
+Replace the `todo` with an implementation before running the program.
+
+    pub fn area(shape) {
+      shape.width * shape.height
+    }",
+    ),
+    (
+        "discarded-result",
+        "A Result value returned by an expression in a sequence is not used, so any
+error it holds is silently ignored.
+
+    pub fn main() {
+      file.write(\"log.txt\", \"Hello\")
+      Nil
+    }
+
+Handle the error with a case expression, or assign it to `_` if the error
+truly does not matter.
+
+    pub fn main() {
+      let _ = file.write(\"log.txt\", \"Hello\")
+      Nil
+    }",
+    ),
+    (
+        "no-fields-record-update",
+        "A record update that changes no fields returns the original record
+unchanged.
+
+    let updated = Person(..person)
+
+Give the fields to change, or use the original record directly.
+
+    let updated = Person(..person, age: 31)",
+    ),
+    (
+        "all-fields-record-update",
+        "A record update that gives every field of the record copies nothing from
+the original record, so it does not need to be an update.
+
+    let updated = Point(..point, x: 1, y: 2)
+
+Construct the record directly instead.
+
+    let updated = Point(x: 1, y: 2)",
+    ),
+    (
+        "unused-type",
+        "A private type is defined but never used in the module, and as it is not
+public it cannot be used anywhere else.
+
+    type Colour {
+      Red
+      Green
+    }
+
+Remove the type, or make it public with `pub` if other modules need it.",
+    ),
+    (
+        "unused-constructor",
+        "A constructor of a private type is never used in the module, so no value
+can ever be built with it.
+
+    type Colour {
+      Red
+      Green
+    }
+
+    pub fn red() { Red }
+
+Remove the constructor, or use it where it was intended to be used.",
+    ),
+    (
+        "unused-type-parameter",
+        "A custom type has a type parameter that none of its constructors use.
+
+    pub type Box(a) {
+      Box(Int)
+    }
+
+This is often a mistake, such as using a concrete type where the parameter was
+intended. A parameter that is purposefully unused, such as for a phantom type
+that only exists to tell values apart, can be prefixed with an underscore.
+
+    pub type Id(_kind) {
+      Id(Int)
+    }",
+    ),
+    (
+        "redundant-let",
+        "A variable is assigned to another variable without being changed, so the
+original variable could be used directly.
+
+    let name = user_name
+    greet(name)
+
+Remove the assignment, or check that the correct variable has been used.
+
+    greet(user_name)",
+    ),
+    (
+        "constant-condition",
+        "The condition of a case expression is made only of literal values, so it is
+always the same and some clauses can never be run.
+
+    case 1 > 2 {
+      True -> \"big\"
+      False -> \"small\"
+    }
+
+Check that the correct values have been used in the condition.",
+    ),
+    (
+        "refutable-let-pattern",
+        "The pattern of a let binding does not match every possible value, so the
+binding crashes at runtime when given a value it does not match.
+
+    let [first, ..] = items
+
+Use `assert` if the pattern is always expected to match, or a case expression
+to handle the other values.
+
+    case items {
+      [first, ..] -> first
+      [] -> 0
+    }",
+    ),
+    (
+        "float-equality",
+        "Floats are subject to rounding errors, so checking them for exact equality
+can give unexpected results.
+
+    0.1 +. 0.2 == 0.3
+
+Check that the difference between the two values is within a small tolerance
+instead.
+
+    float.absolute_value(0.1 +. 0.2 -. 0.3) <. 0.0001",
+    ),
+    (
+        "unnecessary-result",
+        "A private function returns a Result but can only ever return Ok, so callers
+have to handle an error that cannot happen.
+
+    fn double(x) {
+      Ok(x * 2)
+    }
+
+Remove the Result wrapper and return the value directly.
+
+    fn double(x) {
+      x * 2
+    }",
+    ),
+    (
+        "redundant-rewrap",
+        "Each clause of a case expression builds the same value that it matched, so
+the case returns its subject unchanged.
+
+    case result {
+      Ok(value) -> Ok(value)
+      Error(error) -> Error(error)
+    }
+
+Remove the case expression and use the value being matched on directly.
+
+    result",
+    ),
+    (
+        "infinite-recursion",
+        "A function always calls itself with the same arguments before it could
+return, so calling it never finishes.
+
+    pub fn count_down(n) {
+      count_down(n)
+    }
+
+Add a case that returns without calling the function again, and make sure
+each recursive call gets closer to it.
+
+    pub fn count_down(n) {
+      case n {
+        0 -> Nil
+        _ -> count_down(n - 1)
+      }
+    }",
+    ),
+    (
+        "raw-erlang",
+        "An `@erlang` expression inserts Erlang code into the compiled module as it
+is written. The compiler cannot check this code, so it trusts that the value
+has the type given after `as`.
+
+    pub fn now() -> Int {
+      @erlang(\"erlang:system_time()\") as Int
+    }
+
+If the annotation is wrong the program may crash or behave unexpectedly.
+Prefer an external function where one can be used, as its arguments and
+return value are checked where it is called.",
+    ),
+    (
+        "redundant-annotation",
+        "The value of a `let` already has the type given by its annotation, so the
+annotation adds nothing.
+
+    let x: Int = 1
+
+The annotation can be removed.
+
+    let x = 1
+
+An annotation that narrows a type the value does not fix, such as
+`let xs: List(Int) = []`, is not reported. This warning is off by default
+and is enabled by setting `redundant_annotation = true` in the `[warnings]`
+section of `gleam.toml`.",
+    ),
+    (
+        "non-tail-recursion",
+        "A function calls itself in a position other than the last thing it does,
+so it has to keep its own state on the stack until the call returns. Each
+level of recursion uses more memory, which can be a problem when the
+recursion is deep or memory is limited.
+
+    pub fn sum(xs) {
+      case xs {
+        [] -> 0
+        [x, ..rest] -> x + sum(rest)
+      }
+    }
+
+Passing the result so far as an argument puts the recursive call in tail
+position, where it uses no extra memory.
+
+    pub fn sum(xs, total) {
+      case xs {
+        [] -> total
+        [x, ..rest] -> sum(rest, total + x)
+      }
+    }
+
+This warning is off by default and is enabled by setting
+`non_tail_recursion = true` in the `[warnings]` section of `gleam.toml`.",
+    ),
+    (
+        "deprecated",
+        "A function or type marked with a `@deprecated` attribute is being used. The
+attribute's message says why it should no longer be used, and often what to
+use instead.
+
+    @deprecated(\"Use `int.to_string` instead\")
+    pub fn show(x: Int) -> String {
+      int.to_string(x)
+    }
+
+Deprecated items still work, but may be removed in a later version of the
+package that defines them.",
+    ),
+    (
+        "internal",
+        "A function or type marked with an `@internal` attribute is being used from
+outside of the package that defines it.
+
+    @internal
+    pub fn parse_header(header: String) -> Header {
+      todo
+    }
+
+Internal items are public so that other modules of their package can use them,
+but they are not part of the package's documented interface and may change in
+any version of it.",
+    ),
+    (
+        "shadowed-type",
+        "A custom type or type alias has the same name as an imported type that is in
+scope without being qualified, such as a type of the prelude.
+
+    pub type Result(a) =
+      List(a)
+
+Within the module `Result` refers to the local type rather than the prelude's
+`Result`, which is easy to confuse when reading type annotations. Types that
+are only used qualified, such as `option.Option`, are not ambiguous. Renaming
+the local type avoids this.",
+    ),
+];
+
+/// The extended explanation of a warning code, if the code is known.
+///
+pub fn explanation(code: &str) -> Option<&'static str> {
+    EXPLANATIONS
+        .iter()
+        .find(|(known, _)| *known == code)
+        .map(|(_, explanation)| *explanation)
+}
+
+/// All the codes that have an explanation.
+///
+pub fn codes() -> impl Iterator<Item = &'static str> {
+    EXPLANATIONS.iter().map(|(code, _)| *code)
+}
@@ -4,12 +4,14 @@ mod tests;
 
 use crate::{
     ast::{Statement, TypedStatement},
+    cli,
     config::{DocsPage, PackageConfig},
     error::{Error, GleamExpect},
     format,
     fs::OutputFile,
     pretty,
     project::{self, Analysed, ModuleOrigin},
+    typ::interface::ModuleInterface,
 };
 use askama::Template;
 use itertools::Itertools;
@@ -34,18 +36,55 @@ pub fn build_project(
     // Add any user-supplied pages
     pages.extend(config.docs.pages.to_vec());
 
+    for name in missing_modules(&config, analysed.as_slice()) {
+        cli::print_warning(&format!(
+            "The module `{}` is listed in the docs config of gleam.toml but does not exist",
+            name
+        ));
+    }
+
     // Generate HTML
     let outputs = generate_html(&config, analysed.as_slice(), &pages, output_dir);
     Ok((config, outputs))
 }
 
+/// Whether the module is part of the public API of the package, which is
+/// every module unless the config lists the modules to document.
+///
+fn is_documented(config: &PackageConfig, module: &Analysed) -> bool {
+    match &config.docs.modules {
+        Some(names) => names.contains(&module.name.join("/")),
+        None => true,
+    }
+}
+
+/// The modules listed in the docs config that are not modules of the package.
+///
+pub fn missing_modules<'a>(config: &'a PackageConfig, analysed: &[Analysed]) -> Vec<&'a str> {
+    config
+        .docs
+        .modules
+        .iter()
+        .flatten()
+        .filter(|name| {
+            !analysed
+                .iter()
+                .any(|m| m.origin == ModuleOrigin::Src && &m.name.join("/") == *name)
+        })
+        .map(String::as_str)
+        .collect()
+}
+
 pub fn generate_html(
     project_config: &PackageConfig,
     analysed: &[Analysed],
     docspages: &[DocsPage],
     output_dir: &PathBuf,
 ) -> Vec<OutputFile> {
-    let modules = analysed.iter().filter(|m| m.origin == ModuleOrigin::Src);
+    let modules = analysed
+        .iter()
+        .filter(|m| m.origin == ModuleOrigin::Src)
+        .filter(|m| is_documented(project_config, m));
 
     // Define user-supplied (or README) pages
     let pages = docspages
@@ -96,6 +135,14 @@ pub fn generate_html(
     // Generate module documentation pages
     for module in modules {
         let name = module.name.join("/");
+        // Statements with an `@internal` attribute are not documented
+        let documented = || {
+            module
+                .ast
+                .statements
+                .iter()
+                .filter(|statement| !module.ast.is_internal(statement.location().start))
+        };
         let template = ModuleTemplate {
             unnest: module.name.iter().map(|_| "..").intersperse("/").collect(),
             links,
@@ -107,33 +154,41 @@ pub fn generate_html(
             module_name: name,
             project_version: "", // TODO
             functions: {
-                let mut f: Vec<_> = module.ast.statements.iter().flat_map(function).collect();
+                let mut f: Vec<_> = documented().flat_map(function).collect();
                 f.sort();
                 f
             },
             types: {
-                let mut t: Vec<_> = module.ast.statements.iter().flat_map(type_).collect();
+                let mut t: Vec<_> = documented().flat_map(type_).collect();
                 t.sort();
                 t
             },
             constants: {
-                let mut c: Vec<_> = module.ast.statements.iter().flat_map(constant).collect();
+                let mut c: Vec<_> = documented().flat_map(constant).collect();
                 c.sort();
                 c
             },
         };
 
-        let mut path = output_dir.clone();
+        let mut dir = output_dir.clone();
         for segment in module.name.iter() {
-            path.push(segment)
+            dir.push(segment)
         }
-        path.push("index.html");
         files.push(OutputFile {
-            path,
+            path: dir.join("index.html"),
             text: template
                 .render()
                 .gleam_expect("Module documentation template rendering"),
         });
+
+        // The public interface of the module, for package registries and
+        // other tools to read
+        let interface = ModuleInterface::of_module(&module.type_info);
+        files.push(OutputFile {
+            path: dir.join("interface.json"),
+            text: serde_json::to_string_pretty(&interface)
+                .gleam_expect("Module interface serialisation"),
+        });
     }
 
     // Render static assets
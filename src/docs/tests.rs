@@ -1,5 +1,9 @@
 use super::*;
-use crate::{config::PackageConfig, fs::OutputFile, project::Input};
+use crate::{
+    config::{Docs, PackageConfig},
+    fs::OutputFile,
+    project::Input,
+};
 
 #[test]
 fn module_docs_test() {
@@ -34,6 +38,7 @@ pub fn complicated_fun(
 
     let input = Input {
         origin: ModuleOrigin::Src,
+        module_name: None,
         path: PathBuf::from("/src/test.gleam"),
         source_base_path: PathBuf::from("/src"),
         src: src.to_string(),
@@ -47,6 +52,12 @@ pub fn complicated_fun(
         description: Default::default(),
         dependencies: Default::default(),
         otp_start_module: None,
+        erlang: Default::default(),
+        format: Default::default(),
+        warnings: Default::default(),
+        profile: Default::default(),
+        run: Default::default(),
+        modules: Default::default(),
     };
 
     let analysed = crate::project::analysed(vec![input]).expect("Compilation failed");
@@ -78,6 +89,198 @@ pub fn complicated_fun(
   with fun: fn(a, b) -&gt; b,
 ) -&gt; b",
     );
+
+    // The public interface of the module is written alongside its page
+    let interface = output_files
+        .iter()
+        .find(|page| page.path == Path::new("/docs/test/interface.json"))
+        .expect("Missing module interface");
+    let interface: ModuleInterface =
+        serde_json::from_str(&interface.text).expect("Invalid module interface");
+    assert_eq!(
+        vec!["complicated_fun", "implicit_return", "public_fun"],
+        interface
+            .functions
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec!["hello"],
+        interface
+            .constants
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn public_modules_test() {
+    let input = |name: &str, src: &str| Input {
+        origin: ModuleOrigin::Src,
+        module_name: None,
+        path: PathBuf::from(format!("/src/{}.gleam", name)),
+        source_base_path: PathBuf::from("/src"),
+        src: src.to_string(),
+    };
+    let analysed = project::analysed(vec![
+        input("api", "pub fn run() { 1 }"),
+        input("internal", "pub fn helper() { 1 }"),
+    ])
+    .expect("Compilation failed");
+
+    let config = PackageConfig {
+        name: "test".to_string(),
+        docs: Docs {
+            pages: vec![],
+            modules: Some(vec!["api".to_string(), "missing".to_string()]),
+        },
+        ..Default::default()
+    };
+    let pages = [DocsPage {
+        title: "README".to_string(),
+        path: "index.html".to_string(),
+        source: PathBuf::from("/README.md"),
+    }];
+
+    let output_files = generate_html(
+        &config,
+        analysed.as_slice(),
+        &pages,
+        &PathBuf::from("/docs"),
+    );
+    let index = output_files
+        .iter()
+        .find(|page| page.path == Path::new("/docs/index.html"))
+        .expect("Missing index page");
+    index.should_contain(">api</a>");
+    index.should_not_contain("internal");
+    assert!(output_files
+        .iter()
+        .all(|page| page.path != Path::new("/docs/internal/index.html")));
+
+    assert_eq!(
+        vec!["missing"],
+        missing_modules(&config, analysed.as_slice())
+    );
+}
+
+#[test]
+fn internal_test() {
+    let src = r#"
+pub fn run() { helper() }
+
+@internal
+pub fn helper() { 1 }
+
+@internal
+pub type Token {
+  Token
+}
+
+@internal
+pub const limit = 10
+"#;
+    let analysed = project::analysed(vec![Input {
+        origin: ModuleOrigin::Src,
+        module_name: None,
+        path: PathBuf::from("/src/api.gleam"),
+        source_base_path: PathBuf::from("/src"),
+        src: src.to_string(),
+    }])
+    .expect("Compilation failed");
+
+    let output_files = generate_html(
+        &PackageConfig::default(),
+        analysed.as_slice(),
+        &[],
+        &PathBuf::from("/docs"),
+    );
+    let page = output_files
+        .iter()
+        .find(|page| page.path == Path::new("/docs/api/index.html"))
+        .expect("Missing module page");
+    page.should_contain("run");
+    page.should_not_contain("helper");
+    page.should_not_contain("Token");
+    page.should_not_contain("limit");
+
+    let interface = output_files
+        .iter()
+        .find(|page| page.path == Path::new("/docs/api/interface.json"))
+        .expect("Missing module interface");
+    interface.should_contain("run");
+    interface.should_not_contain("helper");
+    interface.should_not_contain("Token");
+    interface.should_not_contain("limit");
+}
+
+#[test]
+fn long_signature_test() {
+    let src = r#"
+pub type Response(body) {
+  Response(status: Int, headers: List(tuple(String, String)), body: body)
+}
+
+pub fn short(x: Int) -> Int {
+  x
+}
+
+pub fn many(
+  status: Int,
+  headers: List(tuple(String, String)),
+  body: String,
+) -> Int {
+  0
+}
+
+pub fn long_return(
+  status: Int,
+) -> fn(Response(String), Response(String), Response(String)) -> Response(String) {
+  todo
+}
+"#;
+
+    let input = Input {
+        origin: ModuleOrigin::Src,
+        module_name: None,
+        path: PathBuf::from("/src/test.gleam"),
+        source_base_path: PathBuf::from("/src"),
+        src: src.to_string(),
+    };
+    let analysed = project::analysed(vec![input]).expect("Compilation failed");
+    let config = PackageConfig {
+        name: "test".to_string(),
+        ..Default::default()
+    };
+
+    let output_files = generate_html(&config, analysed.as_slice(), &[], &PathBuf::from("/docs"));
+    let module_page = output_files
+        .iter()
+        .find(|page| page.path == Path::new("/docs/test/index.html"))
+        .expect("Missing docs page");
+
+    // Short signatures stay on one line
+    module_page.should_contain("pub fn short(x: Int) -&gt; Int");
+
+    // Long ones have each argument on its own line
+    module_page.should_contain(
+        "pub fn many(
+  status: Int,
+  headers: List(tuple(String, String)),
+  body: String,
+) -&gt; Int",
+    );
+
+    // And the return type too if it does not fit after the arguments
+    module_page.should_contain(
+        "pub fn long_return(
+  status: Int,
+) -&gt;
+  fn(Response(String), Response(String), Response(String)) -&gt;
+    Response(String)",
+    );
 }
 
 impl OutputFile {
@@ -1,25 +1,31 @@
+mod optimise;
 #[cfg(test)]
 mod tests;
 
 use crate::{
     ast::*,
+    config::{ErlangConfig, IntArithmetic},
     error::GleamExpect,
     fs::OutputFile,
+    line_numbers::LineNumbers,
     pretty::*,
     project::{self, Analysed},
     typ::{
-        ModuleValueConstructor, PatternConstructor, Type, ValueConstructor, ValueConstructorVariant,
+        ModuleValueConstructor, PatternConstructor, Type, TypeVar, ValueConstructor,
+        ValueConstructorVariant,
     },
 };
 use heck::{CamelCase, SnakeCase};
 use itertools::Itertools;
+use std::cell::RefCell;
 use std::char;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::default::Default;
 use std::sync::Arc;
 
 const INDENT: isize = 4;
 
-pub fn generate_erlang(analysed: &[Analysed]) -> Vec<OutputFile> {
+pub fn generate_erlang(analysed: &[Analysed], config: &ErlangConfig) -> Vec<OutputFile> {
     let mut files = Vec::with_capacity(analysed.len() * 2);
 
     for Analysed {
@@ -44,28 +50,56 @@ pub fn generate_erlang(analysed: &[Analysed]) -> Vec<OutputFile> {
             })
         }
 
+        if config.module_headers {
+            if let Some(text) = module_header(ast) {
+                files.push(OutputFile {
+                    path: gen_dir.join(format!("{}.hrl", erl_module_name)),
+                    text,
+                })
+            }
+        }
+
         files.push(OutputFile {
             path: gen_dir.join(format!("{}.erl", erl_module_name)),
-            text: module(ast),
+            text: module(ast, config),
         });
     }
 
     files
 }
 
+/// The source file of a module being compiled with coverage instrumentation,
+/// and the lines that have been instrumented so far.
+#[derive(Debug)]
+struct Coverage<'a> {
+    path: &'a str,
+    line_numbers: LineNumbers,
+    lines: RefCell<BTreeSet<usize>>,
+}
+
 #[derive(Debug, Clone)]
 struct Env<'a> {
     module: &'a [String],
+    int_arithmetic: IntArithmetic,
+    fold_constants: bool,
+    coverage: Option<&'a Coverage<'a>>,
     current_scope_vars: im::HashMap<String, usize>,
     erl_function_scope_vars: im::HashMap<String, usize>,
 }
 
 impl<'a> Env<'a> {
-    pub fn new(module: &'a [String]) -> Self {
+    pub fn new(
+        module: &'a [String],
+        config: &ErlangConfig,
+        coverage: Option<&'a Coverage<'a>>,
+    ) -> Self {
         Self {
             current_scope_vars: Default::default(),
             erl_function_scope_vars: Default::default(),
             module,
+            int_arithmetic: config.int_arithmetic,
+            fold_constants: matches!(config.release, Some(release) if release.fold_constants),
+            coverage,
         }
     }
 
@@ -116,6 +150,17 @@ pub fn records(module: &TypedModule) -> Vec<(&str, String)> {
         .collect()
 }
 
+/// The header file of the module, with the definitions of all the records
+/// that also have their own header file. `None` if the module has no records.
+///
+pub fn module_header(module: &TypedModule) -> Option<String> {
+    let records = records(module);
+    if records.is_empty() {
+        return None;
+    }
+    Some(records.into_iter().map(|(_, text)| text).join(""))
+}
+
 pub fn record_definition(name: &str, fields: &[&str]) -> String {
     let name = &name.to_snake_case();
     let escaped_name = if is_erlang_reserved_word(name) {
@@ -137,7 +182,35 @@ pub fn record_definition(name: &str, fields: &[&str]) -> String {
     buffer
 }
 
-pub fn module(module: &TypedModule) -> String {
+pub fn module(module: &TypedModule, config: &ErlangConfig) -> String {
+    render_module(module, config, None)
+}
+
+/// Generate the module with a call to `gleam_coverage:hit/2` before each
+/// statement, recording the line of the source file at `path` that the
+/// statement was compiled from. The instrumented lines are returned with the
+/// code so that a coverage report can include the lines that never ran.
+///
+pub fn instrumented_module(
+    module: &TypedModule,
+    config: &ErlangConfig,
+    path: &str,
+    src: &str,
+) -> (String, Vec<usize>) {
+    let coverage = Coverage {
+        path,
+        line_numbers: LineNumbers::new(src),
+        lines: RefCell::new(BTreeSet::new()),
+    };
+    let code = render_module(module, config, Some(&coverage));
+    (code, coverage.lines.into_inner().into_iter().collect())
+}
+
+fn render_module(
+    module: &TypedModule,
+    config: &ErlangConfig,
+    coverage: Option<&Coverage<'_>>,
+) -> String {
     let module_name = module.name.as_slice();
     let exports = concat(
         module
@@ -149,29 +222,53 @@ pub fn module(module: &TypedModule) -> String {
                     name,
                     args,
                     ..
-                } => Some((name.clone(), args.len())),
+                } => vec![(name.clone(), args.len())],
 
                 Statement::ExternalFn {
                     public: true,
                     name,
                     args,
                     ..
-                } => Some((name.clone(), args.len())),
+                } => vec![(name.clone(), args.len())],
+
+                Statement::CustomType {
+                    public: true,
+                    derives,
+                    ..
+                } => derives
+                    .iter()
+                    .map(|derived| (derived.name.clone(), 2))
+                    .collect(),
 
-                _ => None,
+                _ => vec![],
             })
             .map(|(n, a)| atom(n).append("/").append(a))
             .intersperse(", ".to_doc()),
     );
 
+    let unused_functions = match config.release {
+        Some(release) if release.remove_unused_functions => optimise::unused_functions(module),
+        _ => HashSet::new(),
+    };
+
     let statements = concat(
         module
             .statements
             .iter()
-            .flat_map(|s| statement(s, module_name))
+            .filter(|s| match s {
+                Statement::Fn { name, .. } => !unused_functions.contains(name.as_str()),
+                _ => true,
+            })
+            .flat_map(|s| statement(s, module_name, config, coverage))
             .intersperse(lines(2)),
     );
 
+    let type_declarations = if config.type_declarations {
+        type_declarations(module)
+    } else {
+        nil()
+    };
+
     format!("-module({}).", module_name.join("@"))
         .to_doc()
         .append(line())
@@ -186,14 +283,30 @@ pub fn module(module: &TypedModule) -> String {
                 .append("]).")
                 .append(lines(2))
         })
+        .append(type_declarations)
         .append(statements)
         .append(line())
         .format(80)
 }
 
-fn statement(statement: &TypedStatement, module: &[String]) -> Option<Document> {
+fn statement(
+    statement: &TypedStatement,
+    module: &[String],
+    config: &ErlangConfig,
+    coverage: Option<&Coverage<'_>>,
+) -> Option<Document> {
     match statement {
         Statement::TypeAlias { .. } => None,
+        Statement::CustomType {
+            derives,
+            constructors,
+            ..
+        } if !derives.is_empty() => Some(concat(
+            derives
+                .iter()
+                .map(|derived| derived_fun(derived, constructors.as_slice()))
+                .intersperse(lines(2)),
+        )),
         Statement::CustomType { .. } => None,
         Statement::Import { .. } => None,
         Statement::ExternalType { .. } => None,
@@ -201,7 +314,14 @@ fn statement(statement: &TypedStatement, module: &[String]) -> Option<Document>
 
         Statement::Fn {
             args, name, body, ..
-        } => Some(mod_fun(name.as_ref(), args.as_slice(), body, module)),
+        } => Some(mod_fun(
+            name.as_ref(),
+            args.as_slice(),
+            body,
+            module,
+            config,
+            coverage,
+        )),
 
         Statement::ExternalFn { public: false, .. } => None,
         Statement::ExternalFn {
@@ -219,16 +339,308 @@ fn statement(statement: &TypedStatement, module: &[String]) -> Option<Document>
     }
 }
 
-fn mod_fun(name: &str, args: &[TypedArg], body: &TypedExpr, module: &[String]) -> Document {
-    let mut env = Env::new(module);
+/// The `-export_type` attribute and `-type` declarations of the module's custom
+/// and external types. Each constructor of a custom type is declared as the
+/// atom or tagged tuple it is represented by.
+///
+fn type_declarations(module: &TypedModule) -> Document {
+    let exports = join(
+        module.statements.iter().filter_map(|s| match s {
+            Statement::CustomType {
+                public: true,
+                name,
+                parameters: args,
+                ..
+            }
+            | Statement::ExternalType {
+                public: true,
+                name,
+                args,
+                ..
+            } => Some(atom(name.to_snake_case()).append("/").append(args.len())),
+            _ => None,
+        }),
+        ", ".to_doc(),
+    );
+
+    let declarations: Vec<_> = module
+        .statements
+        .iter()
+        .filter_map(|s| match s {
+            Statement::CustomType {
+                name,
+                parameters,
+                constructors,
+                opaque,
+                ..
+            } => Some(custom_type_declaration(
+                name,
+                parameters,
+                constructors,
+                *opaque,
+                &module.name,
+            )),
+
+            Statement::ExternalType { name, args, .. } => Some(
+                type_head("-type", name, args)
+                    .append(" :: any().")
+                    .append(line()),
+            ),
+
+            _ => None,
+        })
+        .collect();
+
+    if exports == nil() && declarations.is_empty() {
+        return nil();
+    }
+
+    let exports = if exports == nil() {
+        nil()
+    } else {
+        "-export_type(["
+            .to_doc()
+            .append(exports)
+            .append("]).")
+            .append(lines(2))
+    };
+
+    exports
+        .append(concat(declarations.into_iter()))
+        .append(line())
+}
+
+fn custom_type_declaration(
+    name: &str,
+    parameters: &[String],
+    constructors: &[TypedRecordConstructor],
+    opaque: bool,
+    module: &[String],
+) -> Document {
+    let attribute = if opaque { "-opaque" } else { "-type" };
+    let constructors = constructors.iter().map(|constructor| {
+        let tag = atom(constructor.name.to_snake_case());
+        match constructor.typ.as_ref() {
+            Type::Fn { args, retrn } => {
+                let vars = type_vars(retrn, parameters);
+                tuple(
+                    std::iter::once(tag).chain(args.iter().map(|arg| erl_type(arg, &vars, module))),
+                )
+            }
+            _ => tag,
+        }
+    });
+
+    type_head(attribute, name, parameters)
+        .append(" ::")
+        .append(
+            break_("", " ")
+                .append(join(constructors, break_("", " ").append("| ")))
+                .nest(INDENT),
+        )
+        .group()
+        .append(".")
+        .append(line())
+}
+
+fn type_head(attribute: &str, name: &str, parameters: &[String]) -> Document {
+    attribute
+        .to_doc()
+        .append(" ")
+        .append(atom(name.to_snake_case()))
+        .append("(")
+        .append(join(
+            parameters.iter().map(|parameter| {
+                // An unused parameter keeps its underscore so Erlang does not
+                // warn about it
+                if parameter.starts_with('_') {
+                    format!("_{}", parameter.to_camel_case()).to_doc()
+                } else {
+                    parameter.to_camel_case().to_doc()
+                }
+            }),
+            ", ".to_doc(),
+        ))
+        .append(")")
+}
+
+/// The names of the type variables of a custom type, keyed by the ids of the
+/// generic type variables its constructors are instantiated with.
+///
+fn type_vars(typ: &Type, parameters: &[String]) -> HashMap<usize, String> {
+    match typ {
+        Type::App { args, .. } => args
+            .iter()
+            .zip(parameters)
+            .filter_map(
+                |(arg, parameter)| match crate::typ::collapse_links(arg.clone()).as_ref() {
+                    Type::Var { typ } => match *typ.borrow() {
+                        TypeVar::Generic { id } => Some((id, parameter.to_camel_case())),
+                        _ => None,
+                    },
+                    _ => None,
+                },
+            )
+            .collect(),
+        _ => HashMap::new(),
+    }
+}
+
+fn erl_type(typ: &Type, vars: &HashMap<usize, String>, module: &[String]) -> Document {
+    let args_doc = |args: &[Arc<Type>]| {
+        join(
+            args.iter().map(|arg| erl_type(arg, vars, module)),
+            ", ".to_doc(),
+        )
+        .surround("(", ")")
+    };
+
+    match typ {
+        Type::App {
+            module: type_module,
+            name,
+            args,
+            ..
+        } if type_module.is_empty() => match (name.as_str(), args.as_slice()) {
+            ("Int", _) => "integer()".to_doc(),
+            ("Float", _) => "float()".to_doc(),
+            ("String", _) => "binary()".to_doc(),
+            ("Bool", _) => "boolean()".to_doc(),
+            ("Nil", _) => "nil".to_doc(),
+            ("BitString", _) => "bitstring()".to_doc(),
+            ("UtfCodepoint", _) => "integer()".to_doc(),
+            ("List", [elem]) => "list("
+                .to_doc()
+                .append(erl_type(elem, vars, module))
+                .append(")"),
+            ("Result", [ok, error]) => {
+                tuple(vec!["ok".to_doc(), erl_type(ok, vars, module)].into_iter())
+                    .append(" | ")
+                    .append(tuple(
+                        vec!["error".to_doc(), erl_type(error, vars, module)].into_iter(),
+                    ))
+            }
+            _ => "any()".to_doc(),
+        },
+
+        Type::App {
+            module: type_module,
+            name,
+            args,
+            ..
+        } if type_module.as_slice() == module => atom(name.to_snake_case()).append(args_doc(args)),
+
+        Type::App {
+            module: type_module,
+            name,
+            args,
+            ..
+        } => atom(type_module.join("@"))
+            .append(":")
+            .append(atom(name.to_snake_case()))
+            .append(args_doc(args)),
+
+        Type::Fn { args, retrn } => "fun("
+            .to_doc()
+            .append(args_doc(args))
+            .append(" -> ")
+            .append(erl_type(retrn, vars, module))
+            .append(")"),
+
+        Type::Tuple { elems } => tuple(elems.iter().map(|elem| erl_type(elem, vars, module))),
+
+        Type::Var { typ } => match &*typ.borrow() {
+            TypeVar::Link { typ } => erl_type(typ, vars, module),
+            TypeVar::Generic { id } => match vars.get(id) {
+                Some(name) => name.clone().to_doc(),
+                None => "any()".to_doc(),
+            },
+            TypeVar::Unbound { .. } => "any()".to_doc(),
+        },
+    }
+}
+
+fn join(docs: impl Iterator<Item = Document>, separator: Document) -> Document {
+    concat(Itertools::intersperse(docs, separator))
+}
+
+fn mod_fun(
+    name: &str,
+    args: &[TypedArg],
+    body_expr: &TypedExpr,
+    module: &[String],
+    config: &ErlangConfig,
+    coverage: Option<&Coverage<'_>>,
+) -> Document {
+    let mut env = Env::new(module, config, coverage);
 
     atom(name.to_string())
         .append(fun_args(args, &mut env))
         .append(" ->")
-        .append(line().append(expr(body, &mut env)).nest(INDENT).group())
+        .append(
+            line()
+                .append(body(body_expr, &mut env))
+                .nest(INDENT)
+                .group(),
+        )
         .append(".")
 }
 
+fn derived_fun(derived: &DerivedFunction, constructors: &[TypedRecordConstructor]) -> Document {
+    let head = atom(derived.name.clone()).append("(A, B) ->");
+    let body = match derived.derive {
+        Derive::Equal => line().append("A =:= B."),
+
+        // Each value is converted to a list of the index of its constructor
+        // followed by its fields, which Erlang compares element by element.
+        Derive::Compare => {
+            let clauses = constructors.iter().enumerate().map(|(index, constructor)| {
+                let tag = atom(constructor.name.to_snake_case());
+                let fields = (1..=constructor.args.len()).map(|i| format!("F{}", i).to_doc());
+                let pattern = if constructor.args.is_empty() {
+                    tag
+                } else {
+                    tuple(std::iter::once(tag).chain(fields.clone()))
+                };
+                let key = concat(
+                    std::iter::once(index.to_doc())
+                        .chain(fields)
+                        .intersperse(", ".to_doc()),
+                )
+                .surround("[", "]");
+                line()
+                    .append("(")
+                    .append(pattern)
+                    .append(") -> ")
+                    .append(key)
+            });
+            line()
+                .append("Key = fun")
+                .append(concat(clauses.intersperse(";".to_doc())).nest(INDENT))
+                .append(line())
+                .append("end,")
+                .append(line())
+                .append("KeyA = Key(A),")
+                .append(line())
+                .append("KeyB = Key(B),")
+                .append(line())
+                .append("if")
+                .append(
+                    line()
+                        .append("KeyA < KeyB -> -1;")
+                        .append(line())
+                        .append("KeyA > KeyB -> 1;")
+                        .append(line())
+                        .append("true -> 0")
+                        .nest(INDENT),
+                )
+                .append(line())
+                .append("end.")
+        }
+    };
+    head.append(body.nest(INDENT))
+}
+
 fn fun_args(args: &[TypedArg], env: &mut Env<'_>) -> Document {
     wrap_args(args.iter().map(|a| match &a.names {
         ArgNames::Discard { .. } | ArgNames::LabelledDiscard { .. } => "_".to_doc(),
@@ -273,7 +685,48 @@ fn atom(value: String) -> Document {
 }
 
 fn string(value: &str) -> Document {
-    value.to_doc().surround("<<\"", "\"/utf8>>")
+    string_inner(value).surround("<<", "/utf8>>")
+}
+
+/// A string literal without the binary surrounds. Gleam's `\u{...}` unicode
+/// escapes are written as Erlang's equivalent `\x{...}` escapes.
+///
+fn string_inner(value: &str) -> Document {
+    let mut escaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        escaped.push(c);
+        if c == '\\' {
+            match chars.next() {
+                Some('u') => escaped.push('x'),
+                Some(c) => escaped.push(c),
+                None => (),
+            }
+        }
+    }
+    escaped.to_doc().surround("\"", "\"")
+}
+
+/// The code of an `@erlang` expression. It is written as it is, other than
+/// the escaped quotes and backslashes of the Gleam string it was written in.
+///
+fn raw_erlang(code: &str) -> Document {
+    let mut unescaped = String::with_capacity(code.len());
+    let mut chars = code.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(c @ ('"' | '\\')) => unescaped.push(c),
+                Some(c) => {
+                    unescaped.push('\\');
+                    unescaped.push(c);
+                }
+                None => unescaped.push('\\'),
+            },
+            _ => unescaped.push(c),
+        }
+    }
+    unescaped.to_doc()
 }
 
 fn tuple(elems: impl Iterator<Item = Document>) -> Document {
@@ -297,7 +750,7 @@ fn const_segment(
 ) -> Document {
     let document = match value {
         // Skip the normal <<value/utf8>> surrounds
-        Constant::String { value, .. } => value.clone().to_doc().surround("\"", "\""),
+        Constant::String { value, .. } => string_inner(value),
 
         // As normal
         Constant::Int { .. } | Constant::Float { .. } | Constant::BitString { .. } => {
@@ -335,7 +788,7 @@ fn expr_segment(
         // Skip the normal <<value/utf8>> surrounds and set the string literal flag
         TypedExpr::String { value, .. } => {
             value_is_a_string_literal = true;
-            value.clone().to_doc().surround("\"", "\"")
+            string_inner(value)
         }
 
         // As normal
@@ -377,7 +830,7 @@ fn pattern_segment(
 ) -> Document {
     let document = match value {
         // Skip the normal <<value/utf8>> surrounds
-        Pattern::String { value, .. } => value.clone().to_doc().surround("\"", "\""),
+        Pattern::String { value, .. } => string_inner(value),
 
         // As normal
         Pattern::Discard { .. }
@@ -468,10 +921,44 @@ fn seq(first: &TypedExpr, then: &TypedExpr, env: &mut Env<'_>) -> Document {
         .append(expr(first, env))
         .append(",")
         .append(line())
-        .append(expr(then, env))
+        .append(body(then, env))
+}
+
+/// A statement in the body of a function or case clause, or the remainder of
+/// the body after an assignment. With coverage enabled the location of the
+/// statement is recorded before it is run.
+///
+fn body(expression: &TypedExpr, env: &mut Env<'_>) -> Document {
+    // The location of a sequence or a let is that of its final expression,
+    // so use the location of the expression that is run first instead
+    let location = match expression {
+        TypedExpr::Seq { first, .. } => first.location(),
+        TypedExpr::Let { location, .. } => location,
+        _ => expression.location(),
+    };
+    let coverage = match env.coverage {
+        None => return expr(expression, env),
+        Some(coverage) => coverage,
+    };
+    let line_number = coverage.line_numbers.line_index(location.start) + 1;
+    let _ = coverage.lines.borrow_mut().insert(line_number);
+    force_break()
+        .append("gleam_coverage:hit(")
+        .append(string(coverage.path))
+        .append(", ")
+        .append(line_number)
+        .append("),")
+        .append(line())
+        .append(expr(expression, env))
 }
 
 fn bin_op(name: &BinOp, left: &TypedExpr, right: &TypedExpr, env: &mut Env<'_>) -> Document {
+    if env.fold_constants {
+        if let Some(value) = optimise::fold_int(name, left, right) {
+            return value.to_doc();
+        }
+    }
+
     let op = match name {
         BinOp::And => "andalso",
         BinOp::Or => "orelse",
@@ -493,20 +980,50 @@ fn bin_op(name: &BinOp, left: &TypedExpr, right: &TypedExpr, env: &mut Env<'_>)
     };
 
     let left_expr = match left {
-        TypedExpr::BinOp { .. } => expr(left, env).surround("(", ")"),
-        _ => expr(left, env),
+        // Raw Erlang could contain operators of its own
+        TypedExpr::BinOp { .. } | TypedExpr::RawErlang { .. } if !folds_to_natural(left, env) => {
+            expr(left, env).surround("(", ")")
+        }
+        _ => maybe_block_expr(left, env),
     };
 
     let right_expr = match right {
-        TypedExpr::BinOp { .. } => expr(right, env).surround("(", ")"),
-        _ => expr(right, env),
+        // Raw Erlang could contain operators of its own
+        TypedExpr::BinOp { .. } | TypedExpr::RawErlang { .. } if !folds_to_natural(right, env) => {
+            expr(right, env).surround("(", ")")
+        }
+        _ => maybe_block_expr(right, env),
     };
 
-    left_expr
+    let doc = left_expr
         .append(break_("", " "))
         .append(op)
         .append(" ")
-        .append(right_expr)
+        .append(right_expr);
+
+    match name {
+        BinOp::AddInt | BinOp::SubInt | BinOp::MultInt | BinOp::DivInt
+            if env.int_arithmetic == IntArithmetic::Wrap64 =>
+        {
+            wrap_int_64(doc)
+        }
+        _ => doc,
+    }
+}
+
+/// Whether the operation is folded into a literal that does not need
+/// parentheses when used as an operand.
+///
+fn folds_to_natural(expression: &TypedExpr, env: &Env<'_>) -> bool {
+    env.fold_constants && matches!(optimise::int_value(expression), Some(value) if value >= 0)
+}
+
+// Wrap the result of an integer operation to a signed 64-bit integer by
+// offsetting it into the unsigned range, masking, and offsetting it back.
+fn wrap_int_64(doc: Document) -> Document {
+    "((".to_doc()
+        .append(doc)
+        .append(" + 16#8000000000000000) band 16#FFFFFFFFFFFFFFFF) - 16#8000000000000000")
 }
 
 fn pipe(value: &TypedExpr, fun: &TypedExpr, env: &mut Env<'_>) -> Document {
@@ -524,7 +1041,7 @@ fn try_(value: &TypedExpr, pat: &TypedPattern, then: &TypedExpr, env: &mut Env<'
 
     "case "
         .to_doc()
-        .append(expr(value, env))
+        .append(maybe_block_expr(value, env))
         .append(" of")
         .append(
             line()
@@ -540,7 +1057,7 @@ fn try_(value: &TypedExpr, pat: &TypedPattern, then: &TypedExpr, env: &mut Env<'
                 .append("{ok, ")
                 .append(pattern(pat, env))
                 .append("} ->")
-                .append(line().append(expr(then, env)).nest(INDENT))
+                .append(line().append(body(then, env)).nest(INDENT))
                 .nest(INDENT),
         )
         .append(line())
@@ -548,14 +1065,85 @@ fn try_(value: &TypedExpr, pat: &TypedPattern, then: &TypedExpr, env: &mut Env<'
         .group()
 }
 
+fn assert(
+    value: &TypedExpr,
+    pat: &TypedPattern,
+    then: &TypedExpr,
+    message: &Option<Box<TypedExpr>>,
+    location: &SrcSpan,
+    env: &mut Env<'_>,
+) -> Document {
+    // Patterns that always match need no failure clause
+    if pat.is_irrefutable() {
+        return let_(value, pat, then, env);
+    }
+
+    let assert_fail_name = "gleam@assert_fail";
+    let value = maybe_block_expr(value, env);
+    let message = match message {
+        Some(message) => maybe_block_expr(message, env),
+        None => string("Assertion pattern match failed"),
+    };
+    let fail_var = env.next_local_var_name(assert_fail_name.to_string());
+    let pattern = pattern(pat, env);
+    let then = body(then, env);
+
+    let error = map(vec![
+        ("gleam_error", "assert".to_doc()),
+        ("message", message),
+        ("value", fail_var.clone()),
+        ("module", string(&env.module.join("/"))),
+        (
+            "location",
+            tuple(vec![location.start.to_doc(), location.end.to_doc()].into_iter()),
+        ),
+    ]);
+
+    "case "
+        .to_doc()
+        .append(value)
+        .append(" of")
+        .append(
+            line()
+                .append(pattern)
+                .append(" ->")
+                .append(line().append(then).nest(INDENT))
+                .append(";")
+                .append(lines(2))
+                .append(fail_var)
+                .append(" ->")
+                .append(
+                    line()
+                        .append("erlang:error(")
+                        .append(error)
+                        .append(")")
+                        .nest(INDENT),
+                )
+                .nest(INDENT),
+        )
+        .append(line())
+        .append("end")
+        .group()
+}
+
+fn map(fields: Vec<(&str, Document)>) -> Document {
+    let fields = fields
+        .into_iter()
+        .map(|(key, value)| atom(key.to_string()).append(" => ").append(value));
+    concat(fields.intersperse(delim(",")))
+        .nest_current()
+        .surround("#{", "}")
+        .group()
+}
+
 fn let_(value: &TypedExpr, pat: &TypedPattern, then: &TypedExpr, env: &mut Env<'_>) -> Document {
-    let body = maybe_block_expr(value, env);
+    let value = maybe_block_expr(value, env);
     pattern(pat, env)
         .append(" = ")
-        .append(body)
+        .append(value)
         .append(",")
         .append(line())
-        .append(expr(then, env))
+        .append(body(then, env))
 }
 
 fn pattern(p: &TypedPattern, env: &mut Env<'_>) -> Document {
@@ -582,7 +1170,7 @@ fn pattern(p: &TypedPattern, env: &mut Env<'_>) -> Document {
 
         Pattern::Constructor {
             args,
-            constructor: PatternConstructor::Record { name },
+            constructor: PatternConstructor::Record { name, .. },
             ..
         } => tag_tuple_pattern(name, args, env),
 
@@ -804,7 +1392,7 @@ fn clause(clause: &TypedClause, env: &mut Env<'_>) -> Document {
             };
 
             if then_doc == Document::Nil {
-                then_doc = expr(then, env);
+                then_doc = body(then, env);
             }
 
             patterns_doc.append(
@@ -1061,6 +1649,24 @@ fn record_update(spread: &TypedExpr, args: &[TypedRecordUpdateArg], env: &mut En
     })
 }
 
+/// Raise an error with the evaluated message, if there is one.
+///
+fn todo_or_panic(
+    kind: &'static str,
+    message: &Option<Box<TypedExpr>>,
+    env: &mut Env<'_>,
+) -> Document {
+    match message {
+        None => kind.to_doc().surround("erlang:error({gleam_error, ", "})"),
+        Some(message) => "erlang:error({gleam_error, "
+            .to_doc()
+            .append(kind)
+            .append(", ")
+            .append(maybe_block_expr(message, env))
+            .append("})"),
+    }
+}
+
 /// Wrap a document in begin end
 ///
 fn begin_end(document: Document) -> Document {
@@ -1084,12 +1690,11 @@ fn expr(expression: &TypedExpr, env: &mut Env<'_>) -> Document {
     match expression {
         TypedExpr::ListNil { .. } => "[]".to_doc(),
 
-        TypedExpr::Todo { label: None, .. } => "erlang:error({gleam_error, todo})".to_doc(),
+        TypedExpr::Todo { message, .. } => todo_or_panic("todo", message, env),
 
-        TypedExpr::Todo { label: Some(l), .. } => l
-            .clone()
-            .to_doc()
-            .surround("erlang:error({gleam_error, todo, \"", "\"})"),
+        TypedExpr::Panic { message, .. } => todo_or_panic("panic", message, env),
+
+        TypedExpr::RawErlang { code, .. } => raw_erlang(code),
 
         TypedExpr::Int { value, .. } => int(value.as_ref()),
         TypedExpr::Float { value, .. } => float(value.as_ref()),
@@ -1150,16 +1755,22 @@ fn expr(expression: &TypedExpr, env: &mut Env<'_>) -> Document {
             value,
             pattern,
             then,
-            kind: BindingKind::Try,
-            ..
-        } => try_(value, pattern, then, env),
-
-        TypedExpr::Let {
-            value,
-            pattern,
-            then,
+            kind,
+            location,
+            message,
             ..
-        } => let_(value, pattern, then, env),
+        } => {
+            // The variables of the pattern are only in scope in the following
+            // expressions, so a block's variables do not shadow those outside it
+            let vars = env.current_scope_vars.clone();
+            let doc = match kind {
+                BindingKind::Try => try_(value, pattern, then, env),
+                BindingKind::Assert => assert(value, pattern, then, message, location, env),
+                BindingKind::Let => let_(value, pattern, then, env),
+            };
+            env.current_scope_vars = vars;
+            doc
+        }
 
         TypedExpr::Case {
             subjects, clauses, ..
@@ -1182,7 +1793,7 @@ fn expr(expression: &TypedExpr, env: &mut Env<'_>) -> Document {
 fn tuple_index(tuple: &TypedExpr, index: u64, env: &mut Env<'_>) -> Document {
     use std::iter::once;
     let index_doc = format!("{}", (index + 1)).to_doc();
-    let tuple_doc = expr(tuple, env);
+    let tuple_doc = maybe_block_expr(tuple, env);
     let iter = once(index_doc).chain(once(tuple_doc));
     "erlang:element".to_doc().append(wrap_args(iter))
 }
@@ -1206,12 +1817,12 @@ fn module_select_fn(typ: Arc<Type>, module_name: &[String], label: &str) -> Docu
     }
 }
 
-fn fun(args: &[TypedArg], body: &TypedExpr, env: &mut Env<'_>) -> Document {
+fn fun(args: &[TypedArg], body_expr: &TypedExpr, env: &mut Env<'_>) -> Document {
     let current_scope_vars = env.current_scope_vars.clone();
     let doc = "fun"
         .to_doc()
         .append(fun_args(args, env).append(" ->"))
-        .append(break_("", " ").append(expr(body, env)).nest(INDENT))
+        .append(break_("", " ").append(body(body_expr, env)).nest(INDENT))
         .append(break_("", " "))
         .append("end")
         .group();
@@ -0,0 +1,99 @@
+use crate::ast::{
+    parse_int,
+    visit::{walk_expr, Visit},
+    BinOp, Statement, TypedExpr, TypedModule,
+};
+use crate::typ::ValueConstructorVariant;
+use std::collections::{HashMap, HashSet};
+
+/// The private functions of the module that cannot be reached from any of its
+/// public functions, and so can be left out of a release build.
+///
+pub fn unused_functions(module: &TypedModule) -> HashSet<&str> {
+    let mut private = HashMap::new();
+    let mut to_visit = vec![];
+    for statement in module.statements.iter() {
+        match statement {
+            Statement::Fn {
+                public: false,
+                name,
+                body,
+                ..
+            } => {
+                let _ = private.insert(name.as_str(), body);
+            }
+            Statement::Fn { body, .. } => to_visit.push(body),
+            _ => (),
+        }
+    }
+
+    let mut used = HashSet::new();
+    while let Some(body) = to_visit.pop() {
+        let mut referenced = vec![];
+        referenced_functions(body, module.name.as_slice(), &mut referenced);
+        for name in referenced {
+            if used.insert(name) {
+                to_visit.extend(private.get(name));
+            }
+        }
+    }
+
+    private
+        .keys()
+        .filter(|name| !used.contains(*name))
+        .copied()
+        .collect()
+}
+
+/// Collect the names of the functions of the given module that are used in
+/// the expression.
+///
+fn referenced_functions<'a>(expr: &'a TypedExpr, module: &[String], names: &mut Vec<&'a str>) {
+    ReferencedFunctions { module, names }.visit_expr(expr)
+}
+
+struct ReferencedFunctions<'a, 'b> {
+    module: &'b [String],
+    names: &'b mut Vec<&'a str>,
+}
+
+impl<'a, 'b> Visit<'a> for ReferencedFunctions<'a, 'b> {
+    fn visit_expr(&mut self, expr: &'a TypedExpr) {
+        if let TypedExpr::Var { constructor, .. } = expr {
+            if let ValueConstructorVariant::ModuleFn { name, module, .. } = &constructor.variant {
+                if module.as_slice() == self.module {
+                    self.names.push(name);
+                }
+            }
+        }
+        walk_expr(self, expr)
+    }
+}
+
+/// The value of an integer operation whose operands are literals, or are
+/// themselves such operations. Operations that could overflow a 64-bit
+/// integer are left for the Erlang runtime so that the result is the same
+/// whichever integer arithmetic is used.
+///
+pub fn fold_int(name: &BinOp, left: &TypedExpr, right: &TypedExpr) -> Option<i64> {
+    let left = int_value(left)?;
+    let right = int_value(right)?;
+    match name {
+        BinOp::AddInt => left.checked_add(right),
+        BinOp::SubInt => left.checked_sub(right),
+        BinOp::MultInt => left.checked_mul(right),
+        _ => None,
+    }
+}
+
+/// The value of an integer literal, or of an operation that can be folded.
+///
+pub fn int_value(expr: &TypedExpr) -> Option<i64> {
+    match expr {
+        TypedExpr::Int { value, .. } => parse_int(value),
+        TypedExpr::BinOp {
+            name, left, right, ..
+        } => fold_int(name, left, right),
+        _ => None,
+    }
+}
@@ -1,4 +1,5 @@
 use super::*;
+use crate::config::ReleaseProfile;
 
 #[test]
 fn record_definition_test() {
@@ -14,17 +15,56 @@ fn record_definition_test() {
     );
 }
 
+#[test]
+fn module_header_test() {
+    let header = |src| {
+        let mut ast = crate::grammar::ModuleParser::new()
+            .parse(src)
+            .expect("syntax error");
+        ast.name = vec!["the_app".to_string()];
+        let ast = crate::typ::infer_module(&mut 0, ast, "", &HashMap::new(), &mut vec![])
+            .expect("should successfully infer");
+        module_header(&ast)
+    };
+
+    // Each public constructor with labelled fields is a record
+    assert_eq!(
+        Some(
+            "-record(circle, {radius}).
+-record(rectangle, {width, height}).
+"
+            .to_string()
+        ),
+        header(
+            "pub type Shape { Circle(radius: Float) Rectangle(width: Float, height: Float) }
+pub type Pair { Pair(Int, Int) }
+type Private { Private(x: Int) }"
+        )
+    );
+
+    assert_eq!(None, header("pub type Pair { Pair(Int, Int) }"));
+}
+
 macro_rules! assert_erl {
     ($src:expr, $erl:expr $(,)?) => {
+        assert_erl!(&Default::default(), $src, $erl);
+    };
+
+    ($config:expr, $src:expr, $erl:expr $(,)?) => {
         println!("\n\n\n{}\n", $src);
         let mut ast = crate::grammar::ModuleParser::new()
             .parse($src)
             .expect("syntax error");
         ast.name = vec!["the_app".to_string()];
-        let ast =
-            crate::typ::infer_module(&mut 0, ast, &std::collections::HashMap::new(), &mut vec![])
-                .expect("should successfully infer");
-        let output = module(&ast);
+        let ast = crate::typ::infer_module(
+            &mut 0,
+            ast,
+            "",
+            &std::collections::HashMap::new(),
+            &mut vec![],
+        )
+        .expect("should successfully infer");
+        let output = module(&ast, $config);
         assert_eq!(($src, output), ($src, $erl.to_string()));
     };
 }
@@ -615,6 +655,68 @@ main(Args) ->
     );
 }
 
+#[test]
+fn bit_string_rest() {
+    assert_erl!(
+        r#"
+pub fn split(packet: BitString) {
+  case packet {
+    <<version:8, length:16-unsigned-big, header:binary-size(4), rest:bits>> ->
+      tuple(version, length, header, rest)
+    <<rest:bytes>> -> tuple(0, 0, rest, rest)
+  }
+}
+"#,
+        r#"-module(the_app).
+-compile(no_auto_import).
+
+-export([split/1]).
+
+split(Packet) ->
+    case Packet of
+        <<Version:8, Length:16/unsigned-big, Header:4/binary, Rest/bitstring>> ->
+            {Version, Length, Header, Rest};
+
+        <<Rest@1/binary>> ->
+            {0, 0, Rest@1, Rest@1}
+    end.
+"#
+    );
+}
+
+#[test]
+fn bit_string_endianness() {
+    assert_erl!(
+        r#"
+pub fn read(header: BitString) {
+  case header {
+    <<offset:little-signed-size(16), length:size(32)-unsigned-big, ratio:float-native>> ->
+      tuple(offset, length, ratio)
+    <<char:utf16_codepoint-little, _:bits>> -> tuple(0, 0, 0.0)
+    _ -> tuple(-1, 0, 0.0)
+  }
+}
+"#,
+        r#"-module(the_app).
+-compile(no_auto_import).
+
+-export([read/1]).
+
+read(Header) ->
+    case Header of
+        <<Offset:16/little-signed, Length:32/unsigned-big, Ratio/float-native>> ->
+            {Offset, Length, Ratio};
+
+        <<Char/utf16-little, _/bitstring>> ->
+            {0, 0, 0.0};
+
+        _ ->
+            {-1, 0, 0.0}
+    end.
+"#
+    );
+}
+
 #[test]
 fn bit_string_discard() {
     // https://github.com/gleam-lang/gleam/issues/704
@@ -1291,7 +1393,59 @@ pub fn main() {
 -export([main/0]).
 
 main() ->
-    erlang:error({gleam_error, todo, "testing"}).
+    erlang:error({gleam_error, todo, <<"testing"/utf8>>}).
+"#,
+    );
+
+    assert_erl!(
+        r#"
+pub fn main(name) {
+  let message = "Not yet implemented"
+  todo as message
+}
+"#,
+        r#"-module(the_app).
+-compile(no_auto_import).
+
+-export([main/1]).
+
+main(Name) ->
+    Message = <<"Not yet implemented"/utf8>>,
+    erlang:error({gleam_error, todo, Message}).
+"#,
+    );
+
+    assert_erl!(
+        r#"
+pub fn main() {
+  panic
+}
+"#,
+        r#"-module(the_app).
+-compile(no_auto_import).
+
+-export([main/0]).
+
+main() ->
+    erlang:error({gleam_error, panic}).
+"#,
+    );
+
+    assert_erl!(
+        r#"
+external fn describe(Int) -> String = "erlang" "integer_to_binary"
+
+pub fn main(x) {
+  panic as describe(x)
+}
+"#,
+        r#"-module(the_app).
+-compile(no_auto_import).
+
+-export([main/1]).
+
+main(X) ->
+    erlang:error({gleam_error, panic, erlang:integer_to_binary(X)}).
 "#,
     );
 
@@ -2016,13 +2170,95 @@ fn main() {
 -compile(no_auto_import).
 
 main() ->
-    100000 = 1,
-    100000.00101 = 1.0,
-    1.
+    case 1 of
+        100000 ->
+            case 1.0 of
+                100000.00101 ->
+                    1;
+
+                GleamAssertFail@1 ->
+                    erlang:error(#{gleam_error => assert,
+                                   message => <<"Assertion pattern match failed"/utf8>>,
+                                   value => GleamAssertFail@1,
+                                   module => <<"the_app"/utf8>>,
+                                   location => {36, 61}})
+            end;
+
+        GleamAssertFail ->
+            erlang:error(#{gleam_error => assert,
+                           message => <<"Assertion pattern match failed"/utf8>>,
+                           value => GleamAssertFail,
+                           module => <<"the_app"/utf8>>,
+                           location => {15, 33}})
+    end.
 "#,
     );
 }
 
+#[test]
+fn assert_message() {
+    assert_erl!(
+        r#"
+fn main(x) {
+  assert Ok(y) = x as "x should be Ok"
+  y
+}
+"#,
+        r#"-module(the_app).
+-compile(no_auto_import).
+
+main(X) ->
+    case X of
+        {ok, Y} ->
+            Y;
+
+        GleamAssertFail ->
+            erlang:error(#{gleam_error => assert,
+                           message => <<"x should be Ok"/utf8>>,
+                           value => GleamAssertFail,
+                           module => <<"the_app"/utf8>>,
+                           location => {16, 32}})
+    end.
+"#,
+    );
+}
+
+#[test]
+fn assert_message_run_test() {
+    use crate::test_support::{compile, erlang_is_installed};
+
+    let compiled = compile(&[(
+        "asserts",
+        r#"pub fn with_message() {
+  assert Ok(x) = Error(1) as "should be Ok"
+  x
+}
+
+pub fn without_message() {
+  assert Ok(x) = Error(1)
+  x
+}
+"#,
+    )])
+    .expect("should compile");
+
+    // Running the code needs an Erlang installation
+    if !erlang_is_installed() {
+        return;
+    }
+    let output = compiled.run("asserts", "with_message").unwrap();
+    assert!(!output.success);
+    assert!(output.value.contains("should be Ok"), "{}", output.value);
+
+    let output = compiled.run("asserts", "without_message").unwrap();
+    assert!(!output.success);
+    assert!(
+        output.value.contains("Assertion pattern match failed"),
+        "{}",
+        output.value
+    );
+}
+
 // https://github.com/gleam-lang/gleam/issues/777
 #[test]
 fn block_assignment() {
@@ -2049,6 +2285,58 @@ main() ->
     );
 }
 
+#[test]
+fn block_scope() {
+    // Variables bound within a block do not shadow those outside it
+    assert_erl!(
+        r#"
+fn main() {
+  let y = 1
+  let x = {
+    let y = 2
+    y
+  }
+  {
+    let y = 3
+    y
+  }
+  x + y
+}
+"#,
+        r#"-module(the_app).
+-compile(no_auto_import).
+
+main() ->
+    Y = 1,
+    X = begin
+        Y@1 = 2,
+        Y@1
+    end,
+    Y@2 = 3,
+    Y@2,
+    X + Y.
+"#,
+    );
+
+    // Blocks used as operands are wrapped with begin and end
+    assert_erl!(
+        r#"
+fn main(x) {
+  x + { let x = 2 x } + x
+}
+"#,
+        r#"-module(the_app).
+-compile(no_auto_import).
+
+main(X) ->
+    (X + begin
+        X@1 = 2,
+        X@1
+    end) + X.
+"#,
+    );
+}
+
 #[test]
 fn recursive_type() {
     // TODO: we should be able to generalise `id` and we should be
@@ -2122,3 +2410,650 @@ a() ->
 "
     );
 }
+
+#[test]
+fn wrapping_int_arithmetic() {
+    // Integer arithmetic is bignum by default
+    assert_erl!(
+        "pub fn add(x, y) { x + y }",
+        "-module(the_app).
+-compile(no_auto_import).
+
+-export([add/2]).
+
+add(X, Y) ->
+    X + Y.
+"
+    );
+
+    // Integer arithmetic can be wrapped to 64-bit
+    assert_erl!(
+        &ErlangConfig {
+            int_arithmetic: IntArithmetic::Wrap64,
+            type_declarations: false,
+            module_headers: false,
+            coverage: false,
+            release: None,
+        },
+        "pub fn add(x, y) { x + y }",
+        "-module(the_app).
+-compile(no_auto_import).
+
+-export([add/2]).
+
+add(X, Y) ->
+    ((X + Y + 16#8000000000000000) band 16#FFFFFFFFFFFFFFFF) - 16#8000000000000000.
+"
+    );
+
+    // Float arithmetic is unaffected
+    assert_erl!(
+        &ErlangConfig {
+            int_arithmetic: IntArithmetic::Wrap64,
+            type_declarations: false,
+            module_headers: false,
+            coverage: false,
+            release: None,
+        },
+        "pub fn add(x, y) { x +. y }",
+        "-module(the_app).
+-compile(no_auto_import).
+
+-export([add/2]).
+
+add(X, Y) ->
+    X + Y.
+"
+    );
+}
+
+#[test]
+fn release_build() {
+    let release = ErlangConfig {
+        int_arithmetic: IntArithmetic::Bignum,
+        type_declarations: false,
+        module_headers: false,
+        coverage: false,
+        release: Some(Default::default()),
+    };
+    let src = "fn unused() { helper() }
+fn helper() { 1 }
+fn used(x) { x * { 2 + 3 } }
+fn recursive(x) { used(recursive(x)) }
+pub fn go(x) { used(x) - 0x10 }";
+
+    // A release build leaves out private functions that cannot be called from
+    // a public function, and folds arithmetic on integer literals
+    assert_erl!(
+        &release,
+        src,
+        "-module(the_app).
+-compile(no_auto_import).
+
+-export([go/1]).
+
+used(X) ->
+    X * 5.
+
+go(X) ->
+    used(X) - 16#10.
+"
+    );
+
+    // A debug build keeps them
+    assert_erl!(
+        src,
+        "-module(the_app).
+-compile(no_auto_import).
+
+-export([go/1]).
+
+unused() ->
+    helper().
+
+helper() ->
+    1.
+
+used(X) ->
+    X * (2 + 3).
+
+recursive(X) ->
+    used(recursive(X)).
+
+go(X) ->
+    used(X) - 16#10.
+"
+    );
+
+    // Each optimisation can be turned off
+    assert_erl!(
+        &ErlangConfig {
+            release: Some(ReleaseProfile {
+                fold_constants: false,
+                remove_unused_functions: true,
+            }),
+            ..Default::default()
+        },
+        "fn unused() { 1 } pub fn go() { 1 + 1 }",
+        "-module(the_app).
+-compile(no_auto_import).
+
+-export([go/0]).
+
+go() ->
+    1 + 1.
+"
+    );
+
+    // Arithmetic that could overflow a 64-bit integer is left to the runtime
+    assert_erl!(
+        &release,
+        "pub fn go() { 9223372036854775807 + 1 - { 3 - 5 } }",
+        "-module(the_app).
+-compile(no_auto_import).
+
+-export([go/0]).
+
+go() ->
+    (9223372036854775807 + 1) - (-2).
+"
+    );
+}
+
+#[test]
+fn release_build_unused_functions() {
+    let release = ErlangConfig {
+        release: Some(Default::default()),
+        ..Default::default()
+    };
+
+    // Functions reached through closures, pipes, case clauses and function
+    // references are kept, while functions only used by each other are not
+    assert_erl!(
+        &release,
+        "external fn apply(fn(Int) -> Int, Int) -> Int = \"erlang\" \"apply\"
+fn double(x) { x * 2 }
+fn negate(x) { 0 - x }
+fn pick(x) { case x { 0 -> negate 1 -> double _ -> fn(y) { y |> double } } }
+fn ping(x) { pong(x) }
+fn pong(x) { ping(x) }
+pub fn go(x) { apply(pick(x), x) }",
+        "-module(the_app).
+-compile(no_auto_import).
+
+-export([go/1]).
+
+double(X) ->
+    X * 2.
+
+negate(X) ->
+    0 - X.
+
+pick(X) ->
+    case X of
+        0 ->
+            fun negate/1;
+
+        1 ->
+            fun double/1;
+
+        _ ->
+            fun(Y) -> double(Y) end
+    end.
+
+go(X) ->
+    erlang:apply(pick(X), X).
+"
+    );
+}
+
+#[test]
+fn type_declarations() {
+    let config = ErlangConfig {
+        int_arithmetic: IntArithmetic::Bignum,
+        type_declarations: true,
+        module_headers: false,
+        coverage: false,
+        release: None,
+    };
+
+    // Each constructor is declared as the tag or tagged tuple that represents it
+    assert_erl!(
+        &config,
+        "pub type Shape { Circle(Float) Point }
+pub fn origin() { Point }",
+        "-module(the_app).
+-compile(no_auto_import).
+
+-export([origin/0]).
+
+-export_type([shape/0]).
+
+-type shape() :: {circle, float()} | point.
+
+origin() ->
+    point.
+"
+    );
+
+    assert_erl!(
+        &config,
+        "pub opaque type Box(a) { Box(inner: a, label: String) }
+type Private { Private(List(Box(Int)), fn(Int) -> Result(Nil, Bool)) }
+pub external type Handle(a)
+pub fn new(x) { Box(x, \"\") }",
+        "-module(the_app).
+-compile(no_auto_import).
+
+-export([new/1]).
+
+-export_type([box/1, handle/1]).
+
+-opaque box(A) :: {box, A, binary()}.
+-type private() ::
+    {private,
+     list(box(integer())),
+     fun((integer()) -> {ok, nil} | {error, boolean()})}.
+-type handle(A) :: any().
+
+new(X) ->
+    {box, X, <<\"\"/utf8>>}.
+"
+    );
+
+    // A phantom type parameter keeps its underscore
+    assert_erl!(
+        &config,
+        "pub type Id(_kind) { Id(Int) }
+pub fn new(x) { Id(x) }",
+        "-module(the_app).
+-compile(no_auto_import).
+
+-export([new/1]).
+
+-export_type([id/1]).
+
+-type id(_Kind) :: {id, integer()}.
+
+new(X) ->
+    {id, X}.
+"
+    );
+}
+
+#[test]
+fn assert_pattern() {
+    // A matching assert binds the variables of the pattern, and
+    // a failure clause raises an error if the pattern does not match
+    assert_erl!(
+        r#"pub fn main(x) {
+  assert Ok(y) = x
+  y
+}"#,
+        r#"-module(the_app).
+-compile(no_auto_import).
+
+-export([main/1]).
+
+main(X) ->
+    case X of
+        {ok, Y} ->
+            Y;
+
+        GleamAssertFail ->
+            erlang:error(#{gleam_error => assert,
+                           message => <<"Assertion pattern match failed"/utf8>>,
+                           value => GleamAssertFail,
+                           module => <<"the_app"/utf8>>,
+                           location => {19, 35}})
+    end.
+"#,
+    );
+
+    // Patterns that always match need no failure clause
+    assert_erl!(
+        r#"pub fn main(x) {
+  assert y = x
+  y
+}"#,
+        r#"-module(the_app).
+-compile(no_auto_import).
+
+-export([main/1]).
+
+main(X) ->
+    Y = X,
+    Y.
+"#,
+    );
+}
+
+#[test]
+fn negative_literal_patterns() {
+    assert_erl!(
+        r#"pub fn main(x, y) {
+  case x {
+    -1 -> 1
+    -0xF -> 2
+    _ -> 3
+  }
+  case y {
+    -0.0 -> 1
+    -2.5 -> 2
+    _ -> 3
+  }
+}"#,
+        r#"-module(the_app).
+-compile(no_auto_import).
+
+-export([main/2]).
+
+main(X, Y) ->
+    case X of
+        -1 ->
+            1;
+
+        -16#F ->
+            2;
+
+        _ ->
+            3
+    end,
+    case Y of
+        -0.0 ->
+            1;
+
+        -2.5 ->
+            2;
+
+        _ ->
+            3
+    end.
+"#,
+    );
+}
+
+#[test]
+fn nested_record_access() {
+    assert_erl!(
+        r#"
+pub type Inner { Inner(value: Int) }
+pub type Outer { Outer(label: String, inner: Inner) }
+pub fn main(x: Outer) { x.inner.value }
+"#,
+        r#"-module(the_app).
+-compile(no_auto_import).
+
+-export([main/1]).
+
+main(X) ->
+    erlang:element(2, erlang:element(3, X)).
+"#,
+    );
+}
+
+#[test]
+fn nil_value() {
+    assert_erl!(
+        r#"
+pub const none = Nil
+pub fn go() { Nil }
+pub fn check(x) { case x { Nil -> tuple(none, [Nil]) } }
+pub fn guard(x) { case x { y if y == Nil -> 1 _ -> 2 } }
+"#,
+        r#"-module(the_app).
+-compile(no_auto_import).
+
+-export([go/0, check/1, guard/1]).
+
+go() ->
+    nil.
+
+check(X) ->
+    case X of
+        nil ->
+            {nil, [nil]}
+    end.
+
+guard(X) ->
+    case X of
+        Y when Y =:= nil ->
+            1;
+
+        _ ->
+            2
+    end.
+"#,
+    );
+}
+
+#[test]
+fn unicode_escape() {
+    assert_erl!(
+        r#"pub fn go(x) {
+  case x {
+    "\u{1F600}" -> <<"\u{E9}":utf8>>
+    _ -> <<"\\u{41}\u{41}":utf8>>
+  }
+}"#,
+        r#"-module(the_app).
+-compile(no_auto_import).
+
+-export([go/1]).
+
+go(X) ->
+    case X of
+        <<"\x{1F600}"/utf8>> ->
+            <<"\x{E9}"/utf8>>;
+
+        _ ->
+            <<"\\u{41}\x{41}"/utf8>>
+    end.
+"#,
+    );
+}
+
+#[test]
+fn derived_functions() {
+    assert_erl!(
+        r#"@derive(equal, compare)
+pub type Shape {
+  Circle(radius: Float)
+  Rectangle(Float, Float)
+  Point
+}
+
+pub fn same(a, b) {
+  shape_equal(a, b)
+}"#,
+        r#"-module(the_app).
+-compile(no_auto_import).
+
+-export([shape_equal/2, shape_compare/2, same/2]).
+
+shape_equal(A, B) ->
+    A =:= B.
+
+shape_compare(A, B) ->
+    Key = fun
+        ({circle, F1}) -> [0, F1];
+        ({rectangle, F1, F2}) -> [1, F1, F2];
+        (point) -> [2]
+    end,
+    KeyA = Key(A),
+    KeyB = Key(B),
+    if
+        KeyA < KeyB -> -1;
+        KeyA > KeyB -> 1;
+        true -> 0
+    end.
+
+same(A, B) ->
+    shape_equal(A, B).
+"#,
+    );
+
+    // Derived functions of private types are not exported
+    assert_erl!(
+        r#"@derive(equal)
+type Box(a) {
+  Box(a)
+}"#,
+        r#"-module(the_app).
+-compile(no_auto_import).
+
+box_equal(A, B) ->
+    A =:= B.
+"#,
+    );
+}
+
+#[test]
+fn multiple_subject_case() {
+    assert_erl!(
+        r#"pub fn go(x, y) {
+  case x, y {
+    0, _ | _, [] -> 0
+    1, [first, ..] -> first
+    _, _ -> 2
+  }
+}"#,
+        r#"-module(the_app).
+-compile(no_auto_import).
+
+-export([go/2]).
+
+go(X, Y) ->
+    case {X, Y} of
+        {0, _} ->
+            0;
+
+        {_, []} ->
+            0;
+
+        {1, [First | _]} ->
+            First;
+
+        {_, _} ->
+            2
+    end.
+"#,
+    );
+}
+
+#[test]
+fn coverage_instrumentation() {
+    let src = "pub fn go(x) {
+  let y = x + 1
+  case y {
+    1 -> 2
+    _ ->
+      y
+  }
+}
+";
+    let mut ast = crate::grammar::ModuleParser::new()
+        .parse(src)
+        .expect("syntax error");
+    ast.name = vec!["the_app".to_string()];
+    let ast = crate::typ::infer_module(&mut 0, ast, "", &HashMap::new(), &mut vec![])
+        .expect("should successfully infer");
+    let (output, lines) = instrumented_module(&ast, &Default::default(), "src/the_app.gleam", src);
+    assert_eq!(
+        r#"-module(the_app).
+-compile(no_auto_import).
+
+-export([go/1]).
+
+go(X) ->
+    gleam_coverage:hit(<<"src/the_app.gleam"/utf8>>, 2),
+    Y = X + 1,
+    gleam_coverage:hit(<<"src/the_app.gleam"/utf8>>, 3),
+    case Y of
+        1 ->
+            gleam_coverage:hit(<<"src/the_app.gleam"/utf8>>, 4),
+            2;
+
+        _ ->
+            gleam_coverage:hit(<<"src/the_app.gleam"/utf8>>, 6),
+            Y
+    end.
+"#,
+        output
+    );
+    assert_eq!(vec![2, 3, 4, 6], lines);
+}
+
+#[test]
+fn raw_erlang() {
+    assert_erl!(
+        r#"pub fn now() -> Int {
+  @erlang("erlang:system_time(millisecond)") as Int
+}
+"#,
+        r#"-module(the_app).
+-compile(no_auto_import).
+
+-export([now/0]).
+
+now() ->
+    erlang:system_time(millisecond).
+"#,
+    );
+
+    // Quotes are unescaped, and the code is given parentheses as an operand
+    assert_erl!(
+        r#"pub fn go(x) {
+  x * { @erlang("length(\"abc\") + 1") as Int }
+}
+"#,
+        r#"-module(the_app).
+-compile(no_auto_import).
+
+-export([go/1]).
+
+go(X) ->
+    X * (length("abc") + 1).
+"#,
+    );
+}
+
+#[test]
+fn hash_tuples() {
+    assert_erl!(
+        r#"pub fn go(triple: #(Int, Int, Int)) {
+  let #(a, _, #(b)) = #(triple, #(), #(1))
+  #(a, b)
+}
+"#,
+        r#"-module(the_app).
+-compile(no_auto_import).
+
+-export([go/1]).
+
+go(Triple) ->
+    {A, _, {B}} = {Triple, {}, {1}},
+    {A, B}.
+"#,
+    );
+}
+
+#[test]
+fn let_tuple_pattern() {
+    assert_erl!(
+        r#"pub fn go(pair) {
+  let tuple(a, tuple(b, _)) = pair
+  a + b
+}
+"#,
+        r#"-module(the_app).
+-compile(no_auto_import).
+
+-export([go/1]).
+
+go(Pair) ->
+    {A, {B, _}} = Pair,
+    A + B.
+"#,
+    );
+}
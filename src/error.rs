@@ -1,9 +1,11 @@
 use crate::{
+    ast::BinOp,
     cli,
     diagnostic::{
         write, write_diagnostic, write_project, Diagnostic, DiagnosticLabel, LabelStyle,
         MultiLineDiagnostic, ProjectErrorDiagnostic, Severity,
     },
+    parser::UnbalancedBrace,
     typ::pretty::Printer,
 };
 use itertools::Itertools;
@@ -84,6 +86,7 @@ pub enum Error {
         path: PathBuf,
         src: String,
         modules: Vec<String>,
+        searched: Vec<PathBuf>,
     },
 
     DuplicateModule {
@@ -92,6 +95,12 @@ pub enum Error {
         second: PathBuf,
     },
 
+    InvalidModuleName {
+        path: PathBuf,
+        name: Name,
+        segment: String,
+    },
+
     SrcImportingTest {
         path: PathBuf,
         src: Src,
@@ -144,6 +153,86 @@ pub enum Error {
         name: String,
         reason: InvalidProjectNameReason,
     },
+
+    MissingProjectName,
+
+    InvalidGlob {
+        glob: String,
+        err: String,
+    },
+
+    UnknownDiagnosticCode {
+        code: String,
+        codes: Vec<String>,
+    },
+
+    MissingConfig {
+        path: PathBuf,
+        searched_up_to: Option<PathBuf>,
+    },
+
+    InvalidConfig {
+        path: PathBuf,
+        err: String,
+    },
+
+    DuplicateDependency {
+        name: String,
+    },
+
+    InvalidUtf8 {
+        path: PathBuf,
+        offset: usize,
+    },
+
+    DeniedTodo {
+        locations: Vec<String>,
+    },
+
+    UnsupportedBuildToolFlag {
+        flag: String,
+    },
+
+    SourceManifestMismatch {
+        changes: Vec<(PathBuf, SourceChange)>,
+    },
+
+    InvalidEntrypoint {
+        module: String,
+        function: String,
+        reason: InvalidEntrypointReason,
+    },
+}
+
+/// The status the `gleam` binary exits with, so that scripts can tell kinds
+/// of failure apart without parsing the error output.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExitCode {
+    /// The command completed successfully.
+    Success = 0,
+
+    /// The project's code could not be compiled, or for `gleam format
+    /// --check`, has not been formatted.
+    CompileError = 1,
+
+    /// The command was invoked incorrectly, such as with an unknown flag or
+    /// an invalid project name.
+    UsageError = 2,
+
+    /// A file, directory, archive, or standard IO stream could not be read
+    /// or written.
+    IOError = 3,
+
+    /// An external program or service, such as a shell command or Hex,
+    /// failed.
+    ExternalError = 4,
+}
+
+impl ExitCode {
+    pub fn code(self) -> i32 {
+        self as i32
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -153,15 +242,43 @@ pub enum InvalidProjectNameReason {
     GleamReservedWord,
 }
 
+#[derive(Debug, PartialEq)]
+pub enum InvalidEntrypointReason {
+    ModuleNotFound,
+    FunctionNotFound,
+    Private,
+    TakesArguments(usize),
+}
+
+/// How a source file differs from the one recorded in the source manifest.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SourceChange {
+    Added,
+    Removed,
+    Changed,
+}
+
+impl SourceChange {
+    fn text(&self) -> &'static str {
+        match self {
+            SourceChange::Added => "added",
+            SourceChange::Removed => "removed",
+            SourceChange::Changed => "changed",
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum StandardIOAction {
     Read,
+    Write,
 }
 
 impl StandardIOAction {
     fn text(&self) -> &'static str {
         match self {
             StandardIOAction::Read => "read from",
+            StandardIOAction::Write => "write to",
         }
     }
 }
@@ -171,7 +288,6 @@ pub enum FileIOAction {
     Open,
     Copy,
     Read,
-    Parse,
     Delete,
     Create,
     WriteTo,
@@ -184,7 +300,6 @@ impl FileIOAction {
             FileIOAction::Open => "open",
             FileIOAction::Copy => "copy",
             FileIOAction::Read => "read",
-            FileIOAction::Parse => "parse",
             FileIOAction::Delete => "delete",
             FileIOAction::Create => "create",
             FileIOAction::WriteTo => "write to",
@@ -226,6 +341,41 @@ fn did_you_mean(name: &str, options: &mut Vec<String>, alt: &'static str) -> Str
 }
 
 impl Error {
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            Error::Parse { .. }
+            | Error::Type { .. }
+            | Error::UnknownImport { .. }
+            | Error::DuplicateModule { .. }
+            | Error::InvalidModuleName { .. }
+            | Error::SrcImportingTest { .. }
+            | Error::ImportCycle { .. }
+            | Error::PackageCycle { .. }
+            | Error::Format { .. }
+            | Error::DeniedTodo { .. }
+            | Error::SourceManifestMismatch { .. } => ExitCode::CompileError,
+
+            Error::InvalidProjectName { .. }
+            | Error::MissingProjectName
+            | Error::InvalidGlob { .. }
+            | Error::UnknownDiagnosticCode { .. }
+            | Error::MissingConfig { .. }
+            | Error::InvalidConfig { .. }
+            | Error::DuplicateDependency { .. }
+            | Error::UnsupportedBuildToolFlag { .. }
+            | Error::InvalidEntrypoint { .. } => ExitCode::UsageError,
+
+            Error::FileIO { .. }
+            | Error::InvalidUtf8 { .. }
+            | Error::StandardIO { .. }
+            | Error::Tar { .. }
+            | Error::TarFinish(_)
+            | Error::Gzip(_) => ExitCode::IOError,
+
+            Error::Hex(_) | Error::ShellCommand { .. } => ExitCode::ExternalError,
+        }
+    }
+
     pub fn pretty(&self, buffer: &mut Buffer) {
         use crate::typ::Error as TypeError;
         use std::io::Write;
@@ -288,6 +438,98 @@ The error from the shell command library was:
                 write_project(buffer, diagnostic);
             }
 
+            Error::UnknownDiagnosticCode { code, codes } => {
+                let diagnostic = ProjectErrorDiagnostic {
+                    title: "Unknown code".to_string(),
+                    label: format!(
+                        "There is no explanation for the code `{}`. These are the known codes:
+
+{}",
+                        code,
+                        codes.iter().map(|code| format!("    {}", code)).join("\n")
+                    ),
+                };
+                write_project(buffer, diagnostic);
+            }
+
+            Error::MissingConfig {
+                path,
+                searched_up_to,
+            } => {
+                let searched = match searched_up_to {
+                    Some(dir) => format!(
+                        "\nor any of its parent directories, up to and including `{}`",
+                        dir.to_str().unwrap()
+                    ),
+                    None => "".to_string(),
+                };
+                let diagnostic = ProjectErrorDiagnostic {
+                    title: "Project not found".to_string(),
+                    label: format!(
+                        "No gleam.toml config file was found in `{}`{}.
+
+Are you in a Gleam project? A new project can be created with `gleam new`.",
+                        path.to_str().unwrap(),
+                        searched
+                    ),
+                };
+                write_project(buffer, diagnostic);
+            }
+
+            Error::InvalidConfig { path, err } => {
+                let diagnostic = ProjectErrorDiagnostic {
+                    title: "Invalid config".to_string(),
+                    label: format!(
+                        "The config file `{}` is not valid.
+
+This was the problem found with it:
+
+    {}",
+                        path.to_str().unwrap(),
+                        err
+                    ),
+                };
+                write_project(buffer, diagnostic);
+            }
+
+            Error::MissingProjectName => {
+                let diagnostic = ProjectErrorDiagnostic {
+                    title: "Missing project name".to_string(),
+                    label: "A name for the project must be given when gleam new is not
+run in a terminal."
+                        .to_string(),
+                };
+                write_project(buffer, diagnostic);
+            }
+
+            Error::DuplicateDependency { name } => {
+                let diagnostic = ProjectErrorDiagnostic {
+                    title: "Dependency already added".to_string(),
+                    label: format!(
+                        "The package `{}` is already a dependency of this project.
+
+To change its version requirement edit the entry in gleam.toml.",
+                        name
+                    ),
+                };
+                write_project(buffer, diagnostic);
+            }
+
+            Error::InvalidGlob { glob, err } => {
+                let diagnostic = ProjectErrorDiagnostic {
+                    title: "Invalid glob".to_string(),
+                    label: format!(
+                        "The glob `{}` could not be parsed.
+
+This was the error from the glob library:
+
+    {}",
+                        glob, err
+                    ),
+                };
+                write_project(buffer, diagnostic);
+            }
+
             Error::Gzip(detail) => {
                 let diagnostic = ProjectErrorDiagnostic {
                     title: "Gzip compression failure".to_string(),
@@ -396,6 +638,30 @@ Second: {}",
                 write_project(buffer, diagnostic);
             }
 
+            Error::InvalidModuleName {
+                path,
+                name,
+                segment,
+            } => {
+                let diagnostic = ProjectErrorDiagnostic {
+                    title: "Invalid module name".to_string(),
+                    label: format!(
+                        "The module `{}` at this path has an invalid name.
+
+Path: {}
+
+The name segment `{}` is not valid. Module names must start with a
+lowercase letter, may only contain lowercase letters, numbers, and
+underscores, and must not be a reserved word. Rename the file or give it a
+valid name in the `[modules]` section of gleam.toml.",
+                        name,
+                        path.to_str().expect("pretty error print PathBuf to_str"),
+                        segment,
+                    ),
+                };
+                write_project(buffer, diagnostic);
+            }
+
             Error::FileIO {
                 kind,
                 action,
@@ -425,6 +691,92 @@ Second: {}",
                 write_project(buffer, diagnostic);
             }
 
+            Error::DeniedTodo { locations } => {
+                let diagnostic = ProjectErrorDiagnostic {
+                    title: "Todos remaining".to_string(),
+                    label: format!(
+                        "This build does not allow `todo`, but it was found at:
+
+{}
+
+Replace each todo with the code it stands in for.",
+                        locations
+                            .iter()
+                            .map(|location| format!("    {}", location))
+                            .join("\n")
+                    ),
+                };
+                write_project(buffer, diagnostic);
+            }
+
+            Error::UnsupportedBuildToolFlag { flag } => {
+                let diagnostic = ProjectErrorDiagnostic {
+                    title: "Unsupported by the build tool".to_string(),
+                    label: format!(
+                        "`{}` is not yet supported by the new build tool.
+Remove `tool = \"gleam\"` from gleam.toml to use it.",
+                        flag
+                    ),
+                };
+                write_project(buffer, diagnostic);
+            }
+
+            Error::InvalidEntrypoint {
+                module,
+                function,
+                reason,
+            } => {
+                let problem = match reason {
+                    InvalidEntrypointReason::ModuleNotFound => {
+                        format!("The module `{}` could not be found.", module)
+                    }
+                    InvalidEntrypointReason::FunctionNotFound => {
+                        format!("The module `{}` has no function `{}`.", module, function)
+                    }
+                    InvalidEntrypointReason::Private => {
+                        format!("The function `{}.{}` is private.", module, function)
+                    }
+                    InvalidEntrypointReason::TakesArguments(arity) => format!(
+                        "The function `{}.{}` takes {} argument{}.",
+                        module,
+                        function,
+                        arity,
+                        if *arity == 1 { "" } else { "s" }
+                    ),
+                };
+                let diagnostic = ProjectErrorDiagnostic {
+                    title: "Invalid entrypoint".to_string(),
+                    label: format!(
+                        "{}
+
+`gleam run` calls a public function that takes no arguments, such as
+
+    pub fn {}() {{
+      ...
+    }}
+
+Command line arguments can be read with `init:get_plain_arguments/0`.",
+                        problem, function
+                    ),
+                };
+                write_project(buffer, diagnostic);
+            }
+
+            Error::InvalidUtf8 { path, offset } => {
+                let diagnostic = ProjectErrorDiagnostic {
+                    title: "Invalid UTF-8".to_string(),
+                    label: format!(
+                        "The file `{}` could not be read as it is not valid UTF-8.
+The first invalid byte is at offset {}.
+
+Gleam source files must be UTF-8 encoded. Re-save the file with that encoding.",
+                        path.to_string_lossy(),
+                        offset
+                    ),
+                };
+                write_project(buffer, diagnostic);
+            }
+
             Error::Type { path, src, error } => match error {
                 TypeError::UnknownLabels {
                     unknown,
@@ -517,6 +869,43 @@ also be labelled.",
                     .unwrap();
                 }
 
+                TypeError::DuplicateDerivedName {
+                    location,
+                    previous_location,
+                    name,
+                    type_name,
+                    previous_type_name,
+                } => {
+                    let diagnostic = MultiLineDiagnostic {
+                        title: format!("Duplicate derived function name `{}`", name),
+                        file: path.to_str().unwrap().to_string(),
+                        src: src.to_string(),
+                        labels: vec![
+                            DiagnosticLabel {
+                                label: format!("derived for `{}` here", type_name),
+                                location: location.clone(),
+                                style: LabelStyle::Primary,
+                            },
+                            DiagnosticLabel {
+                                label: format!(
+                                    "previously derived for `{}` here",
+                                    previous_type_name
+                                ),
+                                location: previous_location.clone(),
+                                style: LabelStyle::Secondary,
+                            },
+                        ],
+                    };
+                    write_diagnostic(buffer, diagnostic, Severity::Error);
+                    writeln!(
+                        buffer,
+                        "Derived functions are named after their type in snake case, so the
+types `{}` and `{}` cannot both derive them. Rename one of the types.",
+                        previous_type_name, type_name
+                    )
+                    .unwrap();
+                }
+
                 TypeError::DuplicateName {
                     location,
                     name: fun,
@@ -569,6 +958,102 @@ also be labelled.",
                     write_diagnostic(buffer, diagnostic, Severity::Error);
                 }
 
+                TypeError::DuplicateImport {
+                    name,
+                    location,
+                    previous_location,
+                    ..
+                } => {
+                    let diagnostic = MultiLineDiagnostic {
+                        title: format!("Duplicate import with name `{}`", name),
+                        file: path.to_str().unwrap().to_string(),
+                        src: src.to_string(),
+                        labels: vec![
+                            DiagnosticLabel {
+                                label: "reimported here".to_string(),
+                                location: location.clone(),
+                                style: LabelStyle::Primary,
+                            },
+                            DiagnosticLabel {
+                                label: "previously imported here".to_string(),
+                                location: previous_location.clone(),
+                                style: LabelStyle::Secondary,
+                            },
+                        ],
+                    };
+                    write_diagnostic(buffer, diagnostic, Severity::Error);
+                    writeln!(
+                        buffer,
+                        "Use `as` to give one of the modules a different name."
+                    )
+                    .unwrap();
+                }
+
+                TypeError::ShadowedUnqualifiedImport {
+                    name,
+                    location,
+                    import_location,
+                } => {
+                    let diagnostic = MultiLineDiagnostic {
+                        title: format!("Definition shadows the imported value `{}`", name),
+                        file: path.to_str().unwrap().to_string(),
+                        src: src.to_string(),
+                        labels: vec![
+                            DiagnosticLabel {
+                                label: "defined here".to_string(),
+                                location: location.clone(),
+                                style: LabelStyle::Primary,
+                            },
+                            DiagnosticLabel {
+                                label: "imported here".to_string(),
+                                location: import_location.clone(),
+                                style: LabelStyle::Secondary,
+                            },
+                        ],
+                    };
+                    write_diagnostic(buffer, diagnostic, Severity::Error);
+                    writeln!(
+                        buffer,
+                        "Rename the definition, or use `as` to import the value with a different name."
+                    )
+                    .unwrap();
+                }
+
+                TypeError::AmbiguousUnqualifiedImport {
+                    location,
+                    previous_location,
+                    name,
+                    module,
+                    previous_module,
+                } => {
+                    let diagnostic = MultiLineDiagnostic {
+                        title: format!("Ambiguous import of `{}`", name),
+                        file: path.to_str().unwrap().to_string(),
+                        src: src.to_string(),
+                        labels: vec![
+                            DiagnosticLabel {
+                                label: format!("imported from `{}` here", module),
+                                location: location.clone(),
+                                style: LabelStyle::Primary,
+                            },
+                            DiagnosticLabel {
+                                label: format!("and from `{}` here", previous_module),
+                                location: previous_location.clone(),
+                                style: LabelStyle::Secondary,
+                            },
+                        ],
+                    };
+                    write_diagnostic(buffer, diagnostic, Severity::Error);
+                    writeln!(
+                        buffer,
+                        "`{}` is imported from both `{}` and `{}`, so it is not clear which
+is meant. Import it from only one module, or use `as` to give one of them a
+different name.",
+                        name, previous_module, module
+                    )
+                    .unwrap();
+                }
+
                 TypeError::DuplicateField { location, label } => {
                     let diagnostic = Diagnostic {
                         title: "Duplicate field".to_string(),
@@ -687,7 +1172,7 @@ also be labelled.",
                         location: location.clone(),
                     };
                     write(buffer, diagnostic, Severity::Error);
-                    let mut printer = Printer::new();
+                    let mut printer = Printer::disambiguating(&[expected, given]);
 
                     writeln!(
                         buffer,
@@ -698,8 +1183,85 @@ also be labelled.",
 Found type:
 
 {}",
-                        printer.pretty_print(expected, 4),
-                        printer.pretty_print(given, 4),
+                        printer.pretty_print(expected, 4),
+                        printer.pretty_print(given, 4),
+                    )
+                    .unwrap();
+                }
+
+                TypeError::OperandTypeMismatch {
+                    location,
+                    operator,
+                    expected,
+                    left,
+                    right,
+                } => {
+                    let diagnostic = Diagnostic {
+                        title: "Type mismatch".to_string(),
+                        label: "".to_string(),
+                        file: path.to_str().unwrap().to_string(),
+                        src: src.to_string(),
+                        location: location.clone(),
+                    };
+                    write(buffer, diagnostic, Severity::Error);
+                    let mut printer = Printer::disambiguating(&[expected, left, right]);
+                    let expected = match operator {
+                        BinOp::Eq | BinOp::NotEq => "operands of the same type".to_string(),
+                        _ => format!("operands of type:\n\n{}", printer.pretty_print(expected, 4)),
+                    };
+
+                    writeln!(
+                        buffer,
+                        "The `{}` operator expects {}
+
+Left operand type:
+
+{}
+
+Right operand type:
+
+{}",
+                        operator.name(),
+                        expected,
+                        printer.pretty_print(left, 4),
+                        printer.pretty_print(right, 4),
+                    )
+                    .unwrap();
+                }
+
+                TypeError::NotComparable {
+                    location,
+                    operator,
+                    typ,
+                } => {
+                    let diagnostic = Diagnostic {
+                        title: "Type cannot be compared".to_string(),
+                        label: "this is a function".to_string(),
+                        file: path.to_str().unwrap().to_string(),
+                        src: src.to_string(),
+                        location: location.clone(),
+                    };
+                    write(buffer, diagnostic, Severity::Error);
+                    let explanation = match operator {
+                        BinOp::Eq | BinOp::NotEq => {
+                            "Functions cannot be checked for equality, as two functions that
+behave the same way may still not be equal."
+                        }
+                        _ => {
+                            "Functions have no order. Ints can be compared with `<`, `<=`, `>`,
+and `>=`, and Floats with `<.`, `<=.`, `>.`, and `>=.`."
+                        }
+                    };
+                    writeln!(
+                        buffer,
+                        "The `{}` operator cannot be used with values of type:
+
+{}
+
+{}",
+                        operator.name(),
+                        Printer::new().pretty_print(typ, 4),
+                        explanation,
                     )
                     .unwrap();
                 }
@@ -728,7 +1290,12 @@ Found type:
                 } => {
                     let diagnostic = Diagnostic {
                         title: "Incorrect arity".to_string(),
-                        label: format!("expected {} arguments, got {}", expected, given),
+                        label: format!(
+                            "expected {} argument{}, got {}",
+                            expected,
+                            if *expected == 1 { "" } else { "s" },
+                            given
+                        ),
                         file: path.to_str().unwrap().to_string(),
                         src: src.to_string(),
                         location: location.clone(),
@@ -749,6 +1316,116 @@ Found type:
                     }
                 }
 
+                TypeError::IncorrectTupleArity {
+                    location,
+                    expected,
+                    given,
+                    typ,
+                } => {
+                    let diagnostic = Diagnostic {
+                        title: "Incorrect tuple size".to_string(),
+                        label: format!("expected {} elements, got {}", expected, given),
+                        file: path.to_str().unwrap().to_string(),
+                        src: src.to_string(),
+                        location: location.clone(),
+                    };
+                    write(buffer, diagnostic, Severity::Error);
+                    let elements = |count: usize| match count {
+                        1 => "1 element".to_string(),
+                        _ => format!("{} elements", count),
+                    };
+                    writeln!(
+                        buffer,
+                        "This pattern has {} but the value it matches is a tuple of {}:\n\n{}",
+                        elements(*given),
+                        elements(*expected),
+                        Printer::new().pretty_print(typ, 4),
+                    )
+                    .unwrap();
+                }
+
+                TypeError::IncorrectRecordArity {
+                    location,
+                    name,
+                    expected,
+                    given,
+                    missing,
+                } => {
+                    let diagnostic = Diagnostic {
+                        title: "Incorrect arity".to_string(),
+                        label: format!("expected {} arguments, got {}", expected, given),
+                        file: path.to_str().unwrap().to_string(),
+                        src: src.to_string(),
+                        location: location.clone(),
+                    };
+                    write(buffer, diagnostic, Severity::Error);
+                    if !missing.is_empty() {
+                        let mut printer = Printer::new();
+                        let fields = missing
+                            .iter()
+                            .map(|field| match &field.label {
+                                Some(label) => format!(
+                                    "  - {}: {}",
+                                    label,
+                                    printer.pretty_print(&field.typ, 0)
+                                ),
+                                None => format!(
+                                    "  - field #{}: {}",
+                                    field.index + 1,
+                                    printer.pretty_print(&field.typ, 0)
+                                ),
+                            })
+                            .join("\n");
+                        writeln!(
+                            buffer,
+                            "The {} record is missing these fields:\n\n{}\n",
+                            name, fields,
+                        )
+                        .unwrap();
+                    } else {
+                        let fields = if *expected == 1 { "field" } else { "fields" };
+                        writeln!(buffer, "The {} record has {} {}.", name, expected, fields)
+                            .unwrap();
+                    }
+                }
+
+                TypeError::IncorrectRecordFieldType {
+                    location,
+                    name,
+                    index,
+                    label,
+                    expected,
+                    given,
+                } => {
+                    let field = match label {
+                        Some(label) => format!("the `{}` field", label),
+                        None => format!("field #{}", index + 1),
+                    };
+                    let diagnostic = Diagnostic {
+                        title: "Type mismatch".to_string(),
+                        label: format!("this is {} of {}", field, name),
+                        file: path.to_str().unwrap().to_string(),
+                        src: src.to_string(),
+                        location: location.clone(),
+                    };
+                    write(buffer, diagnostic, Severity::Error);
+                    let mut printer = Printer::new();
+
+                    writeln!(
+                        buffer,
+                        "Expected type:
+
+{}
+
+Found type:
+
+{}",
+                        printer.pretty_print(expected, 4),
+                        printer.pretty_print(given, 4),
+                    )
+                    .unwrap();
+                }
+
                 TypeError::UnnecessarySpreadOperator { location, arity } => {
                     let diagnostic = Diagnostic {
                         title: "Unnecessary spread operator".to_string(),
@@ -1190,6 +1867,61 @@ This segment has a type of {}.",
                     .unwrap();
                 }
 
+                TypeError::BinaryTypeDoesNotAllowSize { location, typ, .. } => {
+                    let diagnostic = Diagnostic {
+                        title: "Size cannot be specified for given type".to_string(),
+                        label: "".to_string(),
+                        file: path.to_str().unwrap().to_string(),
+                        src: src.to_string(),
+                        location: location.clone(),
+                    };
+                    write(buffer, diagnostic, Severity::Error);
+                    writeln!(
+                        buffer,
+                        "No size specifier must be given for the types utf8, utf16, and utf32.
+This segment has a type of {}.",
+                        typ
+                    )
+                    .unwrap();
+                }
+
+                TypeError::BinaryTypeDoesNotAllowSignedness { location, typ, .. } => {
+                    let diagnostic = Diagnostic {
+                        title: "Signedness cannot be specified for given type".to_string(),
+                        label: "".to_string(),
+                        file: path.to_str().unwrap().to_string(),
+                        src: src.to_string(),
+                        location: location.clone(),
+                    };
+                    write(buffer, diagnostic, Severity::Error);
+                    writeln!(
+                        buffer,
+                        "Only segments of the type int can be signed or unsigned.
+This segment has a type of {}.",
+                        typ
+                    )
+                    .unwrap();
+                }
+
+                TypeError::BinaryTypeDoesNotAllowEndianness { location, typ, .. } => {
+                    let diagnostic = Diagnostic {
+                        title: "Endianness cannot be specified for given type".to_string(),
+                        label: "".to_string(),
+                        file: path.to_str().unwrap().to_string(),
+                        src: src.to_string(),
+                        location: location.clone(),
+                    };
+                    write(buffer, diagnostic, Severity::Error);
+                    writeln!(
+                        buffer,
+                        "Only segments of the types int, float, utf16, utf32, utf16_codepoint, and
+utf32_codepoint can be big, little, or native endian.
+This segment has a type of {}.",
+                        typ
+                    )
+                    .unwrap();
+                }
+
                 TypeError::BinarySegmentMustHaveSize { location, .. } => {
                     let diagnostic = Diagnostic {
                         title: "Bit string segment without required size".to_string(),
@@ -1207,6 +1939,29 @@ at the end of a bin pattern",
                     .unwrap();
                 }
 
+                TypeError::BinarySegmentIntDoesNotFit {
+                    location,
+                    value,
+                    bits,
+                } => {
+                    let diagnostic = Diagnostic {
+                        title: "Integer does not fit in segment".to_string(),
+                        label: "".to_string(),
+                        file: path.to_str().unwrap().to_string(),
+                        src: src.to_string(),
+                        location: location.clone(),
+                    };
+                    write(buffer, diagnostic, Severity::Error);
+                    let (min, max) = crate::bit_string::int_range(*bits);
+                    writeln!(
+                        buffer,
+                        "The value {} cannot be held in {} bits and would be truncated.
+A segment of {} bits can hold integers from {} to {}.",
+                        value, bits, bits, min, max
+                    )
+                    .unwrap();
+                }
+
                 TypeError::InvalidBinarySegmentOption { label, location } => {
                     let diagnostic = Diagnostic {
                         title: "Invalid bit string segment option".to_string(),
@@ -1281,9 +2036,61 @@ When matching you need to use the `{}_codepoint` specifier instead.",
                     )
                     .unwrap();
                 }
+
+                TypeError::UninhabitedType { location, name } => {
+                    let diagnostic = Diagnostic {
+                        title: "Type cannot be constructed".to_string(),
+                        label: "".to_string(),
+                        file: path.to_str().unwrap().to_string(),
+                        src: src.to_string(),
+                        location: location.clone(),
+                    };
+                    write(buffer, diagnostic, Severity::Error);
+                    writeln!(
+                        buffer,
+                        "Every constructor of `{}` needs a value of `{}` to already exist, so
+no value of this type can ever be created. Add a constructor that does not
+contain this type, such as an empty case like `Leaf` or `Nil`.",
+                        name, name
+                    )
+                    .unwrap();
+                }
             },
 
             Error::Parse { path, src, error } => match error {
+                // The contents of a block comment may be anything, so it is
+                // checked for before the braces
+                lalrpop_util::ParseError::UnrecognizedToken { .. }
+                | lalrpop_util::ParseError::UnrecognizedEOF { .. }
+                    if crate::parser::block_comment(src).is_some() =>
+                {
+                    let location = crate::parser::block_comment(src)
+                        .gleam_expect("Error::Parse pretty block_comment");
+                    let diagnostic = Diagnostic {
+                        title: "Syntax error".to_string(),
+                        label: "Block comments are not supported".to_string(),
+                        file: path.to_str().unwrap().to_string(),
+                        src: src.to_string(),
+                        location,
+                    };
+                    write(buffer, diagnostic, Severity::Error);
+                    writeln!(
+                        buffer,
+                        "Gleam only has line comments. Start each line of the comment with `//`
+instead."
+                    )
+                    .expect("error pretty buffer write");
+                }
+
+                lalrpop_util::ParseError::UnrecognizedToken { .. }
+                | lalrpop_util::ParseError::UnrecognizedEOF { .. }
+                    if crate::parser::unbalanced_brace(src).is_some() =>
+                {
+                    let brace = crate::parser::unbalanced_brace(src)
+                        .gleam_expect("Error::Parse pretty unbalanced_brace");
+                    unbalanced_brace(buffer, path.to_str().unwrap(), src, brace);
+                }
+
                 lalrpop_util::ParseError::UnrecognizedToken {
                     token: (start, _, end),
                     expected,
@@ -1318,21 +2125,34 @@ When matching you need to use the `{}_codepoint` specifier instead.",
                 }
 
                 lalrpop_util::ParseError::InvalidToken { location } => {
+                    let character = src.get(*location..).and_then(|rest| rest.chars().next());
+                    let whitespace = character.and_then(crate::parser::lookalike_whitespace);
                     let diagnostic = Diagnostic {
                         title: "Syntax error".to_string(),
-                        label: "Unknown token".to_string(),
+                        label: match whitespace {
+                            Some(_) => "Unexpected whitespace".to_string(),
+                            None => "Unknown token".to_string(),
+                        },
                         file: path.to_str().unwrap().to_string(),
                         src: src.to_string(),
                         location: crate::ast::SrcSpan {
                             start: *location,
-                            end: *location + 1,
+                            end: *location + character.map_or(1, char::len_utf8),
                         },
                     };
                     write(buffer, diagnostic, Severity::Error);
-                    writeln!(
-                        buffer,
-                        "I don't know what this character means. Is it a typo?"
-                    )
+                    match (whitespace, character) {
+                        (Some(name), Some(character)) => writeln!(
+                            buffer,
+                            "This is a {} (U+{:04X}), which looks like a space but is not one.
+Only spaces, tabs, and newlines can be used as whitespace.",
+                            name, character as u32
+                        ),
+                        _ => writeln!(
+                            buffer,
+                            "I don't know what this character means. Is it a typo?"
+                        ),
+                    }
                     .expect("error pretty buffer write");
                 }
 
@@ -1363,6 +2183,123 @@ but this one uses {}. Rewrite this using the fn({}) {{ ... }} syntax.",
                                 )
                                 .expect("error pretty buffer write");
                         }
+
+                        Error::InvalidUnicodeEscape { location, error } => {
+                            use crate::parser::InvalidUnicodeEscape;
+                            let label = match error {
+                                InvalidUnicodeEscape::Malformed => {
+                                    "expected 1 to 6 hex digits between braces"
+                                }
+                                InvalidUnicodeEscape::Unterminated => "missing closing brace",
+                                InvalidUnicodeEscape::InvalidCodePoint => {
+                                    "not a valid unicode code point"
+                                }
+                            };
+                            let diagnostic = Diagnostic {
+                                title: "Invalid unicode escape".to_string(),
+                                label: label.to_string(),
+                                file: path.to_str().unwrap().to_string(),
+                                src: src.to_string(),
+                                location: location.clone(),
+                            };
+                            write(buffer, diagnostic, Severity::Error);
+                            writeln!(
+                                buffer,
+                                "Unicode escapes are written as \\u{{...}} with the hexadecimal
+value of a code point, such as \\u{{1F600}}. Surrogates (D800 to DFFF)
+and values above 10FFFF are not valid code points."
+                            )
+                            .expect("error pretty buffer write");
+                        }
+
+                        Error::UnknownDerive { location, name } => {
+                            let diagnostic = Diagnostic {
+                                title: "Unknown derive".to_string(),
+                                label: "".to_string(),
+                                file: path.to_str().unwrap().to_string(),
+                                src: src.to_string(),
+                                location: location.clone(),
+                            };
+                            write(buffer, diagnostic, Severity::Error);
+                            writeln!(
+                                buffer,
+                                "`{}` cannot be derived. The functions that can be derived for a
+custom type are `equal` and `compare`.",
+                                name
+                            )
+                            .expect("error pretty buffer write");
+                        }
+
+                        Error::UnknownTarget { location, name } => {
+                            let diagnostic = Diagnostic {
+                                title: "Unknown target".to_string(),
+                                label: "".to_string(),
+                                file: path.to_str().unwrap().to_string(),
+                                src: src.to_string(),
+                                location: location.clone(),
+                            };
+                            write(buffer, diagnostic, Severity::Error);
+                            writeln!(
+                                buffer,
+                                "`{}` is not a target. Definitions can be limited to the `erlang`
+or `javascript` target.",
+                                name
+                            )
+                            .expect("error pretty buffer write");
+                        }
+
+                        Error::UnknownWarningCode { location, code } => {
+                            let diagnostic = Diagnostic {
+                                title: "Unknown warning code".to_string(),
+                                label: "".to_string(),
+                                file: path.to_str().unwrap().to_string(),
+                                src: src.to_string(),
+                                location: location.clone(),
+                            };
+                            write(buffer, diagnostic, Severity::Error);
+                            writeln!(
+                                buffer,
+                                "`{}` is not the code of a warning. These are the known codes:
+
+{}",
+                                code,
+                                crate::diagnostic::codes()
+                                    .map(|code| format!("    {}", code))
+                                    .join("\n")
+                            )
+                            .expect("error pretty buffer write");
+                        }
+
+                        Error::DuplicateAttribute {
+                            location,
+                            previous_location,
+                            name,
+                        } => {
+                            let diagnostic = MultiLineDiagnostic {
+                                title: "Duplicate attribute".to_string(),
+                                file: path.to_str().unwrap().to_string(),
+                                src: src.to_string(),
+                                labels: vec![
+                                    DiagnosticLabel {
+                                        label: "given again here".to_string(),
+                                        location: location.clone(),
+                                        style: LabelStyle::Primary,
+                                    },
+                                    DiagnosticLabel {
+                                        label: "first given here".to_string(),
+                                        location: previous_location.clone(),
+                                        style: LabelStyle::Secondary,
+                                    },
+                                ],
+                            };
+                            write_diagnostic(buffer, diagnostic, Severity::Error);
+                            writeln!(
+                                buffer,
+                                "A definition can only have one `@{}` attribute.",
+                                name
+                            )
+                            .expect("error pretty buffer write");
+                        }
                     }
                 }
             },
@@ -1407,6 +2344,7 @@ cycle to continue."
                 path,
                 src,
                 modules,
+                searched,
             } => {
                 let mut modules = modules.clone();
                 let diagnostic = Diagnostic {
@@ -1417,11 +2355,16 @@ cycle to continue."
                     location: location.clone(),
                 };
                 write(buffer, diagnostic, Severity::Error);
+                let searched: String = searched
+                    .iter()
+                    .map(|dir| format!("\n    {}", dir.to_string_lossy()))
+                    .collect();
                 writeln!(
                     buffer,
                     "The module `{}` is trying to import the module `{}`,
-but it cannot be found.",
-                    module, import
+but it cannot be found in any of these directories:
+{}",
+                    module, import, searched
                 )
                 .expect("error pretty buffer write");
             }
@@ -1460,6 +2403,24 @@ but it cannot be found.",
                     label,
                 };
 
+                write_project(buffer, diagnostic);
+            }
+            Error::SourceManifestMismatch { changes } => {
+                let mut label = changes
+                    .iter()
+                    .map(|(path, change)| {
+                        format!("  - {} ({})", path.to_string_lossy(), change.text())
+                    })
+                    .join("\n");
+                label.push_str(&format!(
+                    "\n\nThe sources do not match those recorded in {}.",
+                    crate::build::manifest::MANIFEST_FILE
+                ));
+                let diagnostic = ProjectErrorDiagnostic {
+                    title: "Source files have changed".to_string(),
+                    label,
+                };
+
                 write_project(buffer, diagnostic);
             }
         }
@@ -1505,6 +2466,34 @@ fn std_io_error_kind_text(kind: &std::io::ErrorKind) -> String {
     }
 }
 
+fn unbalanced_brace(buffer: &mut Buffer, path: &str, src: &str, brace: UnbalancedBrace) {
+    use std::io::Write;
+
+    let (label, location, hint) = match brace {
+        UnbalancedBrace::Unclosed { location } => (
+            "This brace is never closed",
+            location,
+            "The braces in this module do not match up, and this brace looks to be
+the one that is missing a closing `}`.",
+        ),
+        UnbalancedBrace::Unexpected { location } => (
+            "This brace closes nothing",
+            location,
+            "The braces in this module do not match up, and this closing brace looks
+to be the one without an opening `{`.",
+        ),
+    };
+    let diagnostic = Diagnostic {
+        title: "Syntax error".to_string(),
+        label: label.to_string(),
+        file: path.to_string(),
+        src: src.to_string(),
+        location,
+    };
+    write(buffer, diagnostic, Severity::Error);
+    writeln!(buffer, "{}", hint).expect("error pretty buffer write");
+}
+
 fn import_cycle(buffer: &mut Buffer, modules: &[String]) {
     use std::io::Write;
     use termcolor::{Color, ColorSpec, WriteColor};
@@ -1528,3 +2517,58 @@ fn import_cycle(buffer: &mut Buffer, modules: &[String]) {
     }
     writeln!(buffer, "    └─────┘\n").unwrap();
 }
+
+#[test]
+fn exit_code_test() {
+    assert_eq!(0, ExitCode::Success.code());
+    assert_eq!(
+        ExitCode::CompileError,
+        Error::ImportCycle {
+            modules: vec!["one".to_string(), "two".to_string()],
+        }
+        .exit_code()
+    );
+    assert_eq!(
+        ExitCode::CompileError,
+        Error::Format {
+            problem_files: vec![],
+        }
+        .exit_code()
+    );
+    assert_eq!(
+        ExitCode::UsageError,
+        Error::InvalidProjectName {
+            name: "Bad".to_string(),
+            reason: InvalidProjectNameReason::Format,
+        }
+        .exit_code()
+    );
+    assert_eq!(
+        ExitCode::IOError,
+        Error::FileIO {
+            kind: FileKind::File,
+            action: FileIOAction::Read,
+            path: PathBuf::from("gleam.toml"),
+            err: None,
+        }
+        .exit_code()
+    );
+    assert_eq!(
+        ExitCode::IOError,
+        Error::StandardIO {
+            action: StandardIOAction::Read,
+            err: None,
+        }
+        .exit_code()
+    );
+    assert_eq!(
+        ExitCode::ExternalError,
+        Error::ShellCommand {
+            command: "erl".to_string(),
+            err: Some(std::io::ErrorKind::NotFound),
+        }
+        .exit_code()
+    );
+    assert_eq!(2, ExitCode::UsageError.code());
+    assert_eq!(3, ExitCode::IOError.code());
+}
@@ -1,3 +1,5 @@
+pub(crate) mod coverage;
+
 use crate::{
     build::{self, project_root::ProjectRoot, Origin},
     error::Error,
@@ -13,16 +15,24 @@ struct EunitFile {
     content: String,
 }
 
-pub fn command(root_string: String) -> Result<(), Error> {
+pub fn command(root_string: String, coverage: bool) -> Result<(), Error> {
     let root_path = PathBuf::from(root_string);
     let root = ProjectRoot::new(root_path.clone());
-    let config = root.root_config()?;
+    let mut config = root.root_config()?;
+    config.erlang.coverage = coverage;
+    let package_name = config.name.clone();
 
     // Build project
     let packages = build::main(config, root_path)?;
 
     crate::cli::print_running("eunit");
 
+    // The lines of the project's source modules that were instrumented
+    let coverage_lines = packages
+        .get(&package_name)
+        .map(|package| package.coverage_lines.clone())
+        .unwrap_or_default();
+
     // Build a list of test modules
     let test_modules = packages
         .into_iter()
@@ -32,7 +42,7 @@ pub fn command(root_string: String) -> Result<(), Error> {
         .join(",");
 
     // Prepare eunit runner and its dependencies.
-    let eunit_files = vec![
+    let mut eunit_files = vec![
         EunitFile {
             should_be_compiled: true,
             path: root.build_path().join("eunit_progress.erl"),
@@ -44,6 +54,13 @@ pub fn command(root_string: String) -> Result<(), Error> {
             content: std::include_str!("eunit/eunit_runner.erl").to_string(),
         },
     ];
+    if coverage {
+        eunit_files.push(EunitFile {
+            should_be_compiled: true,
+            path: root.build_path().join("gleam_coverage.erl"),
+            content: std::include_str!("eunit/gleam_coverage.erl").to_string(),
+        });
+    }
 
     eunit_files.iter().try_for_each(|file| {
         crate::fs::write_output(&OutputFile {
@@ -83,6 +100,16 @@ pub fn command(root_string: String) -> Result<(), Error> {
     command.arg(ebin_paths);
     command.arg(test_modules);
 
+    // and the third, when measuring coverage, is the file to write the number
+    // of times each line was run to
+    // Hits from a previous run are removed so they are not reported as
+    // being from this one
+    let hits_path = root.build_path().join("coverage_hits");
+    if coverage {
+        crate::fs::delete(&hits_path)?;
+        command.arg(&hits_path);
+    }
+
     // Run the shell
     tracing::trace!("Running OS process {:?}", command);
     let status = command.status().map_err(|e| Error::ShellCommand {
@@ -90,6 +117,16 @@ pub fn command(root_string: String) -> Result<(), Error> {
         err: Some(e.kind()),
     })?;
 
+    if coverage && hits_path.exists() {
+        let hits = coverage::parse_hits(&crate::fs::read(&hits_path)?);
+        let report = OutputFile {
+            path: root.build_path().join("lcov.info"),
+            text: coverage::lcov(coverage_lines.as_slice(), &hits),
+        };
+        crate::fs::write_output(&report)?;
+        println!("Coverage report written to {}", report.path.display());
+    }
+
     if status.success() {
         Ok(())
     } else {
@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+
+/// The path of a source module as recorded in the coverage report, relative
+/// to the project root.
+///
+pub fn source_path(module_name: &str) -> String {
+    format!("src/{}.gleam", module_name)
+}
+
+/// Parse the hit counts written by the `gleam_coverage` Erlang module, one
+/// `<path> <line> <count>` entry per line.
+///
+pub fn parse_hits(text: &str) -> HashMap<(String, usize), u64> {
+    text.lines()
+        .filter_map(|entry| {
+            let mut parts = entry.rsplitn(3, ' ');
+            let count = parts.next()?.parse().ok()?;
+            let line = parts.next()?.parse().ok()?;
+            let path = parts.next()?;
+            Some(((path.to_string(), line), count))
+        })
+        .collect()
+}
+
+/// An LCOV tracefile of the instrumented lines of each source file and the
+/// number of times each was run.
+///
+pub fn lcov(files: &[(String, Vec<usize>)], hits: &HashMap<(String, usize), u64>) -> String {
+    use std::fmt::Write;
+    let mut report = String::new();
+
+    for (path, lines) in files {
+        writeln!(report, "TN:\nSF:{}", path).unwrap();
+        let mut hit = 0;
+        for line in lines {
+            let count = hits.get(&(path.clone(), *line)).copied().unwrap_or(0);
+            if count > 0 {
+                hit += 1;
+            }
+            writeln!(report, "DA:{},{}", line, count).unwrap();
+        }
+        writeln!(report, "LF:{}\nLH:{}\nend_of_record", lines.len(), hit).unwrap();
+    }
+
+    report
+}
+
+#[test]
+fn lcov_test() {
+    let hits = parse_hits("src/one.gleam 2 3\nsrc/one.gleam 3 1\nsrc/nested/two.gleam 1 1\n");
+    assert_eq!(Some(&3), hits.get(&("src/one.gleam".to_string(), 2)));
+    assert_eq!(3, hits.len());
+
+    let files = vec![
+        ("src/one.gleam".to_string(), vec![2, 3, 6]),
+        ("src/nested/two.gleam".to_string(), vec![1]),
+        ("src/three.gleam".to_string(), vec![2]),
+    ];
+    assert_eq!(
+        "TN:
+SF:src/one.gleam
+DA:2,3
+DA:3,1
+DA:6,0
+LF:3
+LH:2
+end_of_record
+TN:
+SF:src/nested/two.gleam
+DA:1,1
+LF:1
+LH:1
+end_of_record
+TN:
+SF:src/three.gleam
+DA:2,0
+LF:1
+LH:0
+end_of_record
+",
+        lcov(files.as_slice(), &hits)
+    );
+}
@@ -1,11 +1,24 @@
 #!/usr/bin/env erlang
 -mode(compile).
 
-main([EbinPaths, AllModules])->
+main([EbinPaths, AllModules]) ->
+    add_paths(EbinPaths),
+    halt(run(AllModules));
+
+main([EbinPaths, AllModules, CoverageFile]) ->
+    add_paths(EbinPaths),
+    ok = gleam_coverage:start(),
+    Status = run(AllModules),
+    ok = gleam_coverage:write(CoverageFile),
+    halt(Status).
+
+add_paths(EbinPaths) ->
     true = code:add_patha(filename:dirname(escript:script_name())),
     SeperatedEbinPaths = string:tokens(EbinPaths, ","),
-    ok = code:add_paths(SeperatedEbinPaths),
+    ok = code:add_paths(SeperatedEbinPaths).
+
+run(AllModules) ->
     SeperatedModules = string:tokens(AllModules, ","),
     Modules = lists:map(fun(X) -> list_to_atom(X) end, SeperatedModules),
     code:load_file(eunit_progress),
-    halt(case eunit:test(Modules, [inparallel, verbose, no_tty, {report, {eunit_progress, [{colored, true}]}}]) of ok -> 0; error -> 1 end).
+    case eunit:test(Modules, [inparallel, verbose, no_tty, {report, {eunit_progress, [{colored, true}]}}]) of ok -> 0; error -> 1 end.
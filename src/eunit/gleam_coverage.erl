@@ -0,0 +1,23 @@
+-module(gleam_coverage).
+-export([start/0, hit/2, write/1]).
+
+% Instrumented modules call hit/2 before each statement with the source file
+% and line of the statement. The counts are kept in a table owned by the test
+% runner, and are ignored if the table has not been started.
+
+start() ->
+    gleam_coverage = ets:new(gleam_coverage, [named_table, public, set]),
+    ok.
+
+hit(File, Line) ->
+    Key = {File, Line},
+    try ets:update_counter(gleam_coverage, Key, 1, {Key, 0}) of
+        _ -> nil
+    catch
+        error:badarg -> nil
+    end.
+
+write(Path) ->
+    Lines = [io_lib:format("~s ~b ~b~n", [File, Line, Count])
+             || {{File, Line}, Count} <- ets:tab2list(gleam_coverage)],
+    file:write_file(Path, Lines).
@@ -0,0 +1,32 @@
+use crate::{diagnostic, error::Error};
+
+/// Print the extended explanation of a warning code, such as the `code` field
+/// of the JSON printed by `--warning-format json`.
+///
+pub fn command(code: String) -> Result<(), Error> {
+    println!("{}", explanation(code)?);
+    Ok(())
+}
+
+fn explanation(code: String) -> Result<&'static str, Error> {
+    diagnostic::explanation(&code).ok_or_else(|| Error::UnknownDiagnosticCode {
+        code,
+        codes: diagnostic::codes().map(String::from).collect(),
+    })
+}
+
+#[test]
+fn explanation_test() {
+    assert!(explanation("float-equality".to_string())
+        .unwrap()
+        .starts_with("Floats are subject to rounding errors"));
+
+    let error = explanation("float_equality".to_string()).unwrap_err();
+    let mut buffer = termcolor::Buffer::no_color();
+    error.pretty(&mut buffer);
+    let output = String::from_utf8(buffer.into_inner()).unwrap();
+    assert!(output.contains("error: Unknown code"));
+    assert!(output.contains("float_equality"));
+    assert!(output.contains("    float-equality\n"));
+    assert_eq!(crate::error::ExitCode::UsageError, error.exit_code());
+}
@@ -1,25 +1,89 @@
 pub(crate) mod command;
+pub(crate) mod diff;
+#[cfg(test)]
+mod fuzz;
 #[cfg(test)]
 mod tests;
 
 use crate::{
     ast::*,
+    build::Target,
+    config::FormatConfig,
+    error::{Error, StandardIOAction},
     parser::{Comment, ModuleComments},
     pretty::*,
     typ::{self, Type},
 };
 use itertools::Itertools;
+use std::path::Path;
 use std::sync::Arc;
 
 const INDENT: isize = 2;
 
-pub fn pretty(src: &str) -> Result<String, crate::parser::LalrpopError> {
-    let (stripped_src, comments) = crate::parser::strip_extra(src);
+/// The number of columns that formatted code is fitted to.
+const WIDTH: usize = 80;
+
+/// Format the source of the file at the given path, returning a parse error
+/// that can be printed as a diagnostic pointing at the invalid code.
+///
+pub fn pretty_file(path: &Path, src: &str, config: &FormatConfig) -> Result<String, Error> {
+    pretty(src, config).map_err(|error| parse_error(path, src, error))
+}
+
+/// Format the source of the file at the given path, writing the output to
+/// standard output as it is rendered rather than building it up in memory.
+///
+pub fn pretty_file_to_stdout(path: &Path, src: &str, config: &FormatConfig) -> Result<(), Error> {
+    let document = module_document(src, config).map_err(|error| parse_error(path, src, error))?;
+    let stdout = std::io::stdout();
+    format_to(&mut stdout.lock(), WIDTH as isize, document).map_err(|e| Error::StandardIO {
+        action: StandardIOAction::Write,
+        err: Some(e.kind()),
+    })
+}
+
+/// Format source code to fit the given width with the default configuration,
+/// without touching the file system. Formatting should never panic, and
+/// formatting the output again should not change it, which the fuzzer in
+/// `format/fuzz.rs` checks.
+///
+#[cfg(test)]
+pub fn format_source(src: &str, width: usize) -> Result<String, Error> {
+    pretty_to_width(src, &FormatConfig::default(), width)
+        .map_err(|error| parse_error(Path::new("<source>"), src, error))
+}
+
+pub fn pretty(src: &str, config: &FormatConfig) -> Result<String, crate::parser::LalrpopError> {
+    pretty_to_width(src, config, WIDTH)
+}
+
+fn pretty_to_width(
+    src: &str,
+    config: &FormatConfig,
+    width: usize,
+) -> Result<String, crate::parser::LalrpopError> {
+    Ok(format(width as isize, module_document(src, config)?))
+}
+
+fn module_document(
+    src: &str,
+    config: &FormatConfig,
+) -> Result<Document, crate::parser::LalrpopError> {
+    let src = crate::fs::normalise_line_endings(src);
+    let (stripped_src, comments) = crate::parser::strip_extra(&src);
     let ast = crate::grammar::ModuleParser::new()
         .parse(&stripped_src)
         .map_err(|e| e.map_token(|crate::grammar::Token(a, b)| (a, b.to_string())))?;
-    let mut formatter = Formatter::with_comments(&comments);
-    Ok(pretty_module(&ast, &mut formatter))
+    let mut formatter = Formatter::with_comments(&comments).indent(config.indent as isize);
+    Ok(formatter.module(&ast))
+}
+
+fn parse_error(path: &Path, src: &str, error: crate::parser::LalrpopError) -> Error {
+    Error::Parse {
+        path: path.to_path_buf(),
+        src: crate::fs::normalise_line_endings(src),
+        error,
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -28,6 +92,7 @@ pub struct Formatter<'a> {
     doc_comments: &'a [Comment<'a>],
     module_comments: &'a [&'a str],
     empty_lines: &'a [usize],
+    indent: isize,
 }
 
 impl<'a> Formatter<'a> {
@@ -37,6 +102,7 @@ impl<'a> Formatter<'a> {
             doc_comments: &[],
             module_comments: &[],
             empty_lines: &[],
+            indent: INDENT,
         }
     }
 
@@ -46,9 +112,15 @@ impl<'a> Formatter<'a> {
             doc_comments: comments.doc_comments.as_slice(),
             empty_lines: comments.empty_lines.as_slice(),
             module_comments: comments.module_comments.as_slice(),
+            indent: INDENT,
         }
     }
 
+    /// Set the number of spaces used for each level of indentation.
+    pub fn indent(self, indent: isize) -> Self {
+        Self { indent, ..self }
+    }
+
     // Pop comments that occur before a byte-index in the source
     fn pop_comments(&mut self, limit: usize) -> impl Iterator<Item = &'a str> {
         let (popped, rest) = crate::parser::take_before(self.comments, limit);
@@ -88,14 +160,15 @@ impl<'a> Formatter<'a> {
                 Statement::Import { .. } => {
                     has_imports = true;
                     let comments = self.pop_comments(start);
-                    let statement = self.statement(statement);
+                    let statement = attributes_(module, start).append(self.statement(statement));
                     imports.push(commented(statement, comments))
                 }
 
                 _other => {
                     has_declarations = true;
                     let comments = self.pop_comments(start);
-                    let declaration = self.documented_statement(statement);
+                    let attributes = attributes_(module, start);
+                    let declaration = self.documented_statement(statement, attributes);
                     declarations.push(commented(declaration, comments))
                 }
             }
@@ -167,15 +240,16 @@ impl<'a> Formatter<'a> {
                 constructors,
                 location,
                 opaque,
+                derives,
                 ..
-            } => self.custom_type(
+            } => derives_(derives).append(self.custom_type(
                 *public,
                 *opaque,
                 name,
                 parameters.as_slice(),
                 constructors,
                 location,
-            ),
+            )),
 
             Statement::ExternalFn {
                 public,
@@ -188,9 +262,12 @@ impl<'a> Formatter<'a> {
             } => self
                 .external_fn_signature(*public, name, args, retrn)
                 .append(" =")
-                .append(line())
-                .append(format!("  \"{}\" ", module))
-                .append(format!("\"{}\"", fun)),
+                .append(
+                    line()
+                        .append(format!("\"{}\" ", module))
+                        .append(format!("\"{}\"", fun))
+                        .nest(self.indent),
+                ),
 
             Statement::ExternalType {
                 public, name, args, ..
@@ -214,7 +291,7 @@ impl<'a> Formatter<'a> {
                         .intersperse(delim(",").flex_break());
                     let unqualified = break_("", "")
                         .append(concat(unqualified))
-                        .nest(INDENT)
+                        .nest(self.indent)
                         .append(break_(",", ""))
                         .group();
                     ".{".to_doc().append(unqualified).append("}")
@@ -246,7 +323,7 @@ impl<'a> Formatter<'a> {
         match value {
             Constant::Int { value, .. } | Constant::Float { value, .. } => value.clone().to_doc(),
 
-            Constant::String { value, .. } => value.clone().to_doc().surround("\"", "\""),
+            Constant::String { value, .. } => string(value),
 
             Constant::List { elements, .. } => {
                 let comma: fn() -> Document = if elements.iter().all(|e| e.is_simple()) {
@@ -258,15 +335,20 @@ impl<'a> Formatter<'a> {
                     .iter()
                     .map(|e| self.const_expr(e))
                     .intersperse(comma());
-                list(concat(elements), None)
+                let elements = concat(elements);
+                list(self.indent, elements, None)
             }
 
             Constant::Tuple { elements, .. } => "tuple"
                 .to_doc()
-                .append(wrap_args(elements.iter().map(|e| self.const_expr(e))))
+                .append(wrap_args(
+                    self.indent,
+                    elements.iter().map(|e| self.const_expr(e)),
+                ))
                 .group(),
 
             Constant::BitString { segments, .. } => bit_string(
+                self.indent,
                 segments
                     .iter()
                     .map(|s| bit_string_segment(s, |e| self.const_expr(e))),
@@ -295,7 +377,10 @@ impl<'a> Formatter<'a> {
             } => name
                 .to_string()
                 .to_doc()
-                .append(wrap_args(args.iter().map(|a| self.constant_call_arg(a))))
+                .append(wrap_args(
+                    self.indent,
+                    args.iter().map(|a| self.constant_call_arg(a)),
+                ))
                 .group(),
 
             Constant::Record {
@@ -308,7 +393,10 @@ impl<'a> Formatter<'a> {
                 .to_doc()
                 .append(".")
                 .append(name.to_string())
-                .append(wrap_args(args.iter().map(|a| self.constant_call_arg(a))))
+                .append(wrap_args(
+                    self.indent,
+                    args.iter().map(|a| self.constant_call_arg(a)),
+                ))
                 .group(),
         }
     }
@@ -325,9 +413,12 @@ impl<'a> Formatter<'a> {
             .append(self.const_expr(value))
     }
 
-    fn documented_statement(&mut self, s: &UntypedStatement) -> Document {
+    fn documented_statement(&mut self, s: &UntypedStatement, attributes: Document) -> Document {
         let comments = self.doc_comments(s.location().start);
-        comments.append(self.statement(s)).group()
+        comments
+            .append(attributes)
+            .append(self.statement(s))
+            .group()
     }
 
     fn doc_comments(&mut self, limit: usize) -> Document {
@@ -376,7 +467,11 @@ impl<'a> Formatter<'a> {
                 .append(self.type_arguments(args))
                 .group()
                 .append(" ->")
-                .append(break_("", " ").append(self.type_ast(retrn)).nest(INDENT)),
+                .append(
+                    break_("", " ")
+                        .append(self.type_ast(retrn))
+                        .nest(self.indent),
+                ),
 
             TypeAst::Var { name, .. } => name.clone().to_doc(),
 
@@ -386,7 +481,7 @@ impl<'a> Formatter<'a> {
     }
 
     fn type_arguments(&mut self, args: &[TypeAst]) -> Document {
-        wrap_args(args.iter().map(|t| self.type_ast(t)))
+        wrap_args(self.indent, args.iter().map(|t| self.type_ast(t)))
     }
 
     pub fn type_alias(
@@ -401,11 +496,11 @@ impl<'a> Formatter<'a> {
         let head = if args.is_empty() {
             head
         } else {
-            head.append(wrap_args(args.iter().map(|e| e.clone().to_doc())).group())
+            head.append(wrap_args(self.indent, args.iter().map(|e| e.clone().to_doc())).group())
         };
 
         head.append(" =")
-            .append(line().append(self.type_ast(typ)).group().nest(INDENT))
+            .append(line().append(self.type_ast(typ)).group().nest(self.indent))
     }
 
     fn fn_arg<A>(&mut self, arg: &Arg<A>) -> Document {
@@ -431,7 +526,7 @@ impl<'a> Formatter<'a> {
         let head = pub_(*public)
             .append("fn ")
             .append(name)
-            .append(wrap_args(args.iter().map(|e| self.fn_arg(e))));
+            .append(wrap_args(self.indent, args.iter().map(|e| self.fn_arg(e))));
 
         // Add return annotation
         let head = match return_annotation {
@@ -451,7 +546,7 @@ impl<'a> Formatter<'a> {
 
         // Stick it all together
         head.append(" {")
-            .append(line().append(body).nest(INDENT).group())
+            .append(line().append(body).nest(self.indent).group())
             .append(line())
             .append("}")
     }
@@ -478,7 +573,7 @@ impl<'a> Formatter<'a> {
         return_annotation: Option<&TypeAst>,
         body: &UntypedExpr,
     ) -> Document {
-        let args = wrap_args(args.iter().map(|e| self.fn_arg(e))).group();
+        let args = wrap_args(self.indent, args.iter().map(|e| self.fn_arg(e))).group();
         let body = match body {
             UntypedExpr::Case { .. } => force_break().append(self.expr(body)),
             _ => self.expr(body),
@@ -495,7 +590,7 @@ impl<'a> Formatter<'a> {
             .append(
                 break_(" {", " { ")
                     .append(body)
-                    .nest(INDENT)
+                    .nest(self.indent)
                     .append(delim(""))
                     .append("}"),
             )
@@ -524,6 +619,7 @@ impl<'a> Formatter<'a> {
         then: &UntypedExpr,
         kind: BindingKind,
         annotation: &Option<TypeAst>,
+        message: &Option<Box<UntypedExpr>>,
     ) -> Document {
         self.pop_empty_lines(pattern.location().end);
 
@@ -539,11 +635,31 @@ impl<'a> Formatter<'a> {
             .as_ref()
             .map(|a| ": ".to_doc().append(self.type_ast(a)));
 
+        let value = match value {
+            // A message would be taken to be that of the `assert`, so a `todo`
+            // or `panic` with a message is given braces
+            UntypedExpr::Todo {
+                message: Some(_), ..
+            }
+            | UntypedExpr::Panic {
+                message: Some(_), ..
+            } if kind == BindingKind::Assert => {
+                let doc = self.expr(value);
+                " ".to_doc().append(self.operator_side(doc, u8::MAX, 0))
+            }
+            _ => self.assigned_value(value),
+        };
+
+        let message = message
+            .as_ref()
+            .map(|message| " as ".to_doc().append(self.error_message(message)));
+
         force_break()
             .append(keyword)
             .append(pattern.append(annotation).group())
             .append(" =")
-            .append(self.assigned_value(value))
+            .append(value)
+            .append(message)
             .append(if self.pop_empty_lines(then.start_byte_index()) {
                 lines(2)
             } else {
@@ -556,9 +672,17 @@ impl<'a> Formatter<'a> {
         let comments = self.pop_comments(expr.start_byte_index());
 
         let document = match expr {
-            UntypedExpr::Todo { label: None, .. } => "todo".to_doc(),
+            UntypedExpr::Todo { message, .. } => self.todo_or_panic("todo", message),
+
+            UntypedExpr::Panic { message, .. } => self.todo_or_panic("panic", message),
 
-            UntypedExpr::Todo { label: Some(l), .. } => ["todo", "(\"", l, "\")"].join("").to_doc(),
+            UntypedExpr::RawErlang {
+                code, annotation, ..
+            } => "@erlang(\""
+                .to_doc()
+                .append(code.clone())
+                .append("\") as ")
+                .append(self.type_ast(annotation)),
 
             UntypedExpr::Pipe {
                 left,
@@ -571,7 +695,7 @@ impl<'a> Formatter<'a> {
 
             UntypedExpr::Float { value, .. } => value.clone().to_doc(),
 
-            UntypedExpr::String { value, .. } => value.clone().to_doc().surround("\"", "\""),
+            UntypedExpr::String { value, .. } => string(value),
 
             UntypedExpr::Seq { first, then, .. } => self.seq(first, then),
 
@@ -601,8 +725,12 @@ impl<'a> Formatter<'a> {
             UntypedExpr::Call { fun, args, .. } => self.call(fun, args),
 
             UntypedExpr::BinOp {
-                name, left, right, ..
-            } => self.bin_op(name, left, right),
+                name,
+                left,
+                right,
+                location,
+                ..
+            } => self.bin_op(name, left, right, location),
 
             UntypedExpr::Let {
                 value,
@@ -610,8 +738,9 @@ impl<'a> Formatter<'a> {
                 annotation,
                 then,
                 kind,
+                message,
                 ..
-            } => self.let_(pattern, value, then, *kind, annotation),
+            } => self.let_(pattern, value, then, *kind, annotation, message),
 
             UntypedExpr::Case {
                 subjects, clauses, ..
@@ -623,10 +752,14 @@ impl<'a> Formatter<'a> {
 
             UntypedExpr::Tuple { elems, .. } => "tuple"
                 .to_doc()
-                .append(wrap_args(elems.iter().map(|e| self.wrap_expr(e))))
+                .append(wrap_args(
+                    self.indent,
+                    elems.iter().map(|e| self.wrap_expr(e)),
+                ))
                 .group(),
 
             UntypedExpr::BitString { segments, .. } => bit_string(
+                self.indent,
                 segments
                     .iter()
                     .map(|s| bit_string_segment(s, |e| self.expr(e))),
@@ -668,6 +801,7 @@ impl<'a> Formatter<'a> {
             name
         } else if with_spread {
             name.append(wrap_args_with_spread(
+                self.indent,
                 args.iter().map(|a| self.pattern_call_arg(a)),
             ))
         } else {
@@ -679,7 +813,10 @@ impl<'a> Formatter<'a> {
                     .group(),
 
                 _ => name
-                    .append(wrap_args(args.iter().map(|a| self.pattern_call_arg(a))))
+                    .append(wrap_args(
+                        self.indent,
+                        args.iter().map(|a| self.pattern_call_arg(a)),
+                    ))
                     .group(),
             }
         }
@@ -709,7 +846,10 @@ impl<'a> Formatter<'a> {
 
             _ => self
                 .expr(fun)
-                .append(wrap_args(args.iter().map(|a| self.call_arg(a))))
+                .append(wrap_args(
+                    self.indent,
+                    args.iter().map(|a| self.call_arg(a)),
+                ))
                 .group(),
         }
     }
@@ -732,7 +872,7 @@ impl<'a> Formatter<'a> {
                 line()
                     .append(force_break())
                     .append(clauses_doc)
-                    .nest(INDENT),
+                    .nest(self.indent),
             )
             .append(line())
             .append("}")
@@ -745,17 +885,45 @@ impl<'a> Formatter<'a> {
         args: &[UntypedRecordUpdateArg],
     ) -> Document {
         use std::iter::once;
+        let indent = self.indent;
         let constructor_doc = self.expr(constructor);
         let spread_doc = "..".to_doc().append(spread.clone().name.to_doc());
         let arg_docs = args.iter().map(|a| self.record_update_arg(a));
         let all_arg_docs = once(spread_doc).chain(arg_docs);
-        constructor_doc.append(wrap_args(all_arg_docs)).group()
+        constructor_doc.append(wrap_args(indent, all_arg_docs)).group()
     }
 
-    pub fn bin_op(&mut self, name: &BinOp, left: &UntypedExpr, right: &UntypedExpr) -> Document {
+    pub fn bin_op(
+        &mut self,
+        name: &BinOp,
+        left: &UntypedExpr,
+        right: &UntypedExpr,
+        location: &SrcSpan,
+    ) -> Document {
         let precedence = name.precedence();
-        let left_precedence = left.binop_precedence();
-        let right_precedence = right.binop_precedence();
+
+        // The location of an operation includes any braces around its
+        // operands, so an operand that starts or ends inside it was written
+        // in braces. These are kept when the operand uses a different
+        // operator, as in `a || { b && c }`, to show how the expression is
+        // grouped, and on the right where they change the meaning, as in
+        // `a - { b - c }`.
+        let keep_left = matches!(left, UntypedExpr::BinOp { .. })
+            && left.location().start > location.start
+            && left.binop_precedence() != precedence;
+        let keep_right =
+            matches!(right, UntypedExpr::BinOp { .. }) && right.location().end < location.end;
+
+        let left_precedence = if keep_left {
+            0
+        } else {
+            left.binop_precedence()
+        };
+        let right_precedence = if keep_right {
+            0
+        } else {
+            right.binop_precedence()
+        };
         let left = self.expr(left);
         let right = self.expr(right);
         self.operator_side(left, precedence, left_precedence)
@@ -767,7 +935,7 @@ impl<'a> Formatter<'a> {
         if op > side {
             delim("{")
                 .append(doc)
-                .nest(INDENT)
+                .nest(self.indent)
                 .append(break_("", " "))
                 .append("}")
                 .group()
@@ -777,31 +945,49 @@ impl<'a> Formatter<'a> {
     }
 
     fn pipe(&mut self, left: &UntypedExpr, right: &UntypedExpr, location_start: usize) -> Document {
-        let left_precedence = left.binop_precedence();
-        let right_precedence = right.binop_precedence();
-        let left = self.wrap_expr(left);
-
-        // Get comments before right but after left
-        let comments = self.pop_comments(location_start);
-
-        let right = match right {
-            UntypedExpr::Fn {
-                is_capture: true,
-                body,
-                ..
-            } => self.pipe_capture_right_hand_side(body),
+        // Flatten the chain so that a single group decides whether every `|>`
+        // is broken onto its own line or the whole chain stays on one line
+        let mut steps = vec![(right, location_start)];
+        let mut first = left;
+        while let UntypedExpr::Pipe {
+            left,
+            right,
+            location,
+            ..
+        } = first
+        {
+            steps.push((right, location.start));
+            first = left;
+        }
 
-            _ => self.wrap_expr(right),
-        };
+        let first_precedence = first.binop_precedence();
+        let first = self.wrap_expr(first);
+        let first = self.operator_side(first, 5, first_precedence);
+
+        let steps: Vec<_> = steps
+            .into_iter()
+            .rev()
+            .map(|(right, location_start)| {
+                // Get comments before right but after left
+                let comments = self.pop_comments(location_start);
+
+                let right_precedence = right.binop_precedence();
+                let right = match right {
+                    UntypedExpr::Fn {
+                        is_capture: true,
+                        body,
+                        ..
+                    } => self.pipe_capture_right_hand_side(body),
+
+                    _ => self.wrap_expr(right),
+                };
+                let right = self.operator_side(right, 4, right_precedence);
 
-        // Wrap sides if required
-        let left = self.operator_side(left, 5, left_precedence);
-        let right = self.operator_side(right, 4, right_precedence);
+                break_("", " ").append(commented("|> ".to_doc().append(right), comments))
+            })
+            .collect();
 
-        force_break()
-            .append(left)
-            .append(line())
-            .append(commented("|> ".to_doc().append(right), comments))
+        first.append(concat(steps.into_iter())).group()
     }
 
     fn pipe_capture_right_hand_side(&mut self, fun: &UntypedExpr) -> Document {
@@ -825,12 +1011,13 @@ impl<'a> Formatter<'a> {
             self.expr(fun)
         } else if hole_in_first_position {
             // x |> fun(_, 2, 3)
-            self.expr(fun)
-                .append(wrap_args(args.iter().skip(1).map(|a| self.call_arg(a))).group())
+            self.expr(fun).append(
+                wrap_args(self.indent, args.iter().skip(1).map(|a| self.call_arg(a))).group(),
+            )
         } else {
             // x |> fun(1, _, 3)
             self.expr(fun)
-                .append(wrap_args(args.iter().map(|a| self.call_arg(a))).group())
+                .append(wrap_args(self.indent, args.iter().map(|a| self.call_arg(a))).group())
         }
     }
 
@@ -838,7 +1025,7 @@ impl<'a> Formatter<'a> {
         match call {
             UntypedExpr::Call { fun, args, .. } => self
                 .expr(fun)
-                .append(wrap_args(args.iter().map(|a| self.call_arg(a))).group()),
+                .append(wrap_args(self.indent, args.iter().map(|a| self.call_arg(a))).group()),
 
             // The body of a capture being not a fn shouldn't be possible...
             _ => crate::error::fatal_compiler_bug(
@@ -847,7 +1034,7 @@ impl<'a> Formatter<'a> {
         }
     }
 
-    pub fn record_constructor(&mut self, constructor: &RecordConstructor) -> Document {
+    pub fn record_constructor<A>(&mut self, constructor: &RecordConstructor<A>) -> Document {
         let comments = self.pop_comments(constructor.location.start);
         let doc_comments = self.doc_comments(constructor.location.start);
 
@@ -858,8 +1045,9 @@ impl<'a> Formatter<'a> {
                 .name
                 .to_string()
                 .to_doc()
-                .append(wrap_args(constructor.args.iter().map(
-                    |(label, typ, arg_location)| {
+                .append(wrap_args(
+                    self.indent,
+                    constructor.args.iter().map(|(label, typ, arg_location)| {
                         let arg_comments = self.pop_comments(arg_location.start);
                         let arg = match label {
                             Some(l) => l
@@ -874,21 +1062,21 @@ impl<'a> Formatter<'a> {
                             self.doc_comments(arg_location.start).append(arg).group(),
                             arg_comments,
                         )
-                    },
-                )))
+                    }),
+                ))
                 .group()
         };
 
         commented(doc_comments.append(doc).group(), comments)
     }
 
-    pub fn custom_type(
+    pub fn custom_type<A>(
         &mut self,
         public: bool,
         opaque: bool,
         name: &str,
         args: &[String],
-        constructors: &[RecordConstructor],
+        constructors: &[RecordConstructor<A>],
         location: &SrcSpan,
     ) -> Document {
         self.pop_empty_lines(location.start);
@@ -900,7 +1088,10 @@ impl<'a> Formatter<'a> {
             } else {
                 name.to_string()
                     .to_doc()
-                    .append(wrap_args(args.iter().map(|e| e.clone().to_doc())))
+                    .append(wrap_args(
+                        self.indent,
+                        args.iter().map(|e| e.clone().to_doc()),
+                    ))
                     .group()
             })
             .append(" {")
@@ -911,7 +1102,7 @@ impl<'a> Formatter<'a> {
                     line()
                 }
                 .append(self.record_constructor(c))
-                .nest(INDENT)
+                .nest(self.indent)
                 .group()
             })))
             .append(line())
@@ -932,9 +1123,10 @@ impl<'a> Formatter<'a> {
             .append(if args.is_empty() {
                 name.to_string().to_doc()
             } else {
-                name.to_string()
-                    .to_doc()
-                    .append(wrap_args(args.iter().map(|e| e.clone().to_doc())))
+                name.to_string().to_doc().append(wrap_args(
+                    self.indent,
+                    args.iter().map(|e| e.clone().to_doc()),
+                ))
             })
     }
 
@@ -951,8 +1143,15 @@ impl<'a> Formatter<'a> {
             .append("fn ")
             .append(name)
             .append(self.docs_fn_args(args, &mut printer))
-            .append(" -> ".to_doc())
-            .append(printer.print(return_type.as_ref()))
+            .append(" ->")
+            .append(
+                // A return type too long to follow the arguments goes on its
+                // own line
+                break_("", " ")
+                    .append(printer.print(return_type.as_ref()))
+                    .nest(self.indent)
+                    .group(),
+            )
     }
 
     // Will always print the types, even if they were implicit in the original source
@@ -961,12 +1160,15 @@ impl<'a> Formatter<'a> {
         args: &[TypedArg],
         printer: &mut typ::pretty::Printer,
     ) -> Document {
-        wrap_args(args.iter().map(|arg| {
-            arg.names
-                .to_doc()
-                .append(": ".to_doc().append(printer.print(&arg.typ)))
-                .group()
-        }))
+        wrap_args(
+            self.indent,
+            args.iter().map(|arg| {
+                arg.names
+                    .to_doc()
+                    .append(": ".to_doc().append(printer.print(&arg.typ)))
+                    .group()
+            }),
+        )
     }
 
     fn external_fn_arg(&mut self, arg: &ExternalFnArg) -> Document {
@@ -976,7 +1178,34 @@ impl<'a> Formatter<'a> {
     }
 
     fn external_fn_args(&mut self, args: &[ExternalFnArg]) -> Document {
-        wrap_args(args.iter().map(|e| self.external_fn_arg(e)))
+        wrap_args(self.indent, args.iter().map(|e| self.external_fn_arg(e)))
+    }
+
+    fn todo_or_panic(
+        &mut self,
+        keyword: &'static str,
+        message: &Option<Box<UntypedExpr>>,
+    ) -> Document {
+        match message {
+            None => keyword.to_doc(),
+            Some(message) => keyword
+                .to_doc()
+                .append(" as ")
+                .append(self.error_message(message)),
+        }
+    }
+
+    /// The message of a `todo`, `panic`, or `assert`.
+    ///
+    fn error_message(&mut self, message: &UntypedExpr) -> Document {
+        // The message is a simple expression, so any other is given braces
+        match message {
+            UntypedExpr::Seq { .. } | UntypedExpr::Let { .. } => self.wrap_expr(message),
+            _ => {
+                let doc = self.expr(message);
+                self.operator_side(doc, u8::MAX, message.binop_precedence())
+            }
+        }
     }
 
     fn wrap_expr(&mut self, expr: &UntypedExpr) -> Document {
@@ -984,7 +1213,7 @@ impl<'a> Formatter<'a> {
             UntypedExpr::Seq { .. } | UntypedExpr::Let { .. } => "{"
                 .to_doc()
                 .append(force_break())
-                .append(line().append(self.expr(expr)).nest(INDENT))
+                .append(line().append(self.expr(expr)).nest(self.indent))
                 .append(line())
                 .append("}"),
 
@@ -1024,7 +1253,7 @@ impl<'a> Formatter<'a> {
         match expr {
             UntypedExpr::Seq { .. } | UntypedExpr::Let { .. } => " {"
                 .to_doc()
-                .append(line().append(self.expr(expr)).nest(INDENT).group())
+                .append(line().append(self.expr(expr)).nest(self.indent).group())
                 .append(line())
                 .append(force_break())
                 .append("}"),
@@ -1034,9 +1263,12 @@ impl<'a> Formatter<'a> {
             | UntypedExpr::ListCons { .. }
             | UntypedExpr::BitString { .. } => " ".to_doc().append(self.expr(expr)).group(),
 
-            UntypedExpr::Case { .. } => line().append(self.expr(expr)).nest(INDENT).group(),
+            UntypedExpr::Case { .. } => line().append(self.expr(expr)).nest(self.indent).group(),
 
-            _ => break_("", " ").append(self.expr(expr)).nest(INDENT).group(),
+            _ => break_("", " ")
+                .append(self.expr(expr))
+                .nest(self.indent)
+                .group(),
         }
     }
 
@@ -1082,7 +1314,7 @@ impl<'a> Formatter<'a> {
             .append(if args.is_empty() {
                 nil()
             } else {
-                wrap_args(args.iter().map(|e| e.clone().to_doc()))
+                wrap_args(self.indent, args.iter().map(|e| e.clone().to_doc()))
             })
     }
 
@@ -1096,7 +1328,7 @@ impl<'a> Formatter<'a> {
             };
         let elems = concat(elems.iter().map(|e| self.wrap_expr(e)).intersperse(comma()));
         let tail = tail.map(|e| self.expr(e));
-        list(elems, tail)
+        list(self.indent, elems, tail)
     }
 
     fn pattern(&mut self, pattern: &UntypedPattern) -> Document {
@@ -1106,7 +1338,7 @@ impl<'a> Formatter<'a> {
 
             Pattern::Float { value, .. } => value.clone().to_doc(),
 
-            Pattern::String { value, .. } => value.clone().to_doc().surround("\"", "\""),
+            Pattern::String { value, .. } => string(value),
 
             Pattern::Var { name, .. } => name.to_string().to_doc(),
 
@@ -1131,7 +1363,7 @@ impl<'a> Formatter<'a> {
                         .intersperse(delim(",")),
                 );
                 let tail = tail.map(|e| self.pattern(e));
-                list(elems, tail)
+                list(self.indent, elems, tail)
             }
 
             Pattern::Constructor {
@@ -1144,10 +1376,14 @@ impl<'a> Formatter<'a> {
 
             Pattern::Tuple { elems, .. } => "tuple"
                 .to_doc()
-                .append(wrap_args(elems.iter().map(|e| self.pattern(e))))
+                .append(wrap_args(
+                    self.indent,
+                    elems.iter().map(|e| self.pattern(e)),
+                ))
                 .group(),
 
             Pattern::BitString { segments, .. } => bit_string(
+                self.indent,
                 segments
                     .iter()
                     .map(|s| bit_string_segment(s, |e| self.pattern(e))),
@@ -1251,10 +1487,6 @@ impl<'a> Formatter<'a> {
     }
 }
 
-pub fn pretty_module(m: &UntypedModule, formatter: &mut Formatter<'_>) -> String {
-    format(80, formatter.module(m))
-}
-
 impl Documentable for &ArgNames {
     fn to_doc(self) -> Document {
         match self {
@@ -1267,6 +1499,65 @@ impl Documentable for &ArgNames {
     }
 }
 
+fn derives_(derives: &[DerivedFunction]) -> Document {
+    if derives.is_empty() {
+        return nil();
+    }
+    let names = derives
+        .iter()
+        .map(|derived| derived.derive.name())
+        .join(", ");
+    format!("@derive({})", names).to_doc().append(line())
+}
+
+/// The attributes of the statement starting at the given offset.
+fn attributes_(module: &UntypedModule, start: usize) -> Document {
+    target_(module.target_of(start))
+        .append(deprecated_(module.deprecation_of(start)))
+        .append(internal_(module.is_internal(start)))
+        .append(allow_(module.allowed_codes_of(start)))
+}
+
+fn target_(target: Option<Target>) -> Document {
+    match target {
+        Some(target) => format!("@target({})", target.name())
+            .to_doc()
+            .append(force_break())
+            .append(line()),
+        None => nil(),
+    }
+}
+
+fn deprecated_(message: Option<&str>) -> Document {
+    match message {
+        Some(message) => "@deprecated("
+            .to_doc()
+            .append(string(message))
+            .append(")")
+            .append(force_break())
+            .append(line()),
+        None => nil(),
+    }
+}
+
+fn internal_(internal: bool) -> Document {
+    if internal {
+        "@internal".to_doc().append(force_break()).append(line())
+    } else {
+        nil()
+    }
+}
+
+fn allow_(codes: &[String]) -> Document {
+    if codes.is_empty() {
+        return nil();
+    }
+    format!("@allow({})", codes.join(", "))
+        .to_doc()
+        .append(force_break())
+        .append(line())
+}
+
 fn pub_(public: bool) -> Document {
     if public {
         "pub ".to_doc()
@@ -1284,6 +1575,42 @@ impl Documentable for &UnqualifiedImport {
     }
 }
 
+/// A string literal in the canonical escape style. Escapes are kept as they
+/// were written, other than the hex digits of `\u{...}` escapes which are
+/// written in lowercase. Characters that cannot be seen, other than newlines,
+/// are escaped so that the literal shows what the string holds.
+///
+fn string(value: &str) -> Document {
+    let mut escaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                escaped.push(c);
+                match chars.next() {
+                    Some('u') => {
+                        escaped.push('u');
+                        for c in chars.by_ref() {
+                            escaped.push(c.to_ascii_lowercase());
+                            if c == '}' {
+                                break;
+                            }
+                        }
+                    }
+                    Some(c) => escaped.push(c),
+                    None => (),
+                }
+            }
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            '\n' => escaped.push(c),
+            _ if c.is_control() => escaped.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            _ => escaped.push(c),
+        }
+    }
+    escaped.to_doc().surround("\"", "\"")
+}
+
 fn label(label: &Option<String>) -> Document {
     match label {
         Some(s) => s.clone().to_doc().append(": "),
@@ -1293,30 +1620,7 @@ fn label(label: &Option<String>) -> Document {
 
 impl Documentable for &BinOp {
     fn to_doc(self) -> Document {
-        match self {
-            BinOp::And => " && ",
-            BinOp::Or => " || ",
-            BinOp::LtInt => " < ",
-            BinOp::LtEqInt => " <= ",
-            BinOp::LtFloat => " <. ",
-            BinOp::LtEqFloat => " <=. ",
-            BinOp::Eq => " == ",
-            BinOp::NotEq => " != ",
-            BinOp::GtEqInt => " >= ",
-            BinOp::GtInt => " > ",
-            BinOp::GtEqFloat => " >=. ",
-            BinOp::GtFloat => " >. ",
-            BinOp::AddInt => " + ",
-            BinOp::AddFloat => " +. ",
-            BinOp::SubInt => " - ",
-            BinOp::SubFloat => " -. ",
-            BinOp::MultInt => " * ",
-            BinOp::MultFloat => " *. ",
-            BinOp::DivInt => " / ",
-            BinOp::DivFloat => " /. ",
-            BinOp::ModuloInt => " % ",
-        }
-        .to_doc()
+        format!(" {} ", self.name()).to_doc()
     }
 }
 
@@ -1340,7 +1644,7 @@ fn categorise_list_pattern(expr: &UntypedPattern) -> ListType<&UntypedPattern, &
     }
 }
 
-pub fn wrap_args<I>(args: I) -> Document
+pub fn wrap_args<I>(indent: isize, args: I) -> Document
 where
     I: Iterator<Item = Document>,
 {
@@ -1350,12 +1654,12 @@ where
     }
     break_("(", "(")
         .append(concat(args.intersperse(delim(","))))
-        .nest(INDENT)
+        .nest(indent)
         .append(break_(",", ""))
         .append(")")
 }
 
-pub fn wrap_args_with_spread<I>(args: I) -> Document
+pub fn wrap_args_with_spread<I>(indent: isize, args: I) -> Document
 where
     I: Iterator<Item = Document>,
 {
@@ -1368,7 +1672,7 @@ where
         .append(concat(args.intersperse(delim(","))))
         .append(break_(",", ", "))
         .append("..")
-        .nest(INDENT)
+        .nest(indent)
         .append(break_(",", ""))
         .append(")")
         .group()
@@ -1387,7 +1691,11 @@ where
     (elems, tail)
 }
 
-fn bit_string(segments: impl Iterator<Item = Document>, is_simple: bool) -> Document {
+fn bit_string(
+    indent: isize,
+    segments: impl Iterator<Item = Document>,
+    is_simple: bool,
+) -> Document {
     let comma = if is_simple {
         delim(",").flex_break()
     } else {
@@ -1395,30 +1703,30 @@ fn bit_string(segments: impl Iterator<Item = Document>, is_simple: bool) -> Docu
     };
     break_("<<", "<<")
         .append(concat(segments.intersperse(comma)))
-        .nest(INDENT)
+        .nest(indent)
         .append(break_(",", ""))
         .append(">>")
         .group()
 }
 
-fn list(elems: Document, tail: Option<Document>) -> Document {
+fn list(indent: isize, elems: Document, tail: Option<Document>) -> Document {
     let doc = break_("[", "[").append(elems);
 
     match tail {
-        None => doc.nest(INDENT).append(break_(",", "")),
+        None => doc.nest(indent).append(break_(",", "")),
 
         // Don't print tail if it is a discard
         Some(Document::Text(t)) if t == *"_" => doc
             .append(break_(",", ", "))
             .append("..")
-            .nest(INDENT)
+            .nest(indent)
             .append(break_("", "")),
 
         Some(final_tail) => doc
             .append(break_(",", ", "))
             .append("..")
             .append(final_tail)
-            .nest(INDENT)
+            .nest(indent)
             .append(break_("", "")),
     }
     .append("]")
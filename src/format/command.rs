@@ -1,8 +1,11 @@
-use crate::error::{Error, FileIOAction, FileKind, StandardIOAction};
+use crate::{
+    config::{self, FormatConfig},
+    error::{Error, FileIOAction, FileKind, StandardIOAction},
+};
 use std::{
     fs::File,
     io::{Read, Write},
-    path::PathBuf,
+    path::{Path, PathBuf},
     str::FromStr,
 };
 
@@ -20,19 +23,120 @@ pub enum Destination {
     File { path: PathBuf },
 }
 
-pub fn run(stdin: bool, check: bool, files: Vec<String>) -> Result<(), Error> {
+/// How many of the formatted files were changed by the formatter.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Summary {
+    pub reformatted: usize,
+    pub unchanged: usize,
+}
+
+impl Summary {
+    pub fn new(formatted_files: &[Formatted]) -> Self {
+        let reformatted = formatted_files
+            .iter()
+            .filter(|formatted| formatted.input != formatted.output)
+            .count();
+        Self {
+            reformatted,
+            unchanged: formatted_files.len() - reformatted,
+        }
+    }
+}
+
+impl std::fmt::Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Reformatted {} {}, {} unchanged",
+            self.reformatted,
+            if self.reformatted == 1 {
+                "file"
+            } else {
+                "files"
+            },
+            self.unchanged
+        )
+    }
+}
+
+pub fn run(
+    stdin: bool,
+    check: bool,
+    diff: bool,
+    files: Vec<String>,
+    ignore: Vec<String>,
+) -> Result<(), Error> {
     let formatted = if stdin {
-        vec![read_and_format_stdin()?]
+        let (_, config) = read_format_config(Path::new("."))?;
+
+        // Standard input is formatted straight to standard output, so that
+        // very large generated files are not held in memory as one string
+        if !check && !diff {
+            let src = read_stdin()?;
+            return crate::format::pretty_file_to_stdout(Path::new(STDIN_PATH), &src, &config);
+        }
+        vec![read_and_format_stdin(&config)?]
     } else {
-        read_and_format_paths(files)?
+        // Each path is formatted with the config of the project it is in
+        let mut formatted = Vec::with_capacity(files.len());
+        for file in files {
+            let (root, mut config) = read_format_config(Path::new(&file))?;
+            config.ignore.extend(ignore.iter().cloned());
+            formatted.extend(read_and_format_paths_in(&root, vec![file], &config)?);
+        }
+        formatted
     };
+
+    // Files are never written when showing a diff, which can be combined with
+    // `--check` to also fail if any are not formatted
+    if diff {
+        print!("{}", diffs(formatted.as_slice()));
+        return if check {
+            check_formatting(formatted)
+        } else {
+            Ok(())
+        };
+    }
+
     if check {
         check_formatting(formatted)
     } else {
-        write_formatted(formatted)
+        let summary = Summary::new(formatted.as_slice());
+        write_formatted(formatted)?;
+        println!("{}", summary);
+        Ok(())
     }
 }
 
+/// Check that the project's source and test files are formatted, without
+/// changing them, for `gleam build --verify-format`.
+///
+pub fn verify(root: &Path, config: &FormatConfig) -> Result<(), Error> {
+    let dirs = ["src", "test"]
+        .iter()
+        .map(|dir| root.join(dir))
+        .filter(|dir| dir.is_dir())
+        .map(|dir| dir.to_string_lossy().to_string())
+        .collect();
+    check_formatting(read_and_format_paths_in(root, dirs, config)?)
+}
+
+/// The unified diff of the changes made by the formatter to each file.
+///
+pub fn diffs(formatted_files: &[Formatted]) -> String {
+    formatted_files
+        .iter()
+        .map(|formatted| {
+            crate::format::diff::unified(
+                &formatted.source.to_string_lossy(),
+                &formatted.input,
+                &formatted.output,
+            )
+        })
+        .collect()
+}
+
 fn check_formatting(formatted_files: Vec<Formatted>) -> Result<(), Error> {
     let problem_files: Vec<_> = formatted_files
         .into_iter()
@@ -81,9 +185,88 @@ fn write_formatted(formatted_files: Vec<Formatted>) -> Result<(), Error> {
     Ok(())
 }
 
-pub fn read_and_format_paths(files: Vec<String>) -> Result<Vec<Formatted>, Error> {
+// The config of the project that the path is in, along with the root of the
+// project that its ignore globs are relative to. The formatter can be used
+// outside of a project, in which case the default settings are used.
+fn read_format_config(path: &Path) -> Result<(PathBuf, FormatConfig), Error> {
+    let dir = if path.is_dir() {
+        path
+    } else {
+        path.parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."))
+    };
+    match config::find_project_root(dir) {
+        Ok(root) => {
+            let config = config::read_project_config(&root)?.format;
+            Ok((root_as_written(path, &root), config))
+        }
+        Err(Error::MissingConfig { .. }) => Ok((PathBuf::from("."), FormatConfig::default())),
+        Err(error) => Err(error),
+    }
+}
+
+// Ignore globs are matched against the paths found within a formatted path
+// after removing the root as a prefix, so the root is written in the same
+// form as the path: absolute, or relative to the current directory.
+fn root_as_written(path: &Path, root: &Path) -> PathBuf {
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    if path.is_absolute() {
+        return root;
+    }
+    let current = match std::env::current_dir().and_then(|dir| dir.canonicalize()) {
+        Ok(dir) => dir,
+        Err(_) => return root,
+    };
+    let common = current
+        .ancestors()
+        .find(|dir| root.starts_with(dir))
+        .unwrap_or_else(|| Path::new("/"));
+    let ups = current
+        .strip_prefix(common)
+        .map_or(0, |up| up.components().count());
+    let relative: PathBuf = (0..ups)
+        .map(|_| Path::new(".."))
+        .chain(root.strip_prefix(common).ok())
+        .collect();
+    if relative.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        relative
+    }
+}
+
+#[cfg(test)]
+pub fn read_and_format_paths(
+    files: Vec<String>,
+    config: &FormatConfig,
+) -> Result<Vec<Formatted>, Error> {
+    read_and_format_paths_in(Path::new("."), files, config)
+}
+
+fn read_and_format_paths_in(
+    root: &Path,
+    files: Vec<String>,
+    config: &FormatConfig,
+) -> Result<Vec<Formatted>, Error> {
     let mut formatted_files = Vec::with_capacity(files.len());
 
+    // Ignore globs are matched against paths relative to the root in the same
+    // way as a line of a `.gitignore` file.
+    let mut overrides = ignore::overrides::OverrideBuilder::new(root);
+    for glob in config.ignore.iter() {
+        overrides
+            .add(&format!("!{}", glob))
+            .map_err(|e| Error::InvalidGlob {
+                glob: glob.clone(),
+                err: e.to_string(),
+            })?;
+    }
+    let overrides = overrides.build().map_err(|e| Error::InvalidGlob {
+        glob: config.ignore.join(" "),
+        err: e.to_string(),
+    })?;
+
     for file_path in files {
         let path = PathBuf::from_str(&file_path).map_err(|e| Error::FileIO {
             action: FileIOAction::Open,
@@ -93,30 +276,21 @@ pub fn read_and_format_paths(files: Vec<String>) -> Result<Vec<Formatted>, Error
         })?;
 
         if path.is_dir() {
-            for path in crate::fs::gleam_files_excluding_gitignore(&path).into_iter() {
-                formatted_files.push(format_file(path)?);
+            for path in crate::fs::gleam_files_excluding_gitignore(&path, overrides.clone()) {
+                formatted_files.push(format_file(path, config)?);
             }
         } else {
-            formatted_files.push(format_file(path)?);
+            formatted_files.push(format_file(path, config)?);
         }
     }
 
     Ok(formatted_files)
 }
 
-fn format_file(path: PathBuf) -> Result<Formatted, Error> {
-    let src = std::fs::read_to_string(&path).map_err(|e| Error::FileIO {
-        action: FileIOAction::Read,
-        kind: FileKind::File,
-        path: path.clone(),
-        err: Some(e.to_string()),
-    })?;
+fn format_file(path: PathBuf, config: &FormatConfig) -> Result<Formatted, Error> {
+    let src = crate::fs::read_as_written(&path)?;
 
-    let formatted = crate::format::pretty(src.as_ref()).map_err(|error| Error::Parse {
-        path: path.clone(),
-        src: src.clone(),
-        error,
-    })?;
+    let formatted = crate::format::pretty_file(&path, &src, config)?;
 
     Ok(Formatted {
         source: path.clone(),
@@ -126,7 +300,10 @@ fn format_file(path: PathBuf) -> Result<Formatted, Error> {
     })
 }
 
-pub fn read_and_format_stdin() -> Result<Formatted, Error> {
+/// The path given for standard input in errors and diffs.
+const STDIN_PATH: &str = "<standard input>";
+
+fn read_stdin() -> Result<String, Error> {
     let mut src = String::new();
     std::io::stdin()
         .read_to_string(&mut src)
@@ -134,14 +311,14 @@ pub fn read_and_format_stdin() -> Result<Formatted, Error> {
             action: StandardIOAction::Read,
             err: Some(e.kind()),
         })?;
+    Ok(src)
+}
 
-    let formatted = crate::format::pretty(src.as_ref()).map_err(|error| Error::Parse {
-        path: PathBuf::from("<standard input>"),
-        src: src.clone(),
-        error,
-    })?;
+pub fn read_and_format_stdin(config: &FormatConfig) -> Result<Formatted, Error> {
+    let src = read_stdin()?;
+    let formatted = crate::format::pretty_file(Path::new(STDIN_PATH), &src, config)?;
     Ok(Formatted {
-        source: PathBuf::from("<standard input>"),
+        source: PathBuf::from(STDIN_PATH),
         destination: Destination::Stdout,
         input: src,
         output: formatted,
@@ -0,0 +1,246 @@
+//! Unified diffs between the contents of a file and its formatted contents,
+//! printed by `gleam format --diff`.
+
+/// The number of unchanged lines shown either side of a change.
+const CONTEXT: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Edit {
+    Keep,
+    Delete,
+    Insert,
+}
+
+/// A unified diff of the changes that turn `old` into `new`, which is empty
+/// when they are the same.
+///
+pub fn unified(path: &str, old: &str, new: &str) -> String {
+    let old: Vec<_> = old.split_inclusive('\n').collect();
+    let new: Vec<_> = new.split_inclusive('\n').collect();
+
+    // Each edit with the indexes of the old and new lines it is at
+    let mut lines = Vec::new();
+    let (mut old_index, mut new_index) = (0, 0);
+    for edit in edits(&old, &new) {
+        lines.push((edit, old_index, new_index));
+        match edit {
+            Edit::Keep => {
+                old_index += 1;
+                new_index += 1;
+            }
+            Edit::Delete => old_index += 1,
+            Edit::Insert => new_index += 1,
+        }
+    }
+
+    let changes: Vec<_> = (0..lines.len())
+        .filter(|i| lines[*i].0 != Edit::Keep)
+        .collect();
+    if changes.is_empty() {
+        return String::new();
+    }
+
+    let mut diff = format!("--- {}\n+++ {}\n", path, path);
+    let mut changes = changes.into_iter().peekable();
+    while let Some(first) = changes.next() {
+        // Changes close enough that their context would overlap are shown in
+        // the same hunk
+        let mut last = first;
+        while let Some(next) = changes.peek() {
+            if *next - last > 2 * CONTEXT {
+                break;
+            }
+            last = *next;
+            let _ = changes.next();
+        }
+        let end = (last + CONTEXT + 1).min(lines.len());
+        let hunk = &lines[first.saturating_sub(CONTEXT)..end];
+
+        let old_count = hunk
+            .iter()
+            .filter(|(edit, ..)| *edit != Edit::Insert)
+            .count();
+        let new_count = hunk
+            .iter()
+            .filter(|(edit, ..)| *edit != Edit::Delete)
+            .count();
+        let (_, old_start, new_start) = hunk[0];
+        diff.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            range(old_start, old_count),
+            range(new_start, new_count)
+        ));
+        for (edit, old_index, new_index) in hunk {
+            let (prefix, line) = match edit {
+                Edit::Keep => (' ', old[*old_index]),
+                Edit::Delete => ('-', old[*old_index]),
+                Edit::Insert => ('+', new[*new_index]),
+            };
+            diff.push(prefix);
+            diff.push_str(line);
+            if !line.ends_with('\n') {
+                diff.push_str("\n\\ No newline at end of file\n");
+            }
+        }
+    }
+    diff
+}
+
+/// The range of lines of a hunk, written as in the output of `diff -u`.
+///
+fn range(start: usize, count: usize) -> String {
+    match count {
+        // An empty range is written as the line before it
+        0 => format!("{},0", start),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{}", start + 1, count),
+    }
+}
+
+/// The fewest edits that turn the old lines into the new lines, found with
+/// Myers' algorithm.
+///
+fn edits(old: &[&str], new: &[&str]) -> Vec<Edit> {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    let offset = n + m;
+    let index = |k: isize| (offset + k) as usize;
+    // Whether the path to diagonal `k` with `d` edits is best reached by an
+    // insertion from diagonal `k + 1`, rather than a deletion from `k - 1`
+    let inserted = |furthest: &[isize], k: isize, d: isize| {
+        k == -d || (k != d && furthest[index(k - 1)] < furthest[index(k + 1)])
+    };
+
+    // For each number of edits, the furthest point in the old lines reached
+    // on each diagonal
+    let mut furthest = vec![0; 2 * offset as usize + 2];
+    let mut trace = Vec::new();
+    'search: for d in 0..=offset {
+        trace.push(furthest.clone());
+        for k in (-d..=d).step_by(2) {
+            let mut x = if inserted(&furthest, k, d) {
+                furthest[index(k + 1)]
+            } else {
+                furthest[index(k - 1)] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            furthest[index(k)] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+        }
+    }
+
+    // Follow the path back from the end to find the edits
+    let mut edits = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, furthest) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+        let previous_k = if inserted(furthest, k, d) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let previous_x = furthest[index(previous_k)];
+        let previous_y = previous_x - previous_k;
+        while x > previous_x && y > previous_y {
+            edits.push(Edit::Keep);
+            x -= 1;
+            y -= 1;
+        }
+        if d > 0 {
+            edits.push(if x == previous_x {
+                Edit::Insert
+            } else {
+                Edit::Delete
+            });
+        }
+        x = previous_x;
+        y = previous_y;
+    }
+    edits.reverse();
+    edits
+}
+
+#[test]
+fn edits_test() {
+    use Edit::*;
+    assert_eq!(Vec::<Edit>::new(), edits(&[], &[]));
+    assert_eq!(vec![Keep, Keep], edits(&["a", "b"], &["a", "b"]));
+    assert_eq!(vec![Insert, Insert], edits(&[], &["a", "b"]));
+    assert_eq!(vec![Delete, Delete], edits(&["a", "b"], &[]));
+    assert_eq!(
+        vec![Delete, Keep, Delete, Keep, Insert, Keep],
+        edits(&["a", "b", "c", "d", "e"], &["b", "d", "x", "e"])
+    );
+}
+
+#[test]
+fn unified_test() {
+    assert_eq!("", unified("same.gleam", "a\nb\n", "a\nb\n"));
+
+    let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\n13\n14\n15\n16\n";
+    let new = "1\n2\nthree\n4\n5\n6\n7\n8\n9\n10\n11\n12\n13\n15\n16\n";
+    assert_eq!(
+        "--- numbers.gleam
++++ numbers.gleam
+@@ -1,6 +1,6 @@
+ 1
+ 2
+-3
++three
+ 4
+ 5
+ 6
+@@ -11,6 +11,5 @@
+ 11
+ 12
+ 13
+-14
+ 15
+ 16
+",
+        unified("numbers.gleam", old, new)
+    );
+
+    // Changes with overlapping context are in one hunk
+    assert_eq!(
+        "--- close.gleam
++++ close.gleam
+@@ -1,4 +1,4 @@
+-1
++one
+ 2
+ 3
+-4
++four
+",
+        unified("close.gleam", "1\n2\n3\n4\n", "one\n2\n3\nfour\n")
+    );
+
+    // A missing newline at the end of the file is shown
+    assert_eq!(
+        "--- end.gleam
++++ end.gleam
+@@ -1 +1 @@
+-x
+\\ No newline at end of file
++x
+",
+        unified("end.gleam", "x", "x\n")
+    );
+
+    // Lines added to an empty file
+    assert_eq!(
+        "--- empty.gleam
++++ empty.gleam
+@@ -0,0 +1 @@
++x
+",
+        unified("empty.gleam", "", "x\n")
+    );
+}
@@ -0,0 +1,328 @@
+//! A fuzzer for the formatter that mutates valid Gleam source code, checking
+//! that formatting never panics and that formatting the formatted code does
+//! not change it.
+//!
+//! A short run is part of the test suite. Longer runs can be made with
+//! `GLEAM_FUZZ_ITERATIONS` and `GLEAM_FUZZ_SEED` set, for example:
+//!
+//! ```sh
+//! GLEAM_FUZZ_ITERATIONS=100000 GLEAM_FUZZ_SEED=42 cargo test --release formatter_fuzz
+//! ```
+//!
+//! Inputs that are found to fail are added to `REGRESSIONS`.
+
+use super::format_source;
+use std::panic::{self, AssertUnwindSafe};
+
+/// Inputs the formatter has failed on in the past.
+const REGRESSIONS: &[&str] = &[
+    // A comment before the `.` of a field access was printed inside the call
+    // it was the function of, breaking the arguments of the call
+    "fn main() {\n  list// comment\n.head(xs)\n}\n",
+];
+
+/// Source code the mutated input is built from.
+const FRAGMENTS: &[&str] = &[
+    "(",
+    ")",
+    "{",
+    "}",
+    "[",
+    "]",
+    "<<",
+    ">>",
+    ",",
+    ".",
+    "..",
+    ":",
+    "=",
+    "->",
+    "<-",
+    "|>",
+    "|",
+    "#",
+    "_",
+    "+",
+    "-.",
+    "*",
+    "/",
+    "<=",
+    "==",
+    "&&",
+    "||",
+    " ",
+    "\n",
+    "\n\n",
+    "x",
+    "Ok",
+    "1",
+    "-1",
+    "1.5",
+    "0xFF",
+    "\"string\"",
+    "\"\\n\"",
+    "\"\t\\t\\\"\"",
+    "true",
+    "x.y",
+    "x.0",
+    "let ",
+    "let x = ",
+    "assert ",
+    "assert Ok(x) = y as \"message\"\n",
+    "try ",
+    "case ",
+    "case x {",
+    "if ",
+    "as ",
+    "pub ",
+    "fn",
+    "fn(a) { a }",
+    "fn(x: Int) -> Int {",
+    "tuple(",
+    "#(",
+    "todo",
+    "todo(\"later\")",
+    "import a/b.{c, D as E}\n",
+    "type A {",
+    "B(c: C)",
+    "pub opaque type A(b) {",
+    "type A = B\n",
+    "external type A\n",
+    "external fn f(Int) -> Int = \"m\" \"f\"\n",
+    "const x = 1\n",
+    "<<1:size(8)-unit(2)>>",
+    "[1, ..xs]",
+    "A(..a, b: 1)",
+    "// comment\n",
+    "/// doc comment\n",
+    "//// module comment\n",
+    "@target(erlang)\n",
+    "@deprecated(\"use b\")\n",
+    "@derive(Eq)\n",
+    "@allow(todo, unused-type)\n",
+];
+
+/// Source code that starts a new line.
+const LINE_FRAGMENTS: &[&str] = &[
+    "\n",
+    "// comment\n",
+    "  // indented comment\n",
+    "/// doc comment\n",
+    "//// module comment\n",
+    "let x = 1\n",
+    "x\n",
+    "|> f\n",
+];
+
+/// The widths that code is formatted to, including some narrow enough that
+/// most code does not fit.
+const WIDTHS: &[usize] = &[1, 20, 40, 80, 120];
+
+const CORPUS: &[&str] = &[
+    "pub fn main() {
+  let x = 1
+  case x {
+    1 -> \"one\"
+    _ -> int.to_string(x) |> string.append(\"!\")
+  }
+}
+",
+    "import gleam/list.{map, fold as reduce}
+
+/// A shape
+pub type Shape {
+  Circle(radius: Float)
+  Rectangle(width: Float, height: Float)
+}
+
+pub fn area(shape) {
+  // The area of the shape
+  case shape {
+    Circle(radius: r) -> 3.14 *. r *. r
+    Rectangle(width, height) -> width *. height
+  }
+}
+",
+    "pub external fn print(String) -> Nil = \"io\" \"put_chars\"
+
+const bits = <<1, 2:size(8)-unit(2), \"ok\":utf8>>
+
+fn sum(xs: List(Int)) -> Int {
+  list.fold(xs, 0, fn(x, acc) { x + acc })
+}
+
+fn first(xs) {
+  try x = list.head(xs)
+  assert Ok(y) = Ok(x)
+  let tuple(a, b) = tuple(y, [1, 2, ..xs])
+  Ok(a.0)
+}
+",
+    "@target(erlang)
+@deprecated(\"Use new instead\")
+pub fn old(a, b) {
+  todo
+}
+
+pub type Person {
+  Person(name: String, age: Int)
+}
+
+fn birthday(person) {
+  Person(..person, age: person.age + 1)
+}
+",
+];
+
+/// A xorshift random number generator, so that a run can be repeated from
+/// its seed.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn choose<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[self.below(items.len())]
+    }
+
+    /// A position in the string that is on a character boundary.
+    fn position(&mut self, s: &str) -> usize {
+        let mut index = self.below(s.len() + 1);
+        while !s.is_char_boundary(index) {
+            index -= 1;
+        }
+        index
+    }
+
+    /// The position of the start of a line of the string.
+    fn line_start(&mut self, s: &str) -> usize {
+        let index = self.position(s);
+        s[..index].rfind('\n').map(|i| i + 1).unwrap_or(0)
+    }
+
+    fn range(&mut self, s: &str) -> (usize, usize) {
+        let start = self.position(s);
+        let mut end = (start + self.below(21)).min(s.len());
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+        (start, end)
+    }
+}
+
+fn mutate(rng: &mut Rng, src: &str) -> String {
+    let mut src = src.to_string();
+    for _ in 0..=rng.below(4) {
+        match rng.below(6) {
+            0 => {
+                let index = rng.position(&src);
+                src.insert_str(index, rng.choose(FRAGMENTS));
+            }
+            1 => {
+                let (start, end) = rng.range(&src);
+                src.replace_range(start..end, "");
+            }
+            2 => {
+                let (start, end) = rng.range(&src);
+                let copied = src[start..end].to_string();
+                let index = rng.position(&src);
+                src.insert_str(index, &copied);
+            }
+            3 => {
+                let (start, end) = rng.range(&src);
+                src.replace_range(start..end, rng.choose(FRAGMENTS));
+            }
+            // Lines are moved and comments added between them, as the code
+            // is more likely to still parse
+            4 => {
+                let start = rng.line_start(&src);
+                let end = src[start..].find('\n').map_or(src.len(), |i| start + i + 1);
+                let line = src[start..end].to_string();
+                let index = rng.line_start(&src);
+                src.insert_str(index, &line);
+            }
+            _ => {
+                let index = rng.line_start(&src);
+                src.insert_str(index, rng.choose(LINE_FRAGMENTS));
+            }
+        }
+    }
+    src
+}
+
+/// Why formatting the source code failed, if it did. Source code that cannot
+/// be parsed is not a failure.
+///
+fn check(src: &str, width: usize) -> Option<String> {
+    let format = |src: &str| {
+        panic::catch_unwind(AssertUnwindSafe(|| {
+            format_source(src, width).map_err(|error| format!("{:?}", error))
+        }))
+    };
+    let formatted = match format(src) {
+        Err(_) => return Some("the formatter panicked".to_string()),
+        Ok(Err(_)) => return None,
+        Ok(Ok(formatted)) => formatted,
+    };
+    match format(&formatted) {
+        Err(_) => Some("the formatter panicked on its own output".to_string()),
+        Ok(Err(error)) => Some(format!("the output could not be parsed: {}", error)),
+        Ok(Ok(reformatted)) if reformatted != formatted => Some(format!(
+            "formatting is not idempotent, the output was\n{}\nand then\n{}",
+            formatted, reformatted
+        )),
+        Ok(Ok(_)) => None,
+    }
+}
+
+fn assert_formats(src: &str) {
+    for width in WIDTHS {
+        if let Some(failure) = check(src, *width) {
+            panic!(
+                "Formatting to width {} failed as {}, for the source\n\n{:?}\n",
+                width, failure, src
+            );
+        }
+    }
+}
+
+fn env_number(name: &str, default: u64) -> u64 {
+    std::env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+#[test]
+fn corpus_test() {
+    for src in CORPUS {
+        assert_formats(src);
+    }
+}
+
+#[test]
+fn regressions_test() {
+    for src in REGRESSIONS {
+        assert_formats(src);
+    }
+}
+
+#[test]
+fn formatter_fuzz_test() {
+    let iterations = env_number("GLEAM_FUZZ_ITERATIONS", 100);
+    let mut rng = Rng(env_number("GLEAM_FUZZ_SEED", 1).max(1));
+    for _ in 0..iterations {
+        let src = rng.choose(CORPUS);
+        let src = mutate(&mut rng, src);
+        assert_formats(&src);
+    }
+}
@@ -4,13 +4,13 @@ macro_rules! assert_format {
     ($src:expr $(,)?) => {
         // println!("\n\n\n{}", $src);
         let src = $src.to_string();
-        assert_eq!(src, pretty($src).unwrap());
+        assert_eq!(src, pretty($src, &Default::default()).unwrap());
     };
 }
 
 macro_rules! assert_format_rewrite {
     ($src:expr, $output:expr  $(,)?) => {
-        assert_eq!(pretty($src).unwrap(), $output);
+        assert_eq!(pretty($src, &Default::default()).unwrap(), $output);
     };
 }
 
@@ -310,6 +310,125 @@ type Many(a) =
     );
 }
 
+#[test]
+fn derived_custom_types() {
+    assert_format!(
+        "@derive(equal, compare)
+pub type Shape {
+  Circle(radius: Float)
+  Point
+}
+"
+    );
+
+    assert_format!(
+        "/// A box
+@derive(equal)
+pub opaque type Box(a) {
+  Box(a)
+}
+"
+    );
+}
+
+#[test]
+fn deprecated_attributes() {
+    assert_format!(
+        "/// Show a value
+@deprecated(\"Use `describe` instead\")
+pub fn show(x) {
+  x
+}
+
+@target(erlang)
+@deprecated(\"Use a \\\"tuple\\\"\")
+pub external type Box
+"
+    );
+}
+
+#[test]
+fn allow_attributes() {
+    assert_format!(
+        "@allow(unused-type)
+import other
+
+/// Not yet written
+@target(erlang)
+@deprecated(\"Use `describe` instead\")
+@allow(todo, float-equality)
+pub fn show(x) {
+  todo
+}
+"
+    );
+
+    assert_format_rewrite!(
+        "@allow( todo , redundant-let ) fn main() { todo }",
+        "@allow(todo, redundant-let)
+fn main() {
+  todo
+}
+",
+    );
+}
+
+#[test]
+fn internal_attributes() {
+    assert_format!(
+        "/// Used by the other modules of the package
+@deprecated(\"Use `parse` instead\")
+@internal
+@allow(todo)
+pub fn parse_header(x) {
+  todo
+}
+
+@internal
+pub type Header {
+  Header
+}
+"
+    );
+
+    assert_format_rewrite!(
+        "@internal   pub const limit = 10",
+        "@internal
+pub const limit = 10
+",
+    );
+}
+
+#[test]
+fn target_attributes() {
+    assert_format!(
+        "@target(javascript)
+import other
+
+/// Printing for the Erlang target
+@target(erlang)
+pub external fn print(String) -> Nil =
+  \"io\" \"put_chars\"
+
+@target(javascript)
+const greeting = \"Hello\"
+
+pub fn main() {
+  print(greeting)
+}
+"
+    );
+
+    assert_format!(
+        "@target(erlang)
+@derive(equal)
+pub type Box {
+  Box(Int)
+}
+"
+    );
+}
+
 #[test]
 fn custom_types() {
     assert_format!(
@@ -385,6 +504,13 @@ fn custom_types() {
 pub type Option(a) {
   None
 }
+"
+    );
+
+    assert_format!(
+        "pub type Id(_kind) {
+  Id(Int)
+}
 "
     );
 }
@@ -1051,6 +1177,51 @@ fn expr_int() {
         r#"fn main() {
   -12928347925
 }
+"#
+    );
+
+    assert_format!(
+        r#"fn main() {
+  0xFF
+}
+"#
+    );
+
+    assert_format!(
+        r#"fn main() {
+  0xff_0A
+}
+"#
+    );
+
+    assert_format!(
+        r#"fn main() {
+  0o17
+}
+"#
+    );
+
+    assert_format!(
+        r#"fn main() {
+  0b1010
+}
+"#
+    );
+
+    assert_format!(
+        r#"const x = 0xFF
+
+const y = 0b1_010
+"#
+    );
+
+    assert_format!(
+        r#"fn main(x) {
+  case x {
+    0o17 -> 0b1010
+    _ -> 0xFF
+  }
+}
 "#
     );
 }
@@ -1104,6 +1275,85 @@ World"
 "#
     );
 }
+
+#[test]
+fn string_escapes() {
+    // Quotes and backslashes are escaped as they must be
+    assert_format!(
+        r#"fn main() {
+  "\"quoted\" \\ \t tab"
+}
+"#
+    );
+
+    // Tabs and other characters that cannot be seen are escaped
+    assert_format_rewrite!(
+        "fn main() {\n  \"a\tb\\tc\\\"\t\"\n}\n",
+        r#"fn main() {
+  "a\tb\tc\"\t"
+}
+"#
+    );
+    assert_format_rewrite!(
+        "fn main() {\n  \"\r\u{0}\u{7f}\"\n}\n",
+        r#"fn main() {
+  "\r\u{0}\u{7f}"
+}
+"#
+    );
+
+    // Unicode escapes are written with lowercase hex digits
+    assert_format_rewrite!(
+        r#"fn main() {
+  "\u{1F600}"
+}
+"#,
+        r#"fn main() {
+  "\u{1f600}"
+}
+"#
+    );
+
+    // The same style is used in constants, patterns, and attributes
+    assert_format_rewrite!(
+        "@deprecated(\"use\tb\")\nconst x = \"\t\"\n\nfn main() {\n  case x {\n    \"\t\" -> x\n  }\n}\n",
+        r#"@deprecated("use\tb")
+const x = "\t"
+
+fn main() {
+  case x {
+    "\t" -> x
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn string_escapes_round_trip() {
+    use crate::test_support::{compile, erlang_is_installed};
+
+    let src = "pub fn main() {\n  \"\t\\t \\\"\\\\ \u{1}\\u{1F600}\"\n}\n";
+    let formatted = pretty(src, &Default::default()).unwrap();
+    assert_eq!(
+        "pub fn main() {\n  \"\\t\\t \\\"\\\\ \\u{1}\\u{1f600}\"\n}\n",
+        formatted
+    );
+
+    // Formatting the formatted source does not change it
+    assert_eq!(formatted, pretty(&formatted, &Default::default()).unwrap());
+
+    // The formatted string holds the same characters as the original
+    let original = compile(&[("strings", src)]).expect("should compile");
+    let formatted = compile(&[("strings", &formatted)]).expect("should compile");
+    if !erlang_is_installed() {
+        return;
+    }
+    assert_eq!(
+        original.run("strings", "main").unwrap(),
+        formatted.run("strings", "main").unwrap()
+    );
+}
 #[test]
 fn expr_seq() {
     assert_format!(
@@ -1265,8 +1515,9 @@ fn expr_pipe() {
         r#"fn main() {
   tuple(
     1
-    |> succ
-    |> succ,
+    |> really_long_variable_name
+    |> really_long_variable_name
+    |> really_long_variable_name,
     2,
     3,
   )
@@ -1278,8 +1529,9 @@ fn expr_pipe() {
         r#"fn main() {
   some_call(
     1
-    |> succ
-    |> succ,
+    |> really_long_variable_name
+    |> really_long_variable_name
+    |> really_long_variable_name,
     2,
     3,
   )
@@ -1291,8 +1543,9 @@ fn expr_pipe() {
         r#"fn main() {
   [
     1
-    |> succ
-    |> succ,
+    |> really_long_variable_name
+    |> really_long_variable_name
+    |> really_long_variable_name,
     2,
     3,
   ]
@@ -1304,8 +1557,9 @@ fn expr_pipe() {
         r#"fn main() {
   let x =
     1
-    |> succ
-    |> succ
+    |> really_long_variable_name
+    |> really_long_variable_name
+    |> really_long_variable_name
   x
 }
 "#
@@ -1313,18 +1567,14 @@ fn expr_pipe() {
 
     assert_format!(
         r#"fn main() {
-  tuple(1, 2)
-  |> pair.first
-  |> should.equal(1)
+  tuple(1, 2) |> pair.first |> should.equal(1)
 }
 "#
     );
 
     assert_format!(
         r#"fn main() {
-  tuple(1, 2)
-  |> pair.first(1, 2, 4)
-  |> should.equal(1)
+  tuple(1, 2) |> pair.first(1, 2, 4) |> should.equal(1)
 }
 "#
     );
@@ -1383,6 +1633,39 @@ fn expr_pipe() {
     );
 }
 
+#[test]
+fn pipe_chain_breaks_all_or_nothing() {
+    // A chain that is too long breaks before every `|>`, even those that
+    // would fit on the first line
+    assert_format_rewrite!(
+        "fn main() {
+  list |> map(with: increment) |> filter(keeping: is_even) |> fold(from: 0, with: add)
+}
+",
+        "fn main() {
+  list
+  |> map(with: increment)
+  |> filter(keeping: is_even)
+  |> fold(from: 0, with: add)
+}
+"
+    );
+
+    // A short chain stays on one line
+    assert_format_rewrite!(
+        "fn main() {
+  list
+  |> map(increment)
+  |> sum
+}
+",
+        "fn main() {
+  list |> map(increment) |> sum
+}
+"
+    );
+}
+
 #[test]
 fn expr_let() {
     assert_format!(
@@ -1531,6 +1814,18 @@ fn pattern_simple() {
   let y = 1
   Nil
 }
+"#
+    );
+
+    // Negative literals
+    assert_format!(
+        r#"fn main(x) {
+  case x {
+    -1 -> 1
+    -0xF -> 2
+    _ -> 3
+  }
+}
 "#
     );
 }
@@ -1700,6 +1995,26 @@ fn pattern_constructor() {
     );
 }
 
+#[test]
+fn hash_tuples() {
+    assert_format_rewrite!(
+        r#"const pair = #(1, "one")
+
+fn main(x: #(Int, Float, String)) -> #(Int) {
+  let #(a, _, _) = x
+  #(a)
+}
+"#,
+        r#"const pair = tuple(1, "one")
+
+fn main(x: tuple(Int, Float, String)) -> tuple(Int) {
+  let tuple(a, _, _) = x
+  tuple(a)
+}
+"#,
+    );
+}
+
 #[test]
 fn pattern_tuple() {
     assert_format!(
@@ -1834,6 +2149,28 @@ fn expr_case_nested() {
     );
 }
 
+#[test]
+fn expr_case_clause_body_fits() {
+    assert_format!(
+        r#"fn main() {
+  case x {
+    1 -> "one"
+    2 -> fun(a, b)
+    3 ->
+      some_long_function_name(first_argument, second_argument, third_argument)
+    _ ->
+      Ok(some_long_function_name(
+        first_argument,
+        second_argument,
+        third_argument,
+        fourth_argument,
+      ))
+  }
+}
+"#
+    );
+}
+
 #[test]
 fn expr_case_then_fn() {
     assert_format!(
@@ -2047,14 +2384,127 @@ fn expr_todo() {
 "
     );
 
-    assert_format!(
+    assert_format_rewrite!(
         r#"fn main() {
   todo("todo with a label")
 }
+"#,
+        r#"fn main() {
+  todo as "todo with a label"
+}
+"#
+    );
+
+    assert_format!(
+        r#"fn main() {
+  todo as "todo with a label"
+}
+"#
+    );
+
+    assert_format!(
+        "fn main(message) {
+  todo as message
+}
+"
+    );
+
+    assert_format!(
+        r#"fn main(x) {
+  assert Ok(y) = x as "x should be Ok"
+  y
+}
+"#
+    );
+
+    assert_format!(
+        r#"fn main(x) {
+  assert Ok(y) = x as string.append("not ok: ", describe(x))
+  y
+}
+"#
+    );
+
+    // The message of a todo is not taken to be that of the assert
+    assert_format!(
+        r#"fn main() {
+  assert Ok(y) = { todo as "the value" }
+  y
+}
+"#
+    );
+
+    assert_format!(
+        r#"fn main() {
+  assert Ok(y) = { todo as "the value" } as "the assert"
+  y
+}
+"#
+    );
+
+    assert_format!(
+        "fn main() {
+  todo as { name |> describe }
+}
+"
+    );
+
+    assert_format!(
+        "fn main() {
+  { todo as \"one\" } + 1
+}
+"
+    );
+}
+
+#[test]
+fn expr_raw_erlang() {
+    assert_format!(
+        r#"fn main() {
+  @erlang("erlang:system_time()") as Int
+}
+"#
+    );
+
+    assert_format!(
+        r#"fn main() {
+  @erlang("length(\"abc\")") as Int
+}
+"#
+    );
+
+    assert_format!(
+        r#"fn main() {
+  { @erlang("[]") as List(a) } |> length
+}
 "#
     );
 }
 
+#[test]
+fn expr_panic() {
+    assert_format!(
+        "fn main() {
+  panic
+}
+"
+    );
+
+    assert_format!(
+        r#"fn main() {
+  panic as "unreachable"
+}
+"#
+    );
+
+    assert_format!(
+        "fn main(x) {
+  panic as describe(x)
+}
+"
+    );
+}
+
 #[test]
 fn doc_comments_test() {
     assert_format!(
@@ -2627,11 +3077,9 @@ fn empty_lines() {
         "pub fn main() {
   let inc = fn(a) { a + 1 }
 
-  pair.map_first(tuple(1, 2), inc)
-  |> should.equal(tuple(2, 2))
+  pair.map_first(tuple(1, 2), inc) |> should.equal(tuple(2, 2))
 
-  pair.map_first(tuple(1, 2), inc)
-  |> should.equal(tuple(2, 2))
+  pair.map_first(tuple(1, 2), inc) |> should.equal(tuple(2, 2))
 }
 "
     );
@@ -2667,6 +3115,84 @@ type X {
     );
 }
 
+#[test]
+fn mixed_precedence_braces() {
+    assert_format!(
+        "fn main() {
+  a || { b && c }
+}
+"
+    );
+
+    assert_format!(
+        "fn main() {
+  { a && b } || c
+}
+"
+    );
+
+    assert_format!(
+        "fn main() {
+  a || b && c
+}
+"
+    );
+
+    assert_format!(
+        "fn main() {
+  1 + { 2 * 3 }
+}
+"
+    );
+
+    assert_format!(
+        "fn main() {
+  { 1 * 2 } + { 3 / 4 } - 5
+}
+"
+    );
+
+    assert_format!(
+        "fn main() {
+  1 + 2 * 3
+}
+"
+    );
+
+    assert_format!(
+        "fn main() {
+  1 - { 2 - 3 }
+}
+"
+    );
+
+    assert_format!(
+        "fn main() {
+  a == { b < c }
+}
+"
+    );
+
+    // Braces around an operand using the same operator are redundant
+    assert_format_rewrite!(
+        "fn main() {
+  { 1 + 2 } + 3
+}
+",
+        "fn main() {
+  1 + 2 + 3
+}
+"
+    );
+
+    assert_format!(
+        "fn main() {
+  { a && b && c } || d
+}
+"
+    );
+}
+
 #[test]
 fn binary_operator_precedence() {
     assert_format!(
@@ -2685,36 +3211,28 @@ fn binary_operator_precedence() {
 
     assert_format!(
         "fn main() {
-  3 * {
-    1
-    |> inc
-  }
+  3 * { 1 |> inc }
 }
 "
     );
 
     assert_format!(
         "fn main() {
-  {
-    1
-    |> inc
-  } * 3
+  { 1 |> inc } * 3
 }
 "
     );
 
     assert_format!(
         "fn main() {
-  1
-  |> { a || b }
+  1 |> { a || b }
 }
 "
     );
 
     assert_format!(
         "fn main() {
-  { a || b }
-  |> go
+  { a || b } |> go
 }
 "
     );
@@ -3007,8 +3525,7 @@ fn main() {
 }
 ",
         "fn main() {
-  1
-  |> run(1)
+  1 |> run(1)
 }
 ",
     );
@@ -3020,8 +3537,7 @@ fn main() {
 }
 ",
         "fn main() {
-  1
-  |> run
+  1 |> run
 }
 ",
     );
@@ -3095,3 +3611,301 @@ fn function_type_type() {
 "
     );
 }
+
+#[test]
+fn configurable_indent() {
+    let src = r#"external fn go(Int) -> Int =
+  "erlang" "go"
+
+fn main(x) {
+  case x {
+    1 -> {
+      let y = x + 1
+      y
+    }
+    _ -> 0
+  }
+}
+"#;
+
+    assert_eq!(
+        pretty(
+            src,
+            &FormatConfig {
+                indent: 2,
+                ..Default::default()
+            }
+        )
+        .unwrap(),
+        src.to_string()
+    );
+
+    assert_eq!(
+        pretty(
+            src,
+            &FormatConfig {
+                indent: 4,
+                ..Default::default()
+            }
+        )
+        .unwrap(),
+        r#"external fn go(Int) -> Int =
+    "erlang" "go"
+
+fn main(x) {
+    case x {
+        1 -> {
+            let y = x + 1
+            y
+        }
+        _ -> 0
+    }
+}
+"#
+    );
+}
+
+#[test]
+fn ignore_test() {
+    let dir = crate::test_support::TempDir::new("format-ignore-test");
+    let root = dir.path();
+    std::fs::create_dir_all(root.join("src/gen")).unwrap();
+    let unformatted = "pub fn main() {1}";
+    std::fs::write(root.join("src/one.gleam"), unformatted).unwrap();
+    std::fs::write(root.join("src/two_generated.gleam"), unformatted).unwrap();
+    std::fs::write(root.join("src/gen/three.gleam"), unformatted).unwrap();
+
+    let config = FormatConfig {
+        ignore: vec!["gen".to_string(), "*_generated.gleam".to_string()],
+        ..Default::default()
+    };
+    let formatted =
+        command::read_and_format_paths(vec![root.to_str().unwrap().to_string()], &config).unwrap();
+    assert_eq!(
+        vec![(root.join("src/one.gleam"), "pub fn main() {\n  1\n}\n")],
+        formatted
+            .iter()
+            .map(|f| (f.source.clone(), f.output.as_str()))
+            .collect::<Vec<_>>()
+    );
+
+    let config = FormatConfig {
+        ignore: vec!["[".to_string()],
+        ..Default::default()
+    };
+    assert!(matches!(
+        command::read_and_format_paths(vec![root.to_str().unwrap().to_string()], &config),
+        Err(Error::InvalidGlob { .. })
+    ));
+}
+
+#[test]
+fn project_config_test() {
+    let dir = crate::test_support::TempDir::new("format-project-config-test");
+    let root = dir.path();
+    std::fs::create_dir_all(root.join("wide/src")).unwrap();
+    std::fs::create_dir_all(root.join("narrow/src")).unwrap();
+    std::fs::write(
+        root.join("wide/gleam.toml"),
+        "name = \"wide\"\n[format]\nindent = 4\nignore = [\"src/gen.gleam\"]\n",
+    )
+    .unwrap();
+    std::fs::write(root.join("narrow/gleam.toml"), "name = \"narrow\"\n").unwrap();
+    let src = "pub fn main() {\n    1\n}\n";
+    std::fs::write(root.join("wide/src/one.gleam"), src).unwrap();
+    std::fs::write(root.join("wide/src/gen.gleam"), "pub fn main() {1}").unwrap();
+    std::fs::write(root.join("narrow/src/one.gleam"), src).unwrap();
+
+    // Each path is formatted with the config of the project that contains it,
+    // wherever the formatter is run from
+    fn check(path: &Path) -> Result<(), Error> {
+        command::run(
+            false,
+            true,
+            false,
+            vec![path.to_str().unwrap().to_string()],
+            vec![],
+        )
+    }
+    assert_eq!(Ok(()), check(&root.join("wide/src")));
+    assert_eq!(Ok(()), check(&root.join("wide/src/one.gleam")));
+    assert!(matches!(
+        check(&root.join("narrow/src")),
+        Err(Error::Format { .. })
+    ));
+}
+
+#[test]
+fn summary_test() {
+    let dir = crate::test_support::TempDir::new("format-summary-test");
+    let root = dir.path();
+    std::fs::create_dir_all(root.join("src/nested")).unwrap();
+    std::fs::create_dir_all(root.join("test")).unwrap();
+    let formatted = "pub fn main() {\n  1\n}\n";
+    let unformatted = "pub fn main() {1}";
+    std::fs::write(root.join("src/one.gleam"), unformatted).unwrap();
+    std::fs::write(root.join("src/two.gleam"), formatted).unwrap();
+    std::fs::write(root.join("src/nested/three.gleam"), unformatted).unwrap();
+    std::fs::write(root.join("test/four.gleam"), formatted).unwrap();
+
+    // Files and directories can be given together
+    let paths = vec![
+        root.join("src").to_str().unwrap().to_string(),
+        root.join("test/four.gleam").to_str().unwrap().to_string(),
+    ];
+    let files = command::read_and_format_paths(paths, &Default::default()).unwrap();
+    let summary = command::Summary::new(files.as_slice());
+    assert_eq!(
+        command::Summary {
+            reformatted: 2,
+            unchanged: 2,
+        },
+        summary
+    );
+    assert_eq!("Reformatted 2 files, 2 unchanged", summary.to_string());
+
+    let one = command::read_and_format_paths(
+        vec![root.join("src/one.gleam").to_str().unwrap().to_string()],
+        &Default::default(),
+    )
+    .unwrap();
+    let summary = command::Summary::new(one.as_slice());
+    assert_eq!("Reformatted 1 file, 0 unchanged", summary.to_string());
+}
+
+#[test]
+fn diff_test() {
+    let dir = crate::test_support::TempDir::new("format-diff-test");
+    let root = dir.path();
+    let path = root.join("one.gleam");
+    let unformatted = "import one\n\n\n\npub fn main() {\n  let x = 1\n  x}\n\nfn two() { 2 }";
+    std::fs::write(&path, unformatted).unwrap();
+    std::fs::write(root.join("two.gleam"), "pub fn main() {\n  1\n}\n").unwrap();
+
+    let files = command::read_and_format_paths(
+        vec![root.to_str().unwrap().to_string()],
+        &Default::default(),
+    )
+    .unwrap();
+    let path = path.to_string_lossy();
+    assert_eq!(
+        format!("--- {}\n+++ {}\n", path, path)
+            + "@@ -1,9 +1,10 @@
+ import one
+ 
+-
+-
+ pub fn main() {
+   let x = 1
+-  x}
++  x
++}
+ 
+-fn two() { 2 }
+\\ No newline at end of file
++fn two() {
++  2
++}
+",
+        command::diffs(files.as_slice())
+    );
+}
+
+#[test]
+fn tab_indentation() {
+    // Tabs are accepted wherever spaces are, and lines of only spaces and tabs
+    // are kept as empty lines
+    assert_format_rewrite!(
+        "pub fn main() {\n\tlet x = 1\n\t\n\tcase x {\n\t\t1 ->\t\"one\"\n  \t_ -> \"other\"\n\t}\n}\n",
+        r#"pub fn main() {
+  let x = 1
+
+  case x {
+    1 -> "one"
+    _ -> "other"
+  }
+}
+"#,
+    );
+}
+
+#[test]
+fn windows_line_endings() {
+    assert_format_rewrite!(
+        "//// Module docs\r\n\r\nimport one\r\n\r\n/// Docs\r\npub fn main() {\r\n  // A comment\r\n  let x = \"one\r\ntwo\"\r\n  x\r\n}\r\n",
+        "//// Module docs\n\nimport one\n\n/// Docs\npub fn main() {\n  // A comment\n  let x = \"one\ntwo\"\n  x\n}\n",
+    );
+
+    assert_format_rewrite!(
+        "pub fn main() {\r\n  1\r\n\r\n\r\n  2\r\n}\r\n",
+        "pub fn main() {\n  1\n\n  2\n}\n",
+    );
+}
+
+#[test]
+fn pretty_file_parse_error_test() {
+    let error = pretty_file(
+        Path::new("/src/one.gleam"),
+        "pub fn main() {\r\n  1 +\r\n}\r\n",
+        &Default::default(),
+    )
+    .expect_err("should fail to parse");
+
+    // The location is of the unexpected token in the normalised source
+    match &error {
+        Error::Parse {
+            path,
+            src,
+            error:
+                lalrpop_util::ParseError::UnrecognizedToken {
+                    token: (start, _, end),
+                    ..
+                },
+        } => {
+            assert_eq!(Path::new("/src/one.gleam"), path);
+            assert_eq!("}", &src[*start..*end]);
+        }
+        _ => panic!("unexpected error {:?}", error),
+    }
+
+    let mut buffer = termcolor::Buffer::no_color();
+    error.pretty(&mut buffer);
+    let message = String::from_utf8(buffer.into_inner()).unwrap();
+    assert!(message.contains("/src/one.gleam:3:1"), "{}", message);
+}
+
+#[test]
+fn verify_test() {
+    let dir = crate::test_support::TempDir::new("format-verify-test");
+    let root = dir.path();
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::create_dir_all(root.join("test")).unwrap();
+    std::fs::write(root.join("src/one.gleam"), "pub fn main() {\n  1\n}\n").unwrap();
+    std::fs::write(root.join("test/two.gleam"), "pub fn test() {\n  2\n}\n").unwrap();
+    assert_eq!(Ok(()), command::verify(root, &Default::default()));
+
+    // An unformatted file fails the check and is left unchanged
+    let unformatted = "pub fn main() {1}";
+    std::fs::write(root.join("test/two.gleam"), unformatted).unwrap();
+    match command::verify(root, &Default::default()) {
+        Err(Error::Format { problem_files }) => assert_eq!(
+            vec![root.join("test/two.gleam")],
+            problem_files
+                .into_iter()
+                .map(|formatted| formatted.source)
+                .collect::<Vec<_>>()
+        ),
+        result => panic!("expected a format error, got {:?}", result),
+    }
+    assert_eq!(
+        unformatted,
+        std::fs::read_to_string(root.join("test/two.gleam")).unwrap()
+    );
+
+    // Ignored files are not checked
+    let config = FormatConfig {
+        ignore: vec!["test/*.gleam".to_string()],
+        ..Default::default()
+    };
+    assert_eq!(Ok(()), command::verify(root, &config));
+}
@@ -28,20 +28,20 @@ pub fn delete_dir(dir: &PathBuf) -> Result<(), Error> {
     Ok(())
 }
 
-// pub fn delete(file: &PathBuf) -> Result<(), Error> {
-//     tracing::trace!("Deleting file {:?}", file);
-//     if file.exists() {
-//         std::fs::remove_file(&file).map_err(|e| Error::FileIO {
-//             action: FileIOAction::Delete,
-//             kind: FileKind::File,
-//             path: file.clone(),
-//             err: Some(e.to_string()),
-//         })?;
-//     } else {
-//         tracing::trace!("Did not exist for deletion: {:?}", file);
-//     }
-//     Ok(())
-// }
+pub fn delete(file: &PathBuf) -> Result<(), Error> {
+    tracing::trace!("Deleting file {:?}", file);
+    if file.exists() {
+        std::fs::remove_file(file).map_err(|e| Error::FileIO {
+            action: FileIOAction::Delete,
+            kind: FileKind::File,
+            path: file.clone(),
+            err: Some(e.to_string()),
+        })?;
+    } else {
+        tracing::trace!("Did not exist for deletion: {:?}", file);
+    }
+    Ok(())
+}
 
 pub fn write_outputs(outputs: &[OutputFile]) -> Result<(), Error> {
     for file in outputs {
@@ -139,10 +139,28 @@ pub fn gleam_files(dir: &PathBuf) -> impl Iterator<Item = PathBuf> + '_ {
         .filter(move |d| is_gleam_path(d, dir))
 }
 
-pub fn gleam_files_excluding_gitignore(dir: &PathBuf) -> impl Iterator<Item = PathBuf> + '_ {
+/// All the files with the `.gleam` extension in the directory, including those
+/// whose paths are not valid module names.
+pub fn all_gleam_files(dir: &Path) -> impl Iterator<Item = PathBuf> {
+    walkdir::WalkDir::new(dir)
+        .follow_links(true)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file())
+        .map(|d| d.path().to_path_buf())
+        .filter(|d| d.extension().and_then(|e| e.to_str()) == Some("gleam"))
+}
+
+/// The Gleam files in the directory, skipping those excluded by a
+/// `.gitignore` file or by the given overrides.
+pub fn gleam_files_excluding_gitignore(
+    dir: &PathBuf,
+    overrides: ignore::overrides::Override,
+) -> impl Iterator<Item = PathBuf> + '_ {
     ignore::WalkBuilder::new(&dir)
         .follow_links(true)
         .require_git(false)
+        .overrides(overrides)
         .build()
         .into_iter()
         .filter_map(Result::ok)
@@ -203,16 +221,34 @@ pub fn read_dir(path: impl AsRef<Path> + Debug) -> Result<std::fs::ReadDir, Erro
 }
 
 pub fn read(path: impl AsRef<Path> + Debug) -> Result<String, Error> {
+    read_as_written(path).map(|text| normalise_line_endings(&text))
+}
+
+/// Read a file without normalising its line endings. A file that is not
+/// valid UTF-8 is reported with the offset of the first invalid byte.
+///
+pub fn read_as_written(path: impl AsRef<Path> + Debug) -> Result<String, Error> {
     tracing::trace!("Reading file {:?}", path);
 
-    std::fs::read_to_string(&path).map_err(|err| Error::FileIO {
+    let bytes = std::fs::read(&path).map_err(|err| Error::FileIO {
         action: FileIOAction::Read,
         kind: FileKind::File,
         path: PathBuf::from(path.as_ref()),
         err: Some(err.to_string()),
+    })?;
+    String::from_utf8(bytes).map_err(|err| Error::InvalidUtf8 {
+        path: PathBuf::from(path.as_ref()),
+        offset: err.utf8_error().valid_up_to(),
     })
 }
 
+/// Convert any Windows line endings to `\n`, so that files are compiled and
+/// formatted the same whichever platform they were written on.
+///
+pub fn normalise_line_endings(text: &str) -> String {
+    text.replace("\r\n", "\n")
+}
+
 pub fn copy(path: impl AsRef<Path> + Debug, to: impl AsRef<Path> + Debug) -> Result<(), Error> {
     tracing::trace!("Copying file {:?} to {:?}", path, to);
 
@@ -240,3 +276,22 @@ pub fn copy_dir(path: impl AsRef<Path> + Debug, to: impl AsRef<Path> + Debug) ->
         })
         .map(|_| ())
 }
+
+#[test]
+fn read_invalid_utf8_test() {
+    let dir = crate::test_support::TempDir::new("utf8-test");
+    let root = dir.path();
+    let path = root.join("latin1.gleam");
+    std::fs::write(&path, b"pub fn name() { \"Andr\xe9\" }\r\n").unwrap();
+
+    assert_eq!(
+        Err(Error::InvalidUtf8 {
+            path: path.clone(),
+            offset: 21,
+        }),
+        read(&path)
+    );
+
+    std::fs::write(&path, "pub fn name() { \"André\" }\r\n").unwrap();
+    assert_eq!(Ok("pub fn name() { \"André\" }\n".to_string()), read(&path));
+}
@@ -0,0 +1,109 @@
+use std::ops::Range;
+
+/// The byte offsets at which each line of a source file starts, computed
+/// once so that byte offsets (such as those in a `SrcSpan`) can be converted
+/// into line and column numbers with a binary search.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineNumbers {
+    line_starts: Vec<usize>,
+    length: usize,
+}
+
+impl LineNumbers {
+    pub fn new(src: &str) -> Self {
+        Self {
+            line_starts: std::iter::once(0)
+                .chain(src.match_indices('\n').map(|(i, _)| i + 1))
+                .collect(),
+            length: src.len(),
+        }
+    }
+
+    /// The zero-indexed line that contains the given byte offset. A newline
+    /// belongs to the line it ends.
+    ///
+    pub fn line_index(&self, byte_index: usize) -> usize {
+        match self.line_starts.binary_search(&byte_index) {
+            Ok(line) => line,
+            Err(next_line) => next_line - 1,
+        }
+    }
+
+    /// The byte range of the given zero-indexed line, including its newline.
+    /// The line after the last one is an empty range at the end of the
+    /// source.
+    ///
+    pub fn line_range(&self, line_index: usize) -> Option<Range<usize>> {
+        let start = self.line_start(line_index)?;
+        let end = self.line_start(line_index + 1).unwrap_or(self.length);
+        Some(start..end)
+    }
+
+    /// The zero-indexed column of the given byte offset, counted in
+    /// characters rather than bytes so that multibyte characters occupy a
+    /// single column.
+    ///
+    pub fn column_index(&self, src: &str, byte_index: usize) -> usize {
+        let line_start = self.line_starts[self.line_index(byte_index)];
+        let end = byte_index.min(self.length);
+        (line_start..end)
+            .filter(|i| src.is_char_boundary(i + 1))
+            .count()
+    }
+
+    fn line_start(&self, line_index: usize) -> Option<usize> {
+        match self.line_starts.get(line_index) {
+            Some(start) => Some(*start),
+            None if line_index == self.line_starts.len() => Some(self.length),
+            None => None,
+        }
+    }
+}
+
+#[test]
+fn line_index_test() {
+    let src = "one\ntwo\n\nfour";
+    let lines = LineNumbers::new(src);
+    assert_eq!(0, lines.line_index(0));
+    assert_eq!(0, lines.line_index(2));
+    // The newline is the last byte of its line
+    assert_eq!(0, lines.line_index(3));
+    // The first byte after a newline starts the next line
+    assert_eq!(1, lines.line_index(4));
+    assert_eq!(2, lines.line_index(8));
+    assert_eq!(3, lines.line_index(9));
+    // The end of the source is on the last line
+    assert_eq!(3, lines.line_index(src.len()));
+
+    assert_eq!(Some(0..4), lines.line_range(0));
+    assert_eq!(Some(8..9), lines.line_range(2));
+    assert_eq!(Some(9..13), lines.line_range(3));
+    assert_eq!(Some(13..13), lines.line_range(4));
+    assert_eq!(None, lines.line_range(5));
+}
+
+#[test]
+fn line_index_trailing_newline_test() {
+    let src = "one\n";
+    let lines = LineNumbers::new(src);
+    assert_eq!(0, lines.line_index(3));
+    assert_eq!(1, lines.line_index(4));
+    assert_eq!(Some(4..4), lines.line_range(1));
+    assert_eq!(0, lines.column_index(src, 4));
+}
+
+#[test]
+fn column_index_multibyte_test() {
+    let src = "let ñ = \"😀\"\nlet x = 1";
+    let lines = LineNumbers::new(src);
+    // `ñ` is two bytes and the emoji is four, but each is one column
+    let after_n = src.find(" =").unwrap();
+    assert_eq!(5, lines.column_index(src, after_n));
+    let closing_quote = src.rfind('"').unwrap();
+    assert_eq!(10, lines.column_index(src, closing_quote));
+    let second_line = src.find("let x").unwrap();
+    assert_eq!(1, lines.line_index(second_line));
+    assert_eq!(0, lines.column_index(src, second_line));
+    assert_eq!(4, lines.column_index(src, second_line + 4));
+}
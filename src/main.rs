@@ -41,9 +41,11 @@
     // unused_results
 )]
 
+mod add;
 mod ast;
 mod bit_string;
 mod build;
+mod check;
 mod cli;
 mod config;
 mod diagnostic;
@@ -51,15 +53,21 @@ mod docs;
 mod erl;
 mod error;
 mod eunit;
+mod explain;
 mod format;
 mod fs;
+mod line_numbers;
 mod new;
 mod parser;
 mod pretty;
 mod project;
+mod run;
 mod shell;
+#[cfg(test)]
+mod test_support;
 mod typ;
 mod warning;
+mod watch;
 
 lalrpop_mod!(
     #[allow(
@@ -88,7 +96,7 @@ extern crate lalrpop_util;
 #[macro_use]
 extern crate lazy_static;
 
-use crate::error::Error;
+use crate::error::{Error, ExitCode};
 use std::path::PathBuf;
 use structopt::clap::AppSettings;
 use structopt::StructOpt;
@@ -99,10 +107,85 @@ const VERSION: &str = env!("CARGO_PKG_VERSION");
 #[derive(StructOpt, Debug)]
 #[structopt(global_settings = &[AppSettings::ColoredHelp, AppSettings::VersionlessSubcommands])]
 enum Command {
+    #[structopt(name = "add", about = "Add a dependency to the project")]
+    Add {
+        #[structopt(help = "the name of the package")]
+        package: String,
+
+        #[structopt(help = "the version requirement, such as \"~> 1.0\"")]
+        version: String,
+
+        #[structopt(help = "location of the project root", default_value = ".")]
+        project_root: String,
+    },
+
     #[structopt(name = "build", about = "Compile a project")]
     Build {
         #[structopt(help = "location of the project root", default_value = ".")]
         project_root: String,
+
+        #[structopt(
+            help = "rebuild the project when its source files change",
+            long = "watch"
+        )]
+        watch: bool,
+
+        #[structopt(
+            help = "how to print warnings",
+            long = "warning-format",
+            possible_values = &warning::Format::VARIANTS,
+            case_insensitive = true,
+            default_value = "human"
+        )]
+        warning_format: warning::Format,
+
+        #[structopt(
+            help = "optimise the generated code as configured by [profile.release]",
+            long = "release"
+        )]
+        release: bool,
+
+        #[structopt(
+            help = "print the compile times of the slowest modules (gleam build tool only)",
+            long = "timings"
+        )]
+        timings: bool,
+
+        #[structopt(
+            help = "fail if any source files are not formatted",
+            long = "verify-format"
+        )]
+        verify_format: bool,
+
+        #[structopt(help = "fail if any todos remain", long = "deny-todo")]
+        deny_todo: bool,
+
+        #[structopt(
+            help = "record the source files and their hashes in gleam-sources.toml",
+            long = "write-manifest"
+        )]
+        write_manifest: bool,
+
+        #[structopt(
+            help = "fail if the source files differ from those recorded in gleam-sources.toml",
+            long = "verify-manifest"
+        )]
+        verify_manifest: bool,
+    },
+
+    #[structopt(name = "check", about = "Type check a project without compiling it")]
+    Check {
+        #[structopt(help = "location of the project root", default_value = ".")]
+        project_root: String,
+
+        #[structopt(
+            help = "how to print warnings",
+            long = "warning-format",
+            possible_values = &warning::Format::VARIANTS,
+            case_insensitive = true,
+            default_value = "human"
+        )]
+        warning_format: warning::Format,
     },
 
     #[structopt(name = "docs", about = "Render HTML documentation for a project")]
@@ -110,12 +193,27 @@ enum Command {
 
     #[structopt(name = "new", about = "Create a new project")]
     New {
-        #[structopt(help = "name of the project")]
-        name: String,
+        #[structopt(help = "name of the project, asked for when not given")]
+        name: Option<String>,
+
+        #[structopt(
+            long = "name",
+            conflicts_with = "name",
+            help = "name of the project, as an alternative to giving it as an argument"
+        )]
+        name_flag: Option<String>,
 
         #[structopt(long = "description", help = "description of the project")]
         description: Option<String>,
 
+        #[structopt(
+            long = "license",
+            possible_values = &new::License::VARIANTS,
+            case_insensitive = true,
+            help = "license of the project, which defaults to apache-2.0"
+        )]
+        license: Option<new::License>,
+
         #[structopt(help = "location of the project root")]
         project_root: Option<String>,
 
@@ -126,6 +224,18 @@ enum Command {
             default_value = "lib"
         )]
         template: new::Template,
+
+        #[structopt(
+            long = "json",
+            help = "print a JSON summary of the created project instead of instructions"
+        )]
+        json: bool,
+    },
+
+    #[structopt(name = "explain", about = "Explain a warning code in detail")]
+    Explain {
+        #[structopt(help = "the code to explain, such as float-equality")]
+        code: String,
     },
 
     #[structopt(name = "format", about = "Format source code")]
@@ -141,6 +251,19 @@ enum Command {
             long = "check"
         )]
         check: bool,
+
+        #[structopt(
+            help = "print a diff of the changes the formatter would make without changing files",
+            long = "diff"
+        )]
+        diff: bool,
+
+        #[structopt(
+            help = "skip files matching this glob when formatting a directory",
+            long = "ignore",
+            number_of_values = 1
+        )]
+        ignore: Vec<String>,
     },
 
     #[structopt(
@@ -153,6 +276,15 @@ enum Command {
         project_root: String,
     },
 
+    #[structopt(name = "run", about = "Compile the project and run its main function")]
+    Run {
+        #[structopt(help = "location of the project root", default_value = ".")]
+        project_root: String,
+
+        #[structopt(help = "arguments to pass to the program", last = true)]
+        arguments: Vec<String>,
+    },
+
     #[structopt(
         name = "eunit",
         about = "Run eunit tests",
@@ -161,6 +293,12 @@ enum Command {
     Eunit {
         #[structopt(help = "location of the project root", default_value = ".")]
         project_root: String,
+
+        #[structopt(
+            help = "write an LCOV report of the source lines run by the tests",
+            long = "coverage"
+        )]
+        coverage: bool,
     },
 }
 
@@ -197,72 +335,164 @@ enum Docs {
 fn main() {
     initialise_logger();
 
-    let result = match Command::from_args() {
-        Command::Build { project_root } => command_build(project_root),
+    let command = match Command::clap().get_matches_safe() {
+        Ok(matches) => Command::from_clap(&matches),
+        // Printing the help text or version is not a failure
+        Err(error) if !error.use_stderr() => {
+            println!("{}", error.message);
+            std::process::exit(ExitCode::Success.code());
+        }
+        Err(error) => {
+            eprintln!("{}", error.message);
+            std::process::exit(ExitCode::UsageError.code());
+        }
+    };
 
-        Command::Docs(Docs::Build { project_root, to }) => docs::command::build(project_root, to),
+    match run(command) {
+        Ok(_) => {
+            tracing::info!("Successfully completed");
+        }
+        Err(error) => {
+            tracing::error!(error = ?error, "Failed");
+            error.pretty_print();
+            std::process::exit(error.exit_code().code());
+        }
+    }
+}
+
+fn run(command: Command) -> Result<(), Error> {
+    match command {
+        Command::Add {
+            package,
+            version,
+            project_root,
+        } => add::command(project_root_of(project_root)?, package, version),
+
+        Command::Build {
+            project_root,
+            watch,
+            warning_format,
+            release,
+            timings,
+            verify_format,
+            deny_todo,
+            write_manifest,
+            verify_manifest,
+        } => {
+            let root = project_root_of(project_root)?;
+            let options = project::BuildOptions {
+                warning_format,
+                release,
+                timings,
+                verify_format,
+                deny_todo,
+                write_manifest,
+                verify_manifest,
+            };
+            if watch {
+                if config::read_project_config(&root)?.tool == config::BuildTool::Gleam {
+                    return Err(Error::UnsupportedBuildToolFlag {
+                        flag: "--watch".to_string(),
+                    });
+                }
+                return watch::command(PathBuf::from(root), options);
+            }
+            command_build(root, options)
+        }
+
+        Command::Check {
+            project_root,
+            warning_format,
+        } => check::command(project_root_of(project_root)?, warning_format),
+
+        Command::Docs(Docs::Build { project_root, to }) => {
+            docs::command::build(project_root_of(project_root)?, to)
+        }
 
         Command::Docs(Docs::Publish {
             project_root,
             version,
-        }) => docs::command::publish(project_root, version),
+        }) => docs::command::publish(project_root_of(project_root)?, version),
 
         Command::Docs(Docs::Remove { package, version }) => docs::command::remove(package, version),
 
+        Command::Explain { code } => explain::command(code),
+
         Command::Format {
             stdin,
             files,
             check,
-        } => format::command::run(stdin, check, files),
+            diff,
+            ignore,
+        } => format::command::run(stdin, check, diff, files, ignore),
 
         Command::New {
             name,
+            name_flag,
             description,
+            license,
             project_root,
             template,
-        } => new::create(template, name, description, project_root, VERSION),
+            json,
+        } => new::create(
+            template,
+            name.or(name_flag),
+            description,
+            license,
+            project_root,
+            VERSION,
+            json,
+        ),
 
-        Command::Shell { project_root } => shell::command(project_root),
+        Command::Shell { project_root } => shell::command(project_root_of(project_root)?),
 
-        Command::Eunit { project_root } => eunit::command(project_root),
-    };
+        Command::Run {
+            project_root,
+            arguments,
+        } => run::command(project_root_of(project_root)?, arguments),
 
-    match result {
-        Ok(_) => {
-            tracing::info!("Successfully completed");
-        }
-        Err(error) => {
-            tracing::error!(error = ?error, "Failed");
-            error.pretty_print();
-            std::process::exit(1);
-        }
+        Command::Eunit {
+            project_root,
+            coverage,
+        } => eunit::command(project_root_of(project_root)?, coverage),
     }
 }
 
-fn command_build(root: String) -> Result<(), Error> {
+/// The root of the project containing the given directory, so that commands
+/// can be run from any of the project's directories.
+///
+fn project_root_of(path: String) -> Result<String, Error> {
+    let root = config::find_project_root(&path)?;
+    Ok(root.to_string_lossy().into_owned())
+}
+
+fn command_build(root: String, options: project::BuildOptions) -> Result<(), Error> {
     let root = PathBuf::from(&root);
-    let config = config::read_project_config(&root)?;
+    let mut config = config::read_project_config(&root)?;
 
     // Use new build tool
     if config.tool == config::BuildTool::Gleam {
-        return build::main(config, root).map(|_| ());
+        if options.deny_todo {
+            return Err(Error::UnsupportedBuildToolFlag {
+                flag: "--deny-todo".to_string(),
+            });
+        }
+        options.verify(&root, &config)?;
+        if options.release {
+            config.erlang.release = Some(config.profile.release);
+        }
+        let mut build_timings = build::timings::Timings::default();
+        let _ = build::main_with_timings(config, root.clone(), &mut build_timings)?;
+        if options.timings {
+            print!("{}", build_timings.report());
+        }
+        if options.write_manifest {
+            build::manifest::write(&root)?;
+        }
+        return Ok(());
     }
 
-    // Read and type check project
-    let (_config, analysed) = project::read_and_analyse(&root)?;
-
-    // Generate Erlang code
-    let output_files = erl::generate_erlang(analysed.as_slice());
-
-    // Reset output directory
-    fs::delete_dir(&root.join(project::OUTPUT_DIR_NAME))?;
-
-    // Print warnings
-    warning::print_all(analysed.as_slice());
-
-    // Delete the gen directory before generating the newly compiled files
-    fs::write_outputs(output_files.as_slice())?;
-
+    let _ = project::build(&root, &mut project::Cache::default(), options)?;
     println!("Done!");
 
     Ok(())
@@ -4,6 +4,7 @@ use std::fs::File;
 use std::io::Write;
 use std::path::Path;
 use std::path::PathBuf;
+use strum::VariantNames;
 use strum_macros::{Display, EnumString, EnumVariantNames};
 
 #[derive(Debug, Serialize, Deserialize, Display, EnumString, EnumVariantNames)]
@@ -13,22 +14,171 @@ pub enum Template {
     App,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Display, EnumString, EnumVariantNames)]
+#[strum(serialize_all = "kebab_case")]
+pub enum License {
+    #[strum(serialize = "apache-2.0")]
+    Apache2,
+    Mit,
+}
+
+impl License {
+    /// The name of the license as it is written in the `.app.src` file.
+    ///
+    fn app_src_name(self) -> &'static str {
+        match self {
+            Self::Apache2 => "Apache 2.0",
+            Self::Mit => "MIT",
+        }
+    }
+
+    fn text(self, name: &str) -> String {
+        match self {
+            Self::Apache2 => APACHE_2.to_string(),
+            Self::Mit => mit(name),
+        }
+    }
+}
+
+/// Asks a question, given the default answer, and returns the answer.
+type Prompt<'a> = &'a mut dyn FnMut(&str, &str) -> Result<String, Error>;
+
+/// The name, description, and license of a new project.
+///
+#[derive(Debug, PartialEq)]
+struct Details {
+    name: String,
+    description: String,
+    license: License,
+}
+
+impl Details {
+    /// The details given on the command line, with any that were not given
+    /// asked for when there is a prompt, and otherwise defaulted. There is no
+    /// default name, so it must be given when there is no prompt.
+    ///
+    fn new(
+        name: Option<String>,
+        description: Option<String>,
+        license: Option<License>,
+        mut prompt: Option<Prompt<'_>>,
+    ) -> Result<Self, Error> {
+        let name = match (name, &mut prompt) {
+            (Some(name), _) => name,
+            (None, Some(prompt)) => prompt("Project name", "")?,
+            (None, None) => return Err(Error::MissingProjectName),
+        };
+        let name = validate_name(name)?;
+
+        let description = match (description, &mut prompt) {
+            (Some(description), _) => description,
+            (None, Some(prompt)) => prompt("Description", DEFAULT_DESCRIPTION)?,
+            (None, None) => DEFAULT_DESCRIPTION.to_string(),
+        };
+
+        let license = match (license, &mut prompt) {
+            (Some(license), _) => license,
+            (None, Some(prompt)) => loop {
+                let question = format!("License ({})", License::VARIANTS.join(", "));
+                if let Ok(license) = prompt(&question, DEFAULT_LICENSE)?.parse() {
+                    break license;
+                }
+            },
+            (None, None) => License::Apache2,
+        };
+
+        Ok(Self {
+            name,
+            description,
+            license,
+        })
+    }
+}
+
+const DEFAULT_DESCRIPTION: &str = "A Gleam program";
+
+const DEFAULT_LICENSE: &str = "apache-2.0";
+
+/// Ask a question on the terminal, returning the default answer if nothing
+/// is entered.
+///
+fn ask(question: &str, default: &str) -> Result<String, Error> {
+    let answer = if default.is_empty() {
+        crate::cli::ask(question)?
+    } else {
+        crate::cli::ask(&format!("{} [{}]", question, default))?
+    };
+    Ok(if answer.is_empty() {
+        default.to_string()
+    } else {
+        answer
+    })
+}
+
 pub fn create(
     template: Template,
-    name: String,
+    name: Option<String>,
     description: Option<String>,
+    license: Option<License>,
     path: Option<String>,
     version: &'static str,
+    json: bool,
 ) -> Result<(), Error> {
-    let name = validate_name(name)?;
-
-    let description = description.unwrap_or_else(|| String::from("A Gleam program"));
-    let path = path.unwrap_or_else(|| name.clone());
+    // Details that were not given are asked for only when someone is there to
+    // answer, and never when the output is to be read by another program
+    let interactive = !json && atty::is(atty::Stream::Stdin) && atty::is(atty::Stream::Stdout);
+    let mut ask = ask;
+    let prompt: Option<Prompt<'_>> = if interactive { Some(&mut ask) } else { None };
+    let details = Details::new(name, description, license, prompt)?;
+
+    let path = path.unwrap_or_else(|| details.name.clone());
     let root_dir = Path::new(&path);
+    let files = create_files(template, &details, root_dir, version)?;
+    let name = details.name;
+
+    if json {
+        println!("{}", json_summary(&name, root_dir, files.as_slice()));
+        return Ok(());
+    }
+
+    for file in files {
+        println!(
+            "* creating {}",
+            file.to_str().expect("Unable to display write path")
+        );
+    }
+
+    // Print success message
+    println!(
+        "
+Your Gleam project \"{}\" has been successfully created.
+The rebar3 program can be used to compile and test it.
+
+    cd {}
+    rebar3 eunit
+",
+        name,
+        root_dir.to_str().expect("Unable to display path")
+    );
+    Ok(())
+}
+
+/// Write the files of a new project, returning their paths in the order they
+/// were created.
+///
+fn create_files(
+    template: Template,
+    details: &Details,
+    root_dir: &Path,
+    version: &str,
+) -> Result<Vec<PathBuf>, Error> {
+    let name = details.name.as_str();
+    let description = details.description.as_str();
     let src_dir = root_dir.join("src");
     let test_dir = root_dir.join("test");
     let github_dir = root_dir.join(".github");
     let workflows_dir = github_dir.join("workflows");
+    let mut files = vec![];
 
     // Create directories
     crate::fs::mkdir(&root_dir)?;
@@ -38,54 +188,83 @@ pub fn create(
     crate::fs::mkdir(&workflows_dir)?;
 
     // write files
-    write(root_dir.join("LICENSE"), APACHE_2)?;
-    write(root_dir.join(".gitignore"), GITIGNORE)?;
-    write(root_dir.join("README.md"), &readme(&name, &description))?;
-    write(root_dir.join("gleam.toml"), &gleam_toml(&name))?;
-    write(test_dir.join(format!("{}_test.gleam", name)), &test(&name))?;
-    write(src_dir.join(format!("{}.gleam", name)), &src(&name))?;
-    write(workflows_dir.join("test.yml"), &github_ci(version))?;
+    write(
+        &mut files,
+        root_dir.join("LICENSE"),
+        &details.license.text(name),
+    )?;
+    write(&mut files, root_dir.join(".gitignore"), GITIGNORE)?;
+    write(
+        &mut files,
+        root_dir.join("README.md"),
+        &readme(name, description),
+    )?;
+    write(&mut files, root_dir.join("gleam.toml"), &gleam_toml(name))?;
+    write(
+        &mut files,
+        test_dir.join(format!("{}_test.gleam", name)),
+        &test(name),
+    )?;
+    write(
+        &mut files,
+        src_dir.join(format!("{}.gleam", name)),
+        &src(name),
+    )?;
+    write(
+        &mut files,
+        workflows_dir.join("test.yml"),
+        &github_ci(version),
+    )?;
 
     match template {
         Template::Lib => {
-            write(root_dir.join("rebar.config"), &rebar_config(""))?;
+            write(&mut files, root_dir.join("rebar.config"), &rebar_config(""))?;
             write(
+                &mut files,
                 src_dir.join(format!("{}.app.src", name)),
-                &app_src(&name, &description, false),
+                &app_src(name, description, details.license, false),
             )?;
         }
 
         Template::App => {
-            crate::fs::mkdir(&src_dir.join(&name))?;
-            write(root_dir.join("rebar.config"), &app_rebar_config(&name))?;
-            write(src_dir.join(&name).join("application.gleam"), &src_app())?;
+            crate::fs::mkdir(&src_dir.join(name))?;
+            write(
+                &mut files,
+                root_dir.join("rebar.config"),
+                &app_rebar_config(name),
+            )?;
+            write(
+                &mut files,
+                src_dir.join(name).join("application.gleam"),
+                &src_app(),
+            )?;
             write(
+                &mut files,
                 src_dir.join(format!("{}.app.src", name)),
-                &app_src(&name, &description, true),
+                &app_src(name, description, details.license, true),
             )?;
         }
     }
 
-    // Print success message
-    println!(
-        "
-Your Gleam project \"{}\" has been successfully created.
-The rebar3 program can be used to compile and test it.
+    Ok(files)
+}
 
-    cd {}
-    rebar3 eunit
-",
-        name,
-        root_dir.to_str().expect("Unable to display path")
-    );
-    Ok(())
+/// The project name, root, and created files as a JSON object, for tools
+/// that create projects.
+///
+fn json_summary(name: &str, root_dir: &Path, files: &[PathBuf]) -> String {
+    serde_json::json!({
+        "name": name,
+        "path": root_dir.to_str().expect("Unable to display path"),
+        "files": files
+            .iter()
+            .map(|file| file.to_str().expect("Unable to display write path"))
+            .collect::<Vec<_>>(),
+    })
+    .to_string()
 }
 
-fn write(path: PathBuf, contents: &str) -> Result<(), Error> {
-    println!(
-        "* creating {}",
-        path.to_str().expect("Unable to display write path")
-    );
+fn write(files: &mut Vec<PathBuf>, path: PathBuf, contents: &str) -> Result<(), Error> {
     let mut f = File::create(&*path).map_err(|err| Error::FileIO {
         kind: FileKind::File,
         path: path.clone(),
@@ -96,10 +275,11 @@ fn write(path: PathBuf, contents: &str) -> Result<(), Error> {
     f.write_all(contents.as_bytes())
         .map_err(|err| Error::FileIO {
             kind: FileKind::File,
-            path,
+            path: path.clone(),
             action: FileIOAction::WriteTo,
             err: Some(err.to_string()),
         })?;
+    files.push(path);
     Ok(())
 }
 
@@ -173,7 +353,7 @@ jobs:
     )
 }
 
-fn app_src(name: &str, description: &str, is_application: bool) -> String {
+fn app_src(name: &str, description: &str, license: License, is_application: bool) -> String {
     let module = if is_application {
         format!("\n  {{mod, {{{}@application, []}}}},", name)
     } else {
@@ -193,11 +373,14 @@ fn app_src(name: &str, description: &str, is_application: bool) -> String {
   {{modules, []}},
 
   {{include_files, ["gleam.toml", "gen"]}},
-  {{licenses, ["Apache 2.0"]}},
+  {{licenses, ["{}"]}},
   {{links, []}}
 ]}}.
 "#,
-        name, description, module,
+        name,
+        description,
+        module,
+        license.app_src_name(),
     )
 }
 
@@ -250,6 +433,34 @@ pub fn hello_world_test() {{
     )
 }
 
+fn mit(name: &str) -> String {
+    format!(
+        r#"MIT License
+
+Copyright (c) The {} authors
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+"#,
+        name
+    )
+}
+
 const APACHE_2: &str = r#"                                 Apache License
                            Version 2.0, January 2004
                         http://www.apache.org/licenses/
@@ -522,3 +733,135 @@ fn validate_name(name: String) -> Result<String, Error> {
         Ok(name)
     }
 }
+
+#[test]
+fn json_summary_test() {
+    let dir = crate::test_support::TempDir::new("new-json-test");
+    let root = dir.path();
+    let details = Details {
+        name: "my_app".to_string(),
+        description: "An app".to_string(),
+        license: License::Apache2,
+    };
+    let files =
+        create_files(Template::App, &details, root, "0.1.0").expect("Unable to create project");
+    let path = |file: &str| root.join(file).to_str().unwrap().to_string();
+    let expected = serde_json::json!({
+        "name": "my_app",
+        "path": root.to_str().unwrap(),
+        "files": [
+            path("LICENSE"),
+            path(".gitignore"),
+            path("README.md"),
+            path("gleam.toml"),
+            path("test/my_app_test.gleam"),
+            path("src/my_app.gleam"),
+            path(".github/workflows/test.yml"),
+            path("rebar.config"),
+            path("src/my_app/application.gleam"),
+            path("src/my_app.app.src"),
+        ],
+    });
+    assert_eq!(
+        expected.to_string(),
+        json_summary("my_app", root, files.as_slice())
+    );
+    assert!(files.iter().all(|file| file.is_file()));
+}
+
+#[test]
+fn flags_test() {
+    // Nothing is asked for when every detail is given as a flag
+    fn unexpected(question: &str, _: &str) -> Result<String, Error> {
+        panic!("Unexpected prompt: {}", question)
+    }
+    let mut prompt = unexpected;
+    let details = Details::new(
+        Some("my_lib".to_string()),
+        Some("A library".to_string()),
+        Some(License::Mit),
+        Some(&mut prompt),
+    )
+    .expect("Invalid details");
+    assert_eq!(
+        Details {
+            name: "my_lib".to_string(),
+            description: "A library".to_string(),
+            license: License::Mit,
+        },
+        details
+    );
+
+    let dir = crate::test_support::TempDir::new("new-flags-test");
+    let root = dir.path();
+    let _ = create_files(Template::Lib, &details, root, "0.1.0").expect("Unable to create project");
+    let read = |file: &str| std::fs::read_to_string(root.join(file)).unwrap();
+    assert_eq!("name = \"my_lib\"\n", read("gleam.toml"));
+    assert!(read("README.md").starts_with("# my_lib\n\nA library\n"));
+    assert!(read("LICENSE").starts_with("MIT License\n\nCopyright (c) The my_lib authors\n"));
+    let app_src = read("src/my_lib.app.src");
+    assert!(app_src.contains(r#"{description, "A library"}"#));
+    assert!(app_src.contains(r#"{licenses, ["MIT"]}"#));
+
+    // The name can be given as a flag as well as an argument, but not both
+    use structopt::StructOpt;
+    let parse = |args: &[&str]| crate::Command::from_iter_safe(args);
+    match parse(&[
+        "gleam",
+        "new",
+        "--name",
+        "my_lib",
+        "--description",
+        "d",
+        "--license",
+        "mit",
+    ]) {
+        Ok(crate::Command::New {
+            name: None,
+            name_flag: Some(name),
+            description: Some(description),
+            license: Some(License::Mit),
+            ..
+        }) => assert_eq!(("my_lib", "d"), (name.as_str(), description.as_str())),
+        other => panic!("Unexpected parse: {:?}", other),
+    }
+    assert!(matches!(
+        parse(&["gleam", "new", "my_lib"]),
+        Ok(crate::Command::New {
+            name: Some(_),
+            name_flag: None,
+            ..
+        })
+    ));
+    assert!(parse(&["gleam", "new", "my_lib", "--name", "other"]).is_err());
+
+    // Only the details that were not given are asked for
+    fn answer(question: &str, default: &str) -> Result<String, Error> {
+        assert_ne!("Project name", question);
+        Ok(default.to_string())
+    }
+    let mut prompt = answer;
+    assert_eq!(
+        Ok(Details {
+            name: "my_app".to_string(),
+            description: "A Gleam program".to_string(),
+            license: License::Apache2,
+        }),
+        Details::new(Some("my_app".to_string()), None, None, Some(&mut prompt))
+    );
+
+    // Without a prompt the details that were not given are defaulted, other
+    // than the name
+    assert_eq!(
+        Ok(Details {
+            name: "my_app".to_string(),
+            description: "A Gleam program".to_string(),
+            license: License::Apache2,
+        }),
+        Details::new(Some("my_app".to_string()), None, None, None)
+    );
+    assert_eq!(
+        Err(Error::MissingProjectName),
+        Details::new(None, None, None, None)
+    );
+}
@@ -1,9 +1,107 @@
 use crate::ast::*;
+use crate::build::Target;
 use unicode_segmentation::UnicodeSegmentation;
 
+/// The `@target(...)`, `@deprecated(...)`, `@internal` and `@allow(...)`
+/// attributes that may come before a statement.
+pub type StatementAttributes = (
+    Option<(SrcSpan, Target)>,
+    Option<(SrcSpan, String)>,
+    Option<SrcSpan>,
+    Option<(SrcSpan, Vec<String>)>,
+);
+
+/// One of the attributes that may come before a statement, in whichever order
+/// they are written.
+#[derive(Debug)]
+pub enum Attribute {
+    Target(SrcSpan, Target),
+    Deprecated(SrcSpan, String),
+    Internal(SrcSpan),
+    Allow(SrcSpan, Vec<String>),
+}
+
+/// Collect the attributes of a statement, each of which may only be given
+/// once.
+pub fn collect_attributes(attributes: Vec<Attribute>) -> Result<StatementAttributes, Error> {
+    let mut collected: StatementAttributes = (None, None, None, None);
+    for attribute in attributes {
+        let (name, location, previous) = match &attribute {
+            Attribute::Target(location, _) => {
+                ("target", location, collected.0.as_ref().map(|a| &a.0))
+            }
+            Attribute::Deprecated(location, _) => {
+                ("deprecated", location, collected.1.as_ref().map(|a| &a.0))
+            }
+            Attribute::Internal(location) => ("internal", location, collected.2.as_ref()),
+            Attribute::Allow(location, _) => {
+                ("allow", location, collected.3.as_ref().map(|a| &a.0))
+            }
+        };
+        if let Some(previous_location) = previous {
+            return Err(Error::DuplicateAttribute {
+                location: location.clone(),
+                previous_location: previous_location.clone(),
+                name: name.to_string(),
+            });
+        }
+        match attribute {
+            Attribute::Target(location, target) => collected.0 = Some((location, target)),
+            Attribute::Deprecated(location, message) => collected.1 = Some((location, message)),
+            Attribute::Internal(location) => collected.2 = Some(location),
+            Attribute::Allow(location, codes) => collected.3 = Some((location, codes)),
+        }
+    }
+    Ok(collected)
+}
+
 #[derive(Debug, PartialEq)]
 pub enum Error {
-    TooManyHolesInCapture { location: SrcSpan, count: usize },
+    TooManyHolesInCapture {
+        location: SrcSpan,
+        count: usize,
+    },
+    InvalidUnicodeEscape {
+        location: SrcSpan,
+        error: InvalidUnicodeEscape,
+    },
+    UnknownDerive {
+        location: SrcSpan,
+        name: String,
+    },
+    UnknownTarget {
+        location: SrcSpan,
+        name: String,
+    },
+    UnknownWarningCode {
+        location: SrcSpan,
+        code: String,
+    },
+    DuplicateAttribute {
+        location: SrcSpan,
+        previous_location: SrcSpan,
+        name: String,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+pub enum InvalidUnicodeEscape {
+    /// The escape is not of the form `\u{...}` with 1 to 6 hex digits.
+    Malformed,
+    /// The escape has no closing `}`.
+    Unterminated,
+    /// The escape is a surrogate or larger than the largest code point.
+    InvalidCodePoint,
+}
+
+/// A brace that is likely to be the cause of a syntax error, found by
+/// matching the braces of a module that failed to parse.
+#[derive(Debug, PartialEq)]
+pub enum UnbalancedBrace {
+    /// An opening brace with no closing brace.
+    Unclosed { location: SrcSpan },
+    /// A closing brace with no opening brace.
+    Unexpected { location: SrcSpan },
 }
 
 pub type LalrpopError = lalrpop_util::ParseError<usize, (usize, String), Error>;
@@ -38,6 +136,23 @@ pub fn take_before<'a>(
     (popped, &comments[end..])
 }
 
+/// The name of a character that looks like whitespace but cannot be used as
+/// it, as only spaces, tabs, and newlines are whitespace in Gleam code.
+///
+pub fn lookalike_whitespace(c: char) -> Option<&'static str> {
+    match c {
+        ' ' | '\t' | '\r' | '\n' => None,
+        '\u{a0}' => Some("non-breaking space"),
+        '\u{200b}' => Some("zero width space"),
+        '\u{feff}' => Some("zero width no-break space"),
+        '\u{b}' => Some("vertical tab"),
+        '\u{c}' => Some("form feed"),
+        '\u{2028}' => Some("line separator"),
+        _ if c.is_whitespace() => Some("whitespace character"),
+        _ => None,
+    }
+}
+
 /// Blanks out comments, semicolons, etc
 ///
 pub fn strip_extra(src: &str) -> (String, ModuleComments<'_>) {
@@ -184,11 +299,27 @@ fn chomp_newlines(
     comments: &mut ModuleComments<'_>,
     chars: &mut std::iter::Peekable<unicode_segmentation::GraphemeIndices<'_>>,
 ) {
-    if let Some((_, "\n")) | Some((_, "\r\n")) = chars.peek() {
-        comments.empty_lines.push(position + 1);
-        while let Some((_, grapheme @ "\r\n")) | Some((_, grapheme @ "\n")) = chars.peek() {
-            buffer.push_str(grapheme);
-            chars.next();
+    // Lines of only spaces and tabs are empty lines too, however they are
+    // indented
+    let mut empty = false;
+    loop {
+        let mut rest = chars.clone();
+        let mut indent = 0;
+        while let Some((_, " " | "\t")) = rest.peek() {
+            indent += 1;
+            let _ = rest.next();
+        }
+        match rest.peek() {
+            Some((_, "\n" | "\r\n")) => {
+                if !empty {
+                    comments.empty_lines.push(position + 1);
+                    empty = true;
+                }
+                for (_, grapheme) in chars.take(indent + 1) {
+                    buffer.push_str(grapheme);
+                }
+            }
+            _ => break,
         }
     }
 }
@@ -418,6 +549,47 @@ fn strip_extra_windows_line_endings_test() {
     assert_stripped!("// hi\r\n1", "     \r\n1");
 }
 
+#[test]
+fn strip_extra_indented_empty_lines_test() {
+    // Lines of only spaces and tabs are empty lines
+    assert_stripped!(
+        "1\n\t\n  \t \n2\n \n\n3\n\t4",
+        "1\n\t\n  \t \n2\n \n\n3\n\t4",
+        ModuleComments {
+            module_comments: vec![],
+            doc_comments: vec![],
+            comments: vec![],
+            empty_lines: vec![2, 11],
+        }
+    );
+}
+
+#[test]
+fn whitespace_test() {
+    let parse = |src| crate::grammar::ModuleParser::new().parse(src).map(|_| ());
+
+    // Tabs can be used wherever spaces can
+    assert!(parse("pub fn main() {\n\tlet x =\t1\n\t\tx\n\t|> fn(y)\t{ y }\n}\n").is_ok());
+
+    // Other characters that look like whitespace cannot
+    assert_eq!(
+        Err(lalrpop_util::ParseError::InvalidToken { location: 16 }),
+        parse("pub fn main() {\n\u{a0}1\n}\n")
+    );
+    assert_eq!(
+        Err(lalrpop_util::ParseError::InvalidToken { location: 6 }),
+        parse("pub fn\u{3000}main() { 1 }")
+    );
+
+    assert_eq!(Some("non-breaking space"), lookalike_whitespace('\u{a0}'));
+    assert_eq!(
+        Some("whitespace character"),
+        lookalike_whitespace('\u{3000}')
+    );
+    assert_eq!(None, lookalike_whitespace('\t'));
+    assert_eq!(None, lookalike_whitespace('a'));
+}
+
 pub fn location(start: usize, end: usize) -> SrcSpan {
     SrcSpan { start, end }
 }
@@ -442,6 +614,55 @@ pub fn attach_doc_comments<'a, A, B, C, D>(
     }
 }
 
+/// Check that every `\u{...}` escape in the contents of a string literal
+/// refers to a valid unicode code point. The contents are otherwise kept as
+/// written, with `start` being the offset of the first character after the
+/// opening quote.
+///
+pub fn validate_string_escapes(start: usize, value: String) -> Result<String, Error> {
+    let mut chars = value.char_indices();
+    while let Some((i, c)) = chars.next() {
+        if c != '\\' {
+            continue;
+        }
+        match chars.next() {
+            Some((_, 'u')) => (),
+            _ => continue,
+        }
+        let escape = &value[i..];
+        let error = |end: usize, error| Error::InvalidUnicodeEscape {
+            location: location(start + i, start + i + end),
+            error,
+        };
+
+        if !escape[2..].starts_with('{') {
+            return Err(error(2, InvalidUnicodeEscape::Malformed));
+        }
+        let close = match escape.find('}') {
+            Some(close) => close,
+            None => {
+                let digits = escape[3..]
+                    .find(|c: char| !c.is_ascii_hexdigit())
+                    .unwrap_or_else(|| escape.len() - 3);
+                return Err(error(3 + digits, InvalidUnicodeEscape::Unterminated));
+            }
+        };
+        let digits = &escape[3..close];
+        if digits.is_empty() || digits.len() > 6 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(error(close + 1, InvalidUnicodeEscape::Malformed));
+        }
+        let code_point = u32::from_str_radix(digits, 16)
+            .map_err(|_| error(close + 1, InvalidUnicodeEscape::Malformed))?;
+        if std::char::from_u32(code_point).is_none() {
+            return Err(error(close + 1, InvalidUnicodeEscape::InvalidCodePoint));
+        }
+
+        // Skip the rest of the escape
+        let _ = chars.nth(close - 2);
+    }
+    Ok(value)
+}
+
 pub enum ParserArg {
     Arg(CallArg<UntypedExpr>),
     Hole {
@@ -506,11 +727,334 @@ pub fn make_call(
     }
 }
 
+/// Match up the braces in the source, skipping those in strings and
+/// comments, returning the one most likely to be unbalanced if they do not
+/// all match.
+///
+/// Braces are matched innermost first, so an unclosed brace is usually
+/// matched with the closing brace intended for an enclosing one. A closing
+/// brace on its own line that is indented differently to the line of its
+/// opening brace is taken as a sign that the braces were mismatched there.
+///
+pub fn unbalanced_brace(src: &str) -> Option<UnbalancedBrace> {
+    let brace = |start: usize| SrcSpan {
+        start,
+        end: start + 1,
+    };
+    let mut open: Vec<(usize, usize)> = vec![];
+    let mut suspect_unclosed = None;
+    let mut suspect_unexpected = None;
+    let mut indent = 0;
+    let mut line_start = true;
+    let mut chars = src.char_indices();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\n' => {
+                indent = 0;
+                line_start = true;
+                continue;
+            }
+
+            ' ' | '\t' | '\r' if line_start => {
+                indent += 1;
+                continue;
+            }
+
+            '"' => {
+                while let Some((_, c)) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => {
+                            let _ = chars.next();
+                        }
+                        _ => (),
+                    }
+                }
+            }
+
+            '/' if src[i..].starts_with("//") => {
+                for (_, c) in &mut chars {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+                indent = 0;
+                line_start = true;
+                continue;
+            }
+
+            '{' => open.push((i, indent)),
+
+            '}' => match open.pop() {
+                None => {
+                    return Some(UnbalancedBrace::Unexpected {
+                        location: brace(suspect_unexpected.unwrap_or(i)),
+                    })
+                }
+
+                Some((start, open_indent)) if line_start => {
+                    if indent < open_indent && suspect_unclosed.is_none() {
+                        suspect_unclosed = Some(start);
+                    }
+                    if indent > open_indent && suspect_unexpected.is_none() {
+                        suspect_unexpected = Some(i);
+                    }
+                }
+
+                Some(_) => (),
+            },
+
+            _ => (),
+        }
+        line_start = false;
+    }
+
+    let (innermost, _) = open.last()?;
+    Some(UnbalancedBrace::Unclosed {
+        location: brace(suspect_unclosed.unwrap_or(*innermost)),
+    })
+}
+
+/// The opening `/*` of the first block comment in the source, used to explain
+/// the syntax error caused by one. Gleam only has `//` line comments.
+///
+pub fn block_comment(src: &str) -> Option<SrcSpan> {
+    let mut chars = src.char_indices();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => {
+                while let Some((_, c)) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => {
+                            let _ = chars.next();
+                        }
+                        _ => (),
+                    }
+                }
+            }
+
+            '/' if src[i..].starts_with("//") => {
+                for (_, c) in &mut chars {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+
+            '/' if src[i..].starts_with("/*") => return Some(location(i, i + 2)),
+
+            _ => (),
+        }
+    }
+    None
+}
+
 pub fn is_gleam_reserved_word(s: &str) -> bool {
     match s {
-        "pub" | "fn" | "import" | "as" | "type" | "extern" | "case" | "let" | "try" | "assert" => {
-            true
-        }
+        "pub" | "fn" | "import" | "as" | "type" | "extern" | "case" | "let" | "try" | "assert"
+        | "todo" | "panic" => true,
         _ => false,
     }
 }
+
+#[test]
+fn unicode_escape_test() {
+    let parse = |src| crate::grammar::ModuleParser::new().parse(src).map(|_| ());
+
+    assert!(parse(r#"fn main() { "\u{1F600} \u{0} \u{10FFFF} \\u" }"#).is_ok());
+
+    // Surrogates are not valid code points
+    assert_eq!(
+        Err(lalrpop_util::ParseError::User {
+            error: Error::InvalidUnicodeEscape {
+                location: SrcSpan { start: 14, end: 22 },
+                error: InvalidUnicodeEscape::InvalidCodePoint,
+            }
+        }),
+        parse(r#"fn main() { "a\u{D800}" }"#)
+    );
+
+    assert_eq!(
+        Err(lalrpop_util::ParseError::User {
+            error: Error::InvalidUnicodeEscape {
+                location: SrcSpan { start: 13, end: 23 },
+                error: InvalidUnicodeEscape::InvalidCodePoint,
+            }
+        }),
+        parse(r#"fn main() { "\u{110000}" }"#)
+    );
+
+    assert_eq!(
+        Err(lalrpop_util::ParseError::User {
+            error: Error::InvalidUnicodeEscape {
+                location: SrcSpan { start: 13, end: 21 },
+                error: InvalidUnicodeEscape::Unterminated,
+            }
+        }),
+        parse(r#"fn main() { "\u{1F600" }"#)
+    );
+
+    assert_eq!(
+        Err(lalrpop_util::ParseError::User {
+            error: Error::InvalidUnicodeEscape {
+                location: SrcSpan { start: 13, end: 15 },
+                error: InvalidUnicodeEscape::Malformed,
+            }
+        }),
+        parse(r#"fn main() { "\u0041" }"#)
+    );
+
+    assert_eq!(
+        Err(lalrpop_util::ParseError::User {
+            error: Error::InvalidUnicodeEscape {
+                location: SrcSpan { start: 13, end: 19 },
+                error: InvalidUnicodeEscape::Malformed,
+            }
+        }),
+        parse(r#"fn main() { "\u{xy}" }"#)
+    );
+}
+
+#[test]
+fn unknown_derive_test() {
+    assert_eq!(
+        Err(lalrpop_util::ParseError::User {
+            error: Error::UnknownDerive {
+                location: SrcSpan { start: 15, end: 19 },
+                name: "show".to_string(),
+            }
+        }),
+        crate::grammar::ModuleParser::new()
+            .parse("@derive(equal, show) type Box { Box }")
+            .map(|_| ())
+    );
+}
+
+#[test]
+fn unknown_warning_code_test() {
+    assert_eq!(
+        Err(lalrpop_util::ParseError::User {
+            error: Error::UnknownWarningCode {
+                location: SrcSpan { start: 13, end: 26 },
+                code: "unused-import".to_string(),
+            }
+        }),
+        crate::grammar::ModuleParser::new()
+            .parse("@allow(todo, unused-import) fn go() { 1 }")
+            .map(|_| ())
+    );
+}
+
+#[test]
+fn unknown_target_test() {
+    assert_eq!(
+        Err(lalrpop_util::ParseError::User {
+            error: Error::UnknownTarget {
+                location: SrcSpan { start: 8, end: 12 },
+                name: "beam".to_string(),
+            }
+        }),
+        crate::grammar::ModuleParser::new()
+            .parse("@target(beam) fn go() { 1 }")
+            .map(|_| ())
+    );
+}
+
+#[test]
+fn attribute_order_test() {
+    let parse = |src: &'static str| crate::grammar::ModuleParser::new().parse(src);
+
+    // Attributes may be written in any order
+    let module = parse("@allow(todo)\n@internal\n@deprecated(\"old\")\npub fn main() { todo }")
+        .expect("syntax error");
+    assert!(module.is_internal(42));
+    assert_eq!(Some("old"), module.deprecation_of(42));
+    assert!(module.allows("todo", 42));
+
+    let module = parse("@internal @target(erlang) pub fn main() { 1 }").expect("syntax error");
+    assert!(module.is_internal(26));
+    assert_eq!(Some(Target::Erlang), module.target_of(26));
+
+    // But each may only be given once
+    assert_eq!(
+        Err(lalrpop_util::ParseError::User {
+            error: Error::DuplicateAttribute {
+                location: SrcSpan { start: 23, end: 32 },
+                previous_location: SrcSpan { start: 0, end: 9 },
+                name: "internal".to_string(),
+            }
+        }),
+        parse("@internal @allow(todo) @internal pub fn main() { todo }").map(|_| ())
+    );
+}
+
+#[test]
+fn unbalanced_brace_test() {
+    assert_eq!(None, unbalanced_brace("fn main() { let x = { 1 } x }"));
+
+    // Braces in strings and comments are not counted
+    assert_eq!(
+        None,
+        unbalanced_brace("fn main() {\n  // }\n  \"{\\\"\"\n}\n")
+    );
+
+    // A missing closing brace
+    let src = "fn main() {\n  1\n";
+    assert_eq!(
+        Some(UnbalancedBrace::Unclosed {
+            location: SrcSpan { start: 10, end: 11 }
+        }),
+        unbalanced_brace(src)
+    );
+
+    // A missing closing brace within a function, where the function's closing
+    // brace is taken by the case expression
+    let src = "fn main(x) {\n  case x {\n    _ -> 1\n}\n\nfn other() {\n  2\n}\n";
+    assert_eq!(
+        Some(UnbalancedBrace::Unclosed {
+            location: SrcSpan { start: 22, end: 23 }
+        }),
+        unbalanced_brace(src)
+    );
+
+    // A stray closing brace
+    let src = "fn main() {\n  1\n}\n}\n";
+    assert_eq!(
+        Some(UnbalancedBrace::Unexpected {
+            location: SrcSpan { start: 18, end: 19 }
+        }),
+        unbalanced_brace(src)
+    );
+
+    // A stray closing brace within a function, which closes the function early
+    let src = "fn main() {\n  let x = 1\n    }\n  x\n}\n";
+    assert_eq!(
+        Some(UnbalancedBrace::Unexpected {
+            location: SrcSpan { start: 28, end: 29 }
+        }),
+        unbalanced_brace(src)
+    );
+}
+
+#[test]
+fn block_comment_test() {
+    assert_eq!(None, block_comment("fn main() { 1 / 2 * 3 }"));
+
+    // Comment markers in strings and line comments are not block comments
+    assert_eq!(
+        None,
+        block_comment("fn main() {\n  // /* no\n  \"/* \\\" */\"\n}\n")
+    );
+
+    assert_eq!(
+        Some(SrcSpan { start: 0, end: 2 }),
+        block_comment("/* */\nfn main() { 1 }")
+    );
+    assert_eq!(
+        Some(SrcSpan { start: 14, end: 16 }),
+        block_comment("fn main() {\n  /* one */ 1\n}\n")
+    );
+}
@@ -15,6 +15,9 @@
 #[cfg(test)]
 mod tests;
 
+use crate::error::GleamExpect;
+use std::io::{self, Write};
+
 pub trait Documentable {
     fn to_doc(self) -> Document;
 }
@@ -171,44 +174,51 @@ fn fits(mut limit: isize, mut docs: im::Vector<(isize, Mode, Document)>) -> bool
 }
 
 pub fn format(limit: isize, doc: Document) -> String {
-    let mut buffer = String::new();
+    let mut buffer = Vec::new();
+    format_to(&mut buffer, limit, doc).gleam_expect("Writing to an in-memory buffer");
+    String::from_utf8(buffer).gleam_expect("Formatted document is not valid UTF-8")
+}
+
+/// Render the document to the given writer as it is laid out, rather than
+/// building up the entire output string in memory first.
+///
+pub fn format_to(writer: &mut impl Write, limit: isize, doc: Document) -> io::Result<()> {
     fmt(
-        &mut buffer,
+        writer,
         limit,
         0,
         im::vector![(0, Mode::Unbroken, Document::Group(Box::new(doc)))],
-    );
-    buffer
+    )
 }
 
 fn fmt(
-    b: &mut String,
+    w: &mut impl Write,
     limit: isize,
     mut width: isize,
     mut docs: im::Vector<(isize, Mode, Document)>,
-) {
+) -> io::Result<()> {
     while let Some((indent, mode, document)) = docs.pop_front() {
         match document {
             Document::Nil | Document::ForceBreak => (),
 
             Document::Line(i) => {
                 for _ in 0..i {
-                    b.push_str("\n");
+                    w.write_all(b"\n")?;
                 }
-                b.push_str(" ".repeat(indent as usize).as_str());
+                write_indent(w, indent)?;
                 width = indent;
             }
 
             Document::Break { broken, unbroken } => {
                 width = match mode {
                     Mode::Unbroken => {
-                        b.push_str(unbroken.as_str());
+                        w.write_all(unbroken.as_bytes())?;
                         width + unbroken.len() as isize
                     }
                     Mode::Broken => {
-                        b.push_str(broken.as_str());
-                        b.push_str("\n");
-                        b.push_str(" ".repeat(indent as usize).as_str());
+                        w.write_all(broken.as_bytes())?;
+                        w.write_all(b"\n")?;
+                        write_indent(w, indent)?;
                         indent
                     }
                 };
@@ -216,7 +226,7 @@ fn fmt(
 
             Document::Text(s) => {
                 width += s.len() as isize;
-                b.push_str(s.as_str());
+                w.write_all(s.as_bytes())?;
             }
 
             Document::Cons(left, right) => {
@@ -243,6 +253,11 @@ fn fmt(
             }
         }
     }
+    Ok(())
+}
+
+fn write_indent(w: &mut impl Write, indent: isize) -> io::Result<()> {
+    write!(w, "{:width$}", "", width = indent as usize)
 }
 
 pub fn nil() -> Document {
@@ -224,3 +224,52 @@ fn let_left_side_fits_test() {
 
     assert_eq!("[1] = [1]", format(16, doc.clone()));
 }
+
+/// A writer that discards its input, recording how much was written and the
+/// size of the largest single write.
+#[derive(Debug, Default)]
+struct CountingWriter {
+    written: usize,
+    largest_write: usize,
+}
+
+impl Write for CountingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.written += buf.len();
+        self.largest_write = self.largest_write.max(buf.len());
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn format_to_streams_output_test() {
+    let large_doc = || {
+        concat((0..2_000).map(|i| {
+            let args = concat((0..5).map(|j| break_(",", ", ").append(format!("arg_{}_{}", i, j))));
+            format!("call_{}(", i)
+                .to_doc()
+                .append(args.nest(2))
+                .append(break_(",", ""))
+                .append(")")
+                .group()
+                .append(line())
+        }))
+    };
+
+    // Streamed output is identical to the buffered output
+    let buffered = format(80, large_doc());
+    let mut streamed = Vec::new();
+    format_to(&mut streamed, 80, large_doc()).unwrap();
+    assert_eq!(buffered.as_bytes(), streamed.as_slice());
+
+    // The output is written in small pieces rather than as one string
+    let mut counter = CountingWriter::default();
+    format_to(&mut counter, 80, large_doc()).unwrap();
+    assert_eq!(buffered.len(), counter.written);
+    assert!(buffered.len() > 100_000);
+    assert!(counter.largest_write < 100);
+}
@@ -3,16 +3,22 @@ mod source_tree;
 mod tests;
 
 use crate::{
-    ast::TypedModule,
-    build::Origin,
-    config::{self, PackageConfig},
-    error::{Error, FileIOAction, FileKind, GleamExpect},
-    typ,
-    warning::Warning,
+    ast::{TypedModule, UntypedModule},
+    build::{
+        manifest,
+        timings::{Stage, Timings},
+        Origin,
+    },
+    config::{self, PackageConfig, WarningsConfig},
+    erl,
+    error::{Error, GleamExpect},
+    format, fs, typ,
+    warning::{self, Warning},
 };
 use source_tree::SourceTree;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::time::Instant;
 
 pub const OUTPUT_DIR_NAME: &str = "gen";
 
@@ -22,15 +28,19 @@ pub struct Input {
     pub path: PathBuf,
     pub src: String,
     pub origin: ModuleOrigin,
+    /// The name of the module, if it is not to be derived from the path.
+    pub module_name: Option<String>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Analysed {
     pub ast: TypedModule,
     pub name: Vec<String>,
     pub origin: ModuleOrigin,
     pub type_info: typ::Module,
     pub source_base_path: PathBuf,
+    pub path: PathBuf,
+    pub src: String,
     pub warnings: Vec<Warning>,
 }
 
@@ -63,10 +73,180 @@ pub struct Module {
     path: PathBuf,
     source_base_path: PathBuf,
     origin: ModuleOrigin,
-    module: crate::ast::UntypedModule,
+    module: UntypedModule,
+}
+
+/// The parsed and analysed modules of a previous compilation, used to avoid
+/// reparsing and rechecking modules that have not changed when compiling the
+/// project again.
+///
+#[derive(Debug, Default)]
+pub struct Cache {
+    uid: usize,
+    modules: HashMap<PathBuf, CachedModule>,
+}
+
+#[derive(Debug)]
+struct CachedModule {
+    src: String,
+    parsed: UntypedModule,
+    analysed: Option<Analysed>,
+}
+
+impl Cache {
+    fn parsed(&self, input: &Input) -> Option<UntypedModule> {
+        self.modules
+            .get(&input.path)
+            .filter(|cached| cached.src == input.src)
+            .map(|cached| cached.parsed.clone())
+    }
+
+    fn analysed(&self, path: &Path, src: &str) -> Option<&Analysed> {
+        self.modules
+            .get(path)
+            .filter(|cached| cached.src == src)
+            .and_then(|cached| cached.analysed.as_ref())
+    }
 }
 
 pub fn read_and_analyse(root: impl AsRef<Path>) -> Result<(PackageConfig, Vec<Analysed>), Error> {
+    let (project_config, srcs) = read_source(root)?;
+    let (mut analysed, _) = analysed_with_cache(
+        srcs,
+        &project_config.name,
+        &mut Cache::default(),
+        &mut Timings::default(),
+    )?;
+    lint(&project_config.warnings, &mut analysed);
+    Ok((project_config, analysed))
+}
+
+/// The options of `gleam build` that apply to each build of the project,
+/// whether it is built once or again after every change with `--watch`.
+///
+#[derive(Debug, Clone, Copy)]
+pub struct BuildOptions {
+    pub warning_format: warning::Format,
+    pub release: bool,
+    pub timings: bool,
+    pub verify_format: bool,
+    pub deny_todo: bool,
+    pub write_manifest: bool,
+    pub verify_manifest: bool,
+}
+
+impl BuildOptions {
+    /// Check the project before it is built. Building never changes the
+    /// source files, so their formatting is only checked.
+    pub fn verify(&self, root: &Path, config: &PackageConfig) -> Result<(), Error> {
+        if self.verify_format {
+            format::command::verify(root, &config.format)?;
+        }
+        if self.verify_manifest {
+            manifest::verify(root)?;
+        }
+        Ok(())
+    }
+}
+
+/// Build the project, reusing the cached analysis of unchanged modules, and
+/// replace the generated Erlang with the result. Returns the number of
+/// modules compiled and the total number of modules.
+///
+pub fn build(
+    root: &Path,
+    cache: &mut Cache,
+    options: BuildOptions,
+) -> Result<(usize, usize), Error> {
+    let (mut config, srcs) = read_source(root)?;
+    options.verify(root, &config)?;
+    if options.release {
+        config.erlang.release = Some(config.profile.release);
+    }
+
+    // Read and type check project
+    let mut timings = Timings::default();
+    let (mut analysed, compiled) = analysed_with_cache(srcs, &config.name, cache, &mut timings)?;
+    lint(&config.warnings, &mut analysed);
+
+    // Generate Erlang code, one module at a time so that each is timed
+    let output_files: Vec<_> = analysed
+        .iter()
+        .flat_map(|module| {
+            timings.time(&module.name.join("/"), Stage::Generate, || {
+                erl::generate_erlang(std::slice::from_ref(module), &config.erlang)
+            })
+        })
+        .collect();
+
+    // Print warnings
+    warning::print_all(analysed.as_slice(), options.warning_format);
+
+    // The previous build is kept if this one is rejected
+    if options.deny_todo {
+        warning::deny_todos(analysed.as_slice())?;
+    }
+
+    // Reset output directory
+    fs::delete_dir(&root.join(OUTPUT_DIR_NAME))?;
+
+    // Delete the gen directory before generating the newly compiled files
+    fs::write_outputs(output_files.as_slice())?;
+
+    warning::print_todo_summary(analysed.as_slice());
+    if options.timings {
+        print!("{}", timings.report());
+    }
+
+    // The manifest is only written once the sources are known to compile
+    if options.write_manifest {
+        manifest::write(root)?;
+    }
+    Ok((compiled.len(), analysed.len()))
+}
+
+/// Add the warnings of the opt-in lints enabled in the project config to the
+/// project's own modules.
+///
+pub fn lint(config: &WarningsConfig, analysed: &mut [Analysed]) {
+    if !config.unnecessary_result && !config.redundant_annotation && !config.non_tail_recursion {
+        return;
+    }
+    for module in analysed
+        .iter_mut()
+        .filter(|module| module.origin != ModuleOrigin::Dependency)
+    {
+        let Analysed {
+            ast,
+            path,
+            src,
+            warnings,
+            ..
+        } = module;
+        let mut lints = vec![];
+        if config.unnecessary_result {
+            lints.extend(typ::unnecessary_results(ast));
+        }
+        if config.redundant_annotation {
+            lints.extend(typ::redundant_annotations(ast));
+        }
+        if config.non_tail_recursion {
+            lints.extend(typ::non_tail_recursion(ast));
+        }
+        warnings.extend(
+            lints
+                .into_iter()
+                .filter(|warning| !warning::is_allowed(ast, warning))
+                .map(|warning| Warning::Type {
+                    path: path.clone(),
+                    src: src.clone(),
+                    warning,
+                }),
+        );
+    }
+}
+
+pub fn read_source(root: impl AsRef<Path>) -> Result<(PackageConfig, Vec<Input>), Error> {
     let project_config = config::read_project_config(&root)?;
     let mut srcs = vec![];
 
@@ -91,27 +271,50 @@ pub fn read_and_analyse(root: impl AsRef<Path>) -> Result<(PackageConfig, Vec<An
     collect_source(root.join("src"), ModuleOrigin::Src, &mut srcs)?;
     collect_source(root.join("test"), ModuleOrigin::Test, &mut srcs)?;
 
-    // Analyse source
-    let analysed = analysed(srcs)?;
+    // Use any module names given for the project's own files in the config
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    for input in srcs
+        .iter_mut()
+        .filter(|input| input.origin != ModuleOrigin::Dependency)
+    {
+        input.module_name = input
+            .path
+            .strip_prefix(&root)
+            .ok()
+            .and_then(|path| project_config.modules.get(path))
+            .cloned();
+    }
 
-    Ok((project_config, analysed))
+    Ok((project_config, srcs))
 }
 
+#[cfg(test)]
 pub fn analysed(inputs: Vec<Input>) -> Result<Vec<Analysed>, Error> {
+    analysed_with_cache(inputs, "", &mut Cache::default(), &mut Timings::default())
+        .map(|(analysed, _)| analysed)
+}
+
+/// Analyse the given modules, reusing the results of the previous compilation
+/// held in the cache for any module whose source and dependencies have not
+/// changed. Returns the analysed modules along with the names of the modules
+/// that had to be compiled. The project's own modules belong to the package
+/// with the given name.
+///
+pub fn analysed_with_cache(
+    inputs: Vec<Input>,
+    package: &str,
+    cache: &mut Cache,
+    timings: &mut Timings,
+) -> Result<(Vec<Analysed>, Vec<String>), Error> {
     let module_count = inputs.len();
-    let mut source_tree = SourceTree::new(inputs)?;
+    let paths: HashSet<_> = inputs.iter().map(|input| input.path.clone()).collect();
+    let mut source_tree = SourceTree::new(inputs, cache, timings)?;
     let mut modules_type_infos = HashMap::new();
-    let mut compiled_modules = Vec::with_capacity(module_count);
-    let mut uid = 0;
+    let mut analysed_modules = Vec::with_capacity(module_count);
+    let mut compiled = vec![];
 
-    struct Out {
-        source_base_path: PathBuf,
-        name_string: String,
-        name: Vec<String>,
-        origin: ModuleOrigin,
-        ast: TypedModule,
-        warnings: Vec<Warning>,
-    }
+    // Forget any modules that have been deleted since the last compilation
+    cache.modules.retain(|path, _| paths.contains(path));
 
     for Module {
         src,
@@ -124,64 +327,98 @@ pub fn analysed(inputs: Vec<Input>) -> Result<Vec<Analysed>, Error> {
         let name = module.name.clone();
         let name_string = module.name_string();
 
-        println!("Compiling {}", name_string.as_str());
+        // A module can be reused if neither it nor any of its dependencies
+        // have changed since the last compilation.
+        let dependency_changed = module
+            .dependencies()
+            .iter()
+            .any(|(dependency, _)| compiled.contains(dependency));
+        let cached = cache
+            .analysed(&path, &src)
+            .filter(|analysed| !dependency_changed && analysed.name == name)
+            .cloned();
 
-        let mut warnings = vec![];
-        let result = crate::typ::infer_module(&mut uid, module, &modules_type_infos, &mut warnings);
-        let warnings = warnings
-            .into_iter()
-            .map(|warning| Warning::Type {
-                path: path.clone(),
-                src: src.clone(),
-                warning,
-            })
-            .collect();
+        let analysed = match cached {
+            Some(analysed) => analysed,
+
+            None => {
+                println!("Compiling {}", name_string.as_str());
+
+                let parsed = module.clone();
+                let mut warnings = vec![];
+                let start = Instant::now();
+                let result = crate::typ::infer_module(
+                    &mut cache.uid,
+                    module,
+                    &package_name(package, &origin, &source_base_path),
+                    &modules_type_infos,
+                    &mut warnings,
+                );
+                timings.record(&name_string, Stage::TypeCheck, start.elapsed());
+                let warnings = warnings
+                    .into_iter()
+                    .filter(|warning| !warning::is_allowed(&parsed, warning))
+                    .map(|warning| Warning::Type {
+                        path: path.clone(),
+                        src: src.clone(),
+                        warning,
+                    })
+                    .collect();
 
-        let ast = result.map_err(|error| Error::Type {
-            path: path.clone(),
-            src: src.clone(),
-            error,
-        })?;
+                let analysed = result
+                    .map(|ast| Analysed {
+                        type_info: ast.type_info.clone(),
+                        ast,
+                        name,
+                        source_base_path,
+                        path: path.clone(),
+                        src: src.clone(),
+                        origin: origin.clone(),
+                        warnings,
+                    })
+                    .map_err(|error| Error::Type {
+                        path: path.clone(),
+                        src: src.clone(),
+                        error,
+                    });
+
+                // The parsed module is cached even if it could not be analysed
+                cache.modules.insert(
+                    path,
+                    CachedModule {
+                        src,
+                        parsed,
+                        analysed: analysed.as_ref().ok().cloned(),
+                    },
+                );
+                compiled.push(name_string.clone());
+                analysed?
+            }
+        };
 
         modules_type_infos.insert(
-            name_string.clone(),
-            (origin.to_origin(), ast.type_info.clone()),
+            name_string,
+            (origin.to_origin(), analysed.type_info.clone()),
         );
+        analysed_modules.push(analysed);
+    }
 
-        compiled_modules.push(Out {
-            name,
-            name_string,
-            source_base_path,
-            origin,
-            ast,
-            warnings,
-        });
-    }
-
-    Ok(compiled_modules
-        .into_iter()
-        .map(|out| {
-            let Out {
-                name,
-                source_base_path,
-                name_string,
-                origin,
-                ast,
-                warnings,
-            } = out;
-            Analysed {
-                ast,
-                name,
-                source_base_path,
-                origin,
-                type_info: modules_type_infos
-                    .remove(&name_string)
-                    .gleam_expect("project::compile(): Merging module type info")
-                    .1,
-                warnings,
-            }
-        })
-        .collect())
+    Ok((analysed_modules, compiled))
+}
+
+/// The name of the package that a module belongs to. A dependency's package
+/// is named after the directory containing its source directory, while the
+/// project's own modules belong to the project's package.
+///
+fn package_name(package: &str, origin: &ModuleOrigin, source_base_path: &Path) -> String {
+    match origin {
+        ModuleOrigin::Dependency => source_base_path
+            .parent()
+            .and_then(Path::file_name)
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        ModuleOrigin::Src | ModuleOrigin::Test => package.to_string(),
+    }
 }
 
 pub fn collect_source(
@@ -194,13 +431,16 @@ pub fn collect_source(
         Err(_) => return Ok(()),
     };
 
-    for path in crate::fs::gleam_files(&src_dir) {
-        let src = std::fs::read_to_string(&path).map_err(|err| Error::FileIO {
-            action: FileIOAction::Read,
-            kind: FileKind::File,
-            err: Some(err.to_string()),
-            path: path.clone(),
-        })?;
+    // Only the project's own files must have valid module names, any files
+    // of dependencies that do not are skipped
+    let paths: Vec<_> = if origin == ModuleOrigin::Dependency {
+        fs::gleam_files(&src_dir).collect()
+    } else {
+        fs::all_gleam_files(&src_dir).collect()
+    };
+
+    for path in paths {
+        let src = fs::read(&path)?;
 
         srcs.push(Input {
             path: path
@@ -208,6 +448,7 @@ pub fn collect_source(
                 .gleam_expect("project::collect_source(): path canonicalize"),
             source_base_path: src_dir.clone(),
             origin: origin.clone(),
+            module_name: None,
             src,
         })
     }
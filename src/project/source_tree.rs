@@ -1,8 +1,15 @@
-use super::{GleamExpect, Input, Module, ModuleOrigin};
+use super::{Cache, GleamExpect, Input, Module, ModuleOrigin};
+use crate::ast::UntypedModule;
+use crate::build::{
+    timings::{Stage, Timings},
+    Target,
+};
 use crate::error::Error;
 use crate::parser;
+use itertools::Itertools;
 use petgraph::{algo::Cycle, graph::NodeIndex, Direction};
 use std::collections::{HashMap, HashSet};
+use std::time::Instant;
 
 #[derive(Debug, Default)]
 pub struct SourceTree {
@@ -12,10 +19,11 @@ pub struct SourceTree {
 }
 
 impl SourceTree {
-    pub fn new(inputs: Vec<Input>) -> Result<Self, Error> {
+    pub fn new(inputs: Vec<Input>, cache: &Cache, timings: &mut Timings) -> Result<Self, Error> {
         let mut graph: Self = Default::default();
         for input in inputs.into_iter() {
-            graph.insert(input)?;
+            let parsed = cache.parsed(&input);
+            graph.insert(input, parsed, timings)?;
         }
         graph.calculate_dependencies()?;
         Ok(graph)
@@ -76,6 +84,16 @@ impl SourceTree {
     }
 
     fn calculate_dependencies(&mut self) -> Result<(), Error> {
+        // The directories modules were found in, reported when an import
+        // cannot be found
+        let searched: Vec<_> = self
+            .modules
+            .values()
+            .map(|m| m.source_base_path.clone())
+            .sorted()
+            .dedup()
+            .collect();
+
         for module in self.modules.values() {
             let module_name = module.module.name_string();
             let src = module.src.clone();
@@ -99,6 +117,7 @@ impl SourceTree {
                         .values()
                         .map(|m| m.module.name_string())
                         .collect(),
+                    searched: searched.clone(),
                     location: location.clone(),
                 })?;
 
@@ -125,40 +144,36 @@ impl SourceTree {
         Ok(())
     }
 
-    fn insert(&mut self, input: Input) -> Result<(), Error> {
+    fn insert(
+        &mut self,
+        input: Input,
+        parsed: Option<UntypedModule>,
+        timings: &mut Timings,
+    ) -> Result<(), Error> {
         // Determine the module name
-        let name = input
-            .path
-            .strip_prefix(input.source_base_path.clone())
-            .unwrap()
-            .parent()
-            .unwrap()
-            .join(input.path.file_stem().unwrap())
-            .to_str()
-            .unwrap()
-            .to_string()
-            .replace("\\", "/");
-
-        // Parse the source
-        let (cleaned, comments) = parser::strip_extra(&input.src);
-        let mut module = crate::grammar::ModuleParser::new()
-            .parse(&cleaned)
-            .map_err(|e| Error::Parse {
-                path: input.path.clone(),
-                src: input.src.clone(),
-                error: e.map_token(|crate::grammar::Token(a, b)| (a, b.to_string())),
-            })?;
-
-        // Annotate statements with their inline documentation
-        parser::attach_doc_comments(&mut module, &comments.doc_comments);
-        module.documentation = comments
-            .module_comments
-            .iter()
-            .map(|s| (*s).to_string())
-            .collect();
+        let name = match &input.module_name {
+            Some(name) => name.clone(),
+            None => module_name_from_path(&input),
+        };
+        if let Some(segment) = invalid_module_name_segment(&name) {
+            return Err(Error::InvalidModuleName {
+                path: input.path,
+                segment: segment.to_string(),
+                name,
+            });
+        }
 
-        // Store the name
-        module.name = name.split('/').map(|s| s.to_string()).collect();
+        // Parse the source, unless it was parsed by a previous compilation
+        // under the same name
+        let module = match parsed {
+            Some(module) if module.name_string() == name => module,
+            _ => {
+                let start = Instant::now();
+                let module = parse(&input, &name);
+                timings.record(&name, Stage::Parse, start.elapsed());
+                module?
+            }
+        };
 
         // Check to see if we already have a module with this name
         if let Some(Module { path, .. }) = self.indexes.get(&name).and_then(|i| self.modules.get(i))
@@ -186,3 +201,59 @@ impl SourceTree {
         Ok(())
     }
 }
+
+fn module_name_from_path(input: &Input) -> String {
+    input
+        .path
+        .strip_prefix(input.source_base_path.clone())
+        .unwrap()
+        .parent()
+        .unwrap()
+        .join(input.path.file_stem().unwrap())
+        .to_str()
+        .unwrap()
+        .to_string()
+        .replace("\\", "/")
+}
+
+/// The first segment of the module name that is not a valid Gleam module
+/// name, either because it is not a lowercase snake case identifier or
+/// because it is a reserved word.
+///
+fn invalid_module_name_segment(name: &str) -> Option<&str> {
+    use regex::Regex;
+    lazy_static! {
+        static ref RE: Regex =
+            Regex::new("^[a-z][_a-z0-9]*$").gleam_expect("invalid_module_name_segment() RE regex");
+    }
+
+    name.split('/')
+        .find(|segment| !RE.is_match(segment) || parser::is_gleam_reserved_word(segment))
+}
+
+fn parse(input: &Input, name: &str) -> Result<UntypedModule, Error> {
+    let (cleaned, comments) = parser::strip_extra(&input.src);
+    let mut module = crate::grammar::ModuleParser::new()
+        .parse(&cleaned)
+        .map_err(|e| Error::Parse {
+            path: input.path.clone(),
+            src: input.src.clone(),
+            error: e.map_token(|crate::grammar::Token(a, b)| (a, b.to_string())),
+        })?;
+
+    // Annotate statements with their inline documentation
+    parser::attach_doc_comments(&mut module, &comments.doc_comments);
+    module.documentation = comments
+        .module_comments
+        .iter()
+        .map(|s| (*s).to_string())
+        .collect();
+
+    // Leave out definitions for other targets
+    module.retain_target(Target::ACTIVE);
+
+    // Store the name
+    module.name = name.split('/').map(|s| s.to_string()).collect();
+
+    Ok(module)
+}
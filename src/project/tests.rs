@@ -19,12 +19,14 @@ fn compile_test() {
             input: vec![
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     source_base_path: PathBuf::from("/src"),
                     path: PathBuf::from("/src/one.gleam"),
                     src: "".to_string(),
                 },
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     source_base_path: PathBuf::from("/src"),
                     path: PathBuf::from("/src/two.gleam"),
                     src: "".to_string(),
@@ -45,6 +47,7 @@ fn compile_test() {
         Case {
             input: vec![Input {
                 origin: ModuleOrigin::Test,
+                module_name: None,
                 source_base_path: PathBuf::from("/test"),
                 path: PathBuf::from("/test/one.gleam"),
                 src: "".to_string(),
@@ -59,12 +62,14 @@ fn compile_test() {
             input: vec![
                 Input {
                     origin: ModuleOrigin::Test,
+                    module_name: None,
                     source_base_path: PathBuf::from("/test"),
                     path: PathBuf::from("/test/two.gleam"),
                     src: "".to_string(),
                 },
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     source_base_path: PathBuf::from("/src"),
                     path: PathBuf::from("/src/one.gleam"),
                     src: "import two".to_string(),
@@ -83,12 +88,14 @@ fn compile_test() {
             input: vec![
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/one.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "import two".to_string(),
                 },
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/two.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "".to_string(),
@@ -106,16 +113,109 @@ fn compile_test() {
             ]),
         },
 
+        // A type annotation can refer to a type in an imported module
         Case {
             input: vec![
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
+                    path: PathBuf::from("/src/one.gleam"),
+                    source_base_path: PathBuf::from("/src"),
+                    src: "import two\npub fn unbox(box: two.Box) -> Int {\n  let two.Box(x) = box\n  x\n}"
+                        .to_string(),
+                },
+                Input {
+                    origin: ModuleOrigin::Src,
+                    module_name: None,
+                    path: PathBuf::from("/src/two.gleam"),
+                    source_base_path: PathBuf::from("/src"),
+                    src: "pub type Box {\n  Box(Int)\n}".to_string(),
+                },
+            ],
+            expected: Ok(vec![
+                OutputFile {
+                    path: PathBuf::from("/gen/src/two.erl"),
+                    text: "-module(two).\n-compile(no_auto_import).\n\n\n".to_string(),
+                },
+                OutputFile {
+                    path: PathBuf::from("/gen/src/one.erl"),
+                    text: "-module(one).\n-compile(no_auto_import).\n\n-export([unbox/1]).\n\nunbox(Box) ->\n    {box, X} = Box,\n    X.\n"
+                        .to_string(),
+                },
+            ]),
+        },
+
+        // The module of a qualified type must be imported
+        Case {
+            input: vec![
+                Input {
+                    origin: ModuleOrigin::Src,
+                    module_name: None,
+                    path: PathBuf::from("/src/one.gleam"),
+                    source_base_path: PathBuf::from("/src"),
+                    src: "pub fn unbox(box: two.Box) { box }".to_string(),
+                },
+                Input {
+                    origin: ModuleOrigin::Src,
+                    module_name: None,
+                    path: PathBuf::from("/src/two.gleam"),
+                    source_base_path: PathBuf::from("/src"),
+                    src: "pub type Box {\n  Box(Int)\n}".to_string(),
+                },
+            ],
+            expected: Err(Error::Type {
+                path: PathBuf::from("/src/one.gleam"),
+                src: "pub fn unbox(box: two.Box) { box }".to_string(),
+                error: typ::Error::UnknownModule {
+                    location: crate::ast::SrcSpan { start: 18, end: 25 },
+                    name: "two".to_string(),
+                    imported_modules: vec![],
+                },
+            }),
+        },
+
+        // The qualified type must exist in the imported module
+        Case {
+            input: vec![
+                Input {
+                    origin: ModuleOrigin::Src,
+                    module_name: None,
+                    path: PathBuf::from("/src/one.gleam"),
+                    source_base_path: PathBuf::from("/src"),
+                    src: "import two\npub fn unbox(box: two.Boxx) { box }".to_string(),
+                },
+                Input {
+                    origin: ModuleOrigin::Src,
+                    module_name: None,
+                    path: PathBuf::from("/src/two.gleam"),
+                    source_base_path: PathBuf::from("/src"),
+                    src: "pub type Box {\n  Box(Int)\n}".to_string(),
+                },
+            ],
+            expected: Err(Error::Type {
+                path: PathBuf::from("/src/one.gleam"),
+                src: "import two\npub fn unbox(box: two.Boxx) { box }".to_string(),
+                error: typ::Error::UnknownModuleType {
+                    location: crate::ast::SrcSpan { start: 29, end: 37 },
+                    name: "Boxx".to_string(),
+                    module_name: vec!["two".to_string()],
+                    type_constructors: vec!["Box".to_string()],
+                },
+            }),
+        },
+
+        Case {
+            input: vec![
+                Input {
+                    origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/one.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "".to_string(),
                 },
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/two.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "import one".to_string(),
@@ -137,12 +237,14 @@ fn compile_test() {
             input: vec![
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/one.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "pub type Box { Box(Int) }".to_string(),
                 },
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/two.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "import one pub fn unbox(x) { let one.Box(i) = x i }".to_string(),
@@ -166,12 +268,14 @@ unbox(X) ->\n    {box, I} = X,\n    I.\n"
             input: vec![
                 Input {
                     origin: ModuleOrigin::Dependency,
+                    module_name: None,
                     path: PathBuf::from("/src/one.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "pub type Box { Box(Int) }".to_string(),
                 },
                 Input {
                     origin: ModuleOrigin::Dependency,
+                    module_name: None,
                     path: PathBuf::from("/src/two.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "import one pub fn box(x) { one.Box(x) }".to_string(),
@@ -194,6 +298,7 @@ box(X) ->\n    {box, X}.\n"
         Case {
             input: vec![Input {
                 origin: ModuleOrigin::Src,
+                module_name: None,
                 path: PathBuf::from("/src/one/two.gleam"),
                 source_base_path: PathBuf::from("/src"),
                 src: "pub type Box { Box }".to_string(),
@@ -208,12 +313,14 @@ box(X) ->\n    {box, X}.\n"
             input: vec![
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/one.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "pub type Box { Box }".to_string(),
                 },
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/two.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "import one pub fn box() { one.Box }".to_string(),
@@ -237,12 +344,14 @@ box() ->\n    box.\n"
             input: vec![
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/one.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "pub fn go() { 1 }".to_string(),
                 },
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/two.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "import one as thingy       pub fn call() { thingy.go() }".to_string(),
@@ -270,12 +379,14 @@ call() ->
             input: vec![
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/nested/one.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "pub type Box { Box(Int) }".to_string(),
                 },
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/two.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "import nested/one\npub fn go(x) { let one.Box(y) = x y }".to_string(),
@@ -299,12 +410,14 @@ call() ->
             input: vec![
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/nested/one.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "pub type Box { Box(Int) }".to_string(),
                 },
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/two.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "import nested/one as thingy\npub fn go(x) { let thingy.Box(y) = x y }"
@@ -329,12 +442,14 @@ call() ->
             input: vec![
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/nested/one.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "pub external type Thing pub fn go() { 1 }".to_string(),
                 },
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/two.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "import nested/one
@@ -367,12 +482,14 @@ call_thing() ->\n    thing:new().\n"
             input: vec![
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/one.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "".to_string(),
                 },
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/other/src/one.gleam"),
                     source_base_path: PathBuf::from("/other/src"),
                     src: "".to_string(),
@@ -385,16 +502,80 @@ call_thing() ->\n    thing:new().\n"
             }),
         },
 
+        Case {
+            input: vec![Input {
+                origin: ModuleOrigin::Src,
+                module_name: None,
+                path: PathBuf::from("/src/nested/one-two.gleam"),
+                source_base_path: PathBuf::from("/src"),
+                src: "".to_string(),
+            }],
+            expected: Err(Error::InvalidModuleName {
+                path: PathBuf::from("/src/nested/one-two.gleam"),
+                name: "nested/one-two".to_string(),
+                segment: "one-two".to_string(),
+            }),
+        },
+
+        Case {
+            input: vec![Input {
+                origin: ModuleOrigin::Src,
+                module_name: None,
+                path: PathBuf::from("/src/type.gleam"),
+                source_base_path: PathBuf::from("/src"),
+                src: "".to_string(),
+            }],
+            expected: Err(Error::InvalidModuleName {
+                path: PathBuf::from("/src/type.gleam"),
+                name: "type".to_string(),
+                segment: "type".to_string(),
+            }),
+        },
+
+        // An explicit module name is used in place of the invalid path
+        Case {
+            input: vec![
+                Input {
+                    origin: ModuleOrigin::Src,
+                    module_name: Some("nested/one_two".to_string()),
+                    path: PathBuf::from("/src/nested/one-two.gleam"),
+                    source_base_path: PathBuf::from("/src"),
+                    src: "pub fn go() { 1 }".to_string(),
+                },
+                Input {
+                    origin: ModuleOrigin::Src,
+                    module_name: None,
+                    path: PathBuf::from("/src/two.gleam"),
+                    source_base_path: PathBuf::from("/src"),
+                    src: "import nested/one_two pub fn go() { one_two.go() }".to_string(),
+                },
+            ],
+            expected: Ok(vec![
+                OutputFile {
+                    path: PathBuf::from("/gen/src/nested@one_two.erl"),
+                    text: "-module(nested@one_two).\n-compile(no_auto_import).\n\n-export([go/0]).\n\ngo() ->\n    1.\n"
+                        .to_string(),
+                },
+                OutputFile {
+                    path: PathBuf::from("/gen/src/two.erl"),
+                    text: "-module(two).\n-compile(no_auto_import).\n\n-export([go/0]).\n\ngo() ->\n    nested@one_two:go().\n"
+                        .to_string(),
+                },
+            ]),
+        },
+
         Case {
             input: vec![
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/one.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "pub type Point { Point(x: Int, y: Int) }".to_string(),
                 },
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/two.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "import one
@@ -426,12 +607,14 @@ x(P) ->\n    {point, X, _} = P,\n    X.\n"
             input: vec![
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/one.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "pub fn div(top x, bottom y) { x/y }".to_string(),
                 },
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/two.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "import one.{div}
@@ -459,12 +642,14 @@ run() ->\n    one:'div'(2, one:'div'(2, 4)).\n"
             input: vec![
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/one.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "pub type Empty { Empty }".to_string(),
                 },
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/two.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "import one
@@ -490,12 +675,14 @@ make() ->\n    empty.\n"
             input: vec![
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/one.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "pub fn id(x) { x } pub type Empty { Empty }".to_string(),
                 },
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/two.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "import one.{Empty, id} fn make() { id(Empty) }".to_string(),
@@ -523,12 +710,14 @@ make() ->
             input: vec![
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/one.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "pub fn id(x) { x } pub type Empty { Empty }".to_string(),
                 },
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/two.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "import one.{Empty as e, id as i} fn make() { i(e) }".to_string(),
@@ -555,12 +744,14 @@ make() ->\n    one:id(empty).\n"
             input: vec![
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/one.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "pub fn receive() { 1 }".to_string(),
                 },
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/two.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "import one fn funky() { one.receive }".to_string(),
@@ -588,12 +779,14 @@ funky() ->
             input: vec![
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/one.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "pub fn receive() { 1 }".to_string(),
                 },
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/two.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "import one.{receive} fn funky() { receive }".to_string(),
@@ -621,12 +814,14 @@ funky() ->
             input: vec![
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/one.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "pub fn receive(x) { x }".to_string(),
                 },
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/two.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "import one fn funky() { one.receive(1) }".to_string(),
@@ -655,12 +850,14 @@ funky() ->
             input: vec![
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/one.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "pub type Person { Person(name: String, age: Int) }".to_string(),
                 },
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/two.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "import one.{Person}
@@ -698,12 +895,14 @@ get_name(Person) ->
             input: vec![
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/one.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "pub type Person { Person(name: String, age: Int) }".to_string(),
                 },
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/two.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "import one
@@ -731,12 +930,14 @@ type Two = one.Person"
             input: vec![
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/one.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "pub type Person { Person(name: String, age: Int) }".to_string(),
                 },
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/two.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "import one.{Person}
@@ -765,12 +966,14 @@ type Two = Person"
             input: vec![
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/one.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "pub type T(x) { C(a: Int, b: Int) }".to_string(),
                 },
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/two.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "import one.{C}
@@ -799,12 +1002,14 @@ fn main() { C }"
             input: vec![
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/one.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "pub fn id(x) { x } pub type T { X(x: Int) }".to_string(),
                 },
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/two.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "import one.{X as e, id as i} fn make() { i(e) }".to_string(),
@@ -837,12 +1042,14 @@ make() ->\n    one:id(fun(A) -> {x, A} end).\n"
             input: vec![
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/one.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "pub type T(x) { C(a: Int, b: Int) }".to_string(),
                 },
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/two.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "import one
@@ -872,12 +1079,14 @@ fn main() { one.C }"
             input: vec![
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/one.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "pub opaque type T(x) { C(a: Int, b: Int) }".to_string(),
                 },
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/two.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "import one
@@ -906,12 +1115,14 @@ fn main() { one.C }"
             input: vec![
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/one.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "pub opaque type T { C(a: Int, b: Int) }".to_string(),
                 },
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/two.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "import one
@@ -944,12 +1155,14 @@ fn test(t: one.T) { t.a }"
             input: vec![
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/one.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "pub const const_string = \"hello!\"".to_string(),
                 },
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/two.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "pub const cool_number = 4
@@ -957,6 +1170,7 @@ pub const cool_number2 = 3.14".to_string(),
                 },
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/three.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "import one
@@ -985,11 +1199,96 @@ test() ->\n    <<\"hello!\"/utf8>>,\n    3.14,\n    4.\n"
             ]),
         },
 
+        // Annotated constants can be used qualified from other modules
+        Case {
+            input: vec![
+                Input {
+                    origin: ModuleOrigin::Src,
+                    module_name: None,
+                    path: PathBuf::from("/src/one.gleam"),
+                    source_base_path: PathBuf::from("/src"),
+                    src: "pub type Point { Point(x: Int, y: Int) }
+pub const origin: Point = Point(0, 0)
+pub const timeout: Int = 5_000
+pub const names: List(String) = [\"a\", \"b\"]"
+                        .to_string(),
+                },
+                Input {
+                    origin: ModuleOrigin::Src,
+                    module_name: None,
+                    path: PathBuf::from("/src/two.gleam"),
+                    source_base_path: PathBuf::from("/src"),
+                    src: "import one
+pub fn main() -> tuple(one.Point, Int, List(String)) {
+  tuple(one.origin, one.timeout, one.names)
+}"
+                    .to_string(),
+                },
+            ],
+            expected: Ok(vec![
+                OutputFile {
+                    path: PathBuf::from("/gen/src/one_Point.hrl"),
+                    text: "-record(point, {x, y}).\n".to_string(),
+                },
+                OutputFile {
+                    path: PathBuf::from("/gen/src/one.erl"),
+                    text: "-module(one).\n-compile(no_auto_import).\n\n\n".to_string(),
+                },
+                OutputFile {
+                    path: PathBuf::from("/gen/src/two.erl"),
+                    text: "-module(two).
+-compile(no_auto_import).
+
+-export([main/0]).
+
+main() ->
+    {{point, 0, 0}, 5000, [<<\"a\"/utf8>>, <<\"b\"/utf8>>]}.
+"
+                    .to_string(),
+                },
+            ]),
+        },
+
+        // The value of an annotated constant must have the annotated type
+        Case {
+            input: vec![
+                Input {
+                    origin: ModuleOrigin::Src,
+                    module_name: None,
+                    path: PathBuf::from("/src/one.gleam"),
+                    source_base_path: PathBuf::from("/src"),
+                    src: "pub type Point { Point(x: Int, y: Int) }".to_string(),
+                },
+                Input {
+                    origin: ModuleOrigin::Src,
+                    module_name: None,
+                    path: PathBuf::from("/src/two.gleam"),
+                    source_base_path: PathBuf::from("/src"),
+                    src: "import one\npub const origin: one.Point = tuple(0, 0)".to_string(),
+                },
+            ],
+            expected: Err(Error::Type {
+                path: PathBuf::from("/src/two.gleam"),
+                src: "import one\npub const origin: one.Point = tuple(0, 0)".to_string(),
+                error: typ::Error::CouldNotUnify {
+                    location: crate::ast::SrcSpan { start: 41, end: 52 },
+                    expected: Arc::new(typ::Type::App {
+                        public: true,
+                        module: vec!["one".to_string()],
+                        name: "Point".to_string(),
+                        args: vec![],
+                    }),
+                    given: typ::tuple(vec![typ::int(), typ::int()]),
+                },
+            }),
+        },
+
         // Can use module constants in case guards
         Case {
             input: vec![
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/one.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "pub const string_value = \"constant value\"
@@ -999,6 +1298,7 @@ pub const int_value = 42
                 },
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/two.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "import one.{string_value, float_value, int_value}
@@ -1039,12 +1339,14 @@ main(Arg1, Arg2, Arg3) ->
             input: vec![
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/one.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "pub type One(a) { One(a) }".to_string(),
                 },
                 Input {
                     origin: ModuleOrigin::Src,
+                    module_name: None,
                     path: PathBuf::from("/src/two.gleam"),
                     source_base_path: PathBuf::from("/src"),
                     src: "import one.{One} pub type Two(b) { Two(thing: One(Int)) }"
@@ -1069,10 +1371,1052 @@ main(Arg1, Arg2, Arg3) ->
                 },
             ]),
         },
-    ];
 
-    for Case { input, expected } in cases.into_iter() {
-        let actual = analysed(input).map(|analysed| erl::generate_erlang(analysed.as_slice()));
-        assert_eq!(expected, actual);
-    }
+        // An alias can be used to import two modules with the same name
+        Case {
+            input: vec![
+                Input {
+                    origin: ModuleOrigin::Src,
+                    module_name: None,
+                    path: PathBuf::from("/src/one.gleam"),
+                    source_base_path: PathBuf::from("/src"),
+                    src: "pub fn go() { 1 }".to_string(),
+                },
+                Input {
+                    origin: ModuleOrigin::Src,
+                    module_name: None,
+                    path: PathBuf::from("/src/nested/one.gleam"),
+                    source_base_path: PathBuf::from("/src"),
+                    src: "pub fn go() { 2 }".to_string(),
+                },
+                Input {
+                    origin: ModuleOrigin::Src,
+                    module_name: None,
+                    path: PathBuf::from("/src/two.gleam"),
+                    source_base_path: PathBuf::from("/src"),
+                    src: "import one\nimport nested/one as other\npub fn go() { one.go() + other.go() }"
+                        .to_string(),
+                },
+            ],
+            expected: Ok(vec![
+                OutputFile {
+                    path: PathBuf::from("/gen/src/nested@one.erl"),
+                    text: "-module(nested@one).\n-compile(no_auto_import).\n\n-export([go/0]).\n
+go() ->
+    2.\n"
+                        .to_string(),
+                },
+                OutputFile {
+                    path: PathBuf::from("/gen/src/one.erl"),
+                    text: "-module(one).\n-compile(no_auto_import).\n\n-export([go/0]).\n
+go() ->
+    1.\n"
+                        .to_string(),
+                },
+                OutputFile {
+                    path: PathBuf::from("/gen/src/two.erl"),
+                    text: "-module(two).\n-compile(no_auto_import).\n\n-export([go/0]).\n
+go() ->
+    one:go() + nested@one:go().\n"
+                        .to_string(),
+                },
+            ]),
+        },
+
+        // Two imports cannot have the same name in the importing module
+        Case {
+            input: vec![
+                Input {
+                    origin: ModuleOrigin::Src,
+                    module_name: None,
+                    path: PathBuf::from("/src/one.gleam"),
+                    source_base_path: PathBuf::from("/src"),
+                    src: "pub fn go() { 1 }".to_string(),
+                },
+                Input {
+                    origin: ModuleOrigin::Src,
+                    module_name: None,
+                    path: PathBuf::from("/src/two.gleam"),
+                    source_base_path: PathBuf::from("/src"),
+                    src: "pub fn go() { 2 }".to_string(),
+                },
+                Input {
+                    origin: ModuleOrigin::Src,
+                    module_name: None,
+                    path: PathBuf::from("/src/three.gleam"),
+                    source_base_path: PathBuf::from("/src"),
+                    src: "import one\nimport two as one".to_string(),
+                },
+            ],
+            expected: Err(Error::Type {
+                path: PathBuf::from("/src/three.gleam"),
+                src: "import one\nimport two as one".to_string(),
+                error: crate::typ::Error::DuplicateImport {
+                    location: crate::ast::SrcSpan { start: 18, end: 22 },
+                    previous_location: crate::ast::SrcSpan { start: 7, end: 10 },
+                    name: "one".to_string(),
+                },
+            }),
+        },
+
+        // Names can be imported unqualified, optionally with a new local name
+        Case {
+            input: vec![
+                Input {
+                    origin: ModuleOrigin::Src,
+                    module_name: None,
+                    path: PathBuf::from("/src/one.gleam"),
+                    source_base_path: PathBuf::from("/src"),
+                    src: "pub fn go() { 1 } pub fn stop() { 2 }".to_string(),
+                },
+                Input {
+                    origin: ModuleOrigin::Src,
+                    module_name: None,
+                    path: PathBuf::from("/src/two.gleam"),
+                    source_base_path: PathBuf::from("/src"),
+                    src: "import one.{go, stop as halt} pub fn main() { go() + halt() }"
+                        .to_string(),
+                },
+            ],
+            expected: Ok(vec![
+                OutputFile {
+                    path: PathBuf::from("/gen/src/one.erl"),
+                    text: "-module(one).\n-compile(no_auto_import).\n\n-export([go/0, stop/0]).\n
+go() ->
+    1.\n
+stop() ->
+    2.\n"
+                        .to_string(),
+                },
+                OutputFile {
+                    path: PathBuf::from("/gen/src/two.erl"),
+                    text: "-module(two).\n-compile(no_auto_import).\n\n-export([main/0]).\n
+main() ->
+    one:go() + one:stop().\n"
+                        .to_string(),
+                },
+            ]),
+        },
+
+        // A value imported unqualified with a new name does not clash with a
+        // definition using its original name
+        Case {
+            input: vec![
+                Input {
+                    origin: ModuleOrigin::Src,
+                    module_name: None,
+                    path: PathBuf::from("/src/one.gleam"),
+                    source_base_path: PathBuf::from("/src"),
+                    src: "pub fn go() { 1 }".to_string(),
+                },
+                Input {
+                    origin: ModuleOrigin::Src,
+                    module_name: None,
+                    path: PathBuf::from("/src/two.gleam"),
+                    source_base_path: PathBuf::from("/src"),
+                    src: "import one.{go as one_go}\npub fn go() { one_go() }".to_string(),
+                },
+            ],
+            expected: Ok(vec![
+                OutputFile {
+                    path: PathBuf::from("/gen/src/one.erl"),
+                    text: "-module(one).\n-compile(no_auto_import).\n\n-export([go/0]).\n
+go() ->
+    1.\n"
+                        .to_string(),
+                },
+                OutputFile {
+                    path: PathBuf::from("/gen/src/two.erl"),
+                    text: "-module(two).\n-compile(no_auto_import).\n\n-export([go/0]).\n
+go() ->
+    one:go().\n"
+                        .to_string(),
+                },
+            ]),
+        },
+
+        // A value cannot be imported unqualified from two modules
+        Case {
+            input: vec![
+                Input {
+                    origin: ModuleOrigin::Src,
+                    module_name: None,
+                    path: PathBuf::from("/src/one.gleam"),
+                    source_base_path: PathBuf::from("/src"),
+                    src: "pub fn go() { 1 }".to_string(),
+                },
+                Input {
+                    origin: ModuleOrigin::Src,
+                    module_name: None,
+                    path: PathBuf::from("/src/two.gleam"),
+                    source_base_path: PathBuf::from("/src"),
+                    src: "pub fn go() { 2 }".to_string(),
+                },
+                Input {
+                    origin: ModuleOrigin::Src,
+                    module_name: None,
+                    path: PathBuf::from("/src/three.gleam"),
+                    source_base_path: PathBuf::from("/src"),
+                    src: "import one.{go}\nimport two.{go}\npub fn main() { go() }".to_string(),
+                },
+            ],
+            expected: Err(Error::Type {
+                path: PathBuf::from("/src/three.gleam"),
+                src: "import one.{go}\nimport two.{go}\npub fn main() { go() }".to_string(),
+                error: crate::typ::Error::AmbiguousUnqualifiedImport {
+                    location: crate::ast::SrcSpan { start: 28, end: 30 },
+                    previous_location: crate::ast::SrcSpan { start: 12, end: 14 },
+                    name: "go".to_string(),
+                    module: "two".to_string(),
+                    previous_module: "one".to_string(),
+                },
+            }),
+        },
+
+        // The other module's value can still be used qualified
+        Case {
+            input: vec![
+                Input {
+                    origin: ModuleOrigin::Src,
+                    module_name: None,
+                    path: PathBuf::from("/src/one.gleam"),
+                    source_base_path: PathBuf::from("/src"),
+                    src: "pub fn go() { 1 }".to_string(),
+                },
+                Input {
+                    origin: ModuleOrigin::Src,
+                    module_name: None,
+                    path: PathBuf::from("/src/two.gleam"),
+                    source_base_path: PathBuf::from("/src"),
+                    src: "pub fn go() { 2 }".to_string(),
+                },
+                Input {
+                    origin: ModuleOrigin::Src,
+                    module_name: None,
+                    path: PathBuf::from("/src/three.gleam"),
+                    source_base_path: PathBuf::from("/src"),
+                    src: "import one.{go}\nimport two\npub fn main() { go() + two.go() }".to_string(),
+                },
+            ],
+            expected: Ok(vec![
+                OutputFile {
+                    path: PathBuf::from("/gen/src/two.erl"),
+                    text: "-module(two).\n-compile(no_auto_import).\n\n-export([go/0]).\n
+go() ->
+    2.\n"
+                        .to_string(),
+                },
+                OutputFile {
+                    path: PathBuf::from("/gen/src/one.erl"),
+                    text: "-module(one).\n-compile(no_auto_import).\n\n-export([go/0]).\n
+go() ->
+    1.\n"
+                        .to_string(),
+                },
+                OutputFile {
+                    path: PathBuf::from("/gen/src/three.erl"),
+                    text: "-module(three).\n-compile(no_auto_import).\n\n-export([main/0]).\n
+main() ->
+    one:go() + two:go().\n"
+                        .to_string(),
+                },
+            ]),
+        },
+
+        // Definitions for other targets are left out before type checking
+        Case {
+            input: vec![Input {
+                origin: ModuleOrigin::Src,
+                module_name: None,
+                path: PathBuf::from("/src/one.gleam"),
+                source_base_path: PathBuf::from("/src"),
+                src: "@target(javascript)
+import other
+
+@target(erlang)
+pub external fn print(String) -> Nil = \"io\" \"put_chars\"
+
+@target(javascript)
+pub external fn print(String) -> Nil = \"console\" \"log\"
+
+pub fn go() { print(\"Hi\") }"
+                    .to_string(),
+            }],
+            expected: Ok(vec![OutputFile {
+                path: PathBuf::from("/gen/src/one.erl"),
+                text: "-module(one).\n-compile(no_auto_import).\n\n-export([print/1, go/0]).\n
+print(A) ->
+    io:put_chars(A).\n
+go() ->
+    io:put_chars(<<\"Hi\"/utf8>>).\n"
+                    .to_string(),
+            }]),
+        },
+
+        // A name imported unqualified cannot also be defined in the module
+        Case {
+            input: vec![
+                Input {
+                    origin: ModuleOrigin::Src,
+                    module_name: None,
+                    path: PathBuf::from("/src/one.gleam"),
+                    source_base_path: PathBuf::from("/src"),
+                    src: "pub fn go() { 1 }".to_string(),
+                },
+                Input {
+                    origin: ModuleOrigin::Src,
+                    module_name: None,
+                    path: PathBuf::from("/src/two.gleam"),
+                    source_base_path: PathBuf::from("/src"),
+                    src: "import one.{go}\npub fn go() { 2 }".to_string(),
+                },
+            ],
+            expected: Err(Error::Type {
+                path: PathBuf::from("/src/two.gleam"),
+                src: "import one.{go}\npub fn go() { 2 }".to_string(),
+                error: crate::typ::Error::ShadowedUnqualifiedImport {
+                    location: crate::ast::SrcSpan { start: 16, end: 27 },
+                    import_location: crate::ast::SrcSpan { start: 12, end: 14 },
+                    name: "go".to_string(),
+                },
+            }),
+        },
+    ];
+
+    for Case { input, expected } in cases.into_iter() {
+        let actual = analysed(input)
+            .map(|analysed| erl::generate_erlang(analysed.as_slice(), &Default::default()));
+        assert_eq!(expected, actual);
+    }
+}
+
+#[test]
+fn cached_compilation_test() {
+    let inputs = |one_src: &str| {
+        vec![
+            Input {
+                origin: ModuleOrigin::Src,
+                module_name: None,
+                path: PathBuf::from("/src/one.gleam"),
+                source_base_path: PathBuf::from("/src"),
+                src: one_src.to_string(),
+            },
+            Input {
+                origin: ModuleOrigin::Src,
+                module_name: None,
+                path: PathBuf::from("/src/two.gleam"),
+                source_base_path: PathBuf::from("/src"),
+                src: "import one pub fn go() { one.go() }".to_string(),
+            },
+            Input {
+                origin: ModuleOrigin::Src,
+                module_name: None,
+                path: PathBuf::from("/src/three.gleam"),
+                source_base_path: PathBuf::from("/src"),
+                src: "pub fn go() { 3 }".to_string(),
+            },
+        ]
+    };
+    let compile = |one_src, cache: &mut Cache| {
+        let (analysed, mut compiled) =
+            analysed_with_cache(inputs(one_src), "", cache, &mut Timings::default())
+                .expect("Compilation failed");
+        compiled.sort();
+        let output = erl::generate_erlang(analysed.as_slice(), &Default::default());
+        (compiled, output)
+    };
+    let mut cache = Cache::default();
+
+    // All modules are compiled the first time
+    let (compiled, output) = compile("pub fn go() { 1 }", &mut cache);
+    assert_eq!(vec!["one", "three", "two"], compiled);
+
+    // Nothing is compiled if nothing has changed
+    let (compiled, cached_output) = compile("pub fn go() { 1 }", &mut cache);
+    assert!(compiled.is_empty());
+    assert_eq!(output, cached_output);
+
+    // A changed module is compiled along with the modules that depend on it
+    let (compiled, output) = compile("pub fn go() { 1.0 }", &mut cache);
+    assert_eq!(vec!["one", "two"], compiled);
+    let expected = analysed(inputs("pub fn go() { 1.0 }"))
+        .map(|analysed| erl::generate_erlang(analysed.as_slice(), &Default::default()))
+        .expect("Compilation failed");
+    assert_eq!(expected, output);
+}
+
+#[test]
+fn todo_summary_test() {
+    let inputs = vec![
+        Input {
+            origin: ModuleOrigin::Src,
+            module_name: None,
+            path: PathBuf::from("/src/one.gleam"),
+            source_base_path: PathBuf::from("/src"),
+            src: "pub fn go() {\n  todo\n}".to_string(),
+        },
+        Input {
+            origin: ModuleOrigin::Test,
+            module_name: None,
+            path: PathBuf::from("/test/two.gleam"),
+            source_base_path: PathBuf::from("/test"),
+            src: "pub fn go() { 1 }\npub fn stop() { todo }".to_string(),
+        },
+    ];
+    let modules = analysed(inputs).expect("Compilation failed");
+    assert_eq!(
+        Some(
+            "2 todos remaining:
+  /src/one.gleam:2:3
+  /test/two.gleam:2:17
+"
+            .to_string()
+        ),
+        warning::todo_summary(modules.as_slice())
+    );
+
+    let inputs = vec![Input {
+        origin: ModuleOrigin::Src,
+        module_name: None,
+        path: PathBuf::from("/src/one.gleam"),
+        source_base_path: PathBuf::from("/src"),
+        src: "pub fn go() { 1 }".to_string(),
+    }];
+    let modules = analysed(inputs).expect("Compilation failed");
+    assert_eq!(None, warning::todo_summary(modules.as_slice()));
+}
+
+#[test]
+fn deny_todo_test() {
+    let input = |src: &str| Input {
+        origin: ModuleOrigin::Src,
+        module_name: None,
+        path: PathBuf::from("/src/one.gleam"),
+        source_base_path: PathBuf::from("/src"),
+        src: src.to_string(),
+    };
+
+    // A todo is only a warning normally, but fails a build that denies them
+    let modules = analysed(vec![input("pub fn go() {\n  todo\n}")]).expect("Compilation failed");
+    assert_eq!(
+        Err(Error::DeniedTodo {
+            locations: vec!["/src/one.gleam:2:3".to_string()]
+        }),
+        warning::deny_todos(modules.as_slice())
+    );
+
+    // Allowing the todo warning does not allow the todo itself
+    let modules = analysed(vec![input("@allow(todo)\npub fn go() {\n  todo\n}")])
+        .expect("Compilation failed");
+    assert!(modules[0].warnings.is_empty());
+    assert_eq!(
+        Err(Error::DeniedTodo {
+            locations: vec!["/src/one.gleam:3:3".to_string()]
+        }),
+        warning::deny_todos(modules.as_slice())
+    );
+
+    let modules = analysed(vec![input("pub fn go() { 1 }")]).expect("Compilation failed");
+    assert_eq!(Ok(()), warning::deny_todos(modules.as_slice()));
+}
+
+#[test]
+fn warning_json_test() {
+    let inputs = vec![Input {
+        origin: ModuleOrigin::Src,
+        module_name: None,
+        path: PathBuf::from("/src/one.gleam"),
+        source_base_path: PathBuf::from("/src"),
+        src: "pub fn go() { 1 }\n\ntype Unused =\n  Int\n".to_string(),
+    }];
+    let modules = analysed(inputs).expect("Compilation failed");
+    assert_eq!(
+        vec![
+            r#"{"code":"unused-type","file":"/src/one.gleam","location":{"end":{"column":6,"line":4},"start":{"column":1,"line":3}},"message":"Unused type","severity":"warning"}"#
+        ],
+        modules
+            .iter()
+            .flat_map(|module| module.warnings.iter())
+            .map(|warning| warning.to_json())
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn deprecated_import_test() {
+    let input = |name: &str, src: &str| Input {
+        origin: ModuleOrigin::Src,
+        module_name: None,
+        path: PathBuf::from(format!("/src/{}.gleam", name)),
+        source_base_path: PathBuf::from("/src"),
+        src: src.to_string(),
+    };
+    let inputs = vec![
+        input(
+            "one",
+            "@deprecated(\"Use two.show instead\")\npub fn show(x) { x }",
+        ),
+        input(
+            "two",
+            "import one.{show}\npub fn main() { one.show(1) show(2) }",
+        ),
+    ];
+    let modules = analysed(inputs).expect("Compilation failed");
+    let warnings: Vec<_> = modules
+        .iter()
+        .flat_map(|module| module.warnings.iter())
+        .map(|warning| match warning {
+            Warning::Type { warning, .. } => warning.clone(),
+        })
+        .collect();
+
+    // Uses in other modules are warned about whether or not they are qualified
+    assert_eq!(
+        vec![
+            typ::Warning::Deprecated {
+                location: crate::ast::SrcSpan { start: 37, end: 42 },
+                name: "one.show".to_string(),
+                message: "Use two.show instead".to_string(),
+            },
+            typ::Warning::Deprecated {
+                location: crate::ast::SrcSpan { start: 46, end: 50 },
+                name: "show".to_string(),
+                message: "Use two.show instead".to_string(),
+            },
+        ],
+        warnings
+    );
+}
+
+#[test]
+fn internal_test() {
+    let input = |origin, package: &str, name: &str, src: &str| Input {
+        origin,
+        module_name: None,
+        path: PathBuf::from(format!("/{}/src/{}.gleam", package, name)),
+        source_base_path: PathBuf::from(format!("/{}/src", package)),
+        src: src.to_string(),
+    };
+    let inputs = vec![
+        input(
+            ModuleOrigin::Dependency,
+            "lib",
+            "lib",
+            "@internal\npub fn helper(x) { x }\n@internal\npub type Secret { Secret }",
+        ),
+        input(
+            ModuleOrigin::Dependency,
+            "lib",
+            "lib/public",
+            "import lib.{Secret}\npub fn show(x) { lib.helper(x) }\nfn secret() -> lib.Secret { Secret }",
+        ),
+        // The project's own modules belong to the project's package, whatever
+        // the directory they are in is called
+        input(
+            ModuleOrigin::Src,
+            "lib",
+            "app",
+            "import lib.{Secret}\npub fn main() { lib.helper(Secret) }\nfn secret() -> lib.Secret { Secret }",
+        ),
+    ];
+    let (modules, _) = analysed_with_cache(
+        inputs,
+        "app",
+        &mut Cache::default(),
+        &mut Timings::default(),
+    )
+    .expect("Compilation failed");
+    let warnings = |name: &str| -> Vec<_> {
+        modules
+            .iter()
+            .filter(|module| module.name.join("/") == name)
+            .flat_map(|module| module.warnings.iter())
+            .map(|warning| match warning {
+                Warning::Type { warning, .. } => warning.clone(),
+            })
+            .collect()
+    };
+
+    // Internal items can be used by other modules of the same package
+    assert_eq!(Vec::<typ::Warning>::new(), warnings("lib/public"));
+
+    // Other packages are warned about every use
+    assert_eq!(
+        vec![
+            typ::Warning::Internal {
+                location: crate::ast::SrcSpan { start: 12, end: 18 },
+                name: "lib.Secret".to_string(),
+                package: "lib".to_string(),
+            },
+            typ::Warning::Internal {
+                location: crate::ast::SrcSpan { start: 72, end: 82 },
+                name: "lib.Secret".to_string(),
+                package: "lib".to_string(),
+            },
+            typ::Warning::Internal {
+                location: crate::ast::SrcSpan { start: 39, end: 46 },
+                name: "lib.helper".to_string(),
+                package: "lib".to_string(),
+            },
+        ],
+        warnings("app")
+    );
+}
+
+#[test]
+fn shadowed_imported_type_test() {
+    let warnings = |src: &str| -> Vec<_> {
+        let input = |name: &str, src: &str| Input {
+            origin: ModuleOrigin::Src,
+            module_name: None,
+            path: PathBuf::from(format!("/src/{}.gleam", name)),
+            source_base_path: PathBuf::from("/src"),
+            src: src.to_string(),
+        };
+        let inputs = vec![
+            input("one", "pub type Box(a) { Box(a) }\npub type Id =\n  Int"),
+            input("two", src),
+        ];
+        analysed(inputs)
+            .expect("Compilation failed")
+            .iter()
+            .flat_map(|module| module.warnings.iter())
+            .map(|warning| match warning {
+                Warning::Type { warning, .. } => warning.clone(),
+            })
+            .collect()
+    };
+
+    // Types of the prelude are always in scope unqualified
+    assert_eq!(
+        vec![
+            typ::Warning::ShadowedImportedType {
+                location: crate::ast::SrcSpan { start: 0, end: 30 },
+                name: "Result".to_string(),
+                imported: "Result".to_string(),
+            },
+            typ::Warning::ShadowedImportedType {
+                location: crate::ast::SrcSpan { start: 31, end: 45 },
+                name: "Bool".to_string(),
+                imported: "Bool".to_string(),
+            },
+        ],
+        warnings("pub type Result(a) =\n  List(a)\npub type Bool { Yes No }")
+    );
+
+    // Types of imported modules that can only be used qualified are not
+    // ambiguous
+    assert_eq!(
+        Vec::<typ::Warning>::new(),
+        warnings("import one as other\npub type Box(a) =\n  List(a)\npub type Id { Id }")
+    );
+
+    // Types with names not used by the imported modules are not warned about
+    assert_eq!(
+        Vec::<typ::Warning>::new(),
+        warnings("import one\npub type Item(a) =\n  one.Box(a)\npub type Name { Name }")
+    );
+}
+
+#[test]
+fn allow_attribute_test() {
+    let src = "@allow(todo)
+pub fn one() { todo }
+
+pub fn two() { todo }
+
+@allow(float-equality, todo)
+pub fn three(x) {
+  case x == 2.0 { _ -> todo }
+}
+
+pub fn four() { 1.0 == 2.0 }";
+    let inputs = vec![Input {
+        origin: ModuleOrigin::Src,
+        module_name: None,
+        path: PathBuf::from("/src/one.gleam"),
+        source_base_path: PathBuf::from("/src"),
+        src: src.to_string(),
+    }];
+    let modules = analysed(inputs).expect("Compilation failed");
+    let warnings: Vec<_> = modules[0]
+        .warnings
+        .iter()
+        .map(|warning| match warning {
+            Warning::Type { warning, .. } => (warning.code(), &src[warning.location().start..]),
+        })
+        .map(|(code, rest)| (code, rest.lines().next().unwrap()))
+        .collect();
+
+    // Only the warnings of the statements without the attribute remain
+    assert_eq!(
+        vec![("todo", "todo }"), ("float-equality", "1.0 == 2.0 }")],
+        warnings
+    );
+}
+
+#[test]
+fn module_name_config_test() {
+    let dir = crate::test_support::TempDir::new("module-name-test");
+    let root = dir.path();
+    let src = root.join("src");
+    std::fs::create_dir_all(&src).unwrap();
+    std::fs::write(src.join("legacy-utils.gleam"), "pub fn go() { 1 }").unwrap();
+
+    // The invalid path is reported
+    std::fs::write(root.join("gleam.toml"), "name = \"module_name_test\"\n").unwrap();
+    let result = read_and_analyse(root).map(|(_, modules)| modules.len());
+    assert!(
+        matches!(&result, Err(Error::InvalidModuleName { segment, .. }) if segment == "legacy-utils"),
+        "{:?}",
+        result
+    );
+
+    // The module is given the name in the config
+    std::fs::write(
+        root.join("gleam.toml"),
+        "name = \"module_name_test\"\n\n[modules]\n\"src/legacy-utils.gleam\" = \"legacy_utils\"\n",
+    )
+    .unwrap();
+    let (_, modules) = read_and_analyse(root).expect("Compilation failed");
+    assert_eq!(
+        vec![vec!["legacy_utils".to_string()]],
+        modules
+            .into_iter()
+            .map(|module| module.name)
+            .collect::<Vec<_>>()
+    );
+
+    // Dependency files with invalid module names are skipped
+    let dep_src = root.join("_build/default/lib/some_dep/src");
+    std::fs::create_dir_all(&dep_src).unwrap();
+    std::fs::write(dep_src.join("not-a-module.gleam"), "pub fn go() { 1 }").unwrap();
+    let result = read_and_analyse(root).map(|(_, modules)| modules.len());
+    assert_eq!(Ok(1), result);
+}
+
+#[test]
+fn unknown_import_test() {
+    let inputs = vec![
+        Input {
+            origin: ModuleOrigin::Src,
+            module_name: None,
+            path: PathBuf::from("/src/one.gleam"),
+            source_base_path: PathBuf::from("/src"),
+            src: "import some/missing".to_string(),
+        },
+        Input {
+            origin: ModuleOrigin::Test,
+            module_name: None,
+            path: PathBuf::from("/test/one_test.gleam"),
+            source_base_path: PathBuf::from("/test"),
+            src: "".to_string(),
+        },
+    ];
+    let error = analysed(inputs).expect_err("Compilation should fail");
+    assert!(
+        matches!(
+            &error,
+            Error::UnknownImport { import, searched, .. }
+                if import == "some/missing"
+                    && searched == &[PathBuf::from("/src"), PathBuf::from("/test")]
+        ),
+        "{:?}",
+        error
+    );
+
+    let mut buffer = termcolor::Buffer::no_color();
+    error.pretty(&mut buffer);
+    let message = String::from_utf8(buffer.into_inner()).unwrap();
+    assert!(
+        message.contains(
+            "The module `one` is trying to import the module `some/missing`,
+but it cannot be found in any of these directories:
+
+    /src
+    /test
+"
+        ),
+        "{}",
+        message
+    );
+}
+
+#[test]
+fn operator_type_mismatch_message_test() {
+    let inputs = vec![Input {
+        origin: ModuleOrigin::Src,
+        module_name: None,
+        path: PathBuf::from("/src/one.gleam"),
+        source_base_path: PathBuf::from("/src"),
+        src: "pub fn go() { 1 + \"two\" }".to_string(),
+    }];
+    let error = analysed(inputs).expect_err("Compilation should fail");
+
+    let mut buffer = termcolor::Buffer::no_color();
+    error.pretty(&mut buffer);
+    let message = String::from_utf8(buffer.into_inner()).unwrap();
+    assert!(
+        message.contains(
+            "1 │ pub fn go() { 1 + \"two\" }
+  │                 ^
+"
+        ),
+        "{}",
+        message
+    );
+    assert!(
+        message.contains(
+            "The `+` operator expects operands of type:
+
+    Int
+
+Left operand type:
+
+    Int
+
+Right operand type:
+
+    String
+"
+        ),
+        "{}",
+        message
+    );
+}
+
+#[test]
+fn windows_path_test() {
+    // Module names use `/` whichever separator the path uses
+    let inputs = vec![
+        Input {
+            origin: ModuleOrigin::Src,
+            module_name: None,
+            path: PathBuf::from("/src/one\\two.gleam"),
+            source_base_path: PathBuf::from("/src"),
+            src: "pub fn go() { 1 }\r\n".to_string(),
+        },
+        Input {
+            origin: ModuleOrigin::Src,
+            module_name: None,
+            path: PathBuf::from("/src/three.gleam"),
+            source_base_path: PathBuf::from("/src"),
+            src: "import one/two\r\npub fn go() { two.go() }\r\n".to_string(),
+        },
+    ];
+    let modules = analysed(inputs).expect("Compilation failed");
+    assert_eq!(
+        vec![
+            vec!["one".to_string(), "two".to_string()],
+            vec!["three".to_string()]
+        ],
+        modules
+            .into_iter()
+            .map(|module| module.name)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn ambiguous_import_message_test() {
+    let inputs = vec![
+        Input {
+            origin: ModuleOrigin::Src,
+            module_name: None,
+            path: PathBuf::from("/src/one.gleam"),
+            source_base_path: PathBuf::from("/src"),
+            src: "pub fn go() { 1 }".to_string(),
+        },
+        Input {
+            origin: ModuleOrigin::Src,
+            module_name: None,
+            path: PathBuf::from("/src/other/two.gleam"),
+            source_base_path: PathBuf::from("/src"),
+            src: "pub fn go() { 2 }".to_string(),
+        },
+        Input {
+            origin: ModuleOrigin::Src,
+            module_name: None,
+            path: PathBuf::from("/src/three.gleam"),
+            source_base_path: PathBuf::from("/src"),
+            src: "import one.{go}\nimport other/two.{go}\npub fn main() { go() }".to_string(),
+        },
+    ];
+    let error = analysed(inputs).expect_err("Compilation should fail");
+
+    let mut buffer = termcolor::Buffer::no_color();
+    error.pretty(&mut buffer);
+    let message = String::from_utf8(buffer.into_inner()).unwrap();
+    assert!(
+        message.contains("error: Ambiguous import of `go`"),
+        "{}",
+        message
+    );
+    assert!(
+        message.contains("`go` is imported from both `one` and `other/two`"),
+        "{}",
+        message
+    );
+}
+
+#[test]
+fn same_named_types_message_test() {
+    let inputs = vec![
+        Input {
+            origin: ModuleOrigin::Src,
+            module_name: None,
+            path: PathBuf::from("/src/one.gleam"),
+            source_base_path: PathBuf::from("/src"),
+            src: "pub type Result { Result }".to_string(),
+        },
+        Input {
+            origin: ModuleOrigin::Src,
+            module_name: None,
+            path: PathBuf::from("/src/two.gleam"),
+            source_base_path: PathBuf::from("/src"),
+            src: "import one\npub fn go() -> one.Result { Ok(1) }".to_string(),
+        },
+    ];
+    let error = analysed(inputs).expect_err("Compilation should fail");
+
+    let mut buffer = termcolor::Buffer::no_color();
+    error.pretty(&mut buffer);
+    let message = String::from_utf8(buffer.into_inner()).unwrap();
+    assert!(
+        message.contains(
+            "Expected type:
+
+    one.Result
+
+Found type:
+
+    gleam.Result(Int, a)
+"
+        ),
+        "{}",
+        message
+    );
+}
+
+#[test]
+fn block_comment_message_test() {
+    let inputs = vec![Input {
+        origin: ModuleOrigin::Src,
+        module_name: None,
+        path: PathBuf::from("/src/one.gleam"),
+        source_base_path: PathBuf::from("/src"),
+        src: "pub fn go() {\n  /* { */ 1\n}\n".to_string(),
+    }];
+    let error = analysed(inputs).expect_err("Compilation should fail");
+
+    let mut buffer = termcolor::Buffer::no_color();
+    error.pretty(&mut buffer);
+    let message = String::from_utf8(buffer.into_inner()).unwrap();
+    assert!(
+        message.contains(
+            "  ┌─ /src/one.gleam:2:3
+  │
+2 │   /* { */ 1
+  │   ^^ Block comments are not supported
+
+Gleam only has line comments."
+        ),
+        "{}",
+        message
+    );
+}
+
+#[test]
+fn call_arity_message_test() {
+    fn error_message(src: &str) -> String {
+        let inputs = vec![Input {
+            origin: ModuleOrigin::Src,
+            module_name: None,
+            path: PathBuf::from("/src/one.gleam"),
+            source_base_path: PathBuf::from("/src"),
+            src: src.to_string(),
+        }];
+        let error = analysed(inputs).expect_err("Compilation should fail");
+        let mut buffer = termcolor::Buffer::no_color();
+        error.pretty(&mut buffer);
+        String::from_utf8(buffer.into_inner()).unwrap()
+    }
+
+    // Too few arguments
+    let message = error_message("fn id(x) { x }\n\npub fn go() {\n  id()\n}\n");
+    assert!(
+        message.contains(
+            "4 │   id()
+  │     ^^ expected 1 argument, got 0"
+        ),
+        "{}",
+        message
+    );
+
+    // Too many arguments
+    let message = error_message("fn add(x, y) { x + y }\n\npub fn go() {\n  add(1, 2, 3)\n}\n");
+    assert!(
+        message.contains(
+            "4 │   add(1, 2, 3)
+  │      ^^^^^^^^^ expected 2 arguments, got 3"
+        ),
+        "{}",
+        message
+    );
+}
+
+#[test]
+fn lookalike_whitespace_message_test() {
+    let inputs = vec![Input {
+        origin: ModuleOrigin::Src,
+        module_name: None,
+        path: PathBuf::from("/src/one.gleam"),
+        source_base_path: PathBuf::from("/src"),
+        src: "pub fn main() {\n  let x = 1\n \u{a0}x\n}\n".to_string(),
+    }];
+    let error = analysed(inputs).expect_err("Compilation should fail");
+    let mut buffer = termcolor::Buffer::no_color();
+    error.pretty(&mut buffer);
+    let message = String::from_utf8(buffer.into_inner()).unwrap();
+    assert!(
+        message.contains(
+            "┌─ /src/one.gleam:3:2
+  │
+3 │  \u{a0}x
+  │  ^ Unexpected whitespace
+
+This is a non-breaking space (U+00A0), which looks like a space but is not one.
+Only spaces, tabs, and newlines can be used as whitespace."
+        ),
+        "{}",
+        message
+    );
+}
+
+#[test]
+fn tuple_arity_message_test() {
+    let inputs = vec![Input {
+        origin: ModuleOrigin::Src,
+        module_name: None,
+        path: PathBuf::from("/src/one.gleam"),
+        source_base_path: PathBuf::from("/src"),
+        src: "pub fn go() {\n  let tuple(a, b, c) = tuple(1, 2.0)\n  a\n}\n".to_string(),
+    }];
+    let error = analysed(inputs).expect_err("Compilation should fail");
+
+    let mut buffer = termcolor::Buffer::no_color();
+    error.pretty(&mut buffer);
+    let message = String::from_utf8(buffer.into_inner()).unwrap();
+    assert!(
+        message.contains(
+            "  │       ^^^^^^^^^^^^^^ expected 2 elements, got 3
+
+This pattern has 3 elements but the value it matches is a tuple of 2 elements:
+
+    tuple(Int, Float)
+"
+        ),
+        "{}",
+        message
+    );
 }
@@ -0,0 +1,241 @@
+use crate::{
+    ast::{Statement, TypedModule},
+    build::{self, project_root::ProjectRoot, Origin},
+    config::PackageConfig,
+    error::{Error, InvalidEntrypointReason},
+};
+use std::path::PathBuf;
+use std::process::Command;
+
+pub fn command(root_string: String, arguments: Vec<String>) -> Result<(), Error> {
+    let root_path = PathBuf::from(root_string);
+    let root = ProjectRoot::new(root_path.clone());
+    let config = root.root_config()?;
+    let (module, function) = entrypoint(&config);
+
+    // Build project
+    let package_name = config.name.clone();
+    let packages = build::main(config, root_path)?;
+    let entrypoint_module = packages.get(&package_name).and_then(|package| {
+        package
+            .modules
+            .iter()
+            .find(|m| m.origin == Origin::Src && m.name == module)
+    });
+    check_entrypoint(entrypoint_module.map(|m| &m.ast), &module, &function)?;
+
+    let mut command = erl_command(&root, &module, &function, arguments)?;
+    crate::cli::print_running(&format!("{}.{}", module, function));
+
+    // Run the program
+    tracing::trace!("Running OS process {:?}", command);
+    let status = command.status().map_err(|e| Error::ShellCommand {
+        command: "erl".to_string(),
+        err: Some(e.kind()),
+    })?;
+
+    match status.code() {
+        Some(0) => Ok(()),
+
+        // The program chose its exit status, so it is passed on rather than
+        // being reported as an error
+        Some(code) => std::process::exit(code),
+
+        None => Err(Error::ShellCommand {
+            command: "erl".to_string(),
+            err: None,
+        }),
+    }
+}
+
+/// The module and function to run, which is the `main` function of the module
+/// with the same name as the project unless configured otherwise.
+///
+fn entrypoint(config: &PackageConfig) -> (String, String) {
+    let module = config
+        .run
+        .module
+        .clone()
+        .unwrap_or_else(|| config.name.clone());
+    (module, config.run.function.clone())
+}
+
+/// Check that the entrypoint is a public function that takes no arguments, so
+/// that a mistake is reported before Erlang is started rather than as a crash.
+///
+fn check_entrypoint(
+    module: Option<&TypedModule>,
+    module_name: &str,
+    function: &str,
+) -> Result<(), Error> {
+    let invalid = |reason| Error::InvalidEntrypoint {
+        module: module_name.to_string(),
+        function: function.to_string(),
+        reason,
+    };
+    let module = module.ok_or_else(|| invalid(InvalidEntrypointReason::ModuleNotFound))?;
+    let (public, arity) = module
+        .statements
+        .iter()
+        .find_map(|statement| match statement {
+            Statement::Fn {
+                name, args, public, ..
+            } if name == function => Some((*public, args.len())),
+            Statement::ExternalFn {
+                name, args, public, ..
+            } if name == function => Some((*public, args.len())),
+            _ => None,
+        })
+        .ok_or_else(|| invalid(InvalidEntrypointReason::FunctionNotFound))?;
+
+    if !public {
+        return Err(invalid(InvalidEntrypointReason::Private));
+    }
+    if arity > 0 {
+        return Err(invalid(InvalidEntrypointReason::TakesArguments(arity)));
+    }
+    Ok(())
+}
+
+fn erl_command(
+    root: &ProjectRoot,
+    module: &str,
+    function: &str,
+    arguments: Vec<String>,
+) -> Result<Command, Error> {
+    let mut command = Command::new("erl");
+
+    // Specify locations of .beam files
+    for entry in crate::fs::read_dir(root.default_build_lib_path())?.filter_map(Result::ok) {
+        command.arg("-pa");
+        command.arg(entry.path().join("ebin"));
+    }
+
+    command.arg("-noshell");
+    command.arg("-eval");
+    command.arg(eval_expression(module, function));
+
+    // The arguments can be read by the program with `init:get_plain_arguments/0`
+    if !arguments.is_empty() {
+        command.arg("-extra");
+        command.args(arguments);
+    }
+
+    Ok(command)
+}
+
+/// The Erlang expression that calls the function and then stops the runtime.
+/// A crash in the function stops the runtime with a non-zero exit status.
+///
+fn eval_expression(module: &str, function: &str) -> String {
+    format!(
+        "'{}':'{}'(), erlang:halt(0).",
+        module.replace("/", "@"),
+        function
+    )
+}
+
+#[test]
+fn eval_expression_test() {
+    assert_eq!(
+        "'my_app':'main'(), erlang:halt(0).",
+        eval_expression("my_app", "main")
+    );
+    assert_eq!(
+        "'my_app@cli':'start'(), erlang:halt(0).",
+        eval_expression("my_app/cli", "start")
+    );
+}
+
+#[test]
+fn check_entrypoint_test() {
+    let src = "
+pub fn main() { 1 }
+pub fn start(args) { args }
+fn private() { 1 }
+pub external fn nl() -> Nil = \"io\" \"nl\"
+";
+    let mut ast = crate::grammar::ModuleParser::new()
+        .parse(src)
+        .expect("syntax error");
+    ast.name = vec!["my_app".to_string()];
+    let module = crate::typ::infer_module(
+        &mut 0,
+        ast,
+        "",
+        &std::collections::HashMap::new(),
+        &mut vec![],
+    )
+    .expect("should successfully infer");
+
+    fn reason(module: Option<&TypedModule>, function: &str) -> Option<InvalidEntrypointReason> {
+        match check_entrypoint(module, "my_app", function) {
+            Ok(()) => None,
+            Err(Error::InvalidEntrypoint { reason, .. }) => Some(reason),
+            Err(error) => panic!("unexpected error {:?}", error),
+        }
+    }
+
+    assert_eq!(None, reason(Some(&module), "main"));
+    assert_eq!(None, reason(Some(&module), "nl"));
+    assert_eq!(
+        Some(InvalidEntrypointReason::TakesArguments(1)),
+        reason(Some(&module), "start")
+    );
+    assert_eq!(
+        Some(InvalidEntrypointReason::Private),
+        reason(Some(&module), "private")
+    );
+    assert_eq!(
+        Some(InvalidEntrypointReason::FunctionNotFound),
+        reason(Some(&module), "stop")
+    );
+    assert_eq!(
+        Some(InvalidEntrypointReason::ModuleNotFound),
+        reason(None, "main")
+    );
+}
+
+#[test]
+fn hello_world_test() {
+    // Running the program needs an Erlang installation
+    if Command::new("erl").arg("-version").output().is_err() {
+        return;
+    }
+
+    let dir = crate::test_support::TempDir::new("run-test");
+    let root_path = dir.path();
+    let src = root_path.join("src");
+    std::fs::create_dir_all(&src).unwrap();
+    std::fs::write(root_path.join("gleam.toml"), "name = \"hello\"\n").unwrap();
+    std::fs::write(
+        src.join("hello.gleam"),
+        "external fn print(String) -> Nil = \"io\" \"put_chars\"
+
+pub fn main() {
+  print(\"Hello, world!\\n\")
+}
+",
+    )
+    .unwrap();
+
+    let root = ProjectRoot::new(root_path.to_path_buf());
+    let config = root.root_config().unwrap();
+    let (module, function) = entrypoint(&config);
+    let packages = build::main(config, root_path.to_path_buf()).expect("should build");
+    let hello = packages["hello"]
+        .modules
+        .iter()
+        .find(|m| m.name == "hello")
+        .unwrap();
+    assert_eq!(
+        Ok(()),
+        check_entrypoint(Some(&hello.ast), &module, &function)
+    );
+    let output = erl_command(&root, &module, &function, vec![])
+        .unwrap()
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!("Hello, world!\n", String::from_utf8_lossy(&output.stdout));
+}
@@ -0,0 +1,211 @@
+//! Helpers for tests that compile Gleam modules held in memory to Erlang and
+//! run the generated code, so that code generation can be tested end to end.
+//!
+//! Running the code needs `erlc` and `erl` to be installed, which can be
+//! checked with `erlang_is_installed`.
+
+use crate::{
+    config::ErlangConfig,
+    error::Error,
+    fs::OutputFile,
+    project::{self, Input, ModuleOrigin},
+};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Used to give each temporary directory a name of its own, as tests are run
+/// in parallel.
+static TEMP_DIRS: AtomicUsize = AtomicUsize::new(0);
+
+/// An empty temporary directory for a test to write files to, which is
+/// deleted when this is dropped so that it is cleaned up even if the test
+/// panics.
+#[derive(Debug)]
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!(
+            "gleam-{}-{}-{}",
+            name,
+            std::process::id(),
+            TEMP_DIRS.fetch_add(1, Ordering::SeqCst)
+        ));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).expect("Unable to create temporary directory");
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+/// Gleam modules that have been compiled to Erlang in a temporary directory,
+/// which is deleted when this is dropped.
+#[derive(Debug)]
+pub struct Compiled {
+    dir: TempDir,
+    files: Vec<OutputFile>,
+}
+
+/// The result of running a function of the compiled modules.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Output {
+    /// Whether the function returned rather than crashing.
+    pub success: bool,
+    /// What the function printed.
+    pub stdout: String,
+    /// The value returned by the function, formatted as an Erlang term.
+    pub value: String,
+}
+
+/// Compile the given modules, each of which is a module name such as
+/// `"one/two"` and its source code, to Erlang.
+///
+pub fn compile(modules: &[(&str, &str)]) -> Result<Compiled, Error> {
+    let dir = TempDir::new("test-support");
+    let src = dir.path().join("src");
+    let inputs = modules
+        .iter()
+        .map(|(name, source)| Input {
+            origin: ModuleOrigin::Src,
+            module_name: None,
+            path: src.join(format!("{}.gleam", name)),
+            source_base_path: src.clone(),
+            src: source.to_string(),
+        })
+        .collect();
+
+    let analysed = project::analysed(inputs)?;
+    let files = crate::erl::generate_erlang(&analysed, &ErlangConfig::default());
+    let compiled = Compiled { dir, files };
+    crate::fs::write_outputs(&compiled.files)?;
+    Ok(compiled)
+}
+
+/// Whether the Erlang compiler and runtime are available to run the compiled
+/// code.
+///
+pub fn erlang_is_installed() -> bool {
+    let installed = |command| Command::new(command).arg("-version").output().is_ok();
+    installed("erl") && installed("erlc")
+}
+
+impl Compiled {
+    /// The generated Erlang of the module with the given Gleam name.
+    ///
+    pub fn erlang(&self, module: &str) -> Option<&str> {
+        let file_name = format!("{}.erl", module.replace("/", "@"));
+        self.files
+            .iter()
+            .find(|file| file.path.file_name() == Some(file_name.as_ref()))
+            .map(|file| file.text.as_str())
+    }
+
+    /// Compile the generated Erlang and call the function of the module,
+    /// which must take no arguments, capturing what it prints and returns.
+    ///
+    pub fn run(&self, module: &str, function: &str) -> Result<Output, Error> {
+        let ebin = self.dir.path().join("ebin");
+        crate::fs::mkdir(&ebin)?;
+
+        let mut erlc = Command::new("erlc");
+        erlc.arg("-o").arg(&ebin);
+        for file in self.files.iter() {
+            if file.path.extension() == Some("erl".as_ref()) {
+                erlc.arg(&file.path);
+            }
+        }
+        let status = erlc.status().map_err(|e| Error::ShellCommand {
+            command: "erlc".to_string(),
+            err: Some(e.kind()),
+        })?;
+        if !status.success() {
+            return Err(Error::ShellCommand {
+                command: "erlc".to_string(),
+                err: None,
+            });
+        }
+
+        // The returned value is printed to stderr to keep it apart from what
+        // the function printed
+        let output = Command::new("erl")
+            .arg("-pa")
+            .arg(&ebin)
+            .arg("-noshell")
+            .arg("-eval")
+            .arg(eval_expression(module, function))
+            .output()
+            .map_err(|e| Error::ShellCommand {
+                command: "erl".to_string(),
+                err: Some(e.kind()),
+            })?;
+
+        Ok(Output {
+            success: output.status.success(),
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            value: String::from_utf8_lossy(&output.stderr).to_string(),
+        })
+    }
+}
+
+fn eval_expression(module: &str, function: &str) -> String {
+    format!(
+        "Value = '{}':'{}'(), io:format(standard_error, \"~tp\", [Value]), erlang:halt(0).",
+        module.replace("/", "@"),
+        function
+    )
+}
+
+#[test]
+fn eval_expression_test() {
+    assert_eq!(
+        "Value = 'one@two':'main'(), io:format(standard_error, \"~tp\", [Value]), erlang:halt(0).",
+        eval_expression("one/two", "main")
+    );
+}
+
+#[test]
+fn compile_and_run_test() {
+    let compiled = compile(&[
+        ("greeting", "pub fn greet() { \"Hello, Joe!\" }"),
+        (
+            "main",
+            "import greeting
+external fn print(String) -> Nil = \"io\" \"put_chars\"
+pub fn main() {
+  print(\"Greeting...\\n\")
+  greeting.greet()
+}",
+        ),
+    ])
+    .expect("should compile");
+    assert!(compiled
+        .erlang("main")
+        .unwrap()
+        .contains("greeting:greet()."));
+    assert!(compiled.erlang("missing").is_none());
+
+    // Running the code needs an Erlang installation
+    if !erlang_is_installed() {
+        return;
+    }
+    assert_eq!(
+        Output {
+            success: true,
+            stdout: "Greeting...\n".to_string(),
+            value: "<<\"Hello, Joe!\">>".to_string(),
+        },
+        compiled.run("main", "main").unwrap()
+    );
+}
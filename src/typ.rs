@@ -3,9 +3,12 @@ mod error;
 mod expr;
 mod fields;
 mod hydrator;
+pub mod interface;
+mod lint;
 mod pattern;
 mod prelude;
 pub mod pretty;
+mod symbol;
 #[cfg(test)]
 mod test_helpers;
 #[cfg(test)]
@@ -14,21 +17,23 @@ mod tests;
 pub use environment::*;
 pub use error::{Error, Warning};
 pub use expr::*;
+pub use lint::{non_tail_recursion, redundant_annotations, unnecessary_results};
 pub use prelude::*;
 
 use crate::{
     ast::{
-        self, ArgNames, BitStringSegment, BitStringSegmentOption, CallArg, Constant, Pattern,
-        RecordConstructor, SrcSpan, Statement, TypeAst, TypedConstant, TypedExpr, TypedModule,
-        TypedPattern, TypedPatternBitStringSegment, TypedRecordUpdateArg, TypedStatement,
-        UnqualifiedImport, UntypedModule, UntypedMultiPattern, UntypedPattern,
+        self, ArgNames, BitStringSegment, BitStringSegmentOption, CallArg, Constant, Derive,
+        Pattern, RecordConstructor, SrcSpan, Statement, TypeAst, TypedConstant, TypedExpr,
+        TypedModule, TypedPattern, TypedPatternBitStringSegment, TypedRecordUpdateArg,
+        TypedStatement, UnqualifiedImport, UntypedModule, UntypedMultiPattern, UntypedPattern,
         UntypedRecordUpdateArg, UntypedStatement,
     },
-    bit_string::BinaryTypeSpecifier,
+    bit_string::{BinaryTypeSpecifier, IntLiteral},
     build::Origin,
     error::GleamExpect,
 };
 
+use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
@@ -36,12 +41,13 @@ use std::sync::Arc;
 use error::*;
 use fields::FieldMap;
 use hydrator::Hydrator;
+use symbol::{Symbol, SymbolTable};
 
 pub trait HasType {
     fn typ(&self) -> Arc<Type>;
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Type {
     App {
         public: bool,
@@ -65,6 +71,14 @@ pub enum Type {
 }
 
 impl Type {
+    pub fn is_bool(&self) -> bool {
+        matches!(self, Self::App { name, module, .. } if "Bool" == name && module.is_empty())
+    }
+
+    pub fn is_float(&self) -> bool {
+        matches!(self, Self::App { name, module, .. } if "Float" == name && module.is_empty())
+    }
+
     pub fn is_result(&self) -> bool {
         matches!(self, Self::App { name, module, .. } if "Result" == name && module.is_empty())
     }
@@ -166,6 +180,26 @@ impl Type {
         }
     }
 
+    /// Whether the type is fully known, containing no type variables that are
+    /// unbound or generic.
+    ///
+    pub fn is_concrete(&self) -> bool {
+        match self {
+            Self::App { args, .. } => args.iter().all(|t| t.is_concrete()),
+
+            Self::Tuple { elems, .. } => elems.iter().all(|t| t.is_concrete()),
+
+            Self::Fn { retrn, args, .. } => {
+                retrn.is_concrete() && args.iter().all(|t| t.is_concrete())
+            }
+
+            Self::Var { typ, .. } => match &*typ.borrow() {
+                TypeVar::Link { typ, .. } => typ.is_concrete(),
+                TypeVar::Unbound { .. } | TypeVar::Generic { .. } => false,
+            },
+        }
+    }
+
     pub fn fn_arity(&self) -> Option<usize> {
         match self {
             Self::Fn { args, .. } => Some(args.len()),
@@ -183,21 +217,21 @@ pub fn collapse_links(t: Arc<Type>) -> Arc<Type> {
     t
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct AccessorsMap {
     pub public: bool,
     pub typ: Arc<Type>,
     pub accessors: HashMap<String, RecordAccessor>,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct RecordAccessor {
     pub index: u64,
     pub label: String,
     pub typ: Arc<Type>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ValueConstructorVariant {
     /// A locally defined variable or function parameter
     LocalVariable,
@@ -219,13 +253,16 @@ pub enum ValueConstructorVariant {
     Record {
         name: String,
         field_map: Option<FieldMap>,
+        constructors_count: usize,
     },
 }
 
 impl ValueConstructorVariant {
     fn to_module_value_constructor(&self) -> ModuleValueConstructor {
         match self {
-            Self::Record { name, field_map } => ModuleValueConstructor::Record {
+            Self::Record {
+                name, field_map, ..
+            } => ModuleValueConstructor::Record {
                 name: name.clone(),
                 arity: field_map.as_ref().map_or(0, |fm| fm.arity),
             },
@@ -246,9 +283,12 @@ pub enum ModuleValueConstructor {
     Constant { literal: TypedConstant },
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Module {
     pub name: Vec<String>,
+    /// The name of the package the module belongs to, outside of which its
+    /// internal values and types are not to be used.
+    pub package: String,
     pub types: HashMap<String, TypeConstructor>,
     pub values: HashMap<String, ValueConstructor>,
     pub accessors: HashMap<String, AccessorsMap>,
@@ -256,7 +296,10 @@ pub struct Module {
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum PatternConstructor {
-    Record { name: String },
+    Record {
+        name: String,
+        constructors_count: usize,
+    },
 }
 
 pub trait Typer {
@@ -271,7 +314,7 @@ pub trait Typer {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum TypeVar {
     Unbound { id: usize, level: usize },
     Link { typ: Arc<Type> },
@@ -284,21 +327,29 @@ impl TypeVar {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TypeConstructor {
     pub public: bool,
     pub origin: SrcSpan,
     pub module: Vec<String>,
     pub parameters: Vec<Arc<Type>>,
     pub typ: Arc<Type>,
+    /// The message of the type's `@deprecated(...)` attribute.
+    pub deprecation: Option<String>,
+    /// Whether the type has an `@internal` attribute.
+    pub internal: bool,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ValueConstructor {
     pub public: bool,
     pub origin: SrcSpan,
     pub variant: ValueConstructorVariant,
     pub typ: Arc<Type>,
+    /// The message of the value's `@deprecated(...)` attribute.
+    pub deprecation: Option<String>,
+    /// Whether the value has an `@internal` attribute.
+    pub internal: bool,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -325,20 +376,28 @@ impl ValueConstructor {
 pub fn infer_module(
     uid: &mut usize,
     module: UntypedModule,
+    package: &str,
     modules: &HashMap<String, (Origin, Module)>,
     warnings: &mut Vec<Warning>,
 ) -> Result<TypedModule, Error> {
-    let mut environment = Environment::new(uid, module.name.as_slice(), modules, warnings);
+    let mut environment = Environment::new(uid, module.name.as_slice(), package, modules, warnings);
     let module_name = &module.name;
     let mut type_names = HashMap::with_capacity(module.statements.len());
     let mut value_names = HashMap::with_capacity(module.statements.len());
     let mut hydrators = HashMap::with_capacity(module.statements.len());
+    let mut import_names = HashMap::new();
+    let mut unqualified_import_names = HashMap::new();
 
     // Register any modules, types, and values being imported
     // We process imports first so that anything imported can be referenced
     // anywhere in the module.
     for s in module.statements.iter() {
-        register_import(s, &mut environment)?;
+        register_import(
+            s,
+            &mut import_names,
+            &mut unqualified_import_names,
+            &mut environment,
+        )?;
     }
 
     // Register types so they can be used in constructors and functions
@@ -346,6 +405,7 @@ pub fn infer_module(
     for s in module.statements.iter() {
         register_types(
             s,
+            module.deprecation_of(s.location().start),
             module_name,
             &mut hydrators,
             &mut type_names,
@@ -353,10 +413,15 @@ pub fn infer_module(
         )?;
     }
 
+    // Types with similar names can be given derived functions with the same
+    // name, which is reported with both types rather than as a redefinition.
+    assert_unique_derived_names(module.statements.as_slice())?;
+
     // Register values so they can be used in functions earlier in the module.
     for s in module.statements.iter() {
         register_values(
             s,
+            module.deprecation_of(s.location().start),
             module_name,
             &mut hydrators,
             &mut value_names,
@@ -364,6 +429,9 @@ pub fn infer_module(
         )?;
     }
 
+    // Ensure no values defined in the module shadow an unqualified import
+    assert_no_shadowed_imports(&value_names, &unqualified_import_names)?;
+
     // Infer the types of each statement in the module
     let mut statements = Vec::with_capacity(module.statements.len());
     for statement in module.statements {
@@ -371,15 +439,23 @@ pub fn infer_module(
         statements.push(statement);
     }
 
+    // Ensure every custom type has a constructor that can be used without
+    // first having a value of the type
+    assert_inhabited_types(statements.as_slice(), module_name)?;
+
     // Generalise functions now that the entire module has been inferred
     let statements = statements
         .into_iter()
         .map(|s| generalise_statement(s, module_name, &mut environment))
-        .collect::<Result<_, _>>()?;
+        .collect::<Result<Vec<_>, _>>()?;
 
     // Generate warnings for unused items
     environment.convert_unused_to_warnings();
 
+    environment
+        .warnings
+        .extend(lint::infinite_recursion(statements.as_slice(), module_name));
+
     // Remove private and imported types and values to create the public interface
     environment
         .module_types
@@ -389,6 +465,16 @@ pub fn infer_module(
         .accessors
         .retain(|_, accessors| accessors.public);
 
+    // Mark the values and types defined by `@internal` statements
+    for attribute in module.internal_attributes.iter() {
+        if let Some(statement) = statements
+            .iter()
+            .find(|s| s.location().start == attribute.statement_start)
+        {
+            mark_internal(statement, &mut environment);
+        }
+    }
+
     // Ensure no exported values have private types in their type signature
     for (_, value) in environment.module_values.iter() {
         if let Some(leaked) = value.typ.find_private_type() {
@@ -410,15 +496,96 @@ pub fn infer_module(
         documentation: module.documentation,
         name: module.name.clone(),
         statements,
+        target_attributes: module.target_attributes,
+        deprecated_attributes: module.deprecated_attributes,
+        internal_attributes: module.internal_attributes,
+        allow_attributes: module.allow_attributes,
         type_info: Module {
             name: module.name,
-            types,
-            values,
+            package: package.to_string(),
+            types: types
+                .into_iter()
+                .map(|(name, typ)| (name.to_string(), typ))
+                .collect(),
+            values: values
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value))
+                .collect(),
             accessors,
         },
     })
 }
 
+/// Mark the public values and types defined by the statement as internal.
+///
+fn mark_internal(statement: &TypedStatement, environment: &mut Environment<'_, '_>) {
+    let (types, values): (Vec<&str>, Vec<&str>) = match statement {
+        Statement::Fn { name, .. }
+        | Statement::ExternalFn { name, .. }
+        | Statement::ModuleConstant { name, .. } => (vec![], vec![name]),
+
+        Statement::ExternalType { name, .. } | Statement::TypeAlias { alias: name, .. } => {
+            (vec![name], vec![])
+        }
+
+        Statement::CustomType {
+            name,
+            constructors,
+            derives,
+            ..
+        } => (
+            vec![name],
+            constructors
+                .iter()
+                .map(|constructor| constructor.name.as_str())
+                .chain(derives.iter().map(|derived| derived.name.as_str()))
+                .collect(),
+        ),
+
+        Statement::Import { .. } => (vec![], vec![]),
+    };
+    for name in types {
+        if let Some(typ) = environment.module_types.get_mut(name) {
+            typ.internal = true;
+        }
+    }
+    for name in values {
+        if let Some(value) = environment.module_values.get_mut(name) {
+            value.internal = true;
+        }
+    }
+}
+
+/// Derived functions are named after their type in snake case, so types whose
+/// names differ only in case, such as `HTTPBox` and `HttpBox`, would be given
+/// functions with the same name.
+///
+fn assert_unique_derived_names(statements: &[UntypedStatement]) -> Result<(), Error> {
+    let mut derived_names: HashMap<&str, (&str, &SrcSpan)> = HashMap::new();
+    for statement in statements {
+        if let Statement::CustomType {
+            name: type_name,
+            derives,
+            ..
+        } = statement
+        {
+            for derived in derives {
+                let previous = derived_names.insert(&derived.name, (type_name, &derived.location));
+                if let Some((previous_type_name, previous_location)) = previous {
+                    return Err(Error::DuplicateDerivedName {
+                        location: derived.location.clone(),
+                        previous_location: previous_location.clone(),
+                        name: derived.name.clone(),
+                        type_name: type_name.clone(),
+                        previous_type_name: previous_type_name.to_string(),
+                    });
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
 fn assert_unique_value_name<'a>(
     names: &mut HashMap<&'a str, &'a SrcSpan>,
     name: &'a str,
@@ -434,6 +601,149 @@ fn assert_unique_value_name<'a>(
     }
 }
 
+fn assert_unique_import_name<'a>(
+    names: &mut HashMap<String, &'a SrcSpan>,
+    name: &str,
+    location: &'a SrcSpan,
+) -> Result<(), Error> {
+    match names.insert(name.to_string(), location) {
+        Some(previous_location) => Err(Error::DuplicateImport {
+            name: name.to_string(),
+            previous_location: previous_location.clone(),
+            location: location.clone(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// A value cannot be imported unqualified from two different modules, as it
+/// is not clear which of them a reference to the name means.
+///
+fn assert_unambiguous_import(
+    unqualified_import_names: &HashMap<&str, (&SrcSpan, &[String])>,
+    name: &str,
+    location: &SrcSpan,
+    module: &[String],
+) -> Result<(), Error> {
+    match unqualified_import_names.get(name) {
+        Some((previous_location, previous_module)) if *previous_module != module => {
+            Err(Error::AmbiguousUnqualifiedImport {
+                location: location.clone(),
+                previous_location: (*previous_location).clone(),
+                name: name.to_string(),
+                module: module.join("/"),
+                previous_module: previous_module.join("/"),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+fn assert_no_shadowed_imports(
+    value_names: &HashMap<&str, &SrcSpan>,
+    unqualified_import_names: &HashMap<&str, (&SrcSpan, &[String])>,
+) -> Result<(), Error> {
+    match value_names
+        .iter()
+        .filter_map(|(name, location)| {
+            let (import_location, _) = unqualified_import_names.get(name)?;
+            Some((name, location, import_location))
+        })
+        .min_by_key(|(_, location, _)| location.start)
+    {
+        Some((name, location, import_location)) => Err(Error::ShadowedUnqualifiedImport {
+            name: name.to_string(),
+            location: (*location).clone(),
+            import_location: (*import_location).clone(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Check that a value of each custom type defined in the module can be
+/// constructed in a finite number of steps, so that recursive types such as
+/// lists and trees are accepted but a type such as `type T { T(T) }` is
+/// rejected.
+///
+/// A type can be constructed if one of its constructors has only fields that
+/// can be constructed. Type variables, functions, and types from other
+/// modules are assumed to be constructable. The arguments of a type are not
+/// considered, so a `List(T)` field is always constructable as `[]`.
+///
+fn assert_inhabited_types(
+    statements: &[TypedStatement],
+    module_name: &[String],
+) -> Result<(), Error> {
+    let custom_types: Vec<_> = statements
+        .iter()
+        .filter_map(|statement| match statement {
+            Statement::CustomType {
+                location,
+                name,
+                constructors,
+                ..
+            } => Some((location, name.as_str(), constructors)),
+            _ => None,
+        })
+        .collect();
+    let local: HashSet<&str> = custom_types.iter().map(|(_, name, _)| *name).collect();
+    let mut inhabited = HashSet::with_capacity(custom_types.len());
+
+    // Types are marked as inhabited until no more can be found, allowing
+    // for mutually recursive types
+    loop {
+        let found: Vec<_> = custom_types
+            .iter()
+            .filter(|(_, name, constructors)| {
+                !inhabited.contains(name)
+                    && constructors.iter().any(|constructor| {
+                        match collapse_links(constructor.typ.clone()).as_ref() {
+                            Type::Fn { args, .. } => args.iter().all(|arg| {
+                                is_inhabited(arg.clone(), module_name, &local, &inhabited)
+                            }),
+                            _ => true,
+                        }
+                    })
+            })
+            .map(|(_, name, _)| *name)
+            .collect();
+        if found.is_empty() {
+            break;
+        }
+        inhabited.extend(found);
+    }
+
+    match custom_types
+        .into_iter()
+        .find(|(_, name, _)| !inhabited.contains(name))
+    {
+        Some((location, name, _)) => Err(Error::UninhabitedType {
+            location: location.clone(),
+            name: name.to_string(),
+        }),
+        None => Ok(()),
+    }
+}
+
+fn is_inhabited(
+    typ: Arc<Type>,
+    module_name: &[String],
+    local: &HashSet<&str>,
+    inhabited: &HashSet<&str>,
+) -> bool {
+    match collapse_links(typ).as_ref() {
+        Type::App { module, name, .. } => {
+            module.as_slice() != module_name
+                || !local.contains(name.as_str())
+                || inhabited.contains(name.as_str())
+        }
+        Type::Tuple { elems } => elems
+            .iter()
+            .all(|elem| is_inhabited(elem.clone(), module_name, local, inhabited)),
+        Type::Fn { .. } | Type::Var { .. } => true,
+    }
+}
+
 fn assert_unique_type_name<'a>(
     names: &mut HashMap<&'a str, &'a SrcSpan>,
     name: &'a str,
@@ -451,6 +761,7 @@ fn assert_unique_type_name<'a>(
 
 fn register_values<'a>(
     s: &'a UntypedStatement,
+    deprecation: Option<&str>,
     module_name: &[String],
     hydrators: &mut HashMap<String, Hydrator>,
     names: &mut HashMap<&'a str, &'a SrcSpan>,
@@ -496,8 +807,8 @@ fn register_values<'a>(
             hydrators.insert(name.clone(), hydrator);
 
             // Insert the function into the environment
-            environment.insert_variable(
-                name.clone(),
+            environment.insert_variable_with_deprecation(
+                name,
                 ValueConstructorVariant::ModuleFn {
                     name: name.clone(),
                     field_map,
@@ -505,6 +816,7 @@ fn register_values<'a>(
                     arity: args.len(),
                 },
                 typ,
+                deprecation.map(str::to_string),
             );
         }
 
@@ -557,12 +869,14 @@ fn register_values<'a>(
                         module: vec![module.clone()],
                         arity: args.len(),
                     },
+                    deprecation: deprecation.map(str::to_string),
+                    internal: false,
                 },
             );
 
             // Insert function into module's internal scope
-            environment.insert_variable(
-                name.clone(),
+            environment.insert_variable_with_deprecation(
+                name,
                 ValueConstructorVariant::ModuleFn {
                     name: fun.clone(),
                     module: vec![module.clone()],
@@ -570,6 +884,7 @@ fn register_values<'a>(
                     field_map,
                 },
                 typ,
+                deprecation.map(str::to_string),
             );
         }
 
@@ -579,6 +894,7 @@ fn register_values<'a>(
             opaque,
             name,
             constructors,
+            derives,
             ..
         } => {
             let mut hydrator = hydrators
@@ -588,7 +904,7 @@ fn register_values<'a>(
 
             let typ = environment
                 .module_types
-                .get(name)
+                .get(name.as_str())
                 .gleam_expect("Type for custom type not found in register_values")
                 .typ
                 .clone();
@@ -609,6 +925,7 @@ fn register_values<'a>(
             }
 
             // Check and register constructors
+            let constructors_count = constructors.len();
             for constructor in constructors.iter() {
                 assert_unique_value_name(names, &constructor.name, &constructor.location)?;
 
@@ -643,19 +960,52 @@ fn register_values<'a>(
                             variant: ValueConstructorVariant::Record {
                                 name: constructor.name.clone(),
                                 field_map: field_map.clone(),
+                                constructors_count,
                             },
+                            deprecation: deprecation.map(str::to_string),
+                            internal: false,
                         },
                     );
                 }
 
-                environment.insert_variable(
-                    constructor.name.clone(),
+                environment.insert_variable_with_deprecation(
+                    &constructor.name,
                     ValueConstructorVariant::Record {
                         name: constructor.name.clone(),
                         field_map,
+                        constructors_count,
                     },
                     typ,
+                    deprecation.map(str::to_string),
+                );
+            }
+
+            // Register any derived helper functions
+            for derived in derives {
+                assert_unique_value_name(names, &derived.name, &derived.location)?;
+                let return_type = match derived.derive {
+                    Derive::Equal => bool(),
+                    Derive::Compare => int(),
+                };
+                let typ = fn_(vec![typ.clone(), typ.clone()], return_type);
+                let variant = ValueConstructorVariant::ModuleFn {
+                    name: derived.name.clone(),
+                    field_map: None,
+                    module: module_name.to_vec(),
+                    arity: 2,
+                };
+                environment.insert_module_value(
+                    &derived.name,
+                    ValueConstructor {
+                        public: *public,
+                        typ: typ.clone(),
+                        origin: derived.location.clone(),
+                        variant: variant.clone(),
+                        deprecation: None,
+                        internal: false,
+                    },
                 );
+                environment.insert_variable(&derived.name, variant, typ);
             }
         }
 
@@ -687,6 +1037,7 @@ fn generalise_statement(
                 .gleam_expect("Could not find preregistered type for function");
             let field_map = function.field_map().cloned();
             let typ = function.typ.clone();
+            let deprecation = function.deprecation.clone();
 
             // Generalise the function if not already done so
             let typ = if environment.ungeneralised_functions.remove(name.as_str()) {
@@ -709,6 +1060,8 @@ fn generalise_statement(
                         module: module_name.to_vec(),
                         arity: args.len(),
                     },
+                    deprecation,
+                    internal: false,
                 },
             );
 
@@ -751,6 +1104,7 @@ fn infer_statement(
                 .get_variable(name.as_str())
                 .gleam_expect("Could not find preregistered type for function");
             let field_map = preregistered_fn.field_map().cloned();
+            let deprecation = preregistered_fn.deprecation.clone();
             let preregistered_type = preregistered_fn.typ.clone();
             let (args_types, return_type) = preregistered_type
                 .fn_types()
@@ -785,8 +1139,8 @@ fn infer_statement(
             let typ = if safe_to_generalise {
                 environment.ungeneralised_functions.remove(name.as_str());
                 let typ = generalise(typ, 0);
-                environment.insert_variable(
-                    name.clone(),
+                environment.insert_variable_with_deprecation(
+                    &name,
                     ValueConstructorVariant::ModuleFn {
                         name: name.clone(),
                         field_map,
@@ -794,6 +1148,7 @@ fn infer_statement(
                         arity: args.len(),
                     },
                     typ.clone(),
+                    deprecation,
                 );
                 typ
             } else {
@@ -877,16 +1232,62 @@ fn infer_statement(
             opaque,
             name,
             parameters,
+            parameter_locations,
             constructors,
-        } => Ok(Statement::CustomType {
-            doc,
-            location,
-            public,
-            opaque,
-            name,
-            parameters,
-            constructors,
-        }),
+            derives,
+        } => {
+            // A parameter that no constructor uses is likely a mistake, unless
+            // it is named with an underscore to show that it is a phantom type
+            for (parameter, location) in parameters.iter().zip(parameter_locations.iter()) {
+                let used = constructors
+                    .iter()
+                    .flat_map(|constructor| constructor.args.iter())
+                    .any(|(_, typ, _)| typ.uses_var(parameter));
+                if !used && !parameter.starts_with('_') {
+                    environment.warnings.push(Warning::UnusedTypeParameter {
+                        location: location.clone(),
+                        name: parameter.clone(),
+                    });
+                }
+            }
+
+            let constructors = constructors
+                .into_iter()
+                .map(
+                    |RecordConstructor {
+                         location,
+                         name,
+                         args,
+                         documentation,
+                         ..
+                     }| {
+                        let typ = environment
+                            .get_variable(&name)
+                            .gleam_expect("Could not find custom type constructor")
+                            .typ
+                            .clone();
+                        RecordConstructor {
+                            location,
+                            name,
+                            args,
+                            documentation,
+                            typ,
+                        }
+                    },
+                )
+                .collect();
+            Ok(Statement::CustomType {
+                doc,
+                location,
+                public,
+                opaque,
+                name,
+                parameters,
+                parameter_locations,
+                constructors,
+                derives,
+            })
+        }
 
         Statement::ExternalType {
             doc,
@@ -946,6 +1347,8 @@ fn infer_statement(
                         literal: typed_expr.clone(),
                     },
                     typ: typ.clone(),
+                    deprecation: None,
+                    internal: false,
                 },
             );
 
@@ -1049,6 +1452,21 @@ where
 
 pub type TypedCallArg = CallArg<TypedExpr>;
 
+/// The location of the argument list of a call, from its opening parenthesis
+/// to its closing one. A call made by a pipe may not have an argument list of
+/// its own, in which case this is the location of the whole call.
+///
+fn call_arguments_location(fun: &SrcSpan, call: &SrcSpan) -> SrcSpan {
+    if call.start <= fun.end && fun.end < call.end {
+        SrcSpan {
+            start: fun.end,
+            end: call.end,
+        }
+    } else {
+        call.clone()
+    }
+}
+
 fn assert_no_labelled_arguments<A>(args: &[CallArg<A>]) -> Result<(), Error> {
     for arg in args {
         if let Some(label) = &arg.label {
@@ -1243,7 +1661,7 @@ fn make_type_vars(
 }
 
 fn custom_type_accessors(
-    constructors: &[RecordConstructor],
+    constructors: &[RecordConstructor<()>],
     hydrator: &mut Hydrator,
     environment: &mut Environment<'_, '_>,
 ) -> Result<Option<HashMap<String, RecordAccessor>>, Error> {
@@ -1276,6 +1694,7 @@ fn custom_type_accessors(
 /// Iterate over a module, registering any new types created by the module into the typer
 pub fn register_types<'a>(
     statement: &'a UntypedStatement,
+    deprecation: Option<&str>,
     module: &[String],
     hydrators: &mut HashMap<String, Hydrator>,
     names: &mut HashMap<&'a str, &'a SrcSpan>,
@@ -1303,13 +1722,15 @@ pub fn register_types<'a>(
 
             // Insert into the module scope
             environment.insert_type_constructor(
-                name.clone(),
+                name,
                 TypeConstructor {
                     origin: location.clone(),
                     module: module.to_owned(),
                     public: *public,
                     parameters,
                     typ,
+                    deprecation: deprecation.map(str::to_string),
+                    internal: false,
                 },
             )?;
 
@@ -1329,6 +1750,7 @@ pub fn register_types<'a>(
             ..
         } => {
             assert_unique_type_name(names, name, location)?;
+            environment.warn_if_shadows_imported_type(name, location);
 
             // Build a type from the type AST
             let mut hydrator = Hydrator::new();
@@ -1342,13 +1764,15 @@ pub fn register_types<'a>(
             hydrators.insert(name.to_string(), hydrator);
 
             environment.insert_type_constructor(
-                name.clone(),
+                name,
                 TypeConstructor {
                     origin: location.clone(),
                     module: module.to_owned(),
                     public: *public,
                     parameters,
                     typ,
+                    deprecation: deprecation.map(str::to_string),
+                    internal: false,
                 },
             )?;
         }
@@ -1362,6 +1786,7 @@ pub fn register_types<'a>(
             ..
         } => {
             assert_unique_type_name(names, name, location)?;
+            environment.warn_if_shadows_imported_type(name, location);
 
             // Register the paramerterised types
             let mut hydrator = Hydrator::new();
@@ -1373,13 +1798,15 @@ pub fn register_types<'a>(
             // Create the type that the alias resolves to
             let typ = hydrator.type_from_ast(resolved_type, environment)?;
             environment.insert_type_constructor(
-                name.clone(),
+                name,
                 TypeConstructor {
                     origin: location.clone(),
                     module: module.to_owned(),
                     public: *public,
                     parameters,
                     typ,
+                    deprecation: deprecation.map(str::to_string),
+                    internal: false,
                 },
             )?;
 
@@ -1397,16 +1824,18 @@ pub fn register_types<'a>(
     Ok(())
 }
 
-pub fn register_import(
-    s: &UntypedStatement,
+pub fn register_import<'a>(
+    s: &'a UntypedStatement,
+    import_names: &mut HashMap<String, &'a SrcSpan>,
+    unqualified_import_names: &mut HashMap<&'a str, (&'a SrcSpan, &'a [String])>,
     environment: &mut Environment<'_, '_>,
 ) -> Result<(), Error> {
     match s {
         Statement::Import {
+            location,
             module,
             as_name,
             unqualified,
-            ..
         } => {
             // Find imported module
             let module_info = environment
@@ -1419,6 +1848,12 @@ pub fn register_import(
                 None => module[module.len() - 1].clone(),
                 Some(name) => name.clone(),
             };
+            assert_unique_import_name(import_names, &module_name, location)?;
+
+            // Internal values and types imported from another package are
+            // warned about here, as their later uses are not qualified
+            let other_package = Some(module_info.1.package.as_str())
+                .filter(|package| *package != environment.current_package);
 
             // Insert unqualified imports into scope
             for UnqualifiedImport {
@@ -1435,13 +1870,32 @@ pub fn register_import(
                     Some(alias) => alias,
                 };
 
+                let internal = matches!(module_info.1.values.get(name), Some(v) if v.internal)
+                    || matches!(module_info.1.types.get(name), Some(t) if t.internal);
+                if let (true, Some(package)) = (internal, other_package) {
+                    environment.warnings.push(Warning::Internal {
+                        location: location.clone(),
+                        name: format!("{}.{}", module_name, name),
+                        package: package.to_string(),
+                    });
+                }
+
                 // Register the unqualified import if it is a value
                 if let Some(value) = module_info.1.values.get(name) {
-                    environment.insert_variable(
-                        imported_name.clone(),
+                    assert_unambiguous_import(
+                        unqualified_import_names,
+                        imported_name,
+                        location,
+                        module,
+                    )?;
+                    environment.insert_variable_with_deprecation(
+                        imported_name,
                         value.variant.clone(),
                         value.typ.clone(),
+                        value.deprecation.clone(),
                     );
+                    // Record the name so it cannot be shadowed by the module
+                    unqualified_import_names.insert(imported_name, (location, module));
                     value_imported = true;
                 }
 
@@ -1451,7 +1905,7 @@ pub fn register_import(
                         origin: location.clone(),
                         ..typ.clone()
                     };
-                    match environment.insert_type_constructor(imported_name.clone(), typ_info) {
+                    match environment.insert_type_constructor(imported_name, typ_info) {
                         Ok(_) => (),
                         Err(e) => return Err(e),
                     };
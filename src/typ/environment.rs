@@ -4,19 +4,20 @@ use std::collections::HashMap;
 #[derive(Debug)]
 pub struct Environment<'a, 'b> {
     pub current_module: &'a [String],
+    pub current_package: &'a str,
     pub uid: &'b mut usize,
     pub level: usize,
     pub importable_modules: &'a HashMap<String, (Origin, Module)>,
     pub imported_modules: HashMap<String, (Origin, Module)>,
 
     // Values defined in the current function (or the prelude)
-    pub local_values: im::HashMap<String, ValueConstructor>,
+    pub local_values: im::HashMap<Symbol, ValueConstructor>,
 
     // Types defined in the current module (or the prelude)
-    pub module_types: HashMap<String, TypeConstructor>,
+    pub module_types: HashMap<Symbol, TypeConstructor>,
 
     // Values defined in the current module
-    pub module_values: HashMap<String, ValueConstructor>,
+    pub module_values: HashMap<Symbol, ValueConstructor>,
 
     // Accessors defined in the current module
     pub accessors: HashMap<String, AccessorsMap>,
@@ -39,12 +40,16 @@ pub struct Environment<'a, 'b> {
     // We use this to determine whether functions that call this one
     // can safely be generalised.
     pub ungeneralised_functions: HashSet<String>,
+
+    // The interned names used as the keys of the scopes above
+    symbols: SymbolTable,
 }
 
 impl<'a, 'b> Environment<'a, 'b> {
     pub fn new(
         uid: &'b mut usize,
         current_module: &'a [String],
+        current_package: &'a str,
         importable_modules: &'a HashMap<String, (Origin, Module)>,
         warnings: &'a mut Vec<Warning>,
     ) -> Self {
@@ -54,6 +59,7 @@ impl<'a, 'b> Environment<'a, 'b> {
             unused_private_types: HashMap::new(),
             unused_private_mixed_constructors: HashMap::new(),
             ungeneralised_functions: HashSet::new(),
+            symbols: SymbolTable::default(),
             module_types: HashMap::new(),
             module_values: HashMap::new(),
             imported_modules: HashMap::new(),
@@ -61,6 +67,7 @@ impl<'a, 'b> Environment<'a, 'b> {
             local_values: hashmap![],
             importable_modules,
             current_module,
+            current_package,
             warnings,
         };
         register_prelude(typer)
@@ -68,7 +75,7 @@ impl<'a, 'b> Environment<'a, 'b> {
 }
 
 pub struct ScopeResetData {
-    local_values: im::HashMap<String, ValueConstructor>,
+    local_values: im::HashMap<Symbol, ValueConstructor>,
 }
 
 impl<'a, 'b> Environment<'a, 'b> {
@@ -132,17 +139,32 @@ impl<'a, 'b> Environment<'a, 'b> {
     ///
     pub fn insert_variable(
         &mut self,
-        name: String,
+        name: &str,
+        variant: ValueConstructorVariant,
+        typ: Arc<Type>,
+    ) {
+        self.insert_variable_with_deprecation(name, variant, typ, None)
+    }
+
+    /// Insert a variable in the current scope that is warned about wherever
+    /// it is used if it has a deprecation message.
+    ///
+    pub fn insert_variable_with_deprecation(
+        &mut self,
+        name: &str,
         variant: ValueConstructorVariant,
         typ: Arc<Type>,
+        deprecation: Option<String>,
     ) {
         self.local_values.insert(
-            name,
+            self.symbols.intern(name),
             ValueConstructor {
                 public: false,
                 origin: Default::default(), // TODO: use the real one
                 variant,
                 typ,
+                deprecation,
+                internal: false,
             },
         );
     }
@@ -151,7 +173,8 @@ impl<'a, 'b> Environment<'a, 'b> {
     /// Errors if the module already has a value with that name.
     ///
     pub fn insert_module_value(&mut self, name: &str, value: ValueConstructor) {
-        self.module_values.insert(name.to_string(), value);
+        let name = self.symbols.intern(name);
+        self.module_values.insert(name, value);
     }
 
     /// Lookup a variable in the current scope.
@@ -176,22 +199,55 @@ impl<'a, 'b> Environment<'a, 'b> {
     ///
     pub fn insert_type_constructor(
         &mut self,
-        type_name: String,
+        name: &str,
         info: TypeConstructor,
     ) -> Result<(), Error> {
-        let name = type_name.clone();
         let location = info.origin.clone();
-        match self.module_types.insert(type_name, info) {
+        let symbol = self.symbols.intern(name);
+        match self.module_types.insert(symbol, info) {
             None => Ok(()),
             Some(prelude_type) if prelude_type.module.is_empty() => Ok(()),
             Some(previous) => Err(Error::DuplicateTypeName {
-                name,
+                name: name.to_string(),
                 location,
                 previous_location: previous.origin,
             }),
         }
     }
 
+    /// Warn about a type of the current module that has the same name as an
+    /// imported type that is in scope unqualified, as annotations using the
+    /// name could be mistaken for the imported type. Types of the prelude are
+    /// always in scope unqualified, while those of other modules are only
+    /// ambiguous when imported unqualified, which is an error when the type
+    /// is inserted.
+    ///
+    pub fn warn_if_shadows_imported_type(&mut self, name: &str, location: &SrcSpan) {
+        let imported = match self.module_types.get(name) {
+            Some(typ) if typ.module.is_empty() => name.to_string(),
+            Some(typ) if typ.module.as_slice() != self.current_module => {
+                format!("{}.{}", typ.module.join("/"), name)
+            }
+            _ => return,
+        };
+        self.warnings.push(Warning::ShadowedImportedType {
+            location: location.clone(),
+            name: name.to_string(),
+            imported,
+        });
+    }
+
+    /// The package of the imported module, if it is not the package of the
+    /// module being checked.
+    ///
+    pub fn other_package(&self, module_alias: &str) -> Option<String> {
+        self.imported_modules
+            .get(module_alias)
+            .map(|(_, module)| module.package.as_str())
+            .filter(|package| *package != self.current_package)
+            .map(str::to_string)
+    }
+
     /// Lookup a type in the current scope.
     ///
     pub fn get_type_constructor(
@@ -216,9 +272,9 @@ impl<'a, 'b> Environment<'a, 'b> {
             Some(m) => {
                 let module = &self.imported_modules.get(m).ok_or_else(|| {
                     GetTypeConstructorError::UnknownModule {
-                        name: name.to_string(),
+                        name: m.to_string(),
                         imported_modules: self
-                            .importable_modules
+                            .imported_modules
                             .keys()
                             .map(|t| t.to_string())
                             .collect(),
@@ -255,9 +311,9 @@ impl<'a, 'b> Environment<'a, 'b> {
             Some(module) => {
                 let module = self.imported_modules.get(&*module).ok_or_else(|| {
                     GetValueConstructorError::UnknownModule {
-                        name: name.to_string(),
+                        name: module.to_string(),
                         imported_modules: self
-                            .importable_modules
+                            .imported_modules
                             .keys()
                             .map(|t| t.to_string())
                             .collect(),
@@ -449,12 +505,16 @@ impl<'a, 'b> Environment<'a, 'b> {
                     ..
                 },
             ) if args1.len() == args2.len() => {
-                for (a, b) in args1.iter().zip(args2) {
-                    self.unify(a.clone(), b.clone())
-                        .map_err(|_| UnifyError::CouldNotUnify {
+                for (index, (a, b)) in args1.iter().zip(args2).enumerate() {
+                    self.unify(a.clone(), b.clone()).map_err(|_| {
+                        UnifyError::CouldNotUnifyFnArg {
+                            index,
                             expected: t1.clone(),
                             given: t2.clone(),
-                        })?;
+                            expected_arg: a.clone(),
+                            given_arg: b.clone(),
+                        }
+                    })?;
                 }
                 self.unify(retrn1.clone(), retrn2.clone())
                     .map_err(|_| UnifyError::CouldNotUnify {
@@ -463,6 +523,15 @@ impl<'a, 'b> Environment<'a, 'b> {
                     })
             }
 
+            (Type::Fn { args: args1, .. }, Type::Fn { args: args2, .. }) => {
+                Err(UnifyError::IncorrectFnArity {
+                    expected: t1.clone(),
+                    given: t2.clone(),
+                    expected_arity: args1.len(),
+                    given_arity: args2.len(),
+                })
+            }
+
             (_, _) => Err(UnifyError::CouldNotUnify {
                 expected: t1.clone(),
                 given: t2.clone(),
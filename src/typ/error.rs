@@ -1,4 +1,8 @@
-use crate::{ast::SrcSpan, bit_string::Error as BinaryError, typ::Type};
+use crate::{
+    ast::{BinOp, SrcSpan, TypedExpr},
+    bit_string::Error as BinaryError,
+    typ::Type,
+};
 
 use std::sync::Arc;
 
@@ -69,6 +73,35 @@ pub enum Error {
         labels: Vec<String>,
     },
 
+    /// A tuple pattern has a different number of elements to the tuple it is
+    /// matched against.
+    IncorrectTupleArity {
+        location: SrcSpan,
+        expected: usize,
+        given: usize,
+        typ: Arc<Type>,
+    },
+
+    /// A record constructor was called with the wrong number of arguments.
+    IncorrectRecordArity {
+        location: SrcSpan,
+        name: String,
+        expected: usize,
+        given: usize,
+        missing: Vec<MissingField>,
+    },
+
+    /// An argument given to a record constructor has the wrong type for
+    /// the field at `index`.
+    IncorrectRecordFieldType {
+        location: SrcSpan,
+        name: String,
+        index: usize,
+        label: Option<String>,
+        expected: Arc<Type>,
+        given: Arc<Type>,
+    },
+
     UnnecessarySpreadOperator {
         location: SrcSpan,
         arity: usize,
@@ -87,6 +120,24 @@ pub enum Error {
         given: Arc<Type>,
     },
 
+    /// A comparison operator was used with a value that cannot be compared,
+    /// such as a function.
+    NotComparable {
+        location: SrcSpan,
+        operator: BinOp,
+        typ: Arc<Type>,
+    },
+
+    /// An operand of a binary operator does not have the type the operator
+    /// expects. The location is that of the operator.
+    OperandTypeMismatch {
+        location: SrcSpan,
+        operator: BinOp,
+        expected: Arc<Type>,
+        left: Arc<Type>,
+        right: Arc<Type>,
+    },
+
     RecursiveType {
         location: SrcSpan,
     },
@@ -97,12 +148,40 @@ pub enum Error {
         name: String,
     },
 
+    DuplicateDerivedName {
+        location: SrcSpan,
+        previous_location: SrcSpan,
+        name: String,
+        type_name: String,
+        previous_type_name: String,
+    },
+
     DuplicateTypeName {
         location: SrcSpan,
         previous_location: SrcSpan,
         name: String,
     },
 
+    DuplicateImport {
+        location: SrcSpan,
+        previous_location: SrcSpan,
+        name: String,
+    },
+
+    ShadowedUnqualifiedImport {
+        location: SrcSpan,
+        import_location: SrcSpan,
+        name: String,
+    },
+
+    AmbiguousUnqualifiedImport {
+        location: SrcSpan,
+        previous_location: SrcSpan,
+        name: String,
+        module: String,
+        previous_module: String,
+    },
+
     DuplicateArgument {
         location: SrcSpan,
         label: String,
@@ -204,10 +283,33 @@ pub enum Error {
         typ: String,
     },
 
+    BinaryTypeDoesNotAllowSize {
+        location: SrcSpan,
+        typ: String,
+    },
+
+    BinaryTypeDoesNotAllowSignedness {
+        location: SrcSpan,
+        typ: String,
+    },
+
+    BinaryTypeDoesNotAllowEndianness {
+        location: SrcSpan,
+        typ: String,
+    },
+
     BinarySegmentMustHaveSize {
         location: SrcSpan,
     },
 
+    /// An integer literal is too large or too small for the number of bits
+    /// of its segment.
+    BinarySegmentIntDoesNotFit {
+        location: SrcSpan,
+        value: i64,
+        bits: i64,
+    },
+
     InvalidBinarySegmentOption {
         location: SrcSpan,
         label: String,
@@ -221,21 +323,161 @@ pub enum Error {
         location: SrcSpan,
         option: String,
     },
+
+    UninhabitedType {
+        location: SrcSpan,
+        name: String,
+    },
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum Warning {
-    Todo { location: SrcSpan, typ: Arc<Type> },
+    Todo {
+        location: SrcSpan,
+        typ: Arc<Type>,
+    },
+
+    ImplicitlyDiscardedResult {
+        location: SrcSpan,
+    },
 
-    ImplicitlyDiscardedResult { location: SrcSpan },
+    NoFieldsRecordUpdate {
+        location: SrcSpan,
+    },
+
+    AllFieldsRecordUpdate {
+        location: SrcSpan,
+    },
+
+    UnusedType {
+        location: SrcSpan,
+        name: String,
+    },
 
-    NoFieldsRecordUpdate { location: SrcSpan },
+    UnusedTypeParameter {
+        location: SrcSpan,
+        name: String,
+    },
 
-    AllFieldsRecordUpdate { location: SrcSpan },
+    UnusedConstructor {
+        location: SrcSpan,
+        name: String,
+    },
 
-    UnusedType { location: SrcSpan, name: String },
+    RedundantLet {
+        location: SrcSpan,
+        name: String,
+    },
 
-    UnusedConstructor { location: SrcSpan, name: String },
+    ConstantCondition {
+        location: SrcSpan,
+    },
+
+    RefutableLetPattern {
+        location: SrcSpan,
+    },
+
+    FloatEquality {
+        location: SrcSpan,
+    },
+
+    UnnecessaryResult {
+        location: SrcSpan,
+        name: String,
+    },
+
+    RedundantRewrap {
+        location: SrcSpan,
+    },
+
+    InfiniteRecursion {
+        location: SrcSpan,
+    },
+
+    RawErlang {
+        location: SrcSpan,
+    },
+
+    RedundantAnnotation {
+        location: SrcSpan,
+    },
+
+    NonTailRecursion {
+        location: SrcSpan,
+    },
+
+    Deprecated {
+        location: SrcSpan,
+        name: String,
+        message: String,
+    },
+
+    Internal {
+        location: SrcSpan,
+        name: String,
+        package: String,
+    },
+
+    ShadowedImportedType {
+        location: SrcSpan,
+        name: String,
+        imported: String,
+    },
+}
+
+impl Warning {
+    /// A stable identifier for the kind of warning, used in machine-readable
+    /// output so that tools do not need to match on the message.
+    ///
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Todo { .. } => "todo",
+            Self::ImplicitlyDiscardedResult { .. } => "discarded-result",
+            Self::NoFieldsRecordUpdate { .. } => "no-fields-record-update",
+            Self::AllFieldsRecordUpdate { .. } => "all-fields-record-update",
+            Self::UnusedType { .. } => "unused-type",
+            Self::UnusedConstructor { .. } => "unused-constructor",
+            Self::UnusedTypeParameter { .. } => "unused-type-parameter",
+            Self::RedundantLet { .. } => "redundant-let",
+            Self::ConstantCondition { .. } => "constant-condition",
+            Self::RefutableLetPattern { .. } => "refutable-let-pattern",
+            Self::FloatEquality { .. } => "float-equality",
+            Self::UnnecessaryResult { .. } => "unnecessary-result",
+            Self::RedundantRewrap { .. } => "redundant-rewrap",
+            Self::InfiniteRecursion { .. } => "infinite-recursion",
+            Self::RawErlang { .. } => "raw-erlang",
+            Self::RedundantAnnotation { .. } => "redundant-annotation",
+            Self::NonTailRecursion { .. } => "non-tail-recursion",
+            Self::Deprecated { .. } => "deprecated",
+            Self::Internal { .. } => "internal",
+            Self::ShadowedImportedType { .. } => "shadowed-type",
+        }
+    }
+
+    pub fn location(&self) -> &SrcSpan {
+        match self {
+            Self::Todo { location, .. }
+            | Self::ImplicitlyDiscardedResult { location }
+            | Self::NoFieldsRecordUpdate { location }
+            | Self::AllFieldsRecordUpdate { location }
+            | Self::UnusedType { location, .. }
+            | Self::UnusedConstructor { location, .. }
+            | Self::UnusedTypeParameter { location, .. }
+            | Self::RedundantLet { location, .. }
+            | Self::ConstantCondition { location }
+            | Self::RefutableLetPattern { location }
+            | Self::FloatEquality { location }
+            | Self::RedundantRewrap { location }
+            | Self::InfiniteRecursion { location }
+            | Self::RawErlang { location }
+            | Self::RedundantAnnotation { location }
+            | Self::NonTailRecursion { location }
+            | Self::Deprecated { location, .. }
+            | Self::Internal { location, .. }
+            | Self::ShadowedImportedType { location, .. }
+            | Self::UnnecessaryResult { location, .. } => location,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -374,6 +616,30 @@ pub fn flip_unify_error(e: UnifyError) -> UnifyError {
             expected: given,
             given: expected,
         },
+        UnifyError::IncorrectFnArity {
+            expected,
+            given,
+            expected_arity,
+            given_arity,
+        } => UnifyError::IncorrectFnArity {
+            expected: given,
+            given: expected,
+            expected_arity: given_arity,
+            given_arity: expected_arity,
+        },
+        UnifyError::CouldNotUnifyFnArg {
+            index,
+            expected,
+            given,
+            expected_arg,
+            given_arg,
+        } => UnifyError::CouldNotUnifyFnArg {
+            index,
+            expected: given,
+            given: expected,
+            expected_arg: given_arg,
+            given_arg: expected_arg,
+        },
         other => other,
     }
 }
@@ -385,7 +651,9 @@ pub fn unify_enclosed_type(
 ) -> Result<(), UnifyError> {
     // If types cannot unify, show the type error with the enclosing types, e1 and e2.
     match result {
-        Err(UnifyError::CouldNotUnify { .. }) => Err(UnifyError::CouldNotUnify {
+        Err(UnifyError::CouldNotUnify { .. })
+        | Err(UnifyError::IncorrectFnArity { .. })
+        | Err(UnifyError::CouldNotUnifyFnArg { .. }) => Err(UnifyError::CouldNotUnify {
             expected: e1,
             given: e2,
         }),
@@ -394,6 +662,14 @@ pub fn unify_enclosed_type(
     }
 }
 
+/// A field of a record that was not given a value when constructing it.
+#[derive(Debug, PartialEq, Clone)]
+pub struct MissingField {
+    pub index: usize,
+    pub label: Option<String>,
+    pub typ: Arc<Type>,
+}
+
 #[derive(Debug, PartialEq)]
 pub enum UnifyError {
     CouldNotUnify {
@@ -401,6 +677,23 @@ pub enum UnifyError {
         given: Arc<Type>,
     },
 
+    /// Two function types take a different number of arguments.
+    IncorrectFnArity {
+        expected: Arc<Type>,
+        given: Arc<Type>,
+        expected_arity: usize,
+        given_arity: usize,
+    },
+
+    /// Two function types differ in the type of the argument at `index`.
+    CouldNotUnifyFnArg {
+        index: usize,
+        expected: Arc<Type>,
+        given: Arc<Type>,
+        expected_arg: Arc<Type>,
+        given_arg: Arc<Type>,
+    },
+
     ExtraVarInAlternativePattern {
         name: String,
     },
@@ -464,15 +757,43 @@ pub fn convert_binary_error(e: crate::bit_string::Error, location: &SrcSpan) ->
             Error::BinaryTypeDoesNotAllowUnit { location, typ }
         }
 
+        BinaryError::TypeDoesNotAllowSize { location, typ } => {
+            Error::BinaryTypeDoesNotAllowSize { location, typ }
+        }
+
+        BinaryError::TypeDoesNotAllowSignedness { location, typ } => {
+            Error::BinaryTypeDoesNotAllowSignedness { location, typ }
+        }
+
+        BinaryError::TypeDoesNotAllowEndianness { location, typ } => {
+            Error::BinaryTypeDoesNotAllowEndianness { location, typ }
+        }
+
         BinaryError::SegmentMustHaveSize => Error::BinarySegmentMustHaveSize {
             location: location.clone(),
         },
+
+        BinaryError::IntDoesNotFit {
+            location,
+            value,
+            bits,
+        } => Error::BinarySegmentIntDoesNotFit {
+            location,
+            value,
+            bits,
+        },
     }
 }
 
 pub fn convert_unify_error(e: UnifyError, location: &SrcSpan) -> Error {
     match e {
-        UnifyError::CouldNotUnify { expected, given } => Error::CouldNotUnify {
+        UnifyError::CouldNotUnify { expected, given }
+        | UnifyError::IncorrectFnArity {
+            expected, given, ..
+        }
+        | UnifyError::CouldNotUnifyFnArg {
+            expected, given, ..
+        } => Error::CouldNotUnify {
             location: location.clone(),
             expected,
             given,
@@ -493,3 +814,54 @@ pub fn convert_unify_error(e: UnifyError, location: &SrcSpan) -> Error {
         },
     }
 }
+
+/// Convert a unify error for the type of a value. When the value is an
+/// anonymous function the error points to the arguments that are at fault
+/// rather than the entire function.
+pub fn convert_unify_error_for_value(e: UnifyError, value: &TypedExpr) -> Error {
+    let args = match value {
+        TypedExpr::Fn {
+            is_capture: false,
+            args,
+            ..
+        } => args,
+        _ => return convert_unify_error(e, value.location()),
+    };
+
+    match e {
+        UnifyError::IncorrectFnArity {
+            expected_arity,
+            given_arity,
+            ..
+        } => {
+            // Point to any extra arguments, or to the whole function if
+            // there are too few.
+            let location = match args.get(expected_arity..) {
+                Some(extra) if !extra.is_empty() => SrcSpan {
+                    start: extra[0].location.start,
+                    end: extra[extra.len() - 1].location.end,
+                },
+                _ => value.location().clone(),
+            };
+            Error::IncorrectArity {
+                location,
+                expected: expected_arity,
+                given: given_arity,
+                labels: vec![],
+            }
+        }
+
+        UnifyError::CouldNotUnifyFnArg {
+            index,
+            expected_arg,
+            given_arg,
+            ..
+        } if index < args.len() => Error::CouldNotUnify {
+            location: args[index].location.clone(),
+            expected: expected_arg,
+            given: given_arg,
+        },
+
+        e => convert_unify_error(e, value.location()),
+    }
+}
@@ -1,7 +1,7 @@
 use super::*;
 use crate::ast::{
     Arg, BinOp, BindingKind, BitStringSegment, BitStringSegmentOption, CallArg, Clause,
-    ClauseGuard, Constant, HasLocation, RecordUpdateSpread, SrcSpan, TypeAst, TypedArg,
+    ClauseGuard, Constant, HasLocation, Pattern, RecordUpdateSpread, SrcSpan, TypeAst, TypedArg,
     TypedClause, TypedClauseGuard, TypedConstant, TypedExpr, TypedMultiPattern, UntypedArg,
     UntypedClause, UntypedClauseGuard, UntypedConstant, UntypedConstantBitStringSegment,
     UntypedExpr, UntypedExprBitStringSegment, UntypedMultiPattern, UntypedPattern,
@@ -72,8 +72,18 @@ impl<'a, 'b, 'c> ExprTyper<'a, 'b, 'c> {
             UntypedExpr::ListNil { location, .. } => self.infer_nil(location),
 
             UntypedExpr::Todo {
-                location, label, ..
-            } => self.infer_todo(location, label),
+                location, message, ..
+            } => self.infer_todo(location, message),
+
+            UntypedExpr::Panic {
+                location, message, ..
+            } => self.infer_panic(location, message),
+
+            UntypedExpr::RawErlang {
+                location,
+                code,
+                annotation,
+            } => self.infer_raw_erlang(location, code, &annotation),
 
             UntypedExpr::Var { location, name, .. } => self.infer_var(name, location),
 
@@ -117,8 +127,21 @@ impl<'a, 'b, 'c> ExprTyper<'a, 'b, 'c> {
                 then,
                 kind,
                 annotation,
-                ..
-            } => self.infer_let(pattern, *value, *then, kind, &annotation, location),
+                message,
+            } => {
+                let mut expr =
+                    self.infer_let(pattern, *value, *then, kind, &annotation, location)?;
+                // The message is used when the pattern does not match, so it is
+                // checked after the variables of the pattern have gone out of scope
+                if let TypedExpr::Let {
+                    message: typed_message,
+                    ..
+                } = &mut expr
+                {
+                    *typed_message = self.infer_error_message(message)?;
+                }
+                Ok(expr)
+            }
 
             UntypedExpr::Case {
                 location,
@@ -144,10 +167,10 @@ impl<'a, 'b, 'c> ExprTyper<'a, 'b, 'c> {
             UntypedExpr::BinOp {
                 location,
                 name,
+                name_location,
                 left,
                 right,
-                ..
-            } => self.infer_binop(name, *left, *right, location),
+            } => self.infer_binop(name, name_location, *left, *right, location),
 
             UntypedExpr::FieldAccess {
                 location,
@@ -290,20 +313,79 @@ impl<'a, 'b, 'c> ExprTyper<'a, 'b, 'c> {
         })
     }
 
-    fn infer_todo(&mut self, location: SrcSpan, label: Option<String>) -> Result<TypedExpr, Error> {
+    fn infer_todo(
+        &mut self,
+        location: SrcSpan,
+        message: Option<Box<UntypedExpr>>,
+    ) -> Result<TypedExpr, Error> {
         let typ = self.new_unbound_var(self.environment.level);
         self.environment.warnings.push(Warning::Todo {
             location: location.clone(),
             typ: typ.clone(),
         });
+        let message = self.infer_error_message(message)?;
 
         Ok(TypedExpr::Todo {
             location,
-            label,
+            message,
             typ,
         })
     }
 
+    fn infer_panic(
+        &mut self,
+        location: SrcSpan,
+        message: Option<Box<UntypedExpr>>,
+    ) -> Result<TypedExpr, Error> {
+        let typ = self.new_unbound_var(self.environment.level);
+        let message = self.infer_error_message(message)?;
+
+        Ok(TypedExpr::Panic {
+            location,
+            message,
+            typ,
+        })
+    }
+
+    /// The Erlang code cannot be checked, so the annotation is trusted and
+    /// each use is reported so that it is not forgotten about.
+    ///
+    fn infer_raw_erlang(
+        &mut self,
+        location: SrcSpan,
+        code: String,
+        annotation: &TypeAst,
+    ) -> Result<TypedExpr, Error> {
+        let typ = self.type_from_ast(annotation)?;
+        self.environment.warnings.push(Warning::RawErlang {
+            location: location.clone(),
+        });
+
+        Ok(TypedExpr::RawErlang {
+            location,
+            code,
+            typ,
+        })
+    }
+
+    /// The message of a `todo`, `panic`, or `assert` can be any expression that
+    /// evaluates to a String.
+    ///
+    fn infer_error_message(
+        &mut self,
+        message: Option<Box<UntypedExpr>>,
+    ) -> Result<Option<Box<TypedExpr>>, Error> {
+        match message {
+            None => Ok(None),
+            Some(message) => {
+                let message = self.infer(*message)?;
+                self.unify(string(), message.typ())
+                    .map_err(|e| convert_unify_error(e, message.location()))?;
+                Ok(Some(Box::new(message)))
+            }
+        }
+    }
+
     fn infer_string(&mut self, value: String, location: SrcSpan) -> Result<TypedExpr, Error> {
         Ok(TypedExpr::String {
             location,
@@ -332,7 +414,7 @@ impl<'a, 'b, 'c> ExprTyper<'a, 'b, 'c> {
         let first = self.infer(first)?;
         let then = self.infer(then)?;
 
-        match first.typ().as_ref() {
+        match collapse_links(first.typ()).as_ref() {
             typ if typ.is_result() => {
                 self.environment
                     .warnings
@@ -458,7 +540,7 @@ impl<'a, 'b, 'c> ExprTyper<'a, 'b, 'c> {
     ) -> Result<TypedExpr, Error> {
         match container {
             UntypedExpr::Var { name, location, .. }
-                if !self.environment.local_values.contains_key(&name) =>
+                if !self.environment.local_values.contains_key(name.as_str()) =>
             {
                 self.infer_module_access(name.as_ref(), label, &location, access_location)
             }
@@ -549,7 +631,7 @@ impl<'a, 'b, 'c> ExprTyper<'a, 'b, 'c> {
     ) -> Result<BitStringSegment<TypedValue, Arc<Type>>, Error>
     where
         InferFn: FnMut(&mut Self, UntypedValue) -> Result<TypedValue, Error>,
-        TypedValue: HasType + HasLocation + Clone,
+        TypedValue: HasType + HasLocation + IntLiteral + Clone,
     {
         let value = infer(self, value)?;
 
@@ -573,6 +655,9 @@ impl<'a, 'b, 'c> ExprTyper<'a, 'b, 'c> {
 
         self.unify(typ.clone(), value.typ())
             .map_err(|e| convert_unify_error(e, value.location()))?;
+        type_specifier
+            .check_literal_fits(&value, value.location())
+            .map_err(|e| convert_binary_error(e, &location))?;
 
         Ok(BitStringSegment {
             location,
@@ -585,6 +670,7 @@ impl<'a, 'b, 'c> ExprTyper<'a, 'b, 'c> {
     fn infer_binop(
         &mut self,
         name: BinOp,
+        name_location: SrcSpan,
         left: UntypedExpr,
         right: UntypedExpr,
         location: SrcSpan,
@@ -592,9 +678,19 @@ impl<'a, 'b, 'c> ExprTyper<'a, 'b, 'c> {
         let (input_type, output_type) = match name {
             BinOp::Eq | BinOp::NotEq => {
                 let left = self.infer(left)?;
+                assert_comparable(&name, &left)?;
                 let right = self.infer(right)?;
-                self.unify(left.typ(), right.typ())
-                    .map_err(|e| convert_unify_error(e, right.location()))?;
+                assert_comparable(&name, &right)?;
+                self.unify(left.typ(), right.typ()).map_err(|e| {
+                    convert_operand_unify_error(e, &name, name_location, left.typ(), &left, &right)
+                })?;
+
+                // Rounding errors make exact comparison of floats unreliable
+                if collapse_links(left.typ()).is_float() {
+                    self.environment.warnings.push(Warning::FloatEquality {
+                        location: location.clone(),
+                    });
+                }
 
                 return Ok(TypedExpr::BinOp {
                     location,
@@ -625,12 +721,28 @@ impl<'a, 'b, 'c> ExprTyper<'a, 'b, 'c> {
             BinOp::ModuloInt => (int(), int()),
         };
 
+        // Both operands are inferred before either is checked so that a
+        // mismatch can be reported with the types of both
         let left = self.infer(left)?;
-        self.unify(input_type.clone(), left.typ())
-            .map_err(|e| convert_unify_error(e, left.location()))?;
+        if name.is_comparison() {
+            assert_comparable(&name, &left)?;
+        }
         let right = self.infer(right)?;
-        self.unify(input_type, right.typ())
-            .map_err(|e| convert_unify_error(e, right.location()))?;
+        if name.is_comparison() {
+            assert_comparable(&name, &right)?;
+        }
+        for operand in &[&left, &right] {
+            self.unify(input_type.clone(), operand.typ()).map_err(|e| {
+                convert_operand_unify_error(
+                    e,
+                    &name,
+                    name_location.clone(),
+                    input_type.clone(),
+                    &left,
+                    &right,
+                )
+            })?;
+        }
 
         Ok(TypedExpr::BinOp {
             location,
@@ -641,6 +753,46 @@ impl<'a, 'b, 'c> ExprTyper<'a, 'b, 'c> {
         })
     }
 
+    // Rebinding a variable to itself (`let x = x`), or binding a variable to
+    // another only to return it straight away (`let x = y x`), has no effect
+    // and is likely a mistake. Only these exact forms are flagged so that
+    // there are no false positives.
+    fn check_redundant_let(
+        &mut self,
+        pattern: &UntypedPattern,
+        value: &UntypedExpr,
+        then: &UntypedExpr,
+        kind: BindingKind,
+        annotation: &Option<TypeAst>,
+        location: &SrcSpan,
+    ) {
+        if kind != BindingKind::Let || annotation.is_some() {
+            return;
+        }
+
+        let name = match pattern {
+            Pattern::Var { name, .. } => name,
+            _ => return,
+        };
+
+        fn is_var(expr: &UntypedExpr, var: &str) -> bool {
+            match expr {
+                UntypedExpr::Var { name, .. } => name == var,
+                _ => false,
+            }
+        }
+
+        let rebinds_self = is_var(value, name);
+        let returns_alias = matches!(value, UntypedExpr::Var { .. }) && is_var(then, name);
+
+        if rebinds_self || returns_alias {
+            self.environment.warnings.push(Warning::RedundantLet {
+                location: location.clone(),
+                name: name.clone(),
+            });
+        }
+    }
+
     fn infer_let(
         &mut self,
         pattern: UntypedPattern,
@@ -650,6 +802,8 @@ impl<'a, 'b, 'c> ExprTyper<'a, 'b, 'c> {
         annotation: &Option<TypeAst>,
         location: SrcSpan,
     ) -> Result<TypedExpr, Error> {
+        self.check_redundant_let(&pattern, &value, &then, kind, annotation, &location);
+
         let value = self.in_new_scope(|value_typer| value_typer.infer(value))?;
 
         let try_value_type = self.new_unbound_var(self.environment.level);
@@ -667,16 +821,34 @@ impl<'a, 'b, 'c> ExprTyper<'a, 'b, 'c> {
             _ => value.typ(),
         };
 
+        // A value whose type is already fully known does not need annotating
+        let value_typ_is_concrete = value_typ.is_concrete();
+
         let value_typ = generalise(value_typ, self.environment.level + 1);
 
+        // The variables of the pattern are only in scope in the following
+        // code, so those bound within a block are not visible outside it
+        let local_values = self.environment.local_values.clone();
+
         // Ensure the pattern matches the type of the value
         let pattern =
             pattern::PatternTyper::new(self.environment, &self.hydrator, self.environment.level)
                 .unify(pattern, value_typ.clone())?;
 
+        // A `let` cannot fail at runtime, so patterns that may not match the
+        // value should use `assert` or a `case` expression instead
+        if kind == BindingKind::Let && !pattern.is_irrefutable() {
+            self.environment
+                .warnings
+                .push(Warning::RefutableLetPattern {
+                    location: pattern.location().clone(),
+                });
+        }
+
         // Check the type of the following code
         let then = self.infer(then)?;
         let typ = then.typ();
+        self.environment.local_values = local_values;
 
         // Ensure that a Result with the right error type is returned for `try`
         if kind == BindingKind::Try {
@@ -691,8 +863,12 @@ impl<'a, 'b, 'c> ExprTyper<'a, 'b, 'c> {
                 .type_from_ast(ann)
                 .map(|t| self.instantiate(t, self.environment.level, &mut hashmap![]))?;
             self.unify(ann_typ, value_typ)
-                .map_err(|e| convert_unify_error(e, value.location()))?;
+                .map_err(|e| convert_unify_error_for_value(e, &value))?;
         }
+        let redundant_annotation = annotation
+            .as_ref()
+            .filter(|_| value_typ_is_concrete)
+            .map(|ann| ann.location().clone());
 
         Ok(TypedExpr::Let {
             location,
@@ -701,6 +877,8 @@ impl<'a, 'b, 'c> ExprTyper<'a, 'b, 'c> {
             pattern,
             value: Box::new(value),
             then: Box::new(then),
+            redundant_annotation,
+            message: None,
         })
     }
 
@@ -725,6 +903,12 @@ impl<'a, 'b, 'c> ExprTyper<'a, 'b, 'c> {
                 Ok((subject, subject_type))
             })?;
 
+            if is_constant_condition(&subject) {
+                self.environment.warnings.push(Warning::ConstantCondition {
+                    location: subject.location().clone(),
+                });
+            }
+
             typed_subjects.push(subject);
             subject_types.push(subject_type);
         }
@@ -735,6 +919,13 @@ impl<'a, 'b, 'c> ExprTyper<'a, 'b, 'c> {
                 .map_err(|e| convert_unify_error(e, typed_clause.then.location()))?;
             typed_clauses.push(typed_clause);
         }
+
+        if is_identity_rewrap(typed_subjects.as_slice(), typed_clauses.as_slice()) {
+            self.environment.warnings.push(Warning::RedundantRewrap {
+                location: location.clone(),
+            });
+        }
+
         Ok(TypedExpr::Case {
             location,
             typ: return_type,
@@ -796,8 +987,17 @@ impl<'a, 'b, 'c> ExprTyper<'a, 'b, 'c> {
         // check every pattern.
         let mut typed_alternatives = Vec::with_capacity(alternatives.len());
         for m in alternatives {
+            // Errors for an alternative point to its patterns rather than the
+            // whole clause
+            let location = match (m.first(), m.last()) {
+                (Some(first), Some(last)) => SrcSpan {
+                    start: first.location().start,
+                    end: last.location().end,
+                },
+                _ => location.clone(),
+            };
             typed_alternatives
-                .push(pattern_typer.infer_alternative_multi_pattern(m, subjects, location)?);
+                .push(pattern_typer.infer_alternative_multi_pattern(m, subjects, &location)?);
         }
 
         Ok((typed_pattern, typed_alternatives))
@@ -816,6 +1016,11 @@ impl<'a, 'b, 'c> ExprTyper<'a, 'b, 'c> {
                 let guard = self.infer_clause_guard(guard)?;
                 self.unify(bool(), guard.typ())
                     .map_err(|e| convert_unify_error(e, guard.location()))?;
+                if is_constant_guard(&guard) {
+                    self.environment.warnings.push(Warning::ConstantCondition {
+                        location: guard.location().clone(),
+                    });
+                }
                 Ok(Some(guard))
             }
         }
@@ -1155,6 +1360,24 @@ impl<'a, 'b, 'c> ExprTyper<'a, 'b, 'c> {
             (module_info.1.name.clone(), constructor.clone())
         };
 
+        if let Some(message) = &constructor.deprecation {
+            self.environment.warnings.push(Warning::Deprecated {
+                location: select_location.clone(),
+                name: format!("{}.{}", module_alias, label),
+                message: message.clone(),
+            });
+        }
+
+        if constructor.internal {
+            if let Some(package) = self.environment.other_package(module_alias) {
+                self.environment.warnings.push(Warning::Internal {
+                    location: select_location.clone(),
+                    name: format!("{}.{}", module_alias, label),
+                    package,
+                });
+            }
+        }
+
         Ok(TypedExpr::ModuleSelect {
             label,
             typ: self.instantiate(constructor.typ, self.environment.level, &mut hashmap![]),
@@ -1373,6 +1596,8 @@ impl<'a, 'b, 'c> ExprTyper<'a, 'b, 'c> {
             variant,
             origin,
             typ,
+            deprecation,
+            internal,
         } = self
             .environment
             .get_variable(name)
@@ -1398,6 +1623,14 @@ impl<'a, 'b, 'c> ExprTyper<'a, 'b, 'c> {
         // Register the value as seen for detection of unused values
         self.environment.value_used(name);
 
+        if let Some(message) = &deprecation {
+            self.environment.warnings.push(Warning::Deprecated {
+                location: location.clone(),
+                name: name.to_string(),
+                message: message.clone(),
+            });
+        }
+
         // Instantiate generic variables into unbound variables for this usage
         let typ = self.instantiate(typ, self.environment.level, &mut hashmap![]);
         Ok(ValueConstructor {
@@ -1405,6 +1638,8 @@ impl<'a, 'b, 'c> ExprTyper<'a, 'b, 'c> {
             variant,
             origin,
             typ,
+            deprecation,
+            internal,
         })
     }
 
@@ -1507,20 +1742,22 @@ impl<'a, 'b, 'c> ExprTyper<'a, 'b, 'c> {
                 // except the args are typed with infer_clause_guard() here.
                 // This duplication is a bit awkward but it works!
                 // Potentially this could be improved later
+                let arguments_location = call_arguments_location(fun.location(), &location);
                 match self
                     .get_field_map(&fun)
                     .map_err(|e| convert_get_value_constructor_error(e, &location))?
                 {
                     // The fun has a field map so labelled arguments may be present and need to be reordered.
-                    Some(field_map) => field_map.reorder(&mut args, &location)?,
+                    Some(field_map) => field_map.reorder(&mut args, &arguments_location)?,
 
                     // The fun has no field map and so we error if arguments have been labelled
                     None => assert_no_labelled_arguments(&args)?,
                 }
 
                 let (mut args_types, return_type) =
-                    match_fun_type(fun.typ(), args.len(), self.environment)
-                        .map_err(|e| convert_not_fun_error(e, fun.location(), &location))?;
+                    match_fun_type(fun.typ(), args.len(), self.environment).map_err(|e| {
+                        convert_not_fun_error(e, fun.location(), &arguments_location)
+                    })?;
                 let args = args_types
                     .iter_mut()
                     .zip(args)
@@ -1621,6 +1858,55 @@ impl<'a, 'b, 'c> ExprTyper<'a, 'b, 'c> {
             .field_map())
     }
 
+    fn get_record_fields(
+        &mut self,
+        constructor: &TypedExpr,
+    ) -> Result<Option<RecordFields>, GetValueConstructorError> {
+        let (module, name) = match constructor {
+            TypedExpr::ModuleSelect {
+                module_alias,
+                label,
+                ..
+            } => (Some(module_alias), label),
+
+            TypedExpr::Var { name, .. } => (None, name),
+
+            _ => return Ok(None),
+        };
+
+        let (name, field_map) = match &self
+            .environment
+            .get_value_constructor(module, name)?
+            .variant
+        {
+            ValueConstructorVariant::Record {
+                name, field_map, ..
+            } => (name.clone(), field_map.clone()),
+            _ => return Ok(None),
+        };
+
+        // The types of the fields are taken from the instantiated type of the
+        // constructor so that they are as specific as possible
+        let types = match collapse_links(constructor.typ()).as_ref() {
+            Type::Fn { args, .. } => args.clone(),
+            _ => return Ok(None),
+        };
+
+        let mut labels = vec![None; types.len()];
+        if let Some(field_map) = field_map {
+            for (label, index) in field_map.fields {
+                if let Some(slot) = labels.get_mut(index) {
+                    *slot = Some(label);
+                }
+            }
+        }
+
+        Ok(Some(RecordFields {
+            name,
+            fields: labels.into_iter().zip(types).collect(),
+        }))
+    }
+
     pub fn do_infer_call(
         &mut self,
         fun: UntypedExpr,
@@ -1638,13 +1924,24 @@ impl<'a, 'b, 'c> ExprTyper<'a, 'b, 'c> {
         mut args: Vec<CallArg<UntypedExpr>>,
         location: &SrcSpan,
     ) -> Result<(TypedExpr, Vec<TypedCallArg>, Arc<Type>), Error> {
+        // Record constructors report arity and type errors in terms of their fields
+        let record = self
+            .get_record_fields(&fun)
+            .map_err(|e| convert_get_value_constructor_error(e, location))?;
+        if let Some(record) = &record {
+            record.assert_arity(&args, location)?;
+        }
+
+        // An incorrect number of arguments is reported at the argument list
+        let arguments_location = call_arguments_location(fun.location(), location);
+
         // Check to see if the function accepts labelled arguments
         match self
             .get_field_map(&fun)
             .map_err(|e| convert_get_value_constructor_error(e, location))?
         {
             // The fun has a field map so labelled arguments may be present and need to be reordered.
-            Some(field_map) => field_map.reorder(&mut args, location)?,
+            Some(field_map) => field_map.reorder(&mut args, &arguments_location)?,
 
             // The fun has no field map and so we error if arguments have been labelled
             None => assert_no_labelled_arguments(&args)?,
@@ -1652,13 +1949,14 @@ impl<'a, 'b, 'c> ExprTyper<'a, 'b, 'c> {
 
         // Extract the type of the fun, ensuring it actually is a function
         let (mut args_types, return_type) = match_fun_type(fun.typ(), args.len(), self.environment)
-            .map_err(|e| convert_not_fun_error(e, fun.location(), location))?;
+            .map_err(|e| convert_not_fun_error(e, fun.location(), &arguments_location))?;
 
         // Ensure that the given args have the correct types
         let args = args_types
             .iter_mut()
             .zip(args)
-            .map(|(typ, arg): (&mut Arc<Type>, _)| {
+            .enumerate()
+            .map(|(index, (typ, arg)): (usize, (&mut Arc<Type>, _))| {
                 let CallArg {
                     label,
                     value,
@@ -1666,7 +1964,11 @@ impl<'a, 'b, 'c> ExprTyper<'a, 'b, 'c> {
                 } = arg;
                 let value = self.infer(value)?;
                 self.unify(typ.clone(), value.typ())
-                    .map_err(|e| convert_unify_error(e, value.location()))?;
+                    .map_err(|e| convert_unify_error_for_value(e, &value))
+                    .map_err(|e| match &record {
+                        Some(record) => record.field_type_error(e, index),
+                        None => e,
+                    })?;
                 Ok(CallArg {
                     label,
                     value,
@@ -1707,13 +2009,9 @@ impl<'a, 'b, 'c> ExprTyper<'a, 'b, 'c> {
         let body = self.in_new_scope(|body_typer| {
             for (arg, t) in args.iter().zip(args.iter().map(|arg| arg.typ.clone())) {
                 match &arg.names {
-                    ArgNames::Named { name } | ArgNames::NamedLabelled { name, .. } => {
-                        body_typer.environment.insert_variable(
-                            name.to_string(),
-                            ValueConstructorVariant::LocalVariable,
-                            t,
-                        )
-                    }
+                    ArgNames::Named { name } | ArgNames::NamedLabelled { name, .. } => body_typer
+                        .environment
+                        .insert_variable(name, ValueConstructorVariant::LocalVariable, t),
                     ArgNames::Discard { .. } | ArgNames::LabelledDiscard { .. } => (),
                 };
             }
@@ -1730,3 +2028,256 @@ impl<'a, 'b, 'c> ExprTyper<'a, 'b, 'c> {
         Ok((args, body))
     }
 }
+
+// Conditions are only considered constant if they are made from literals, so
+// that the warning is never emitted for a condition that could vary.
+fn is_constant_condition(expr: &TypedExpr) -> bool {
+    match expr {
+        TypedExpr::Var {
+            constructor:
+                ValueConstructor {
+                    variant: ValueConstructorVariant::Record { .. },
+                    typ,
+                    ..
+                },
+            ..
+        } => typ.is_bool(),
+
+        TypedExpr::BinOp {
+            name: BinOp::And,
+            left,
+            right,
+            ..
+        }
+        | TypedExpr::BinOp {
+            name: BinOp::Or,
+            left,
+            right,
+            ..
+        } => is_constant_condition(left) && is_constant_condition(right),
+
+        TypedExpr::BinOp {
+            name, left, right, ..
+        } if name.is_comparison() => is_constant_value(left) && is_constant_value(right),
+
+        _ => false,
+    }
+}
+
+fn is_constant_value(expr: &TypedExpr) -> bool {
+    match expr {
+        TypedExpr::Int { .. } | TypedExpr::Float { .. } | TypedExpr::String { .. } => true,
+        _ => is_constant_condition(expr),
+    }
+}
+
+fn is_constant_guard(guard: &TypedClauseGuard) -> bool {
+    match guard {
+        ClauseGuard::Constant(constant @ Constant::Record { .. }) => constant.typ().is_bool(),
+
+        ClauseGuard::And { left, right, .. } | ClauseGuard::Or { left, right, .. } => {
+            is_constant_guard(left) && is_constant_guard(right)
+        }
+
+        ClauseGuard::Equals { left, right, .. }
+        | ClauseGuard::NotEquals { left, right, .. }
+        | ClauseGuard::GtInt { left, right, .. }
+        | ClauseGuard::GtEqInt { left, right, .. }
+        | ClauseGuard::LtInt { left, right, .. }
+        | ClauseGuard::LtEqInt { left, right, .. }
+        | ClauseGuard::GtFloat { left, right, .. }
+        | ClauseGuard::GtEqFloat { left, right, .. }
+        | ClauseGuard::LtFloat { left, right, .. }
+        | ClauseGuard::LtEqFloat { left, right, .. } => {
+            is_constant_guard_value(left) && is_constant_guard_value(right)
+        }
+
+        _ => false,
+    }
+}
+
+fn is_constant_guard_value(guard: &TypedClauseGuard) -> bool {
+    match guard {
+        ClauseGuard::Constant(Constant::Int { .. })
+        | ClauseGuard::Constant(Constant::Float { .. })
+        | ClauseGuard::Constant(Constant::String { .. }) => true,
+        _ => is_constant_guard(guard),
+    }
+}
+
+/// Whether the case expression matches on a `Result` only to rebuild it
+/// unchanged, as in `case x { Ok(y) -> Ok(y) Error(e) -> Error(e) }`.
+///
+fn is_identity_rewrap(subjects: &[TypedExpr], clauses: &[TypedClause]) -> bool {
+    match subjects {
+        [subject] if collapse_links(subject.typ()).is_result() => (),
+        _ => return false,
+    }
+    let rewrapped: Vec<_> = clauses.iter().filter_map(rewrapped_constructor).collect();
+    clauses.len() == 2 && rewrapped.contains(&"Ok") && rewrapped.contains(&"Error")
+}
+
+/// The name of the constructor matched by a clause that returns a new value
+/// built with the same constructor from the value it matched, as in
+/// `Ok(x) -> Ok(x)`.
+///
+fn rewrapped_constructor(clause: &TypedClause) -> Option<&str> {
+    if clause.guard.is_some() || !clause.alternative_patterns.is_empty() {
+        return None;
+    }
+
+    let (constructor, bound) = match clause.pattern.as_slice() {
+        [Pattern::Constructor { name, args, .. }] => match args.as_slice() {
+            [CallArg {
+                value: Pattern::Var { name: var, .. },
+                ..
+            }] => (name, var),
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    match &clause.then {
+        TypedExpr::Call { fun, args, typ, .. } if collapse_links(typ.clone()).is_result() => {
+            match (fun.as_ref(), args.as_slice()) {
+                (
+                    TypedExpr::Var {
+                        name,
+                        constructor:
+                            ValueConstructor {
+                                variant: ValueConstructorVariant::Record { .. },
+                                ..
+                            },
+                        ..
+                    },
+                    [CallArg {
+                        value:
+                            TypedExpr::Var {
+                                name: arg,
+                                constructor:
+                                    ValueConstructor {
+                                        variant: ValueConstructorVariant::LocalVariable,
+                                        ..
+                                    },
+                                ..
+                            },
+                        ..
+                    }],
+                ) if name == constructor && arg == bound => Some(constructor.as_str()),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The fields of a record constructor that is being called, used to report
+/// errors in terms of the record's fields rather than function arguments.
+///
+struct RecordFields {
+    name: String,
+    fields: Vec<(Option<String>, Arc<Type>)>,
+}
+
+impl RecordFields {
+    fn assert_arity<A>(&self, args: &[CallArg<A>], location: &SrcSpan) -> Result<(), Error> {
+        let expected = self.fields.len();
+        if args.len() == expected {
+            return Ok(());
+        }
+
+        // Point to any extra arguments, or to the whole call if there are too few.
+        let location = match args.get(expected..) {
+            Some(extra) if !extra.is_empty() => SrcSpan {
+                start: extra[0].location.start,
+                end: extra[extra.len() - 1].location.end,
+            },
+            _ => location.clone(),
+        };
+
+        // Positional arguments fill the first fields, labelled arguments fill
+        // the field with their label.
+        let positional = args.iter().take_while(|arg| arg.label.is_none()).count();
+        let missing = self
+            .fields
+            .iter()
+            .enumerate()
+            .skip(positional)
+            .filter(|(_, (label, _))| {
+                label.is_none() || !args.iter().any(|arg| arg.label.as_ref() == label.as_ref())
+            })
+            .map(|(index, (label, typ))| MissingField {
+                index,
+                label: label.clone(),
+                typ: typ.clone(),
+            })
+            .collect();
+
+        Err(Error::IncorrectRecordArity {
+            location,
+            name: self.name.clone(),
+            expected,
+            given: args.len(),
+            missing,
+        })
+    }
+
+    fn field_type_error(&self, error: Error, index: usize) -> Error {
+        match error {
+            Error::CouldNotUnify {
+                location,
+                expected,
+                given,
+            } => Error::IncorrectRecordFieldType {
+                location,
+                name: self.name.clone(),
+                index,
+                label: self.fields.get(index).and_then(|(label, _)| label.clone()),
+                expected,
+                given,
+            },
+            _ => error,
+        }
+    }
+}
+
+/// A mismatch between the types of the operands of a binary operator and the
+/// type it expects is reported at the operator, naming the type of each
+/// operand.
+///
+fn convert_operand_unify_error(
+    e: UnifyError,
+    operator: &BinOp,
+    location: SrcSpan,
+    expected: Arc<Type>,
+    left: &TypedExpr,
+    right: &TypedExpr,
+) -> Error {
+    match e {
+        UnifyError::CouldNotUnify { .. }
+        | UnifyError::IncorrectFnArity { .. }
+        | UnifyError::CouldNotUnifyFnArg { .. } => Error::OperandTypeMismatch {
+            location,
+            operator: operator.clone(),
+            expected: collapse_links(expected),
+            left: collapse_links(left.typ()),
+            right: collapse_links(right.typ()),
+        },
+        _ => convert_unify_error(e, &location),
+    }
+}
+
+/// Functions cannot be compared, so using one with a comparison operator is
+/// reported as such rather than as a type mismatch.
+///
+fn assert_comparable(operator: &BinOp, operand: &TypedExpr) -> Result<(), Error> {
+    let typ = collapse_links(operand.typ());
+    match typ.as_ref() {
+        Type::Fn { .. } => Err(Error::NotComparable {
+            location: operand.location().clone(),
+            operator: operator.clone(),
+            typ,
+        }),
+        _ => Ok(()),
+    }
+}
@@ -99,6 +99,8 @@ impl Hydrator {
                 let TypeConstructor {
                     parameters,
                     typ: return_type,
+                    deprecation,
+                    internal,
                     ..
                 } = environment
                     .get_type_constructor(module, name)
@@ -112,6 +114,29 @@ impl Hydrator {
                     environment.type_used(name.as_str());
                 }
 
+                if let Some(message) = deprecation {
+                    environment.warnings.push(Warning::Deprecated {
+                        location: location.clone(),
+                        name: match module {
+                            Some(module) => format!("{}.{}", module, name),
+                            None => name.to_string(),
+                        },
+                        message,
+                    });
+                }
+
+                // Unqualified uses of an internal type of another package are
+                // warned about where the type is imported
+                if let (true, Some(module)) = (internal, module) {
+                    if let Some(package) = environment.other_package(module) {
+                        environment.warnings.push(Warning::Internal {
+                            location: location.clone(),
+                            name: format!("{}.{}", module, name),
+                            package,
+                        });
+                    }
+                }
+
                 // Ensure that the correct number of arguments have been given to the constructor
                 if args.len() != parameters.len() {
                     return Err(Error::IncorrectTypeArity {
@@ -0,0 +1,380 @@
+//! The public interface of a module, which is what other modules and packages
+//! can use of it, in a form that can be serialised. This lets a package
+//! registry describe a module, or a compiler use it, without the module's
+//! source code having to be parsed and type checked again.
+
+use super::{Module, Type, TypeVar, ValueConstructorVariant};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModuleInterface {
+    pub name: Vec<String>,
+    /// The public types of the module, in order of their names.
+    pub types: Vec<TypeInterface>,
+    /// The public functions of the module, including external functions, in
+    /// order of their names.
+    pub functions: Vec<FunctionInterface>,
+    /// The public constants of the module, in order of their names.
+    pub constants: Vec<ConstantInterface>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TypeInterface {
+    pub name: String,
+    pub parameters: Vec<TypeSignature>,
+    /// The public constructors of the type, in order of their names. This is
+    /// empty for opaque and external types.
+    pub constructors: Vec<FunctionInterface>,
+    pub deprecation: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionInterface {
+    pub name: String,
+    pub typ: TypeSignature,
+    /// The label of each argument, if it has one.
+    pub labels: Vec<Option<String>>,
+    pub deprecation: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConstantInterface {
+    pub name: String,
+    pub typ: TypeSignature,
+    pub deprecation: Option<String>,
+}
+
+/// A type as it appears in the interface. Type variables are numbered in the
+/// order they are first used by the function, constant, or type and its
+/// constructors.
+///
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum TypeSignature {
+    Named {
+        module: Vec<String>,
+        name: String,
+        args: Vec<TypeSignature>,
+    },
+
+    Fn {
+        args: Vec<TypeSignature>,
+        retrn: Box<TypeSignature>,
+    },
+
+    Var {
+        id: usize,
+    },
+
+    Tuple {
+        elems: Vec<TypeSignature>,
+    },
+}
+
+impl ModuleInterface {
+    /// The public interface of the type checked module, which does not include
+    /// its internal values and types.
+    ///
+    pub fn of_module(module: &Module) -> Self {
+        let mut functions = vec![];
+        let mut constants = vec![];
+        let mut constructors: HashMap<&str, Vec<_>> = HashMap::new();
+
+        for (name, value) in module
+            .values
+            .iter()
+            .filter(|(_, value)| value.public && !value.internal)
+        {
+            let mut signatures = Signatures::default();
+            let typ = signatures.of(&value.typ);
+            match &value.variant {
+                ValueConstructorVariant::LocalVariable => (),
+
+                ValueConstructorVariant::ModuleConstant { .. } => {
+                    constants.push(ConstantInterface {
+                        name: name.clone(),
+                        typ,
+                        deprecation: value.deprecation.clone(),
+                    })
+                }
+
+                ValueConstructorVariant::ModuleFn { field_map, .. } => {
+                    functions.push(FunctionInterface {
+                        name: name.clone(),
+                        labels: labels(field_map.as_ref(), &typ),
+                        typ,
+                        deprecation: value.deprecation.clone(),
+                    })
+                }
+
+                ValueConstructorVariant::Record { field_map, .. } => {
+                    if let Some(type_name) = constructed_type(module, &value.typ) {
+                        constructors.entry(type_name).or_default().push((
+                            value,
+                            name,
+                            field_map.as_ref(),
+                        ));
+                    }
+                }
+            }
+        }
+
+        let mut types: Vec<_> = module
+            .types
+            .iter()
+            .filter(|(_, typ)| typ.public && !typ.internal)
+            .map(|(name, typ)| {
+                // The type and its constructors share their type variables
+                let mut signatures = Signatures::default();
+                let parameters = typ.parameters.iter().map(|p| signatures.of(p)).collect();
+                let mut constructors: Vec<_> = constructors
+                    .remove(name.as_str())
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(value, name, field_map)| {
+                        let typ = signatures.of(&value.typ);
+                        FunctionInterface {
+                            name: name.clone(),
+                            labels: labels(field_map, &typ),
+                            typ,
+                            deprecation: value.deprecation.clone(),
+                        }
+                    })
+                    .collect();
+                constructors.sort_by(|a, b| a.name.cmp(&b.name));
+                TypeInterface {
+                    name: name.clone(),
+                    parameters,
+                    constructors,
+                    deprecation: typ.deprecation.clone(),
+                }
+            })
+            .collect();
+
+        types.sort_by(|a, b| a.name.cmp(&b.name));
+        functions.sort_by(|a, b| a.name.cmp(&b.name));
+        constants.sort_by(|a, b| a.name.cmp(&b.name));
+        Self {
+            name: module.name.clone(),
+            types,
+            functions,
+            constants,
+        }
+    }
+}
+
+/// The name of the custom type of the module that a record constructor of
+/// the given type constructs.
+///
+fn constructed_type<'a>(module: &Module, typ: &'a Type) -> Option<&'a str> {
+    let typ = match typ {
+        Type::Fn { retrn, .. } => retrn.as_ref(),
+        _ => typ,
+    };
+    match typ {
+        Type::App {
+            module: type_module,
+            name,
+            ..
+        } if type_module == &module.name => Some(name.as_str()),
+        _ => None,
+    }
+}
+
+fn labels(field_map: Option<&super::FieldMap>, typ: &TypeSignature) -> Vec<Option<String>> {
+    let arity = match typ {
+        TypeSignature::Fn { args, .. } => args.len(),
+        _ => 0,
+    };
+    let mut labels = vec![None; arity];
+    for (label, index) in field_map
+        .iter()
+        .flat_map(|field_map| field_map.fields.iter())
+    {
+        if let Some(slot) = labels.get_mut(*index) {
+            *slot = Some(label.clone());
+        }
+    }
+    labels
+}
+
+#[derive(Debug, Default)]
+struct Signatures {
+    vars: HashMap<usize, usize>,
+}
+
+impl Signatures {
+    fn of(&mut self, typ: &Type) -> TypeSignature {
+        match typ {
+            Type::App {
+                module, name, args, ..
+            } => TypeSignature::Named {
+                module: module.clone(),
+                name: name.clone(),
+                args: args.iter().map(|arg| self.of(arg)).collect(),
+            },
+
+            Type::Fn { args, retrn } => TypeSignature::Fn {
+                args: args.iter().map(|arg| self.of(arg)).collect(),
+                retrn: Box::new(self.of(retrn)),
+            },
+
+            Type::Tuple { elems } => TypeSignature::Tuple {
+                elems: elems.iter().map(|elem| self.of(elem)).collect(),
+            },
+
+            Type::Var { typ } => match &*typ.borrow() {
+                TypeVar::Link { typ } => self.of(typ),
+                TypeVar::Unbound { id, .. } | TypeVar::Generic { id } => {
+                    let next = self.vars.len();
+                    TypeSignature::Var {
+                        id: *self.vars.entry(*id).or_insert(next),
+                    }
+                }
+            },
+        }
+    }
+}
+
+#[test]
+fn of_module_test() {
+    let src = "
+/// A box
+pub type Box(a) {
+  Box(inner: a)
+  Empty
+}
+
+pub opaque type Id {
+  Id(Int)
+}
+
+type Private {
+  Private
+}
+
+pub external type Thing
+
+pub external fn thing() -> Thing = \"thing\" \"new\"
+
+pub const one = 1
+
+const two = 2
+
+@deprecated(\"Use map instead\")
+pub fn apply(box: Box(a), with f: fn(a) -> b) -> Box(b) {
+  case box {
+    Box(x) -> Box(f(x))
+    Empty -> Empty
+  }
+}
+
+pub fn swap(pair) {
+  let tuple(x, y) = pair
+  tuple(y, x)
+}
+
+fn private() {
+  two
+}
+";
+    let (src, _) = crate::parser::strip_extra(src);
+    let mut ast = crate::grammar::ModuleParser::new()
+        .parse(&src)
+        .expect("syntax error");
+    ast.name = vec!["my".to_string(), "box".to_string()];
+    let module = super::infer_module(&mut 0, ast, "", &HashMap::new(), &mut vec![])
+        .expect("should type check");
+    let interface = ModuleInterface::of_module(&module.type_info);
+
+    let named = |module: &[&str], name: &str, args| TypeSignature::Named {
+        module: module.iter().map(|s| s.to_string()).collect(),
+        name: name.to_string(),
+        args,
+    };
+    let fn_ = |args, retrn| TypeSignature::Fn {
+        args,
+        retrn: Box::new(retrn),
+    };
+    let var = |id| TypeSignature::Var { id };
+    let box_ = |arg| named(&["my", "box"], "Box", vec![arg]);
+    let thing = named(&["my", "box"], "Thing", vec![]);
+
+    assert_eq!(
+        ModuleInterface {
+            name: vec!["my".to_string(), "box".to_string()],
+            types: vec![
+                TypeInterface {
+                    name: "Box".to_string(),
+                    parameters: vec![var(0)],
+                    constructors: vec![
+                        FunctionInterface {
+                            name: "Box".to_string(),
+                            typ: fn_(vec![var(0)], box_(var(0))),
+                            labels: vec![Some("inner".to_string())],
+                            deprecation: None,
+                        },
+                        FunctionInterface {
+                            name: "Empty".to_string(),
+                            typ: box_(var(0)),
+                            labels: vec![],
+                            deprecation: None,
+                        },
+                    ],
+                    deprecation: None,
+                },
+                TypeInterface {
+                    name: "Id".to_string(),
+                    parameters: vec![],
+                    constructors: vec![],
+                    deprecation: None,
+                },
+                TypeInterface {
+                    name: "Thing".to_string(),
+                    parameters: vec![],
+                    constructors: vec![],
+                    deprecation: None,
+                },
+            ],
+            functions: vec![
+                FunctionInterface {
+                    name: "apply".to_string(),
+                    typ: fn_(vec![box_(var(0)), fn_(vec![var(0)], var(1))], box_(var(1))),
+                    labels: vec![None, Some("with".to_string())],
+                    deprecation: Some("Use map instead".to_string()),
+                },
+                FunctionInterface {
+                    name: "swap".to_string(),
+                    typ: fn_(
+                        vec![TypeSignature::Tuple {
+                            elems: vec![var(0), var(1)]
+                        }],
+                        TypeSignature::Tuple {
+                            elems: vec![var(1), var(0)]
+                        }
+                    ),
+                    labels: vec![None],
+                    deprecation: None,
+                },
+                FunctionInterface {
+                    name: "thing".to_string(),
+                    typ: fn_(vec![], thing),
+                    labels: vec![],
+                    deprecation: None,
+                },
+            ],
+            constants: vec![ConstantInterface {
+                name: "one".to_string(),
+                typ: named(&[], "Int", vec![]),
+                deprecation: None,
+            }],
+        },
+        interface
+    );
+
+    let json = serde_json::to_string(&interface).expect("should serialise");
+    assert!(json.contains(r#"{"kind":"var","id":0}"#));
+    let decoded: ModuleInterface = serde_json::from_str(&json).expect("should deserialise");
+    assert_eq!(interface, decoded);
+}
@@ -0,0 +1,394 @@
+use super::{collapse_links, ValueConstructorVariant, Warning};
+use crate::ast::{
+    visit::{walk_expr, Visit},
+    ArgNames, BinOp, BindingKind, CallArg, Pattern, SrcSpan, Statement, TypedArg, TypedExpr,
+    TypedModule, TypedStatement,
+};
+
+/// Find the functions that return a `Result` but can only ever return `Ok`,
+/// and so could return the wrapped value directly.
+///
+/// This lint is opt-in as a function may return a `Result` to fit an
+/// interface or to leave room for errors in future.
+///
+pub fn unnecessary_results(module: &TypedModule) -> Vec<Warning> {
+    let mut lint = UnnecessaryResults { warnings: vec![] };
+    lint.visit_module(module);
+    lint.warnings
+}
+
+struct UnnecessaryResults {
+    warnings: Vec<Warning>,
+}
+
+impl<'ast> Visit<'ast> for UnnecessaryResults {
+    fn visit_statement(&mut self, statement: &'ast TypedStatement) {
+        match statement {
+            Statement::Fn {
+                location,
+                name,
+                body,
+                return_type,
+                ..
+            } if collapse_links(return_type.clone()).is_result() && always_ok(body) => {
+                self.warnings.push(Warning::UnnecessaryResult {
+                    location: location.clone(),
+                    name: name.clone(),
+                })
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Whether every value the expression can evaluate to is constructed with
+/// `Ok`. Any expression that could produce an `Error`, such as a function
+/// call or a `try`, is assumed to do so.
+///
+fn always_ok(expr: &TypedExpr) -> bool {
+    match expr {
+        TypedExpr::Call { fun, typ, .. } => {
+            collapse_links(typ.clone()).is_result() && is_ok_constructor(fun)
+        }
+
+        TypedExpr::Seq { then, .. } => always_ok(then),
+
+        TypedExpr::Let { kind, then, .. } => *kind != BindingKind::Try && always_ok(then),
+
+        TypedExpr::Case { clauses, .. } => {
+            !clauses.is_empty() && clauses.iter().all(|clause| always_ok(&clause.then))
+        }
+
+        _ => false,
+    }
+}
+
+fn is_ok_constructor(fun: &TypedExpr) -> bool {
+    match fun {
+        TypedExpr::Var {
+            constructor, name, ..
+        } => {
+            name == "Ok"
+                && matches!(constructor.variant, ValueConstructorVariant::Record { .. })
+                && matches!(
+                    collapse_links(constructor.typ.clone()).return_type(),
+                    Some(typ) if typ.is_result()
+                )
+        }
+        _ => false,
+    }
+}
+
+/// Find the `let` type annotations that give the type the value would have
+/// without them, such as `let x: Int = 1`.
+///
+/// This lint is opt-in as an annotation may be written to document the code.
+///
+pub fn redundant_annotations(module: &TypedModule) -> Vec<Warning> {
+    let mut lint = RedundantAnnotations { warnings: vec![] };
+    lint.visit_module(module);
+    lint.warnings
+}
+
+struct RedundantAnnotations {
+    warnings: Vec<Warning>,
+}
+
+impl<'ast> Visit<'ast> for RedundantAnnotations {
+    fn visit_expr(&mut self, expr: &'ast TypedExpr) {
+        if let TypedExpr::Let {
+            redundant_annotation: Some(location),
+            ..
+        } = expr
+        {
+            self.warnings.push(Warning::RedundantAnnotation {
+                location: location.clone(),
+            })
+        }
+        walk_expr(self, expr)
+    }
+}
+
+/// Find the calls a function makes to itself that are not in tail position.
+/// The function has to wait for such a call to return before it can return
+/// itself, so each level of recursion uses more of the stack.
+///
+/// This lint is opt-in as a non-tail recursive function is often clearer and
+/// only needs much memory when the recursion is deep.
+///
+pub fn non_tail_recursion(module: &TypedModule) -> Vec<Warning> {
+    let mut warnings = vec![];
+    for statement in module.statements.iter() {
+        if let Statement::Fn { name, body, .. } = statement {
+            let mut lint = NonTailRecursion {
+                module: module.name.as_slice(),
+                name,
+                warnings: &mut warnings,
+            };
+            lint.tail_expr(body);
+        }
+    }
+    warnings
+}
+
+struct NonTailRecursion<'a> {
+    module: &'a [String],
+    name: &'a str,
+    warnings: &'a mut Vec<Warning>,
+}
+
+impl<'a> NonTailRecursion<'a> {
+    /// Check an expression whose value is returned by the function, so that a
+    /// call it ends with is in tail position.
+    ///
+    fn tail_expr(&mut self, expr: &TypedExpr) {
+        match expr {
+            TypedExpr::Call { fun, args, .. } if is_call_to(fun, self.module, self.name) => {
+                for arg in args {
+                    self.visit_expr(&arg.value);
+                }
+            }
+
+            TypedExpr::Pipe { left, right, .. } if is_call_to(right, self.module, self.name) => {
+                self.visit_expr(left)
+            }
+
+            TypedExpr::Seq { first, then, .. } => {
+                self.visit_expr(first);
+                self.tail_expr(then);
+            }
+
+            TypedExpr::Let { value, then, .. } => {
+                self.visit_expr(value);
+                self.tail_expr(then);
+            }
+
+            TypedExpr::Case {
+                subjects, clauses, ..
+            } => {
+                for subject in subjects {
+                    self.visit_expr(subject);
+                }
+                for clause in clauses {
+                    self.tail_expr(&clause.then);
+                }
+            }
+
+            // The right hand side of `&&` and `||` is compiled to a tail call
+            TypedExpr::BinOp {
+                name: BinOp::And,
+                left,
+                right,
+                ..
+            }
+            | TypedExpr::BinOp {
+                name: BinOp::Or,
+                left,
+                right,
+                ..
+            } => {
+                self.visit_expr(left);
+                self.tail_expr(right);
+            }
+
+            _ => self.visit_expr(expr),
+        }
+    }
+}
+
+impl<'a, 'ast> Visit<'ast> for NonTailRecursion<'a> {
+    fn visit_expr(&mut self, expr: &'ast TypedExpr) {
+        match expr {
+            TypedExpr::Call { location, fun, .. }
+            | TypedExpr::Pipe {
+                location,
+                right: fun,
+                ..
+            } if is_call_to(fun, self.module, self.name) => {
+                self.warnings.push(Warning::NonTailRecursion {
+                    location: location.clone(),
+                });
+                walk_expr(self, expr)
+            }
+
+            // The body of an anonymous function is returned by that function
+            TypedExpr::Fn { body, .. } => self.tail_expr(body),
+
+            _ => walk_expr(self, expr),
+        }
+    }
+}
+
+/// Whether the expression is the function with the given name from the given
+/// module.
+///
+fn is_call_to(fun: &TypedExpr, module: &[String], name: &str) -> bool {
+    match fun {
+        TypedExpr::Var { constructor, .. } => matches!(
+            &constructor.variant,
+            ValueConstructorVariant::ModuleFn { name: fn_name, module: fn_module, .. }
+                if fn_name == name && fn_module.as_slice() == module
+        ),
+        _ => false,
+    }
+}
+
+/// Find the functions that call themselves with their own arguments, unchanged,
+/// every time they are run, and so can never return.
+///
+/// Only the parts of the body that are always evaluated are considered, and
+/// the arguments must be the parameters themselves, so a function with a base
+/// case or that changes its arguments is never reported.
+///
+pub fn infinite_recursion(statements: &[TypedStatement], module: &[String]) -> Vec<Warning> {
+    statements
+        .iter()
+        .filter_map(|statement| match statement {
+            Statement::Fn {
+                name, args, body, ..
+            } => {
+                let recursion = Recursion { module, name, args };
+                let location = recursion.unconditional_call(body)?;
+                Some(Warning::InfiniteRecursion {
+                    location: location.clone(),
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+struct Recursion<'a> {
+    module: &'a [String],
+    name: &'a str,
+    args: &'a [TypedArg],
+}
+
+impl<'a> Recursion<'a> {
+    /// The location of a call to the function with its own arguments that
+    /// is made whenever the expression is evaluated.
+    ///
+    fn unconditional_call<'b>(&self, expr: &'b TypedExpr) -> Option<&'b SrcSpan> {
+        match expr {
+            TypedExpr::Call {
+                location,
+                fun,
+                args,
+                ..
+            } => {
+                if self.is_self(fun) && self.are_own_args(args) {
+                    return Some(location);
+                }
+                self.unconditional_call(fun).or_else(|| {
+                    args.iter()
+                        .find_map(|arg| self.unconditional_call(&arg.value))
+                })
+            }
+
+            TypedExpr::Seq { first, then, .. } => self
+                .unconditional_call(first)
+                .or_else(|| self.unconditional_call(then)),
+
+            TypedExpr::Let {
+                value,
+                pattern,
+                kind,
+                then,
+                ..
+            } => self.unconditional_call(value).or_else(|| {
+                // Rebinding a parameter may change the arguments of a later
+                // call, and the rest of a `try` only runs for an `Ok`
+                let shadows = match pattern {
+                    Pattern::Discard { .. } => false,
+                    Pattern::Var { name, .. } => self.is_param(name),
+                    _ => true,
+                };
+                if *kind == BindingKind::Let && !shadows {
+                    self.unconditional_call(then)
+                } else {
+                    None
+                }
+            }),
+
+            // The right hand side of `&&` and `||` is not always evaluated
+            TypedExpr::BinOp {
+                name: BinOp::And,
+                left,
+                ..
+            }
+            | TypedExpr::BinOp {
+                name: BinOp::Or,
+                left,
+                ..
+            } => self.unconditional_call(left),
+
+            TypedExpr::BinOp { left, right, .. } => self
+                .unconditional_call(left)
+                .or_else(|| self.unconditional_call(right)),
+
+            TypedExpr::Pipe { left, .. } => self.unconditional_call(left),
+
+            TypedExpr::Case { subjects, .. } => subjects
+                .iter()
+                .find_map(|subject| self.unconditional_call(subject)),
+
+            TypedExpr::ListCons { head, tail, .. } => self
+                .unconditional_call(head)
+                .or_else(|| self.unconditional_call(tail)),
+
+            TypedExpr::Tuple { elems, .. } => {
+                elems.iter().find_map(|elem| self.unconditional_call(elem))
+            }
+
+            TypedExpr::RecordAccess { record: value, .. }
+            | TypedExpr::TupleIndex { tuple: value, .. } => self.unconditional_call(value),
+
+            _ => None,
+        }
+    }
+
+    fn is_self(&self, fun: &TypedExpr) -> bool {
+        is_call_to(fun, self.module, self.name)
+    }
+
+    fn is_param(&self, name: &str) -> bool {
+        self.args.iter().any(|arg| match &arg.names {
+            ArgNames::Named { name: param } | ArgNames::NamedLabelled { name: param, .. } => {
+                param == name
+            }
+            _ => false,
+        })
+    }
+
+    /// Whether each argument is the parameter in the same position. Any
+    /// argument may be given for a discarded parameter.
+    ///
+    fn are_own_args(&self, args: &[CallArg<TypedExpr>]) -> bool {
+        args.len() == self.args.len()
+            && self
+                .args
+                .iter()
+                .zip(args)
+                .all(|(param, arg)| match (&param.names, &arg.value) {
+                    (ArgNames::Discard { .. } | ArgNames::LabelledDiscard { .. }, _) => true,
+
+                    (
+                        ArgNames::Named { name: param },
+                        TypedExpr::Var {
+                            name, constructor, ..
+                        },
+                    )
+                    | (
+                        ArgNames::NamedLabelled { name: param, .. },
+                        TypedExpr::Var {
+                            name, constructor, ..
+                        },
+                    ) => {
+                        param == name
+                            && matches!(constructor.variant, ValueConstructorVariant::LocalVariable)
+                    }
+
+                    _ => false,
+                })
+    }
+}
@@ -42,11 +42,8 @@ impl<'a, 'b, 'c> PatternTyper<'a, 'b, 'c> {
                     });
                 }
                 self.initial_pattern_vars.insert(name.to_string());
-                self.environment.insert_variable(
-                    name.to_string(),
-                    ValueConstructorVariant::LocalVariable,
-                    typ,
-                );
+                self.environment
+                    .insert_variable(name, ValueConstructorVariant::LocalVariable, typ);
                 Ok(())
             }
 
@@ -275,11 +272,11 @@ impl<'a, 'b, 'c> PatternTyper<'a, 'b, 'c> {
             Pattern::Tuple { elems, location } => match &*collapse_links(typ.clone()) {
                 Type::Tuple { elems: type_elems } => {
                     if elems.len() != type_elems.len() {
-                        return Err(Error::IncorrectArity {
-                            labels: vec![],
+                        return Err(Error::IncorrectTupleArity {
                             location,
                             expected: type_elems.len(),
                             given: elems.len(),
+                            typ: typ.clone(),
                         });
                     }
 
@@ -390,9 +387,14 @@ impl<'a, 'b, 'c> PatternTyper<'a, 'b, 'c> {
 
                 let constructor_typ = cons.typ.clone();
                 let constructor = match cons.variant {
-                    ValueConstructorVariant::Record { ref name, .. } => {
-                        PatternConstructor::Record { name: name.clone() }
-                    }
+                    ValueConstructorVariant::Record {
+                        ref name,
+                        constructors_count,
+                        ..
+                    } => PatternConstructor::Record {
+                        name: name.clone(),
+                        constructors_count,
+                    },
                     ValueConstructorVariant::LocalVariable
                     | ValueConstructorVariant::ModuleConstant { .. }
                     | ValueConstructorVariant::ModuleFn { .. } => crate::error::fatal_compiler_bug(
@@ -94,42 +94,48 @@ pub fn utf_codepoint() -> Arc<Type> {
 pub fn register_prelude<'a, 'b>(mut typer: Environment<'a, 'b>) -> Environment<'a, 'b> {
     typer
         .insert_type_constructor(
-            "Int".to_string(),
+            "Int",
             TypeConstructor {
                 parameters: vec![],
                 typ: int(),
                 origin: Default::default(),
                 module: vec![],
                 public: true,
+                deprecation: None,
+                internal: false,
             },
         )
         .gleam_expect("prelude inserting Int type");
 
     typer.insert_variable(
-        "True".to_string(),
+        "True",
         ValueConstructorVariant::Record {
             name: "True".to_string(),
             field_map: None,
+            constructors_count: 2,
         },
         bool(),
     );
     typer.insert_variable(
-        "False".to_string(),
+        "False",
         ValueConstructorVariant::Record {
             name: "False".to_string(),
             field_map: None,
+            constructors_count: 2,
         },
         bool(),
     );
     typer
         .insert_type_constructor(
-            "Bool".to_string(),
+            "Bool",
             TypeConstructor {
                 origin: Default::default(),
                 parameters: vec![],
                 typ: bool(),
                 module: vec![],
                 public: true,
+                deprecation: None,
+                internal: false,
             },
         )
         .gleam_expect("prelude inserting Bool type");
@@ -137,39 +143,45 @@ pub fn register_prelude<'a, 'b>(mut typer: Environment<'a, 'b>) -> Environment<'
     let list_parameter = typer.new_generic_var();
     typer
         .insert_type_constructor(
-            "List".to_string(),
+            "List",
             TypeConstructor {
                 origin: Default::default(),
                 parameters: vec![list_parameter.clone()],
                 typ: list(list_parameter),
                 module: vec![],
                 public: true,
+                deprecation: None,
+                internal: false,
             },
         )
         .gleam_expect("prelude inserting List type");
 
     typer
         .insert_type_constructor(
-            "Float".to_string(),
+            "Float",
             TypeConstructor {
                 origin: Default::default(),
                 parameters: vec![],
                 typ: float(),
                 module: vec![],
                 public: true,
+                deprecation: None,
+                internal: false,
             },
         )
         .gleam_expect("prelude inserting Float type");
 
     typer
         .insert_type_constructor(
-            "String".to_string(),
+            "String",
             TypeConstructor {
                 origin: Default::default(),
                 parameters: vec![],
                 typ: string(),
                 module: vec![],
                 public: true,
+                deprecation: None,
+                internal: false,
             },
         )
         .gleam_expect("prelude inserting String type");
@@ -178,60 +190,69 @@ pub fn register_prelude<'a, 'b>(mut typer: Environment<'a, 'b>) -> Environment<'
     let result_error = typer.new_generic_var();
     typer
         .insert_type_constructor(
-            "Result".to_string(),
+            "Result",
             TypeConstructor {
                 origin: Default::default(),
                 parameters: vec![result_value.clone(), result_error.clone()],
                 typ: result(result_value, result_error),
                 module: vec![],
                 public: true,
+                deprecation: None,
+                internal: false,
             },
         )
         .gleam_expect("prelude inserting Result type");
 
     typer.insert_variable(
-        "Nil".to_string(),
+        "Nil",
         ValueConstructorVariant::Record {
             name: "Nil".to_string(),
             field_map: None,
+            constructors_count: 1,
         },
         nil(),
     );
     typer
         .insert_type_constructor(
-            "Nil".to_string(),
+            "Nil",
             TypeConstructor {
                 origin: Default::default(),
                 parameters: vec![],
                 typ: nil(),
                 module: vec![],
                 public: true,
+                deprecation: None,
+                internal: false,
             },
         )
         .gleam_expect("prelude inserting Nil type");
 
     typer
         .insert_type_constructor(
-            "BitString".to_string(),
+            "BitString",
             TypeConstructor {
                 origin: Default::default(),
                 parameters: vec![],
                 typ: bit_string(),
                 module: vec![],
                 public: true,
+                deprecation: None,
+                internal: false,
             },
         )
         .gleam_expect("prelude inserting BitString type");
 
     typer
         .insert_type_constructor(
-            "UtfCodepoint".to_string(),
+            "UtfCodepoint",
             TypeConstructor {
                 origin: Default::default(),
                 parameters: vec![],
                 typ: utf_codepoint(),
                 module: vec![],
                 public: true,
+                deprecation: None,
+                internal: false,
             },
         )
         .gleam_expect("prelude inserting UTF Codepoint type");
@@ -239,10 +260,11 @@ pub fn register_prelude<'a, 'b>(mut typer: Environment<'a, 'b>) -> Environment<'
     let ok = typer.new_generic_var();
     let error = typer.new_generic_var();
     typer.insert_variable(
-        "Ok".to_string(),
+        "Ok",
         ValueConstructorVariant::Record {
             name: "Ok".to_string(),
             field_map: None,
+            constructors_count: 2,
         },
         fn_(vec![ok.clone()], result(ok, error)),
     );
@@ -250,10 +272,11 @@ pub fn register_prelude<'a, 'b>(mut typer: Environment<'a, 'b>) -> Environment<'
     let ok = typer.new_generic_var();
     let error = typer.new_generic_var();
     typer.insert_variable(
-        "Error".to_string(),
+        "Error",
         ValueConstructorVariant::Record {
             name: "Error".to_string(),
             field_map: None,
+            constructors_count: 2,
         },
         fn_(vec![error.clone()], result(ok, error)),
     );
@@ -1,6 +1,7 @@
 use super::{Type, TypeVar};
 use crate::pretty::{nil, *};
 use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 #[cfg(test)]
@@ -13,6 +14,7 @@ const INDENT: isize = 2;
 pub struct Printer {
     names: im::HashMap<usize, String>,
     uid: usize,
+    qualified: HashSet<String>,
 }
 
 impl Printer {
@@ -20,6 +22,28 @@ impl Printer {
         Self {
             names: im::HashMap::new(),
             uid: 0,
+            qualified: HashSet::new(),
+        }
+    }
+
+    /// A printer for types that are shown together, such as the expected and
+    /// given types of an error. Names that refer to types from more than one
+    /// module among them are printed with their module, as `one.Box` and
+    /// `two.Box`, so that the types can be told apart. Prelude types are
+    /// qualified with `gleam`.
+    ///
+    pub fn disambiguating(types: &[&Type]) -> Self {
+        let mut modules = HashMap::new();
+        for typ in types {
+            collect_type_modules(typ, &mut modules);
+        }
+        Self {
+            qualified: modules
+                .into_iter()
+                .filter(|(_, modules)| modules.len() > 1)
+                .map(|(name, _)| name)
+                .collect(),
+            ..Self::new()
         }
     }
 
@@ -39,12 +63,23 @@ impl Printer {
 
     pub fn print(&mut self, typ: &Type) -> Document {
         match typ {
-            Type::App { name, args, .. } => {
-                if args.is_empty() {
-                    name.clone().to_doc()
+            Type::App {
+                name, args, module, ..
+            } => {
+                let name = if self.qualified.contains(name) {
+                    let module = if module.is_empty() {
+                        "gleam".to_string()
+                    } else {
+                        module.join("/")
+                    };
+                    format!("{}.{}", module, name)
                 } else {
                     name.clone()
-                        .to_doc()
+                };
+                if args.is_empty() {
+                    name.to_doc()
+                } else {
+                    name.to_doc()
                         .append("(")
                         .append(self.args_to_gleam_doc(args.as_slice()))
                         .append(")")
@@ -128,6 +163,43 @@ impl Printer {
     }
 }
 
+/// Record the modules of each named type in the type and its arguments.
+///
+fn collect_type_modules(typ: &Type, modules: &mut HashMap<String, HashSet<Vec<String>>>) {
+    match typ {
+        Type::App {
+            name, module, args, ..
+        } => {
+            let _ = modules
+                .entry(name.clone())
+                .or_default()
+                .insert(module.clone());
+            for arg in args {
+                collect_type_modules(arg, modules);
+            }
+        }
+
+        Type::Fn { args, retrn } => {
+            for arg in args {
+                collect_type_modules(arg, modules);
+            }
+            collect_type_modules(retrn, modules);
+        }
+
+        Type::Var { typ } => {
+            if let TypeVar::Link { typ } = &*typ.borrow() {
+                collect_type_modules(typ, modules);
+            }
+        }
+
+        Type::Tuple { elems } => {
+            for elem in elems {
+                collect_type_modules(elem, modules);
+            }
+        }
+    }
+}
+
 #[test]
 fn next_letter_test() {
     let mut printer = Printer::new();
@@ -413,6 +485,42 @@ fn function_test() {
     );
 }
 
+#[test]
+fn disambiguating_test() {
+    let local_result = Type::App {
+        module: vec!["my".to_string(), "result".to_string()],
+        name: "Result".to_string(),
+        public: true,
+        args: vec![int()],
+    };
+    let prelude_result = result(int(), string());
+
+    // Names are qualified when they refer to types from different modules
+    let mut printer = Printer::disambiguating(&[&local_result, &prelude_result]);
+    assert_eq!(
+        "my/result.Result(Int)",
+        printer.pretty_print(&local_result, 0)
+    );
+    assert_eq!(
+        "gleam.Result(Int, String)",
+        printer.pretty_print(&prelude_result, 0)
+    );
+
+    let list = list(prelude_result.clone());
+    let mut printer = Printer::disambiguating(&[&list, &local_result]);
+    assert_eq!(
+        "List(gleam.Result(Int, String))",
+        printer.pretty_print(&list, 0)
+    );
+
+    // And otherwise left unqualified
+    let mut printer = Printer::disambiguating(&[&prelude_result, &result(float(), bool())]);
+    assert_eq!(
+        "Result(Int, String)",
+        printer.pretty_print(&prelude_result, 0)
+    );
+}
+
 #[cfg(test)]
 fn pretty_print(typ: Arc<Type>) -> String {
     Printer::new().pretty_print(&typ, 0)
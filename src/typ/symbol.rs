@@ -0,0 +1,65 @@
+use std::borrow::Borrow;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+/// An interned identifier, used as the key of the type checker's scopes.
+///
+/// Symbols are created by the `SymbolTable` of the module being checked, so
+/// that symbols with the same text share a single allocation. Creating a
+/// symbol for a name that has been seen before does not allocate, cloning a
+/// symbol is only a reference count increment, and symbols are compared by
+/// their pointers rather than their text.
+///
+/// Symbols are hashed by their text so that they can still be looked up with
+/// a `&str`.
+///
+#[derive(Debug, Clone)]
+pub struct Symbol(Arc<str>);
+
+impl PartialEq for Symbol {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for Symbol {}
+
+impl Hash for Symbol {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+impl Borrow<str> for Symbol {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for Symbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// The symbols of the module being type checked. Symbols from different
+/// tables must not be compared, as they are compared by pointer.
+///
+#[derive(Debug, Default)]
+pub struct SymbolTable {
+    symbols: HashSet<Arc<str>>,
+}
+
+impl SymbolTable {
+    pub fn intern(&mut self, name: &str) -> Symbol {
+        match self.symbols.get(name) {
+            Some(symbol) => Symbol(symbol.clone()),
+            None => {
+                let symbol: Arc<str> = Arc::from(name);
+                let _ = self.symbols.insert(symbol.clone());
+                Symbol(symbol)
+            }
+        }
+    }
+}
@@ -9,7 +9,7 @@ pub fn env_types_with(things: &[&str]) -> Vec<String> {
 }
 
 pub fn env_types() -> Vec<String> {
-    Environment::new(&mut 0, &[], &HashMap::new(), &mut vec![])
+    Environment::new(&mut 0, &[], "", &HashMap::new(), &mut vec![])
         .module_types
         .keys()
         .map(|s| s.to_string())
@@ -25,7 +25,7 @@ pub fn env_vars_with(things: &[&str]) -> Vec<String> {
 }
 
 pub fn env_vars() -> Vec<String> {
-    Environment::new(&mut 0, &[], &HashMap::new(), &mut vec![])
+    Environment::new(&mut 0, &[], "", &HashMap::new(), &mut vec![])
         .local_values
         .keys()
         .map(|s| s.to_string())
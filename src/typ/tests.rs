@@ -1,6 +1,6 @@
 use super::test_helpers::*;
 use super::*;
-use crate::ast::UntypedExpr;
+use crate::ast::{BinOp, UntypedExpr};
 
 macro_rules! assert_infer {
     ($src:expr, $typ:expr $(,)?) => {
@@ -13,6 +13,7 @@ macro_rules! assert_infer {
         let result = ExprTyper::new(&mut Environment::new(
             &mut 0,
             &[],
+            "",
             &HashMap::new(),
             &mut vec![],
         ))
@@ -32,7 +33,7 @@ macro_rules! assert_module_error {
             .parse(&src)
             .expect("syntax error");
         ast.name = vec!["my_module".to_string()];
-        let ast = infer_module(&mut 0, ast, &HashMap::new(), &mut vec![])
+        let ast = infer_module(&mut 0, ast, "", &HashMap::new(), &mut vec![])
             .expect_err("should infer an error");
         assert_eq!(($src, sort_options($error)), ($src, sort_options(ast)));
     };
@@ -41,7 +42,8 @@ macro_rules! assert_module_error {
         let ast = crate::grammar::ModuleParser::new()
             .parse($src)
             .expect("syntax error");
-        infer_module(&mut 0, ast, &HashMap::new(), &mut vec![]).expect_err("should infer an error");
+        infer_module(&mut 0, ast, "", &HashMap::new(), &mut vec![])
+            .expect_err("should infer an error");
     };
 }
 
@@ -53,6 +55,7 @@ macro_rules! assert_error {
         let result = ExprTyper::new(&mut Environment::new(
             &mut 0,
             &[],
+            "",
             &HashMap::new(),
             &mut vec![],
         ))
@@ -69,7 +72,7 @@ macro_rules! assert_module_infer {
         let ast = crate::grammar::ModuleParser::new()
             .parse(&src)
             .expect("syntax error");
-        let ast = infer_module(&mut 0, ast, &HashMap::new(), &mut vec![])
+        let ast = infer_module(&mut 0, ast, "", &HashMap::new(), &mut vec![])
             .expect("should successfully infer");
         let mut constructors: Vec<(_, _)> = ast
             .type_info
@@ -97,7 +100,7 @@ macro_rules! assert_warning {
             .expect("syntax error");
         ast.name = vec!["my_module".to_string()];
         let mut warnings = vec![];
-        let _ = infer_module(&mut 0, ast, &HashMap::new(), &mut warnings);
+        let _ = infer_module(&mut 0, ast, "", &HashMap::new(), &mut warnings);
 
         assert!(!warnings.is_empty());
         assert_eq!($warning, warnings[0]);
@@ -113,12 +116,25 @@ macro_rules! assert_no_warnings {
         ast.name = vec!["my_module".to_string()];
         let expected: Vec<Warning> = vec![];
         let mut warnings = vec![];
-        let _ = infer_module(&mut 0, ast, &HashMap::new(), &mut warnings);
+        let _ = infer_module(&mut 0, ast, "", &HashMap::new(), &mut warnings);
 
         assert_eq!(expected, warnings);
     };
 }
 
+/// Infer the module, which must type check, returning it along with its
+/// warnings.
+///
+fn infer_with_warnings(src: &str) -> (TypedModule, Vec<Warning>) {
+    let mut ast = crate::grammar::ModuleParser::new()
+        .parse(src)
+        .expect("syntax error");
+    ast.name = vec!["my_module".to_string()];
+    let mut warnings = vec![];
+    let module = infer_module(&mut 0, ast, "", &HashMap::new(), &mut warnings).expect("type error");
+    (module, warnings)
+}
+
 #[test]
 fn field_map_reorder_test() {
     let int = |value: &str| UntypedExpr::Int {
@@ -246,15 +262,20 @@ fn infer_module_type_retention_test() {
         name: vec!["ok".to_string()],
         statements: vec![],
         type_info: (),
+        target_attributes: vec![],
+        deprecated_attributes: vec![],
+        internal_attributes: vec![],
+        allow_attributes: vec![],
     };
     let mut uid = 0;
     let module =
-        infer_module(&mut uid, module, &HashMap::new(), &mut vec![]).expect("Should infer OK");
+        infer_module(&mut uid, module, "", &HashMap::new(), &mut vec![]).expect("Should infer OK");
 
     assert_eq!(
         module.type_info,
         Module {
             name: vec!["ok".to_string()],
+            package: "".to_string(),
             types: HashMap::new(), // Core type constructors like String and Int are not included
             values: HashMap::new(),
             accessors: HashMap::new(),
@@ -295,6 +316,26 @@ fn simple_exprs() {
     assert_infer!("todo != 1", "Bool");
     assert_infer!("todo + 1", "Int");
     assert_infer!("todo(\"test\") + 1", "Int");
+    assert_infer!("todo as \"test\"", "a");
+    assert_infer!("let message = \"test\" todo as message", "a");
+
+    // panic
+    assert_infer!("panic", "a");
+    assert_infer!("panic + 1", "Int");
+    assert_infer!("let message = \"test\" panic as message", "a");
+    assert_infer!("fn(x) { panic as x }", "fn(String) -> a");
+
+    // assert
+    assert_infer!("assert Ok(x) = Ok(1) as \"not ok\" x", "Int");
+    assert_infer!(
+        "fn(x, m) { assert 1 = x as m m }",
+        "fn(Int, String) -> String"
+    );
+
+    // raw erlang
+    assert_infer!("@erlang(\"1\") as Int", "Int");
+    assert_infer!("@erlang(\"[]\") as List(a)", "List(a)");
+    assert_infer!("{ @erlang(\"1\") as Int } + 1", "Int");
 
     // hex, octal, and binary literals
     assert_infer!("0xF", "Int");
@@ -340,6 +381,10 @@ fn let_() {
     assert_infer!("let _x = 1 2.0", "Float");
     assert_infer!("let _ = 1 2.0", "Float");
     assert_infer!("let tuple(tag, x) = tuple(1.0, 1) x", "Int");
+    assert_infer!(
+        "let tuple(a, tuple(b, c)) = tuple(1, tuple(2.0, \"three\")) tuple(c, b, a)",
+        "tuple(String, Float, Int)"
+    );
     assert_infer!("fn(x) { let tuple(a, b) = x a }", "fn(tuple(a, b)) -> a");
 
     // assert
@@ -410,6 +455,35 @@ fn tuples() {
     );
 }
 
+#[test]
+fn hash_tuples() {
+    assert_infer!("#(1, 2.0, \"3\")", "tuple(Int, Float, String)");
+    assert_infer!("#(1, tuple(2.0))", "tuple(Int, tuple(Float))");
+    assert_infer!("#()", "tuple()");
+
+    // A single element tuple, rather than an expression in brackets
+    assert_infer!("#(1)", "tuple(Int)");
+    assert_infer!("{ 1 }", "Int");
+
+    assert_infer!("let #(a, b) = tuple(1, 2.0) b", "Float");
+    assert_infer!(
+        "let x: #(Int, String) = #(1, \"one\") x",
+        "tuple(Int, String)"
+    );
+    assert_infer!("case #(1, 2) { x if x == #(1, 2) -> 1 }", "Int");
+    assert_module_infer!(
+        "pub const origin = #(0, 0)
+         pub fn swap(pair: #(a, b)) -> #(b, a) {
+           let #(x, y) = pair
+           #(y, x)
+         }",
+        vec![
+            ("origin", "tuple(Int, Int)"),
+            ("swap", "fn(tuple(a, b)) -> tuple(b, a)")
+        ],
+    );
+}
+
 #[test]
 fn expr_fn() {
     assert_infer!("fn(x) { x }", "fn(a) -> a");
@@ -478,6 +552,11 @@ fn case() {
     assert_infer!("case 2.0 { 2.0 -> 1 x -> 0 }", "Int");
     assert_infer!(r#"case "ok" { "ko" -> 1 x -> 0 }"#, "Int");
 
+    // Negative literals
+    assert_infer!("case 1 { -1 -> 10 x -> x }", "Int");
+    assert_infer!("case 1 { -0xF -> 10 -0b1 -> 20 -0o7 -> 30 x -> x }", "Int");
+    assert_infer!("case 2.0 { -0.0 -> 1 -2.5 -> 2 x -> 0 }", "Int");
+
     // Multiple subject case
     assert_infer!("case 1, 2.0 { a, b -> a }", "Int");
     assert_infer!("case 1, 2.0 { a, b -> b }", "Float");
@@ -536,6 +615,19 @@ fn bit_strings() {
         "BitString"
     );
 
+    // Integers are the default type, so can be signed or given an endianness
+    // without their type
+    assert_infer!("let <<x:little-signed-size(16)>> = <<1, 2>> x", "Int");
+    assert_infer!("let <<x:int-big-unsigned>> = <<1>> x", "Int");
+    assert_infer!("let <<x:float-little>> = <<1.0:float>> x", "Float");
+    assert_infer!("<<\"a\":utf32-native>>", "BitString");
+
+    // The rest of a bit string after fixed size segments
+    assert_infer!(
+        "case <<1, 2, 3>> { <<_:8, header:bytes-size(1), rest:bits>> -> tuple(header, rest) }",
+        "tuple(BitString, BitString)"
+    );
+
     assert_module_error!(
         "fn x() { \"test\" }
 
@@ -575,6 +667,61 @@ fn main() {
     );
 }
 
+#[test]
+fn string_bit_string_conversion_test() {
+    // Strings are encoded into bit strings with the utf segment types
+    assert_infer!("<<\"hello\":utf8>>", "BitString");
+    assert_infer!(
+        "let s = \"hello\" <<s:utf8, \" \":utf16, s:utf32>>",
+        "BitString"
+    );
+    assert_infer!("fn(s) { <<s:utf8>> }", "fn(String) -> BitString");
+
+    // And matched out of them by their literal value or by codepoint
+    assert_infer!(
+        "case <<\"hello\":utf8>> { <<\"he\":utf8, rest:binary>> -> rest }",
+        "BitString"
+    );
+    assert_infer!(
+        "let <<c:utf8_codepoint, _:binary>> = <<\"hello\":utf8>> c",
+        "UtfCodepoint"
+    );
+
+    assert_error!(
+        "let x = <<1:utf8>> x",
+        Error::CouldNotUnify {
+            location: SrcSpan { start: 10, end: 11 },
+            expected: string(),
+            given: int(),
+        }
+    );
+
+    assert_error!(
+        "let x = <<\"hello\":binary>> x",
+        Error::CouldNotUnify {
+            location: SrcSpan { start: 10, end: 17 },
+            expected: bit_string(),
+            given: string(),
+        }
+    );
+
+    assert_error!(
+        "let x = <<\"hello\":utf8-size(8)>> x",
+        Error::BinaryTypeDoesNotAllowSize {
+            typ: "utf8".to_string(),
+            location: SrcSpan { start: 23, end: 30 },
+        }
+    );
+
+    assert_error!(
+        "case <<1>> { <<\"a\":utf16-size(1)>> -> 1 }",
+        Error::BinaryTypeDoesNotAllowSize {
+            typ: "utf16".to_string(),
+            location: SrcSpan { start: 25, end: 32 },
+        }
+    );
+}
+
 #[test]
 fn infer_bit_string_error_test() {
     assert_error!(
@@ -636,6 +783,14 @@ fn infer_bit_string_error_test() {
         },
     );
 
+    // Only the last segment can bind the rest of the bit string
+    assert_error!(
+        "case <<1, 2>> { <<rest:bits, x:8>> -> x }",
+        Error::BinarySegmentMustHaveSize {
+            location: SrcSpan { start: 18, end: 27 },
+        },
+    );
+
     // Options
 
     assert_error!(
@@ -759,6 +914,38 @@ fn infer_bit_string_error_test() {
         }
     );
 
+    assert_error!(
+        "let x = <<1.0:float-signed>> x",
+        Error::BinaryTypeDoesNotAllowSignedness {
+            typ: "float".to_string(),
+            location: SrcSpan { start: 20, end: 26 },
+        }
+    );
+
+    assert_error!(
+        "case <<1>> { <<x:binary-unsigned>> -> x }",
+        Error::BinaryTypeDoesNotAllowSignedness {
+            typ: "binary".to_string(),
+            location: SrcSpan { start: 24, end: 32 },
+        }
+    );
+
+    assert_error!(
+        "let x = <<\"a\":utf8-little>> x",
+        Error::BinaryTypeDoesNotAllowEndianness {
+            typ: "utf8".to_string(),
+            location: SrcSpan { start: 19, end: 25 },
+        }
+    );
+
+    assert_error!(
+        "case <<1>> { <<x:bit_string-big>> -> x }",
+        Error::BinaryTypeDoesNotAllowEndianness {
+            typ: "bit_string".to_string(),
+            location: SrcSpan { start: 28, end: 31 },
+        }
+    );
+
     // Size and unit values
 
     assert_error!(
@@ -780,71 +967,215 @@ fn infer_bit_string_error_test() {
     );
 }
 
+#[test]
+fn bit_string_int_does_not_fit_test() {
+    // Literals that fit in the bits of their segment are accepted
+    assert_infer!("<<255:8>>", "BitString");
+    assert_infer!("<<-128:8>>", "BitString");
+    assert_infer!("<<300:16>>", "BitString");
+    assert_infer!("<<255>>", "BitString");
+    assert_infer!("<<1023:size(5)-unit(2)>>", "BitString");
+    assert_infer!("let n = 8 <<300:size(n)>>", "BitString");
+
+    assert_error!(
+        "<<300:8>>",
+        Error::BinarySegmentIntDoesNotFit {
+            location: SrcSpan { start: 2, end: 5 },
+            value: 300,
+            bits: 8,
+        },
+    );
+
+    assert_error!(
+        "<<-129:8>>",
+        Error::BinarySegmentIntDoesNotFit {
+            location: SrcSpan { start: 2, end: 6 },
+            value: -129,
+            bits: 8,
+        },
+    );
+
+    // Integer segments are 8 bits by default
+    assert_error!(
+        "<<256>>",
+        Error::BinarySegmentIntDoesNotFit {
+            location: SrcSpan { start: 2, end: 5 },
+            value: 256,
+            bits: 8,
+        },
+    );
+
+    assert_error!(
+        "<<0x400:size(5)-unit(2)>>",
+        Error::BinarySegmentIntDoesNotFit {
+            location: SrcSpan { start: 2, end: 7 },
+            value: 1024,
+            bits: 10,
+        },
+    );
+
+    assert_module_error!(
+        "const bits = <<1, 2, 300:8>>",
+        Error::BinarySegmentIntDoesNotFit {
+            location: SrcSpan { start: 21, end: 24 },
+            value: 300,
+            bits: 8,
+        },
+    );
+}
+
 #[test]
 fn binop_unification_errors() {
     assert_error!(
         "1 + 1.0",
-        Error::CouldNotUnify {
-            location: SrcSpan { start: 4, end: 7 },
+        Error::OperandTypeMismatch {
+            location: SrcSpan { start: 2, end: 3 },
+            operator: BinOp::AddInt,
             expected: int(),
-            given: float(),
+            left: int(),
+            right: float(),
         },
     );
 
     assert_error!(
         "1 +. 1.0",
-        Error::CouldNotUnify {
-            location: SrcSpan { start: 0, end: 1 },
+        Error::OperandTypeMismatch {
+            location: SrcSpan { start: 2, end: 4 },
+            operator: BinOp::AddFloat,
             expected: float(),
-            given: int(),
+            left: int(),
+            right: float(),
         },
     );
 
     assert_error!(
         "1 == 1.0",
-        Error::CouldNotUnify {
-            location: SrcSpan { start: 5, end: 8 },
+        Error::OperandTypeMismatch {
+            location: SrcSpan { start: 2, end: 4 },
+            operator: BinOp::Eq,
             expected: int(),
-            given: float(),
+            left: int(),
+            right: float(),
         },
     );
 
     assert_error!(
         "1 > 1.0",
-        Error::CouldNotUnify {
-            location: SrcSpan { start: 4, end: 7 },
+        Error::OperandTypeMismatch {
+            location: SrcSpan { start: 2, end: 3 },
+            operator: BinOp::GtInt,
             expected: int(),
-            given: float(),
+            left: int(),
+            right: float(),
         },
     );
 
     assert_error!(
         "1.0 >. 1",
-        Error::CouldNotUnify {
-            location: SrcSpan { start: 7, end: 8 },
+        Error::OperandTypeMismatch {
+            location: SrcSpan { start: 4, end: 6 },
+            operator: BinOp::GtFloat,
             expected: float(),
-            given: int(),
+            left: float(),
+            right: int(),
         },
     );
 
+    // Functions cannot be compared
     assert_error!(
         "fn() { 1 } == fn(x) { x + 1 }",
-        Error::CouldNotUnify {
-            location: SrcSpan { start: 14, end: 29 },
-            expected: Arc::new(Type::Fn {
+        Error::NotComparable {
+            location: SrcSpan { start: 0, end: 10 },
+            operator: BinOp::Eq,
+            typ: Arc::new(Type::Fn {
                 args: vec![],
                 retrn: int(),
             }),
-            given: Arc::new(Type::Fn {
-                args: vec![Arc::new(Type::Var {
-                    typ: Arc::new(RefCell::new(TypeVar::Link { typ: int() })),
-                })],
-                retrn: int(),
-            }),
         },
     );
 }
 
+#[test]
+fn operator_type_mismatch_test() {
+    // The error points at the operator and names the type of each operand
+    assert_error!(
+        "1 + \"one\"",
+        Error::OperandTypeMismatch {
+            location: SrcSpan { start: 2, end: 3 },
+            operator: BinOp::AddInt,
+            expected: int(),
+            left: int(),
+            right: string(),
+        },
+    );
+
+    assert_error!(
+        "\"one\" + 1",
+        Error::OperandTypeMismatch {
+            location: SrcSpan { start: 6, end: 7 },
+            operator: BinOp::AddInt,
+            expected: int(),
+            left: string(),
+            right: int(),
+        },
+    );
+
+    assert_error!(
+        "1 + 2 + \"three\"",
+        Error::OperandTypeMismatch {
+            location: SrcSpan { start: 6, end: 7 },
+            operator: BinOp::AddInt,
+            expected: int(),
+            left: int(),
+            right: string(),
+        },
+    );
+}
+
+#[test]
+fn error_message_type_test() {
+    // The message of a todo or panic must be a String
+    assert_error!(
+        "panic as 1",
+        Error::CouldNotUnify {
+            location: SrcSpan { start: 9, end: 10 },
+            expected: string(),
+            given: int(),
+        },
+    );
+
+    assert_error!(
+        "let message = 1.0 todo as message",
+        Error::CouldNotUnify {
+            location: SrcSpan { start: 26, end: 33 },
+            expected: string(),
+            given: float(),
+        },
+    );
+
+    assert_error!(
+        "assert Ok(x) = Ok(1) as 1 x",
+        Error::CouldNotUnify {
+            location: SrcSpan { start: 24, end: 25 },
+            expected: string(),
+            given: int(),
+        },
+    );
+
+    // The variables of the pattern are not bound when the message is used
+    assert_error!(
+        "assert Ok(x) = Ok(\"\") as x x",
+        Error::UnknownVariable {
+            location: SrcSpan { start: 25, end: 26 },
+            name: "x".to_string(),
+            variables: env_vars(),
+        },
+    );
+
+    // Unlike todo, panic does not emit a warning
+    assert_no_warnings!("fn main(message) { panic as message }");
+}
+
 #[test]
 fn unknown_variable() {
     assert_error!(
@@ -882,6 +1213,35 @@ fn unknown_variable() {
             variables: env_vars_with(&["add"]),
         },
     );
+
+    // Variables bound within a block are not in scope after it
+    assert_error!(
+        "let x = { let y = 1 y + 1 } y",
+        Error::UnknownVariable {
+            location: SrcSpan { start: 28, end: 29 },
+            name: "y".to_string(),
+            variables: env_vars_with(&["x"]),
+        },
+    );
+
+    assert_error!(
+        "{ let y = 1 y } y",
+        Error::UnknownVariable {
+            location: SrcSpan { start: 16, end: 17 },
+            name: "y".to_string(),
+            variables: env_vars(),
+        },
+    );
+}
+
+#[test]
+fn block_test() {
+    assert_infer!("let x = { 1 2.0 } x", "Float");
+    assert_infer!("let x = { let y = 1 y + 1 } x", "Int");
+    // A variable shadowed within a block keeps its outer type after it
+    assert_infer!("let x = 1 let y = { let x = \"one\" x } x", "Int");
+    assert_infer!("let x = 1 { let x = \"one\" x } x", "Int");
+    assert_infer!("let x = 1 x + { let x = 2.0 1 } + x", "Int");
 }
 
 #[test]
@@ -890,7 +1250,7 @@ fn incorrect_arity_error() {
         "let id = fn(x) { x } id()",
         Error::IncorrectArity {
             labels: vec![],
-            location: SrcSpan { start: 21, end: 25 },
+            location: SrcSpan { start: 23, end: 25 },
             expected: 1,
             given: 0,
         },
@@ -900,11 +1260,33 @@ fn incorrect_arity_error() {
         "let id = fn(x) { x } id(1, 2)",
         Error::IncorrectArity {
             labels: vec![],
-            location: SrcSpan { start: 21, end: 29 },
+            location: SrcSpan { start: 23, end: 29 },
+            expected: 1,
+            given: 2,
+        },
+    );
+
+    // A function that was inferred from an earlier call
+    assert_error!(
+        "fn(f) { f(1) f(1, 2) }",
+        Error::IncorrectArity {
+            labels: vec![],
+            location: SrcSpan { start: 14, end: 20 },
             expected: 1,
             given: 2,
         },
     );
+
+    // A function with labelled arguments
+    assert_module_error!(
+        "fn f(a a, b b) { a } fn main() { f(b: 1) }",
+        Error::IncorrectArity {
+            labels: vec!["a".to_string()],
+            location: SrcSpan { start: 34, end: 40 },
+            expected: 2,
+            given: 1,
+        },
+    );
 }
 
 #[test]
@@ -938,10 +1320,12 @@ fn case_clause_unification_error() {
 
     assert_error!(
         "case 1, 2.0 { a, b -> a + b }",
-        Error::CouldNotUnify {
-            location: SrcSpan { start: 26, end: 27 },
+        Error::OperandTypeMismatch {
+            location: SrcSpan { start: 24, end: 25 },
+            operator: BinOp::AddInt,
             expected: int(),
-            given: float(),
+            left: int(),
+            right: float(),
         },
     );
 
@@ -1071,19 +1455,23 @@ fn the_rest() {
 
     assert_error!(
         "tuple(1, 2) == tuple(1, 2, 3)",
-        Error::CouldNotUnify {
-            location: SrcSpan { start: 15, end: 29 },
+        Error::OperandTypeMismatch {
+            location: SrcSpan { start: 12, end: 14 },
+            operator: BinOp::Eq,
             expected: tuple(vec![int(), int()]),
-            given: tuple(vec![int(), int(), int()])
+            left: tuple(vec![int(), int()]),
+            right: tuple(vec![int(), int(), int()]),
         },
     );
 
     assert_error!(
         "tuple(1.0, 2, 3) == tuple(1, 2, 3)",
-        Error::CouldNotUnify {
-            location: SrcSpan { start: 20, end: 34 },
+        Error::OperandTypeMismatch {
+            location: SrcSpan { start: 17, end: 19 },
+            operator: BinOp::Eq,
             expected: tuple(vec![float(), int(), int()]),
-            given: tuple(vec![int(), int(), int()]),
+            left: tuple(vec![float(), int(), int()]),
+            right: tuple(vec![int(), int(), int()]),
         },
     );
 
@@ -1105,12 +1493,16 @@ fn the_rest() {
 
     assert_error!(
         "[1.0] == [1]",
-        Error::CouldNotUnify {
-            location: SrcSpan { start: 9, end: 12 },
+        Error::OperandTypeMismatch {
+            location: SrcSpan { start: 6, end: 8 },
+            operator: BinOp::Eq,
             expected: list(Arc::new(Type::Var {
                 typ: Arc::new(RefCell::new(TypeVar::Link { typ: float() }))
             })),
-            given: list(Arc::new(Type::Var {
+            left: list(Arc::new(Type::Var {
+                typ: Arc::new(RefCell::new(TypeVar::Link { typ: float() }))
+            })),
+            right: list(Arc::new(Type::Var {
                 typ: Arc::new(RefCell::new(TypeVar::Link { typ: int() }))
             }))
         },
@@ -1358,11 +1750,31 @@ fn the_rest() {
     // https://github.com/gleam-lang/gleam/issues/714
     assert_error!(
         "case tuple(1, 2) { tuple(1, _, _, _) -> 1 }",
-        Error::IncorrectArity {
-            labels: vec![],
+        Error::IncorrectTupleArity {
             location: SrcSpan { start: 19, end: 36 },
             expected: 2,
             given: 4,
+            typ: tuple(vec![int(), int()]),
+        },
+    );
+
+    assert_error!(
+        "let tuple(a, b, c) = tuple(1, 2) a",
+        Error::IncorrectTupleArity {
+            location: SrcSpan { start: 4, end: 18 },
+            expected: 2,
+            given: 3,
+            typ: tuple(vec![int(), int()]),
+        },
+    );
+
+    assert_error!(
+        "let tuple(a, tuple(b)) = tuple(1, tuple(2, 3)) a",
+        Error::IncorrectTupleArity {
+            location: SrcSpan { start: 13, end: 21 },
+            expected: 2,
+            given: 1,
+            typ: tuple(vec![int(), int()]),
         },
     );
 
@@ -2059,19 +2471,23 @@ pub fn main() {
 fn infer_module_error_test() {
     assert_module_error!(
         "fn go() { 1 + 2.0 }",
-        Error::CouldNotUnify {
-            location: SrcSpan { start: 14, end: 17 },
+        Error::OperandTypeMismatch {
+            location: SrcSpan { start: 12, end: 13 },
+            operator: BinOp::AddInt,
             expected: int(),
-            given: float(),
+            left: int(),
+            right: float(),
         }
     );
 
     assert_module_error!(
         "fn go() { 1 + 2.0 }",
-        Error::CouldNotUnify {
-            location: SrcSpan { start: 14, end: 17 },
+        Error::OperandTypeMismatch {
+            location: SrcSpan { start: 12, end: 13 },
+            operator: BinOp::AddInt,
             expected: int(),
-            given: float(),
+            left: int(),
+            right: float(),
         }
     );
 
@@ -2244,11 +2660,14 @@ fn demo() {
             let x: Person = Person(name: \"Quinn\", age: age)
             x
         }",
-        Error::CouldNotUnify {
+        Error::IncorrectRecordFieldType {
             location: SrcSpan {
                 start: 179,
                 end: 182
             },
+            name: "Person".to_string(),
+            index: 1,
+            label: Some("age".to_string()),
             expected: int(),
             given: float(),
         },
@@ -2680,7 +3099,7 @@ fn correct_pipe_arity_error_location() {
          fn main() { 1 |> x() }",
         Error::IncorrectArity {
             labels: vec![],
-            location: SrcSpan { start: 40, end: 46 },
+            location: SrcSpan { start: 44, end: 46 },
             expected: 2,
             given: 0,
         },
@@ -2732,10 +3151,12 @@ fn module_constants() {
     assert_module_error!(
         "const pair = tuple(1, 2.0)
          fn main() { 1 == pair }",
-        Error::CouldNotUnify {
-            location: SrcSpan { start: 53, end: 57 },
+        Error::OperandTypeMismatch {
+            location: SrcSpan { start: 50, end: 52 },
+            operator: BinOp::Eq,
             expected: int(),
-            given: tuple(vec![int(), float()]),
+            left: int(),
+            right: tuple(vec![int(), float()]),
         },
     );
 
@@ -2796,6 +3217,20 @@ fn x() {
             supplied: vec!["a".to_string()],
         })
     );
+
+    // An unknown label in a pattern that ignores the other fields
+    assert_module_error!(
+        r#"type X { X(a: Int, b: Float) }
+fn x() {
+    let X(c: c, ..) = X(1, 2.0)
+    c
+}"#,
+        sort_options(Error::UnknownLabels {
+            unknown: vec![("c".to_string(), SrcSpan { start: 50, end: 54 })],
+            valid: vec!["a".to_string(), "b".to_string()],
+            supplied: vec![],
+        })
+    );
 }
 
 #[test]
@@ -3057,14 +3492,52 @@ fn main() { foo(); 5 }",
         }
     );
 
-    // Explicitly discarded Results do not emit warnings
-    assert_no_warnings!(
-        "
+    // Results whose type was inferred through a type variable emit warnings
+    assert_warning!(
+        "fn main(x) { case x { _ -> Ok(1) } 5 }",
+        Warning::ImplicitlyDiscardedResult {
+            location: SrcSpan { start: 13, end: 34 }
+        }
+    );
+
+    // Explicitly discarded Results do not emit warnings
+    assert_no_warnings!(
+        "
 fn foo() { Ok(5) }
 fn main() { let _ = foo(); 5 }",
     );
 }
 
+#[test]
+fn redundant_let_warning_test() {
+    // Rebinding a variable to itself emits a warning
+    assert_warning!(
+        "fn main(x) { let x = x x }",
+        Warning::RedundantLet {
+            location: SrcSpan { start: 13, end: 22 },
+            name: "x".to_string(),
+        }
+    );
+
+    // Binding a variable to another only to return it emits a warning
+    assert_warning!(
+        "fn main(y) { let x = y x }",
+        Warning::RedundantLet {
+            location: SrcSpan { start: 13, end: 22 },
+            name: "x".to_string(),
+        }
+    );
+
+    // Rebinding a variable to a new value derived from itself does not warn
+    assert_no_warnings!("fn main(x) { let x = x + 1 x }");
+
+    // Further use of the new variable does not warn
+    assert_no_warnings!("fn main(y) { let x = y x + 1 }");
+
+    // Annotated bindings are checking the type so do not warn
+    assert_no_warnings!("fn main(x) { let x: Int = x x }");
+}
+
 #[test]
 fn record_update_warnings_test() {
     // Some fields are given in a record update do not emit warnings
@@ -3146,6 +3619,33 @@ fn unused_type_warnings_test() {
     assert_no_warnings!("type Y = Int fn run(x: Y) { x }");
 }
 
+#[test]
+fn unused_type_parameter_warnings_test() {
+    assert_warning!(
+        "pub type Box(a, b) { Box(a) }",
+        Warning::UnusedTypeParameter {
+            name: "b".to_string(),
+            location: SrcSpan { start: 16, end: 17 }
+        }
+    );
+
+    assert_warning!(
+        "pub type Box(a) { Empty Full(Int) }",
+        Warning::UnusedTypeParameter {
+            name: "a".to_string(),
+            location: SrcSpan { start: 13, end: 14 }
+        }
+    );
+
+    // Parameters used anywhere in a constructor are not warned for
+    assert_no_warnings!("pub type Box(a) { Empty Full(List(a)) }");
+    assert_no_warnings!("pub type Box(a, b) { Box(value: fn(a) -> tuple(Int, b)) }");
+
+    // A phantom type can be named with an underscore
+    assert_no_warnings!("pub type Id(_kind) { Id(Int) }");
+    assert_no_warnings!("pub type Id(_kind) { Id(Int) } pub fn id(x: Id(Int)) { x }");
+}
+
 #[test]
 fn functions_used_before_definition() {
     assert_module_infer!(
@@ -3279,3 +3779,801 @@ fn early_function_generalisation() {
         ],
     );
 }
+
+#[test]
+fn fn_type_unify_error_location_test() {
+    // Extra arguments of an anonymous function are highlighted
+    assert_module_error!(
+        "fn apply(f: fn(Int, Int) -> Int) { f(1, 2) }
+fn main() { apply(fn(a, b, c) { a }) }",
+        Error::IncorrectArity {
+            location: SrcSpan { start: 72, end: 73 },
+            expected: 2,
+            given: 3,
+            labels: vec![],
+        },
+    );
+
+    // The entire anonymous function is highlighted if it has too few arguments
+    assert_module_error!(
+        "fn main() { let f: fn(Int, Int) -> Int = fn(a) { a } f }",
+        Error::IncorrectArity {
+            location: SrcSpan { start: 41, end: 52 },
+            expected: 2,
+            given: 1,
+            labels: vec![],
+        },
+    );
+
+    // An argument with the wrong type is highlighted
+    assert_module_error!(
+        "fn apply(f: fn(Int, Int) -> Int) { f(1, 2) }
+fn main() { apply(fn(a, b: String) { a }) }",
+        Error::CouldNotUnify {
+            location: SrcSpan { start: 69, end: 78 },
+            expected: int(),
+            given: string(),
+        },
+    );
+}
+
+#[test]
+fn constant_condition_warning_test() {
+    // Literal booleans as case subjects emit a warning
+    assert_warning!(
+        "fn main() { case True { True -> 1 False -> 2 } }",
+        Warning::ConstantCondition {
+            location: SrcSpan { start: 17, end: 21 },
+        }
+    );
+
+    // Comparisons of literals as case subjects emit a warning
+    assert_warning!(
+        "fn main() { case 1 == 1 { True -> 1 False -> 2 } }",
+        Warning::ConstantCondition {
+            location: SrcSpan { start: 17, end: 23 },
+        }
+    );
+
+    // Constant clause guards emit a warning
+    assert_warning!(
+        "fn main(x) { case x { _ if False -> 1 _ -> 2 } }",
+        Warning::ConstantCondition {
+            location: SrcSpan { start: 27, end: 32 },
+        }
+    );
+
+    assert_warning!(
+        "fn main(x) { case x { _ if 1 < 2 -> 1 _ -> 2 } }",
+        Warning::ConstantCondition {
+            location: SrcSpan { start: 27, end: 32 },
+        }
+    );
+
+    // Conditions that depend on variables do not emit a warning
+    assert_no_warnings!("fn main(x) { case x == 1 { True -> 1 False -> 2 } }");
+    assert_no_warnings!("fn main(x) { case x { y if y > 1 -> 1 _ -> 2 } }");
+    assert_no_warnings!("fn main(x) { case x && True { True -> 1 False -> 2 } }");
+}
+
+#[test]
+fn refutable_let_pattern_warning_test() {
+    assert_warning!(
+        "fn main() { let [x] = [1] x }",
+        Warning::RefutableLetPattern {
+            location: SrcSpan { start: 16, end: 18 },
+        }
+    );
+
+    assert_warning!(
+        "fn main(x) { let tuple(1, y) = x y }",
+        Warning::RefutableLetPattern {
+            location: SrcSpan { start: 17, end: 28 },
+        }
+    );
+
+    assert_warning!(
+        "fn main(x) { let Ok(y) = x y }",
+        Warning::RefutableLetPattern {
+            location: SrcSpan { start: 17, end: 22 },
+        }
+    );
+
+    // Patterns that match every value do not emit a warning
+    assert_no_warnings!("fn main() { let tuple(a, b) = tuple(1, 2) a + b }");
+    assert_no_warnings!("fn main() { let tuple(_, y) as x = tuple(1, 2) x }");
+    assert_no_warnings!("type Box { Box(Int) } fn main(b) { let Box(x) = b x }");
+
+    // Patterns in `assert` may not match
+    assert_no_warnings!("fn main() { assert [x] = [1] x }");
+}
+
+#[test]
+fn nested_record_access_test() {
+    assert_module_infer!(
+        "
+pub type Inner { Inner(value: Int) }
+pub type Outer { Outer(inner: Inner) }
+pub fn main(x: Outer) { x.inner.value }
+",
+        vec![
+            ("Inner", "fn(Int) -> Inner"),
+            ("Outer", "fn(Inner) -> Outer"),
+            ("main", "fn(Outer) -> Int"),
+        ],
+    );
+
+    // The error points to the step of the chain naming the unknown field
+    assert_module_error!(
+        "
+pub type Inner { Inner(value: Int) }
+pub type Outer { Outer(inner: Inner) }
+pub fn main(x: Outer) { x.missing.value }
+",
+        Error::UnknownField {
+            location: SrcSpan {
+                start: 102,
+                end: 110
+            },
+            label: "missing".to_string(),
+            fields: vec!["inner".to_string()],
+            typ: Arc::new(Type::App {
+                args: vec![],
+                public: true,
+                module: vec!["my_module".to_string()],
+                name: "Outer".to_string(),
+            }),
+        },
+    );
+}
+
+#[test]
+fn float_equality_warning_test() {
+    assert_warning!(
+        "fn main(x) { x == 1.0 }",
+        Warning::FloatEquality {
+            location: SrcSpan { start: 13, end: 21 },
+        }
+    );
+
+    assert_warning!(
+        "fn main(x: Float, y) { x != y }",
+        Warning::FloatEquality {
+            location: SrcSpan { start: 23, end: 29 },
+        }
+    );
+
+    // Other types can be compared for equality without a warning
+    assert_no_warnings!("fn main(x) { x == 1 }");
+    assert_no_warnings!("fn main(x) { x != \"1.0\" }");
+}
+
+#[test]
+fn redundant_rewrap_warning_test() {
+    assert_warning!(
+        "pub fn main(x) { case x { Ok(y) -> Ok(y) Error(e) -> Error(e) } }",
+        Warning::RedundantRewrap {
+            location: SrcSpan { start: 17, end: 63 },
+        }
+    );
+
+    assert_warning!(
+        "pub fn main(x: Result(Int, Nil)) { case x { Error(e) -> Error(e) Ok(y) -> Ok(y) } }",
+        Warning::RedundantRewrap {
+            location: SrcSpan { start: 35, end: 81 },
+        }
+    );
+
+    // The value is changed
+    assert_no_warnings!("pub fn main(x) { case x { Ok(y) -> Ok(y + 1) Error(e) -> Error(e) } }");
+    assert_no_warnings!("pub fn main(x) { case x { Ok(y) -> Error(y) Error(e) -> Ok(e) } }");
+    assert_no_warnings!("pub fn main(x, z) { case x { Ok(y) -> Ok(z) Error(e) -> Error(e) } }");
+    assert_no_warnings!("pub fn main(x) { case x { Ok(y) -> Ok(y) Error(_) -> Error(Nil) } }");
+
+    // Not every value is rebuilt
+    assert_no_warnings!(
+        "pub fn main(x) { case x { Ok(y) if y > 1 -> Ok(y) Error(e) -> Error(e) _ -> x } }"
+    );
+    assert_no_warnings!(
+        "pub fn main(x, z) { case x, z { Ok(y), _ -> Ok(y) Error(e), _ -> Error(e) } }"
+    );
+
+    // Other types with constructors of the same name
+    assert_no_warnings!(
+        "type Either { Ok(Int) Error(Int) }
+pub fn main(x) { case x { Ok(y) -> Ok(y) Error(e) -> Error(e) } }"
+    );
+}
+
+#[test]
+fn infinite_recursion_warning_test() {
+    // A function that calls itself with its own arguments never returns
+    assert_warning!(
+        "pub fn go(x) { go(x) }",
+        Warning::InfiniteRecursion {
+            location: SrcSpan { start: 15, end: 20 },
+        }
+    );
+
+    assert_warning!(
+        "pub fn go(x, y) { let z = x + y go(x, y) }",
+        Warning::InfiniteRecursion {
+            location: SrcSpan { start: 32, end: 40 },
+        }
+    );
+
+    // Any argument may be given for a discarded parameter
+    assert_warning!(
+        "pub fn go(_) { go(1) }",
+        Warning::InfiniteRecursion {
+            location: SrcSpan { start: 15, end: 20 },
+        }
+    );
+
+    // A recursive function with a base case is fine
+    assert_no_warnings!(
+        "pub fn count(x) {
+  case x {
+    0 -> 0
+    n -> 1 + count(n - 1)
+  }
+}",
+    );
+
+    // Only calls with the parameters unchanged are reported
+    assert_no_warnings!("pub fn go(x) { go(x + 1) }");
+    assert_no_warnings!("pub fn go(x) { let x = x + 1 go(x) }");
+    assert_no_warnings!("pub fn go(x, y) { go(y, x) }");
+
+    // Calls that are not always made are not reported
+    assert_no_warnings!("pub fn go(x) { x > 0 && go(x) }");
+    assert_no_warnings!("pub fn go(x) { fn() { go(x) } }");
+}
+
+#[test]
+fn raw_erlang_warning_test() {
+    assert_warning!(
+        "pub fn main() { @erlang(\"erlang:system_time()\") as Int }",
+        Warning::RawErlang {
+            location: SrcSpan { start: 16, end: 54 },
+        }
+    );
+}
+
+#[test]
+fn unnecessary_result_warning_test() {
+    let lint = |src: &str| unnecessary_results(&infer_with_warnings(src).0);
+
+    // Every branch of the function returns Ok
+    assert_eq!(
+        vec![Warning::UnnecessaryResult {
+            location: SrcSpan { start: 0, end: 12 },
+            name: "always".to_string(),
+        }],
+        lint(
+            "fn always(x) {
+  let y = x + 1
+  case y > 2 {
+    True -> Ok(y)
+    False -> Ok(0)
+  }
+}"
+        )
+    );
+
+    // One branch returns an Error
+    assert_eq!(
+        Vec::<Warning>::new(),
+        lint(
+            "fn sometimes(x) {
+  case x > 2 {
+    True -> Ok(x)
+    False -> Error(Nil)
+  }
+}"
+        )
+    );
+
+    // Errors from other functions may be returned
+    assert_eq!(
+        Vec::<Warning>::new(),
+        lint(
+            "fn parse(x) { Error(x) }
+fn wrapped(x) { parse(x) }
+fn tried(x) {
+  try y = parse(x)
+  Ok(y)
+}"
+        )
+    );
+}
+
+#[test]
+fn redundant_annotation_warning_test() {
+    let lint = |src: &str| redundant_annotations(&infer_with_warnings(src).0);
+
+    // The value is an Int without the annotation
+    assert_eq!(
+        vec![Warning::RedundantAnnotation {
+            location: SrcSpan { start: 23, end: 26 },
+        }],
+        lint("pub fn main() { let x: Int = 1 x }")
+    );
+
+    assert_eq!(
+        vec![Warning::RedundantAnnotation {
+            location: SrcSpan { start: 69, end: 85 },
+        }],
+        lint(
+            "fn parse(x) -> Result(Int, Nil) { Ok(x + 1) }
+pub fn main() { let x: Result(Int, Nil) = parse(1) x }"
+        )
+    );
+
+    // The annotation fixes a type the value leaves open
+    assert_eq!(
+        Vec::<Warning>::new(),
+        lint("pub fn main() { let x: List(Int) = [] x }")
+    );
+    assert_eq!(
+        Vec::<Warning>::new(),
+        lint("pub fn main(f) { let x: Int = f() x }")
+    );
+
+    // The value is fixed by later code, but is ambiguous where it is bound
+    assert_eq!(
+        Vec::<Warning>::new(),
+        lint("pub fn main() { let xs: List(Int) = [] [1, ..xs] }")
+    );
+
+    // Values without annotations are not reported
+    assert_eq!(Vec::<Warning>::new(), lint("pub fn main() { let x = 1 x }"));
+}
+
+#[test]
+fn non_tail_recursion_warning_test() {
+    let lint = |src: &str| non_tail_recursion(&infer_with_warnings(src).0);
+
+    // The result of the recursive call is used by the function
+    assert_eq!(
+        vec![Warning::NonTailRecursion {
+            location: SrcSpan { start: 54, end: 63 },
+        }],
+        lint("pub fn sum(xs) { case xs { [] -> 0 [x, ..rest] -> x + sum(rest) } }")
+    );
+    assert_eq!(
+        vec![Warning::NonTailRecursion {
+            location: SrcSpan { start: 46, end: 58 },
+        }],
+        lint("pub fn count(x) { case x { 0 -> [] _ -> [x, ..count(x - 1)] } }")
+    );
+    assert_eq!(
+        vec![Warning::NonTailRecursion {
+            location: SrcSpan { start: 46, end: 55 },
+        }],
+        lint("pub fn go(x) { case x { 0 -> 0 _ -> { let y = go(x - 1) y } } }")
+    );
+    assert_eq!(
+        vec![Warning::NonTailRecursion {
+            location: SrcSpan { start: 22, end: 27 },
+        }],
+        lint("pub fn go(x: Int) { x |> go |> fn(y) { y } }")
+    );
+
+    // The recursive call is the last thing the function does
+    assert_eq!(
+        Vec::<Warning>::new(),
+        lint(
+            "pub fn sum(xs, total) {
+  case xs {
+    [] -> total
+    [x, ..rest] -> sum(rest, total + x)
+  }
+}"
+        )
+    );
+    assert_eq!(
+        Vec::<Warning>::new(),
+        lint("pub fn all(x) { x == 0 || all(x - 1) }")
+    );
+    assert_eq!(
+        Vec::<Warning>::new(),
+        lint("pub fn go(x) { case x { 0 -> 0 _ -> { let y = x - 1 y |> go } } }")
+    );
+    assert_eq!(
+        Vec::<Warning>::new(),
+        lint("fn apply(f, x) { f(x) } pub fn go(x: Int) -> Int { apply(fn(y) { go(y) }, x) }")
+    );
+}
+
+// A benchmark of inferring a large module, run with
+// `cargo test --release infer_large_module_benchmark -- --ignored --nocapture`
+#[test]
+#[ignore]
+fn infer_large_module_benchmark() {
+    let mut src = "pub type Pair { Pair(left: Int, right: Int) }\n".to_string();
+    src.push_str("pub fn fun0(pair: Pair) { pair.left }\n");
+    for i in 1..2000 {
+        src.push_str(&format!(
+            "pub fn fun{}(pair: Pair) {{
+  let Pair(left: left, right: right) = pair
+  let total = left + right
+  case total > 100 {{
+    True -> fun{}(Pair(left: right, right: total - 100))
+    False -> fun{}(Pair(left: left, right: total))
+  }}
+}}
+",
+            i,
+            i - 1,
+            i - 1
+        ));
+    }
+
+    let (src, _) = crate::parser::strip_extra(&src);
+    let ast = crate::grammar::ModuleParser::new()
+        .parse(&src)
+        .expect("syntax error");
+
+    let runs = 5;
+    let start = std::time::Instant::now();
+    for _ in 0..runs {
+        let _ = infer_module(&mut 0, ast.clone(), "", &HashMap::new(), &mut vec![])
+            .expect("should successfully infer");
+    }
+    println!(
+        "Inferred a module of 2000 functions in {:?} on average",
+        start.elapsed() / runs
+    );
+}
+
+#[test]
+fn nil_test() {
+    assert_module_infer!(
+        "
+pub fn go() { Nil }
+pub fn check(x) { case x { Nil -> 1 } }
+pub fn ignore(x: a) -> Nil { Nil }
+",
+        vec![
+            ("check", "fn(Nil) -> Int"),
+            ("go", "fn() -> Nil"),
+            ("ignore", "fn(a) -> Nil"),
+        ],
+    );
+
+    assert_module_error!(
+        "pub fn go() -> Nil { 1 }",
+        Error::CouldNotUnify {
+            location: SrcSpan { start: 21, end: 22 },
+            expected: nil(),
+            given: int(),
+        },
+    );
+}
+
+#[test]
+fn record_construction_error_test() {
+    assert_module_infer!(
+        "pub type Person { Person(name: String, age: Int) }
+pub fn go() { Person(\"Quinn\", 28) }",
+        vec![
+            ("Person", "fn(String, Int) -> Person"),
+            ("go", "fn() -> Person")
+        ],
+    );
+
+    // Too few arguments lists the fields that were not given
+    assert_module_error!(
+        "pub type Person { Person(name: String, age: Int) }
+pub fn go() { Person(\"Quinn\") }",
+        Error::IncorrectRecordArity {
+            location: SrcSpan { start: 65, end: 80 },
+            name: "Person".to_string(),
+            expected: 2,
+            given: 1,
+            missing: vec![MissingField {
+                index: 1,
+                label: Some("age".to_string()),
+                typ: int(),
+            }],
+        },
+    );
+
+    // Extra arguments are highlighted
+    assert_module_error!(
+        "pub type Point { Point(Int, Int) }
+pub fn go() { Point(1, 2, 3) }",
+        Error::IncorrectRecordArity {
+            location: SrcSpan { start: 61, end: 62 },
+            name: "Point".to_string(),
+            expected: 2,
+            given: 3,
+            missing: vec![],
+        },
+    );
+
+    // A mismatched argument names the field it was given for
+    assert_module_error!(
+        "pub type Person { Person(name: String, age: Int) }
+pub fn go() { Person(28, \"Quinn\") }",
+        Error::IncorrectRecordFieldType {
+            location: SrcSpan { start: 72, end: 74 },
+            name: "Person".to_string(),
+            index: 0,
+            label: Some("name".to_string()),
+            expected: string(),
+            given: int(),
+        },
+    );
+}
+
+#[test]
+fn derived_functions_test() {
+    assert_module_infer!(
+        "@derive(equal, compare)
+pub type Shape { Circle(Float) Point }
+pub fn same() { shape_equal(Circle(1.0), Point) }
+pub fn order() { shape_compare(Circle(1.0), Point) }",
+        vec![
+            ("Circle", "fn(Float) -> Shape"),
+            ("Point", "Shape"),
+            ("order", "fn() -> Int"),
+            ("same", "fn() -> Bool"),
+            ("shape_compare", "fn(Shape, Shape) -> Int"),
+            ("shape_equal", "fn(Shape, Shape) -> Bool"),
+        ],
+    );
+
+    assert_module_infer!(
+        "@derive(equal)
+pub type HttpBox(a) { HttpBox(a) }",
+        vec![
+            ("HttpBox", "fn(a) -> HttpBox(a)"),
+            ("http_box_equal", "fn(HttpBox(a), HttpBox(a)) -> Bool"),
+        ],
+    );
+
+    assert_module_error!(
+        "@derive(equal)
+pub type Box { Box }
+pub fn go() { box_equal(Box, 1) }",
+        Error::CouldNotUnify {
+            location: SrcSpan { start: 65, end: 66 },
+            expected: Arc::new(Type::App {
+                public: true,
+                module: vec!["my_module".to_string()],
+                name: "Box".to_string(),
+                args: vec![],
+            }),
+            given: int(),
+        },
+    );
+
+    assert_module_error!(
+        "@derive(equal)
+pub type Box { Box }
+pub fn box_equal(a, b) { a == b }",
+        Error::DuplicateName {
+            location: SrcSpan { start: 36, end: 58 },
+            previous_location: SrcSpan { start: 8, end: 13 },
+            name: "box_equal".to_string(),
+        },
+    );
+
+    // Type names that differ only in case can give derived functions the
+    // same Erlang name
+    assert_module_error!(
+        "@derive(equal)
+pub type HTTPBox { HTTPBox }
+@derive(equal)
+pub type HttpBox { HttpBox }",
+        Error::DuplicateDerivedName {
+            location: SrcSpan { start: 52, end: 57 },
+            previous_location: SrcSpan { start: 8, end: 13 },
+            name: "http_box_equal".to_string(),
+            type_name: "HttpBox".to_string(),
+            previous_type_name: "HTTPBox".to_string(),
+        },
+    );
+
+    assert_module_infer!(
+        "@derive(equal)
+pub type HttpBox { HttpBox }
+@derive(equal)
+pub type Httpbox { Httpbox }",
+        vec![
+            ("HttpBox", "HttpBox"),
+            ("Httpbox", "Httpbox"),
+            ("http_box_equal", "fn(HttpBox, HttpBox) -> Bool"),
+            ("httpbox_equal", "fn(Httpbox, Httpbox) -> Bool"),
+        ],
+    );
+}
+
+#[test]
+fn multiple_subject_case_test() {
+    assert_infer!(
+        "case 1, [2.0] { 0, [] | _, [_, ..] -> 1.0 _, [x] -> x _, _ -> 0.0 }",
+        "Float"
+    );
+
+    // Each alternative must have a pattern for each subject
+    assert_error!(
+        "case 1, 2 { a -> a }",
+        Error::IncorrectNumClausePatterns {
+            location: SrcSpan { start: 12, end: 18 },
+            expected: 2,
+            given: 1,
+        },
+    );
+
+    assert_error!(
+        "case 1, 2 { a, b, c -> a }",
+        Error::IncorrectNumClausePatterns {
+            location: SrcSpan { start: 12, end: 24 },
+            expected: 2,
+            given: 3,
+        },
+    );
+
+    assert_error!(
+        "case 1, 2 { 1, _ | 2 -> 1 _, _ -> 2 }",
+        Error::IncorrectNumClausePatterns {
+            location: SrcSpan { start: 19, end: 20 },
+            expected: 2,
+            given: 1,
+        },
+    );
+}
+
+#[test]
+fn not_comparable_test() {
+    assert_infer!("1 < 2", "Bool");
+    assert_infer!("let f = fn() { 1 } f() >= f()", "Bool");
+
+    assert_error!(
+        "let f = fn() { 1 } f < f",
+        Error::NotComparable {
+            location: SrcSpan { start: 19, end: 20 },
+            operator: BinOp::LtInt,
+            typ: Arc::new(Type::Fn {
+                args: vec![],
+                retrn: int(),
+            }),
+        },
+    );
+
+    assert_error!(
+        "let f = fn(x) { x +. 1.0 } 1.0 >. f",
+        Error::NotComparable {
+            location: SrcSpan { start: 34, end: 35 },
+            operator: BinOp::GtFloat,
+            typ: Arc::new(Type::Fn {
+                args: vec![float()],
+                retrn: float(),
+            }),
+        },
+    );
+
+    assert_error!(
+        "let f = fn() { 1 } f != f",
+        Error::NotComparable {
+            location: SrcSpan { start: 19, end: 20 },
+            operator: BinOp::NotEq,
+            typ: Arc::new(Type::Fn {
+                args: vec![],
+                retrn: int(),
+            }),
+        },
+    );
+}
+
+#[test]
+fn uninhabited_type_test() {
+    // Recursive types with a base case can be constructed
+    assert_module_infer!(
+        "pub type Tree(a) { Leaf Node(Tree(a), a, Tree(a)) }",
+        vec![
+            ("Leaf", "Tree(a)"),
+            ("Node", "fn(Tree(a), a, Tree(a)) -> Tree(a)")
+        ],
+    );
+
+    assert_module_infer!(
+        "pub type Rose(a) { Rose(a, List(Rose(a))) }",
+        vec![("Rose", "fn(a, List(Rose(a))) -> Rose(a)")],
+    );
+
+    assert_module_infer!(
+        "pub type A { A(B) } pub type B { B(A) End }",
+        vec![("A", "fn(B) -> A"), ("B", "fn(A) -> B"), ("End", "B")],
+    );
+
+    // Types whose every constructor needs a value of the type cannot
+    assert_module_error!(
+        "type T { T(T) }",
+        Error::UninhabitedType {
+            location: SrcSpan { start: 0, end: 7 },
+            name: "T".to_string(),
+        },
+    );
+
+    assert_module_error!(
+        "type T { T(Int, tuple(String, T)) }",
+        Error::UninhabitedType {
+            location: SrcSpan { start: 0, end: 7 },
+            name: "T".to_string(),
+        },
+    );
+
+    assert_module_error!(
+        "type A { A(B) } type B { B(A) }",
+        Error::UninhabitedType {
+            location: SrcSpan { start: 0, end: 7 },
+            name: "A".to_string(),
+        },
+    );
+}
+
+#[test]
+fn deprecated_test() {
+    let warnings = |src: &str| infer_with_warnings(src).1;
+
+    // Each call to a deprecated function is warned about
+    assert_eq!(
+        vec![
+            Warning::Deprecated {
+                location: SrcSpan { start: 69, end: 73 },
+                name: "show".to_string(),
+                message: "Use describe instead".to_string(),
+            },
+            Warning::Deprecated {
+                location: SrcSpan { start: 77, end: 81 },
+                name: "show".to_string(),
+                message: "Use describe instead".to_string(),
+            },
+        ],
+        warnings(
+            r#"@deprecated("Use describe instead")
+fn show(x) { x }
+pub fn main() { show(1) show(2) }"#
+        )
+    );
+
+    // As is each use of a deprecated type
+    assert_eq!(
+        vec![
+            Warning::Deprecated {
+                location: SrcSpan { start: 75, end: 78 },
+                name: "Box".to_string(),
+                message: "Use a tuple".to_string(),
+            },
+            Warning::Deprecated {
+                location: SrcSpan {
+                    start: 109,
+                    end: 112
+                },
+                name: "Box".to_string(),
+                message: "Use a tuple".to_string(),
+            },
+        ],
+        warnings(
+            r#"@deprecated("Use a tuple")
+pub external type Box
+pub external fn make() -> Box = "box" "make"
+pub fn id(box: Box) { box }"#
+        )
+    );
+
+    // Calls to other functions are not
+    assert_eq!(
+        Vec::<Warning>::new(),
+        warnings(
+            r#"@deprecated("Use describe instead")
+pub fn show(x) { x }
+fn describe(x) { x }
+pub fn main() { describe(1) }"#
+        )
+    );
+}
@@ -1,14 +1,32 @@
 use crate::{
+    ast::{
+        visit::{walk_expr, Visit},
+        Module, TypedExpr,
+    },
     cli,
     diagnostic::{write, Diagnostic, Severity},
+    error::Error,
+    line_numbers::LineNumbers,
+    project::Analysed,
     typ::pretty::Printer,
 };
 use std::path::PathBuf;
+use strum_macros::{Display, EnumString, EnumVariantNames};
 use termcolor::Buffer;
 
 pub type Src = String;
 
-#[derive(Debug, PartialEq)]
+/// How warnings are printed: as diagnostics for people to read, or as one
+/// JSON object per line for editors and other tools.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Display, EnumString, EnumVariantNames)]
+#[strum(serialize_all = "kebab_case")]
+pub enum Format {
+    Human,
+    Json,
+}
+
+#[derive(Debug, PartialEq, Clone)]
 pub enum Warning {
     Type {
         path: PathBuf,
@@ -30,7 +48,7 @@ impl Warning {
             Self::Type { path, src, warning } => match warning {
                 Warning::Todo { location, typ } => {
                     let diagnostic = Diagnostic {
-                        title: "Todo found".to_string(),
+                        title: title(warning).to_string(),
                         label: "".to_string(),
                         file: path.to_str().unwrap().to_string(),
                         src: src.to_string(),
@@ -52,7 +70,7 @@ your program.",
 
                 Warning::ImplicitlyDiscardedResult { location } => {
                     let diagnostic = Diagnostic {
-                        title: "Unused result value".to_string(),
+                        title: title(warning).to_string(),
                         label: "".to_string(),
                         file: path.to_str().unwrap().to_string(),
                         src: src.to_string(),
@@ -68,7 +86,7 @@ variable _ if you are sure the error does not matter.")
 
                 Warning::NoFieldsRecordUpdate { location } => {
                     let diagnostic = Diagnostic {
-                        title: "Fieldless record update".to_string(),
+                        title: title(warning).to_string(),
                         label: "".to_string(),
                         file: path.to_str().unwrap().to_string(),
                         src: src.to_string(),
@@ -83,7 +101,7 @@ record without modification. Add some fields or remove this update.")
 
                 Warning::AllFieldsRecordUpdate { location } => {
                     let diagnostic = Diagnostic {
-                        title: "Redundant record update".to_string(),
+                        title: title(warning).to_string(),
                         label: "".to_string(),
                         file: path.to_str().unwrap().to_string(),
                         src: src.to_string(),
@@ -98,7 +116,7 @@ be an update. Remove the update or remove fields that need to be copied.")
 
                 Warning::UnusedType { location, name } => {
                     let diagnostic = Diagnostic {
-                        title: "Unused type".to_string(),
+                        title: title(warning).to_string(),
                         label: "".to_string(),
                         file: path.to_str().unwrap().to_string(),
                         src: src.to_string(),
@@ -115,7 +133,7 @@ be an update. Remove the update or remove fields that need to be copied.")
 
                 Warning::UnusedConstructor { location, name } => {
                     let diagnostic = Diagnostic {
-                        title: "Unused constructor".to_string(),
+                        title: title(warning).to_string(),
                         label: "".to_string(),
                         file: path.to_str().unwrap().to_string(),
                         src: src.to_string(),
@@ -129,6 +147,253 @@ be an update. Remove the update or remove fields that need to be copied.")
                     )
                     .unwrap();
                 }
+
+                Warning::UnusedTypeParameter { location, name } => {
+                    let diagnostic = Diagnostic {
+                        title: title(warning).to_string(),
+                        label: "this parameter is not used by any constructor".to_string(),
+                        file: path.to_str().unwrap().to_string(),
+                        src: src.to_string(),
+                        location: location.clone(),
+                    };
+                    write(buffer, diagnostic, Severity::Warning);
+                    writeln!(
+                        buffer,
+                        "The type parameter {} is never used, it can be removed. If it is a
+phantom type it can be named _{} to show that it is not used.",
+                        name, name
+                    )
+                    .unwrap();
+                }
+
+                Warning::RedundantLet { location, name } => {
+                    let diagnostic = Diagnostic {
+                        title: title(warning).to_string(),
+                        label: "".to_string(),
+                        file: path.to_str().unwrap().to_string(),
+                        src: src.to_string(),
+                        location: location.clone(),
+                    };
+                    write(buffer, diagnostic, Severity::Warning);
+                    writeln!(
+                        buffer,
+                        "This assignment of {} has no effect, the original value can be used
+directly. Remove the assignment or check the correct variable has been used.",
+                        name
+                    )
+                    .unwrap();
+                }
+
+                Warning::ConstantCondition { location } => {
+                    let diagnostic = Diagnostic {
+                        title: title(warning).to_string(),
+                        label: "".to_string(),
+                        file: path.to_str().unwrap().to_string(),
+                        src: src.to_string(),
+                        location: location.clone(),
+                    };
+                    write(buffer, diagnostic, Severity::Warning);
+                    writeln!(buffer,
+"This condition is made only of literal values so it is always the same, and
+some of the code that depends on it will never run. Check that the correct
+values have been used.")
+                    .unwrap();
+                }
+
+                Warning::RefutableLetPattern { location } => {
+                    let diagnostic = Diagnostic {
+                        title: title(warning).to_string(),
+                        label: "".to_string(),
+                        file: path.to_str().unwrap().to_string(),
+                        src: src.to_string(),
+                        location: location.clone(),
+                    };
+                    write(buffer, diagnostic, Severity::Warning);
+                    writeln!(buffer,
+"This pattern does not match every possible value, so this let binding could
+fail at runtime. Use `assert` if the pattern is always expected to match, or a
+`case` expression to handle the other values.")
+                    .unwrap();
+                }
+
+                Warning::FloatEquality { location } => {
+                    let diagnostic = Diagnostic {
+                        title: title(warning).to_string(),
+                        label: "".to_string(),
+                        file: path.to_str().unwrap().to_string(),
+                        src: src.to_string(),
+                        location: location.clone(),
+                    };
+                    write(buffer, diagnostic, Severity::Warning);
+                    writeln!(buffer,
+"Floats are subject to rounding errors so checking them for exact equality can
+give unexpected results. Consider checking that the difference between the two
+values is within a small tolerance instead.")
+                    .unwrap();
+                }
+
+                Warning::UnnecessaryResult { location, name } => {
+                    let diagnostic = Diagnostic {
+                        title: title(warning).to_string(),
+                        label: "this function can only return Ok".to_string(),
+                        file: path.to_str().unwrap().to_string(),
+                        src: src.to_string(),
+                        location: location.clone(),
+                    };
+                    write(buffer, diagnostic, Severity::Warning);
+                    writeln!(
+                        buffer,
+                        "`{}` never returns an Error so the Result wrapper could be removed,
+returning the Ok value directly.",
+                        name
+                    )
+                    .unwrap();
+                }
+
+                Warning::RedundantRewrap { location } => {
+                    let diagnostic = Diagnostic {
+                        title: title(warning).to_string(),
+                        label: "this returns the Result unchanged".to_string(),
+                        file: path.to_str().unwrap().to_string(),
+                        src: src.to_string(),
+                        location: location.clone(),
+                    };
+                    write(buffer, diagnostic, Severity::Warning);
+                    writeln!(buffer,
+"Each clause of this case expression builds the same value that it matched, so
+the case can be removed and the value being matched on used directly.")
+                    .unwrap();
+                }
+
+                Warning::InfiniteRecursion { location } => {
+                    let diagnostic = Diagnostic {
+                        title: title(warning).to_string(),
+                        label: "this call never returns".to_string(),
+                        file: path.to_str().unwrap().to_string(),
+                        src: src.to_string(),
+                        location: location.clone(),
+                    };
+                    write(buffer, diagnostic, Severity::Warning);
+                    writeln!(
+                        buffer,
+                        "Every time this function is run it calls itself with the same arguments,
+so it can never return. A recursive function needs a case that returns
+without calling itself."
+                    )
+                    .unwrap();
+                }
+
+                Warning::RawErlang { location } => {
+                    let diagnostic = Diagnostic {
+                        title: title(warning).to_string(),
+                        label: "this code is not type checked".to_string(),
+                        file: path.to_str().unwrap().to_string(),
+                        src: src.to_string(),
+                        location: location.clone(),
+                    };
+                    write(buffer, diagnostic, Severity::Warning);
+                    writeln!(
+                        buffer,
+                        "This Erlang code is inserted into the compiled module as it is written.
+The compiler trusts that it has the annotated type, so a mistake here can
+crash the program or make it behave unexpectedly."
+                    )
+                    .unwrap();
+                }
+
+                Warning::RedundantAnnotation { location } => {
+                    let diagnostic = Diagnostic {
+                        title: title(warning).to_string(),
+                        label: "this type is already known".to_string(),
+                        file: path.to_str().unwrap().to_string(),
+                        src: src.to_string(),
+                        location: location.clone(),
+                    };
+                    write(buffer, diagnostic, Severity::Warning);
+                    writeln!(
+                        buffer,
+                        "The value is inferred to have this type without the annotation, so the
+annotation could be removed."
+                    )
+                    .unwrap();
+                }
+
+                Warning::NonTailRecursion { location } => {
+                    let diagnostic = Diagnostic {
+                        title: title(warning).to_string(),
+                        label: "this call is not in tail position".to_string(),
+                        file: path.to_str().unwrap().to_string(),
+                        src: src.to_string(),
+                        location: location.clone(),
+                    };
+                    write(buffer, diagnostic, Severity::Warning);
+                    writeln!(
+                        buffer,
+                        "The function has to wait for this call to return before it can finish, so
+each level of recursion uses more memory."
+                    )
+                    .unwrap();
+                }
+
+                Warning::Deprecated {
+                    location,
+                    name,
+                    message,
+                } => {
+                    let diagnostic = Diagnostic {
+                        title: title(warning).to_string(),
+                        label: "this has been deprecated".to_string(),
+                        file: path.to_str().unwrap().to_string(),
+                        src: src.to_string(),
+                        location: location.clone(),
+                    };
+                    write(buffer, diagnostic, Severity::Warning);
+                    writeln!(buffer, "`{}` is deprecated: {}", name, message).unwrap();
+                }
+
+                Warning::Internal {
+                    location,
+                    name,
+                    package,
+                } => {
+                    let diagnostic = Diagnostic {
+                        title: title(warning).to_string(),
+                        label: "this is internal to another package".to_string(),
+                        file: path.to_str().unwrap().to_string(),
+                        src: src.to_string(),
+                        location: location.clone(),
+                    };
+                    write(buffer, diagnostic, Severity::Warning);
+                    writeln!(
+                        buffer,
+                        "`{}` is internal to the `{}` package, so it may be changed or removed
+without warning in any version of it.",
+                        name, package
+                    )
+                    .unwrap();
+                }
+
+                Warning::ShadowedImportedType {
+                    location,
+                    name,
+                    imported,
+                } => {
+                    let diagnostic = Diagnostic {
+                        title: title(warning).to_string(),
+                        label: "this shadows an imported type".to_string(),
+                        file: path.to_str().unwrap().to_string(),
+                        src: src.to_string(),
+                        location: location.clone(),
+                    };
+                    write(buffer, diagnostic, Severity::Warning);
+                    writeln!(
+                        buffer,
+                        "The type `{}` has the same name as the imported type `{}`, so
+annotations using `{}` may be mistaken for it. Consider renaming this type.",
+                        name, imported, name
+                    )
+                    .unwrap();
+                }
             },
         }
     }
@@ -139,12 +404,158 @@ be an update. Remove the update or remove fields that need to be copied.")
         self.pretty(&mut buffer);
         buffer_writer.print(&buffer).unwrap();
     }
+
+    /// The warning as a single line of JSON, with its location given as
+    /// one-indexed lines and columns.
+    ///
+    pub fn to_json(&self) -> String {
+        match self {
+            Self::Type { path, src, warning } => {
+                let lines = LineNumbers::new(src);
+                let position = |byte_index| {
+                    serde_json::json!({
+                        "line": lines.line_index(byte_index) + 1,
+                        "column": lines.column_index(src, byte_index) + 1,
+                    })
+                };
+                let location = warning.location();
+                serde_json::json!({
+                    "severity": "warning",
+                    "code": warning.code(),
+                    "message": title(warning),
+                    "file": path.to_str().unwrap(),
+                    "location": {
+                        "start": position(location.start),
+                        "end": position(location.end),
+                    },
+                })
+                .to_string()
+            }
+        }
+    }
+}
+
+fn title(warning: &crate::typ::Warning) -> &'static str {
+    use crate::typ::Warning;
+    match warning {
+        Warning::Todo { .. } => "Todo found",
+        Warning::ImplicitlyDiscardedResult { .. } => "Unused result value",
+        Warning::NoFieldsRecordUpdate { .. } => "Fieldless record update",
+        Warning::AllFieldsRecordUpdate { .. } => "Redundant record update",
+        Warning::UnusedType { .. } => "Unused type",
+        Warning::UnusedConstructor { .. } => "Unused constructor",
+        Warning::UnusedTypeParameter { .. } => "Unused type parameter",
+        Warning::RedundantLet { .. } => "Redundant assignment",
+        Warning::ConstantCondition { .. } => "Constant condition",
+        Warning::RefutableLetPattern { .. } => "Refutable pattern in let",
+        Warning::FloatEquality { .. } => "Float equality",
+        Warning::UnnecessaryResult { .. } => "Unnecessary Result",
+        Warning::RedundantRewrap { .. } => "Redundant case",
+        Warning::InfiniteRecursion { .. } => "Infinite recursion",
+        Warning::RawErlang { .. } => "Unchecked Erlang code",
+        Warning::RedundantAnnotation { .. } => "Redundant type annotation",
+        Warning::NonTailRecursion { .. } => "Non-tail recursion",
+        Warning::Deprecated { .. } => "Use of deprecated code",
+        Warning::Internal { .. } => "Use of internal code",
+        Warning::ShadowedImportedType { .. } => "Shadowed type",
+    }
+}
+
+/// Whether the warning is suppressed by an `@allow(...)` attribute of the
+/// statement of the module that it occurs in.
+///
+pub fn is_allowed<A, B, C, D>(module: &Module<A, B, C, D>, warning: &crate::typ::Warning) -> bool {
+    module.allows(warning.code(), warning.location().start)
 }
 
-pub fn print_all(analysed: &[crate::project::Analysed]) {
+pub fn print_all(analysed: &[Analysed], format: Format) {
     for a in analysed.iter() {
         for w in a.warnings.iter() {
-            w.pretty_print()
+            match format {
+                Format::Human => w.pretty_print(),
+                Format::Json => eprintln!("{}", w.to_json()),
+            }
+        }
+    }
+}
+
+pub fn print_todo_summary(analysed: &[Analysed]) {
+    if let Some(summary) = todo_summary(analysed) {
+        print!("{}", summary);
+    }
+}
+
+/// A count of the `todo`s remaining in the project along with their
+/// locations, or `None` if there are none.
+///
+pub fn todo_summary(analysed: &[Analysed]) -> Option<String> {
+    let locations = todo_locations(analysed);
+    if locations.is_empty() {
+        return None;
+    }
+    let count = locations.len();
+    let locations: String = locations
+        .into_iter()
+        .map(|location| format!("  {}\n", location))
+        .collect();
+
+    let noun = if count == 1 { "todo" } else { "todos" };
+    Some(format!("{} {} remaining:\n{}", count, noun, locations))
+}
+
+/// An error if there are any `todo`s remaining in the project, for builds
+/// that must be complete such as those for a release.
+///
+pub fn deny_todos(analysed: &[Analysed]) -> Result<(), Error> {
+    let locations = todo_locations(analysed);
+    if locations.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::DeniedTodo { locations })
+    }
+}
+
+/// The locations of the `todo`s in the project as `path:line:column`, in
+/// order of path and then position. These are found in the modules rather
+/// than their warnings so that a `todo` is still counted when its warning is
+/// suppressed with `@allow(todo)`.
+///
+fn todo_locations(analysed: &[Analysed]) -> Vec<String> {
+    let mut todos: Vec<_> = analysed
+        .iter()
+        .flat_map(|a| {
+            let mut finder = TodoFinder { starts: vec![] };
+            finder.visit_module(&a.ast);
+            finder
+                .starts
+                .into_iter()
+                .map(move |start| (&a.path, &a.src, start))
+        })
+        .collect();
+    todos.sort_by_key(|(path, _, start)| (*path, *start));
+    todos
+        .into_iter()
+        .map(|(path, src, start)| {
+            let lines = LineNumbers::new(src);
+            format!(
+                "{}:{}:{}",
+                path.to_str().unwrap(),
+                lines.line_index(start) + 1,
+                lines.column_index(src, start) + 1
+            )
+        })
+        .collect()
+}
+
+struct TodoFinder {
+    starts: Vec<usize>,
+}
+
+impl<'ast> Visit<'ast> for TodoFinder {
+    fn visit_expr(&mut self, expr: &'ast TypedExpr) {
+        if let TypedExpr::Todo { location, .. } = expr {
+            self.starts.push(location.start);
         }
+        walk_expr(self, expr)
     }
 }
@@ -0,0 +1,69 @@
+use crate::{
+    error::Error,
+    fs,
+    project::{self, BuildOptions, Cache},
+};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// How often the source directories are checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How long the source directories must be unchanged before rebuilding, so
+/// that saving several files at once triggers a single rebuild.
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+pub fn command(root: PathBuf, options: BuildOptions) -> Result<(), Error> {
+    let mut cache = Cache::default();
+
+    loop {
+        let snapshot = snapshot(&root);
+        let start = Instant::now();
+
+        match project::build(&root, &mut cache, options) {
+            Ok((compiled, total)) => println!(
+                "Compiled {} of {} modules in {}ms",
+                compiled,
+                total,
+                start.elapsed().as_millis()
+            ),
+            Err(error) => error.pretty_print(),
+        }
+
+        println!("Watching for changes...");
+        wait_for_change(&root, snapshot);
+    }
+}
+
+/// The modification times of all the Gleam source files in the project.
+///
+fn snapshot(root: &Path) -> HashMap<PathBuf, SystemTime> {
+    ["src", "test"]
+        .iter()
+        .map(|dir| root.join(dir))
+        .flat_map(|dir| fs::all_gleam_files(&dir).collect::<Vec<_>>())
+        .filter_map(|path| {
+            let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            Some((path, modified))
+        })
+        .collect()
+}
+
+fn wait_for_change(root: &Path, previous: HashMap<PathBuf, SystemTime>) {
+    let mut current = previous.clone();
+    while current == previous {
+        std::thread::sleep(POLL_INTERVAL);
+        current = snapshot(root);
+    }
+
+    // Wait for the files to settle before rebuilding
+    loop {
+        std::thread::sleep(DEBOUNCE);
+        let settled = snapshot(root);
+        if settled == current {
+            return;
+        }
+        current = settled;
+    }
+}